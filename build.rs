@@ -0,0 +1,29 @@
+/*
+ * Security Compliance CLI - Build Script
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+use std::process::Command;
+
+fn main() {
+    // Evidence bundles record the commit the binary was built from. A
+    // GIT_COMMIT set by CI wins; builds without git leave it unset.
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    if std::env::var_os("GIT_COMMIT").is_some() {
+        return;
+    }
+    let Ok(output) = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+    else {
+        return;
+    };
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && !commit.is_empty() {
+        println!("cargo:rustc-env=GIT_COMMIT={}", commit);
+    }
+}
@@ -0,0 +1,219 @@
+/*
+ * Security Compliance CLI - Resumable Run Checkpoints
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+//! Checkpoint files that let an interrupted run pick up where it stopped.
+//!
+//! `--checkpoint` appends every completed test result to a JSON Lines file as
+//! the run goes. Started again with the same file, the run skips the tests
+//! already in it and reports them together with the ones it still had to run.
+//! The first line names the device, suite and mode the file belongs to, so a
+//! checkpoint is never resumed against a different run.
+
+use crate::error::Result;
+use crate::tests::{TestResult, TestStatus};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// The run a checkpoint belongs to, written as its first line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointRun {
+    pub host: String,
+    pub suite: String,
+    pub mode: String,
+}
+
+pub struct Checkpoint {
+    path: PathBuf,
+    file: File,
+    completed: Vec<TestResult>,
+}
+
+impl Checkpoint {
+    /// Open a checkpoint for `run`, loading the results of an earlier attempt
+    /// if the file exists. A file left by a different run is started afresh.
+    pub fn open(path: &Path, run: &CheckpointRun) -> Result<Self> {
+        let mut content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let recorded = content
+            .lines()
+            .next()
+            .and_then(|line| serde_json::from_str::<CheckpointRun>(line).ok());
+        if !content.is_empty() && recorded.as_ref() != Some(run) {
+            let previous = recorded.map_or("an unknown run".to_string(), |recorded| {
+                format!(
+                    "{} ({} suite, {} mode)",
+                    recorded.host, recorded.suite, recorded.mode
+                )
+            });
+            warn!(
+                "⚠️  Checkpoint {} belongs to {}, not {} ({} suite, {} mode); starting over",
+                path.display(),
+                previous,
+                run.host,
+                run.suite,
+                run.mode
+            );
+            content.clear();
+        }
+        if content.is_empty() {
+            std::fs::write(path, format!("{}\n", serde_json::to_string(run)?))?;
+        }
+
+        let completed = parse_checkpoint(&content);
+        if !completed.is_empty() {
+            info!(
+                "⏯️  Resuming from checkpoint {}: {} test(s) already completed",
+                path.display(),
+                completed.len()
+            );
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        // Start new records on a line of their own after a cut-off one
+        if !content.is_empty() && !content.ends_with('\n') {
+            writeln!(file)?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            completed,
+        })
+    }
+
+    /// Result an earlier attempt recorded for this test
+    pub fn completed(&self, test_id: &str) -> Option<&TestResult> {
+        self.completed
+            .iter()
+            .find(|result| result.test_id == test_id)
+    }
+
+    /// Append a completed result. Tests that errored out, e.g. because the
+    /// link dropped, are not recorded so a resumed run tries them again.
+    pub fn record(&mut self, result: &TestResult) -> Result<()> {
+        if result.status == TestStatus::Error {
+            return Ok(());
+        }
+        writeln!(self.file, "{}", serde_json::to_string(result)?)?;
+        self.file.flush()?;
+        self.completed.push(result.clone());
+        Ok(())
+    }
+
+    /// Remove the checkpoint once the run it belongs to has completed
+    pub fn finish(self) -> Result<()> {
+        drop(self.file);
+        std::fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Results from a checkpoint file, one JSON object per line. A line cut off
+/// when the previous run died is dropped; for a test recorded more than once
+/// the last result wins.
+fn parse_checkpoint(content: &str) -> Vec<TestResult> {
+    let mut results: Vec<TestResult> = Vec::new();
+    // The first line is the `CheckpointRun` header
+    for (number, line) in content.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<TestResult>(line) {
+            Ok(result) => {
+                results.retain(|earlier| earlier.test_id != result.test_id);
+                results.push(result);
+            }
+            Err(e) => warn!("Ignoring unreadable checkpoint line {}: {}", number + 1, e),
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_test_result;
+    use std::time::Duration;
+
+    fn result(test_id: &str, status: TestStatus) -> TestResult {
+        create_test_result(test_id, test_id, "boot", status, "", None, Duration::ZERO)
+    }
+
+    fn run(host: &str) -> CheckpointRun {
+        CheckpointRun {
+            host: host.to_string(),
+            suite: "Boot".to_string(),
+            mode: "PreProduction".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_resumes_completed_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.ckpt");
+
+        let mut checkpoint = Checkpoint::open(&path, &run("192.168.0.36")).unwrap();
+        checkpoint
+            .record(&result("boot_001", TestStatus::Passed))
+            .unwrap();
+        checkpoint
+            .record(&result("boot_002", TestStatus::Error))
+            .unwrap();
+        checkpoint
+            .record(&result("boot_003", TestStatus::Failed))
+            .unwrap();
+        drop(checkpoint);
+
+        // Simulate the link dying halfway through writing a line
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"test_id\":\"boot_00").unwrap();
+        drop(file);
+
+        let mut checkpoint = Checkpoint::open(&path, &run("192.168.0.36")).unwrap();
+        checkpoint
+            .record(&result("boot_004", TestStatus::Warning))
+            .unwrap();
+        drop(checkpoint);
+
+        let checkpoint = Checkpoint::open(&path, &run("192.168.0.36")).unwrap();
+        assert!(checkpoint.completed("boot_001").is_some());
+        assert!(checkpoint.completed("boot_004").is_some());
+        assert!(checkpoint.completed("boot_002").is_none());
+        assert_eq!(
+            checkpoint.completed("boot_003").unwrap().status,
+            TestStatus::Failed
+        );
+
+        checkpoint.finish().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_checkpoint_from_another_run_is_not_resumed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.ckpt");
+
+        let mut checkpoint = Checkpoint::open(&path, &run("192.168.0.36")).unwrap();
+        checkpoint
+            .record(&result("boot_001", TestStatus::Passed))
+            .unwrap();
+        drop(checkpoint);
+
+        let checkpoint = Checkpoint::open(&path, &run("192.168.0.37")).unwrap();
+        assert!(checkpoint.completed("boot_001").is_none());
+        drop(checkpoint);
+
+        // The file now belongs to the new run
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.contains("192.168.0.37"));
+    }
+}
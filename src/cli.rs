@@ -5,6 +5,7 @@
  */
 
 use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -50,6 +51,15 @@ pub struct Cli {
     #[arg(short = 'i', long)]
     pub identity_file: Option<PathBuf>,
 
+    /// 🏠 Test the machine this tool runs on
+    ///
+    /// Runs commands in a local shell instead of connecting over SSH or serial,
+    /// e.g. when the CLI is installed on the device during manufacturing.
+    /// Privileged probes need root or passwordless sudo; set `password` in a
+    /// config file with channel_type = "local" to pipe a sudo password instead.
+    #[arg(long, conflicts_with = "serial_device")]
+    pub local: bool,
+
     /// 📡 Serial device path (for direct cable connection)
     ///
     /// Use this instead of SSH when device has no network.
@@ -97,6 +107,27 @@ pub struct Cli {
     #[arg(long, default_value = "$ ")]
     pub serial_shell_prompt: String,
 
+    /// 🔀 Source address for SSH connections (multi-homed hosts)
+    ///
+    /// Local IP address (or interface name on Linux, e.g. eth1) that outbound
+    /// SSH connections should originate from, such as a management network.
+    #[arg(long, alias = "interface")]
+    pub bind_address: Option<String>,
+
+    /// 📼 Save every command sent to the device and its output to a snapshot
+    ///
+    /// The JSON snapshot can be replayed later with --replay to re-run the
+    /// tests offline, e.g. for reproducible audits. Sudo passwords are masked.
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// ⏯️ Run against a recorded snapshot instead of a device
+    ///
+    /// Commands are answered from a file written by --record; a command that
+    /// was not recorded fails. No connection settings are needed.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
     /// ⏱️ Connection timeout in seconds
     ///
     /// How long to wait for device to respond before giving up.
@@ -112,8 +143,23 @@ pub struct Cli {
     /// • json: Machine-readable data for automation
     /// • cra: EU Cyber Resilience Act compliance report
     /// • red: UK CE RED compliance report
-    #[arg(short = 'f', long, default_value = "human")]
-    pub format: OutputFormat,
+    /// • nist: NIST IR 8259A / SP 800-213 IoT baseline report
+    /// • iec62443: IEC 62443-4-2 component requirements report
+    /// • prometheus: Metrics for the node_exporter textfile collector
+    /// • sarif: SARIF 2.1.0 findings for GitHub code scanning
+    /// • csv: One row per test for spreadsheets
+    ///
+    /// Give several comma separated formats to produce them all from one run;
+    /// --output is then a directory that receives one report per format.
+    /// Example: --format human,json,junit --output reports/
+    #[arg(
+        short = 'f',
+        long,
+        alias = "output-format",
+        value_delimiter = ',',
+        default_value = "human"
+    )]
+    pub format: Vec<OutputFormat>,
 
     /// 🔍 Verbose output (use -v, -vv, or -vvv for more detail)
     ///
@@ -124,6 +170,90 @@ pub struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
 
+    /// 🤫 Only print the final summary
+    ///
+    /// Hides the progress display, per-test lines and timing summary so CI
+    /// logs stay clean.
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// 🐢 Number of slowest tests to list after the run
+    ///
+    /// Shows which tests dominate run time. Use 0 to disable. Default: 5.
+    #[arg(long, global = true, value_name = "N")]
+    pub slowest: Option<usize>,
+
+    /// 🧾 How much of each test's details to include in reports
+    ///
+    /// • off: results and messages only (concise manager report)
+    /// • summary: the first few lines of each test's findings
+    /// • full: everything the test captured (engineer report)
+    ///
+    /// Applies to human and markdown output; JSON keeps full details unless
+    /// --no-details is given.
+    /// Default: full details in human output with -vv, otherwise off.
+    #[arg(long, value_name = "LEVEL")]
+    pub details: Option<DetailLevel>,
+
+    /// 🗜️ Write minimal JSON for fleet storage
+    ///
+    /// Leaves each test's details and metadata out of JSON output, keeping
+    /// test id, status, duration and timestamp alongside the run summary.
+    #[arg(long, global = true)]
+    pub no_details: bool,
+
+    /// 🔓 Keep sensitive data in test details
+    ///
+    /// By default password hashes (e.g. from /etc/shadow), PEM private keys
+    /// and anything matching output.redact_patterns are masked before results
+    /// are printed or written. Only use this for trusted local runs.
+    #[arg(long, global = true)]
+    pub no_redact: bool,
+
+    /// 🧪 Show warnings as failures in JUnit output
+    ///
+    /// By default a warning is a passing test case with its message attached.
+    #[arg(long, global = true)]
+    pub junit_warnings_as_failures: bool,
+
+    /// ⏲️ Give up on any single test that runs longer than this
+    ///
+    /// A test that hangs (e.g. on an unreachable port) is recorded as an error
+    /// and the run moves on to the next test. Accepts seconds or a unit
+    /// suffix: 90, 30s, 2m. Use 0 to disable.
+    /// Default: 60s, or tests.timeout_per_test.
+    #[arg(long, global = true, value_name = "DURATION", value_parser = parse_duration_secs)]
+    pub timeout_per_test: Option<u64>,
+
+    /// 🏷️ Product name in compliance report headers
+    ///
+    /// Default: the device's OS name from /etc/os-release.
+    #[arg(long, global = true, value_name = "NAME")]
+    pub product_name: Option<String>,
+
+    /// 🔖 Product version in compliance report headers
+    ///
+    /// Default: VERSION_ID from /etc/os-release and the OSTree commit.
+    #[arg(long, global = true, value_name = "VERSION")]
+    pub product_version: Option<String>,
+
+    /// 🏭 Manufacturer in compliance report headers
+    #[arg(long, global = true, value_name = "NAME")]
+    pub manufacturer: Option<String>,
+
+    /// 📟 Product model in compliance report headers
+    ///
+    /// Default: the detected machine type.
+    #[arg(long, global = true, value_name = "MODEL")]
+    pub model: Option<String>,
+
+    /// 🌐 Format of cra, red, nist and iec62443 reports
+    ///
+    /// • markdown: plain document for archiving (default)
+    /// • html: self-contained page with expandable evidence per requirement
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub compliance_format: Option<ComplianceFormat>,
+
     /// 📋 Configuration file (saves typing common options)
     ///
     /// Load settings from a TOML file instead of typing them each time.
@@ -134,8 +264,9 @@ pub struct Cli {
     /// 💾 Save results to file
     ///
     /// Write the test results to a file instead of just showing on screen.
-    /// File format depends on --format option.
-    #[arg(short, long)]
+    /// File format depends on --format option (a directory for several formats).
+    /// Files are written to a temporary name and renamed into place when complete.
+    #[arg(short, long, alias = "output-file")]
     pub output: Option<PathBuf>,
 
     /// 🎯 Target device type (helps run appropriate tests)
@@ -149,6 +280,8 @@ pub struct Cli {
     pub command: Commands,
 }
 
+// Parsed once per invocation, so the size of the Test arguments doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// 🧪 Run security compliance tests on your device
@@ -160,6 +293,14 @@ pub enum Commands {
     /// • Network device: test --host 192.168.1.100
     /// • Serial device: test --serial-device /dev/ttyUSB0
     /// • Auto-detect: test (uses default settings)
+    #[command(after_long_help = "EXIT CODES:\n\
+        • 0: the run passed its gate\n\
+        • 1: the run failed its gate, or a baseline/reference comparison, a required hook or the setup failed\n\n\
+        Which results fail the gate:\n\
+        • default: any failure; warnings in --environment prod's critical categories; errors unless --errors-non-gating\n\
+        • --fail-on SEVERITY: failures and warnings at or above SEVERITY; errors unless --errors-non-gating\n\
+        • --fail-on-status warning|failed|error: any test at or above that status (errors unless --errors-non-gating)\n\
+        • --exit-zero-on-warning: as above, but warnings never fail the run")]
     Test {
         /// 📦 Which group of tests to run
         ///
@@ -191,6 +332,252 @@ pub enum Commands {
         /// Includes technical details, remediation steps, and compliance mapping.
         #[arg(long)]
         detailed_report: bool,
+
+        /// 🚀 Number of tests to run at the same time
+        ///
+        /// Limited to what the connection can safely handle:
+        /// • serial: 1 (a console cannot interleave commands)
+        /// • ssh: 4 by default (see ssh_max_sessions in the config file)
+        ///
+        /// Over SSH each concurrent worker opens its own session to the device.
+        #[arg(short, long, alias = "parallel", value_name = "N")]
+        jobs: Option<usize>,
+
+        /// 🧾 Show which tests would run, without connecting to the device
+        ///
+        /// Lists the test IDs, names and categories selected by the suite, mode,
+        /// excluded categories and configured machine type, then exits.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// 🚫 Leave out whole test categories
+        ///
+        /// Run the selected suite minus these categories (comma separated or repeated).
+        /// Example: --exclude-category production,container
+        #[arg(long, value_delimiter = ',')]
+        exclude_category: Vec<String>,
+
+        /// ✂️ Leave out individual tests
+        ///
+        /// Run the selected suite minus these test IDs (comma separated or
+        /// repeated), e.g. ones that don't apply to this device. Unknown IDs
+        /// are an error. Example: --exclude-tests certificate_005,network_004
+        #[arg(long, value_delimiter = ',', value_name = "TEST_IDS")]
+        exclude_tests: Vec<String>,
+
+        /// 🎚️ Only fail the run on findings of at least this severity
+        ///
+        /// Every test has a severity (low, medium, high, critical). With this
+        /// set, failures and warnings below it are reported but don't fail the
+        /// run, while those at or above it always do.
+        /// Example: --fail-on high
+        #[arg(long, value_name = "SEVERITY")]
+        fail_on: Option<Severity>,
+
+        /// 🚦 Exit non-zero when any test reaches this status
+        ///
+        /// Computes the exit code from the result counts instead of the
+        /// severity and category gating:
+        /// • warning: any warning, failure or error
+        /// • failed: any failure or error
+        /// • error: only tests that errored out
+        /// Example: --fail-on-status failed
+        #[arg(long, value_name = "STATUS", conflicts_with = "fail_on")]
+        fail_on_status: Option<FailOnStatus>,
+
+        /// 🟡 Exit zero when warnings are the only findings
+        ///
+        /// Warnings stay in the report but never fail the run, e.g. while a
+        /// stricter environment is being rolled out. Failures and errors
+        /// still do.
+        #[arg(long, conflicts_with = "fail_on_status")]
+        exit_zero_on_warning: bool,
+
+        /// 🏷️ Only run tests carrying one of these tags
+        ///
+        /// Tags name the frameworks and platforms a test covers: cra, red,
+        /// imx93, imx8mm, foundries, wireless. Repeat or comma separate to
+        /// select the union. The 'list' command shows each test's tags.
+        /// Example: --tag red --tag imx93
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+
+        /// 🆚 Compare this device against a golden reference device
+        ///
+        /// Runs the same suite on a second SSH host (same credentials) and reports
+        /// every test where this device diverges from the reference. With --output,
+        /// the reference results go to a `.reference` file next to it, for example
+        /// report.reference.json for report.json.
+        /// Example: --compare-device 192.168.0.40
+        #[arg(long, value_name = "HOST")]
+        compare_device: Option<String>,
+
+        /// 📉 Compare this run against a saved baseline results file
+        ///
+        /// Loads results saved earlier with --format json and reports which tests
+        /// regressed, improved, or were added or removed. The run then fails only
+        /// if something regressed, whatever failures the baseline already had.
+        /// Example: --baseline release-1.2.json
+        #[arg(long, value_name = "FILE", conflicts_with = "compare_device")]
+        baseline: Option<PathBuf>,
+
+        /// 🚚 Run the suite against every device listed in a targets file
+        ///
+        /// The file is TOML, YAML (.yaml/.yml) or JSON and lists
+        /// [[targets]] with a host and optionally name, port, user, password
+        /// and ssh_key_path; anything left out comes from the usual connection
+        /// settings. Devices are tested concurrently,
+        /// each target's JSON results are written to --fleet-dir together with
+        /// an aggregate fleet.json, and a summary shows which devices passed.
+        /// A --checkpoint is kept per target in --fleet-dir, and post-run hooks
+        /// and webhooks fire once per target.
+        /// Example: --targets lab.toml
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = [
+                "compare_device",
+                "baseline",
+                "fix_and_verify",
+                "emit_remediation",
+                "dump_evidence_bundle",
+            ]
+        )]
+        targets: Option<PathBuf>,
+
+        /// Devices tested at the same time with --targets (default: 4)
+        #[arg(long, value_name = "N", requires = "targets")]
+        target_jobs: Option<usize>,
+
+        /// Directory for the per-target and aggregate results of --targets
+        #[arg(
+            long,
+            value_name = "DIR",
+            default_value = "fleet-results",
+            requires = "targets"
+        )]
+        fleet_dir: PathBuf,
+
+        /// 🩹 Write suggested fixes for failed tests to a shell script
+        ///
+        /// Collects remediation commands from failed and warning tests into a
+        /// script you can review and then run on the device by hand.
+        /// Example: --emit-remediation remediate.sh
+        #[arg(long, alias = "generate-remediation", value_name = "FILE")]
+        emit_remediation: Option<PathBuf>,
+
+        /// 🩺 Don't fail the run because of tests that errored out
+        ///
+        /// A test errors when it cannot determine a result (e.g. a probe is not
+        /// supported by the hardware). Genuine failures still fail the run.
+        #[arg(long)]
+        errors_non_gating: bool,
+
+        /// 🗃️ Reuse output of read-only commands that several tests run
+        ///
+        /// Commands like dmesg, lsmod or reading /proc and /etc/os-release run
+        /// once per test run instead of once per test, which helps on slow
+        /// links. Commands that change state or read volatile files always run.
+        #[arg(long)]
+        cache_commands: bool,
+
+        /// 🔬 Keep complete command output as evidence
+        ///
+        /// Tests normally quote only the first few lines of long command output
+        /// in their details. For forensic audits this keeps all of it; the
+        /// Markdown and HTML reports fold long evidence into collapsible blocks.
+        /// Implies --details full unless --details is given.
+        #[arg(long)]
+        verbose_evidence: bool,
+
+        /// 🌍 Where the device is headed: sets how strict pass/fail is
+        ///
+        /// • dev: lenient, tests that error out don't fail the run
+        /// • staging: failures and errors fail the run
+        /// • prod: also fails on warnings in boot, hardware, runtime and network tests
+        /// Explicit options such as --errors-non-gating still apply on top.
+        #[arg(long)]
+        environment: Option<Environment>,
+
+        /// 🛠️ Offer to fix failed tests, then re-test them straight away
+        ///
+        /// For each failed or warning test with suggested commands, shows the
+        /// commands and asks before running them on the device. The test is
+        /// re-run afterwards so the report shows whether the fix worked.
+        /// Fixes that write to a read-only mount, such as /usr on OSTree
+        /// images, are skipped without asking.
+        #[arg(long)]
+        fix_and_verify: bool,
+
+        /// ⏯️ Save progress to a checkpoint file and resume from it
+        ///
+        /// Each completed test is appended to the file as the run goes. Run
+        /// again with the same file after a dropped connection and tests
+        /// already in it are skipped; the report covers the whole suite.
+        /// Tests that errored out are run again. The file is removed once
+        /// the suite completes, and one left by a different device, suite or
+        /// mode is started over rather than resumed.
+        /// Example: --checkpoint run.ckpt
+        #[arg(long, value_name = "FILE", conflicts_with = "compare_device")]
+        checkpoint: Option<PathBuf>,
+
+        /// 📦 Package the run into a certification evidence bundle (.tar.gz)
+        ///
+        /// Bundles the JSON results, CRA, RED, NIST and IEC 62443 reports,
+        /// redacted per-test transcripts, system info and run metadata into one
+        /// archive for submission to a notified body, with a SHA-256 for every
+        /// file in its manifest.
+        #[arg(long, value_name = "FILE")]
+        dump_evidence_bundle: Option<PathBuf>,
+
+        /// 🔏 Sign the evidence bundle manifest with this OpenSSH private key
+        ///
+        /// Writes manifest.json.sig, checked with
+        /// `ssh-keygen -Y verify -n security-compliance-evidence`.
+        /// Default: output.signing_key
+        #[arg(long, value_name = "FILE", requires = "dump_evidence_bundle")]
+        signing_key: Option<PathBuf>,
+
+        /// ⏱️ Warn if the device has been up for less than this many seconds
+        ///
+        /// Entropy, log and audit checks need a settled device; results from a
+        /// device that just booted are annotated as possibly unreliable.
+        /// Use 0 to disable the check. Default: 300, or tests.min_uptime_secs.
+        #[arg(long, value_name = "SECS", alias = "since-boot")]
+        min_uptime: Option<u64>,
+
+        /// 🕵️ Look this far back in the journal for security events
+        ///
+        /// compliance_004 and compliance_005 count failed sudo attempts, SSH
+        /// authentication failures and SELinux denials logged since then.
+        /// Accepts any journalctl --since value. Default: "24 hours ago", or
+        /// tests.log_since. Example: --since "7 days ago"
+        #[arg(long, value_name = "WHEN")]
+        since: Option<String>,
+
+        /// 🪝 Run a local command after the suite completes
+        ///
+        /// Runs through `sh -c` with SECURITY_COMPLIANCE_RESULTS_FILE (JSON results)
+        /// and SECURITY_COMPLIANCE_EXIT_STATUS set, for custom notification or
+        /// archival. A failing hook only produces a warning unless --require-hook.
+        #[arg(long, value_name = "COMMAND")]
+        post_run_hook: Option<String>,
+
+        /// ❗ Fail the run if the post-run hook fails or times out
+        #[arg(long)]
+        require_hook: bool,
+
+        /// 📣 POST a JSON summary of the run to this URL when it completes
+        ///
+        /// The summary has the host, overall_passed, counts, success_rate and
+        /// the IDs of failed tests, and works with Slack-style incoming
+        /// webhooks. A delivery failure only logs a warning.
+        #[arg(long, value_name = "URL")]
+        notify_webhook: Option<String>,
+
+        /// 🔕 Which runs send the webhook notification
+        #[arg(long, value_enum, value_name = "WHEN")]
+        notify_on: Option<NotifyOn>,
     },
     /// 📋 Show all available tests (what can be checked)
     ///
@@ -202,9 +589,67 @@ pub enum Commands {
     ///
     /// Verifies your config file has correct syntax and valid settings
     /// before running tests. Helps catch configuration errors early.
+    /// Also checks every ID in custom_tests (the tests run by
+    /// --test-suite custom) is a known test.
     Validate {
         /// 📄 Path to the configuration file to check
         config_file: PathBuf,
+
+        /// 🩺 Also connect to the configured target and check it can be tested
+        ///
+        /// Runs the healthcheck with this config's connection settings: the
+        /// login user, sudo access and the tools tests rely on (dmesg,
+        /// openssl, systemctl, ss, ...), listing the tests that will report
+        /// reduced results because something is missing.
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// 📦 Build a certification evidence bundle from a stored results file
+    ///
+    /// Takes results saved with --format json and packages them with the
+    /// CRA, RED, NIST and IEC 62443 reports, redacted per-test transcripts,
+    /// system info and run metadata into a single .tar.gz, with a SHA-256 for
+    /// every file in its manifest. No device connection is needed.
+    EvidenceBundle {
+        /// 📄 JSON results file from an earlier run
+        results_file: PathBuf,
+
+        /// 💾 Where to write the bundle
+        #[arg(short, long, default_value = "evidence-bundle.tar.gz")]
+        output: PathBuf,
+
+        /// 🔏 Sign the manifest with this OpenSSH private key (default: output.signing_key)
+        #[arg(long, value_name = "FILE")]
+        signing_key: Option<PathBuf>,
+    },
+
+    /// 🗺️ Regenerate a compliance report from stored results
+    ///
+    /// Applies the current compliance mappings to results saved earlier with
+    /// --format json, so mapping improvements reach historical runs without
+    /// re-testing the device. Writes to --output, or prints to the console.
+    Remap {
+        /// 📄 JSON results file from an earlier run
+        results_file: PathBuf,
+
+        /// 📋 Compliance framework to map the results to
+        #[arg(long, default_value = "cra")]
+        framework: ComplianceFramework,
+
+        /// 📄 Report format
+        #[arg(long, default_value = "markdown")]
+        report_format: RemapFormat,
+    },
+
+    /// 🧭 Explain how a compliance framework maps tests to requirements
+    ///
+    /// Prints every requirement with its risk level, remediation and the tests
+    /// that feed it, then the coverage gaps: requirements with no existing test
+    /// and tests that don't count towards any requirement.
+    ExplainCompliance {
+        /// 📋 Compliance framework to explain
+        framework: ComplianceFramework,
     },
 
     /// 🔍 Automatically identify your device type and capabilities
@@ -217,9 +662,24 @@ pub enum Commands {
     /// Run this first if you're unsure about your device specifications.
     Detect,
 
+    /// 🩺 Check that the tool can test your device (no security tests run)
+    ///
+    /// Preflight check of the tool's environment and the target connection:
+    /// • Can we connect and run a simple command?
+    /// • Is the target running Linux?
+    /// • Which shell does the device use?
+    /// • Does sudo work (passwordless or with the login password)?
+    /// • Which user are we logged in as?
+    /// • Which tools are present (dmesg, openssl, systemctl, ss, iptables, nft, docker, ostree),
+    ///   and which tests report reduced results without them or without sudo?
+    ///
+    /// Run this before a full test run to avoid confusing half-empty reports.
+    Healthcheck,
+
     /// 🔑 Install SSH public key for secure authentication
     ///
-    /// Installs an SSH public key on the target device via serial console.
+    /// Installs an SSH public key on the target device via serial console, or
+    /// over an SSH connection that already works to rotate in a new key.
     /// This is essential for testing when password authentication is disabled.
     ///
     /// KEY SOURCES:
@@ -228,7 +688,7 @@ pub enum Commands {
     /// • Configure validity period (--key-validity-hours)
     ///
     /// WORKFLOW:
-    /// 1. Connect via serial console
+    /// 1. Connect via serial console (or SSH)
     /// 2. Generate or load SSH key pair
     /// 3. Install public key to ~/.ssh/authorized_keys
     /// 4. Test SSH connection with new key
@@ -264,9 +724,18 @@ pub enum Commands {
         /// 👤 Target username for SSH key installation
         ///
         /// Which user account to install the SSH key for.
-        /// Defaults to the serial login username.
+        /// Defaults to the serial or SSH login username.
         #[arg(long)]
         target_user: Option<String>,
+
+        /// 🧹 Install a systemd timer on the target that deletes expired keys
+        ///
+        /// Generated keys carry an expiry-time option where the target's sshd
+        /// supports it (OpenSSH 8.2+); otherwise the expiry is only tracked
+        /// client-side. The timer removes lapsed keys every 15 minutes either
+        /// way. Needs root or passwordless sudo on the target.
+        #[arg(long)]
+        install_cleanup_timer: bool,
     },
 
     /// 🔍 Check for installed SSH test keys
@@ -312,6 +781,21 @@ pub enum Commands {
         temp_keys_only: bool,
     },
 
+    /// 🕵️ Audit every authorized SSH key on the device
+    ///
+    /// Lists the authorized_keys entries of every user with a home directory,
+    /// for offboarding audits. Each key is shown with its SHA256 fingerprint,
+    /// type, size, comment and restriction options.
+    ///
+    /// FLAGGED KEYS:
+    /// • No comment identifying the owner
+    /// • Weak types: ssh-dss, or ssh-rsa under 3072 bits
+    /// • No from= source or expiry-time= restriction
+    ///
+    /// Reading other users' keys needs root or passwordless sudo.
+    /// Use --format json for machine-readable output.
+    AuditSshKeys,
+
     /// 🗑️ Remove SSH public keys from target device
     ///
     /// Removes SSH public keys from the target device for security cleanup.
@@ -359,7 +843,9 @@ pub enum Commands {
 
         /// ✅ Verify key removal
         ///
-        /// Test that SSH connection fails after key removal (for verification).
+        /// Re-read authorized_keys to confirm no removed key remains and, with
+        /// --private-key-file, that an SSH login with the key is rejected.
+        /// Exits non-zero if a removed key still works.
         #[arg(long, default_value = "true")]
         verify_removal: bool,
     },
@@ -379,18 +865,129 @@ pub enum OutputFormat {
     Cra,
     /// 🇬🇧 UK CE RED (Radio Equipment Directive) compliance report
     Red,
+    /// 🇺🇸 NIST IR 8259A IoT device baseline compliance report
+    Nist,
+    /// 🏭 IEC 62443-4-2 component requirements traceability report
+    Iec62443,
     /// 📄 Professional PDF report (for certification bodies)
     Pdf,
+    /// 📈 Prometheus textfile metrics (for node_exporter monitoring)
+    Prometheus,
+    /// 🛡️ SARIF 2.1.0 findings (for GitHub code scanning)
+    Sarif,
+    /// 📊 One CSV row per test (for spreadsheet review)
+    Csv,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetailLevel {
+    /// 🚫 Leave details out
+    Off,
+    /// ✂️ First few lines of each test's details
+    Summary,
+    /// 📜 Complete details
+    Full,
+}
+
+/// How much a failed or warning test matters, lowest first
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// ℹ️ Informational or defence in depth
+    Low,
+    /// ⚠️ Weakens the security posture
+    #[default]
+    Medium,
+    /// 🔶 Directly exploitable or a certification blocker
+    High,
+    /// 🛑 Compromises boot integrity, access control or credentials
+    Critical,
+}
+
+/// Lowest test status that makes a run exit non-zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailOnStatus {
+    /// Any warning, failure or error
+    Warning,
+    /// Any failure or error
+    Failed,
+    /// Only tests that errored out
+    Error,
+}
+
+/// Which run outcomes send a webhook notification
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyOn {
+    /// 📣 Every completed run
+    #[default]
+    Always,
+    /// ❌ Only runs that did not pass
+    Failure,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+pub enum ComplianceFramework {
+    /// 🇪🇺 EU Cyber Resilience Act
+    Cra,
+    /// 📡 Radio Equipment Directive
+    Red,
+    /// 🇺🇸 NIST IR 8259A / SP 800-213 IoT device baseline
+    Nist,
+    /// 🏭 IEC 62443-4-2 industrial component requirements
+    Iec62443,
+}
+
+/// Text format of the framework compliance reports (cra, red, nist, iec62443)
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComplianceFormat {
+    /// 📝 Markdown document
+    #[default]
+    Markdown,
+    /// 🌐 Self-contained HTML with expandable evidence
+    Html,
+    /// 📊 CSV of requirement results for tracking spreadsheets
+    Csv,
 }
 
 #[derive(Clone, Debug, ValueEnum)]
+pub enum RemapFormat {
+    /// 📝 Markdown report
+    Markdown,
+    /// 🌐 HTML report with expandable evidence
+    Html,
+    /// 🤖 ComplianceReport as JSON
+    Json,
+    /// 📄 PDF report
+    Pdf,
+    /// 📊 CSV of requirement results
+    Csv,
+}
+
+#[derive(Clone, Debug, Default, ValueEnum)]
 pub enum TestMode {
     /// 🔧 Development/testing mode (allows warnings, good for debugging)
+    #[default]
     PreProduction,
     /// 🏭 Final certification mode (strict compliance, no warnings allowed)
     Production,
 }
 
+#[derive(Clone, Debug, ValueEnum)]
+pub enum Environment {
+    /// 🧪 Lenient: only genuine failures fail the run
+    Dev,
+    /// 🚦 Failures and errors fail the run
+    Staging,
+    /// 🏭 Strict: warnings in security-critical categories also fail the run
+    Prod,
+}
+
 #[derive(Clone, Debug, ValueEnum)]
 pub enum TestSuite {
     /// 🎯 Complete security audit (all tests - recommended for certification)
@@ -411,14 +1008,80 @@ pub enum TestSuite {
     Certificate,
     /// 🏭 Production hardening (final deployment checks)
     Production,
-    /// ⚙️ Custom test suite (defined in configuration file)
+    /// ⚙️ Custom test suite (the custom_tests list in the configuration file)
     Custom,
 }
 
-#[derive(Clone, Debug, ValueEnum)]
+#[derive(Clone, Debug, PartialEq, Eq, ValueEnum)]
 pub enum MachineType {
     /// 📱 i.MX93 Jaguar E-Ink platform (e-paper display devices)
     Imx93JaguarEink,
     /// 🎮 i.MX8MM Jaguar Sentai platform (multimedia/gaming devices)
     Imx8mmJaguarSentai,
+    /// 🍓 Raspberry Pi Compute Module 4 (BCM2711)
+    RaspberryPiCm4,
+    /// 🍓 Raspberry Pi Compute Module 5 (BCM2712)
+    RaspberryPiCm5,
+}
+
+impl MachineType {
+    /// Name used for the machine in configuration files and reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Imx93JaguarEink => "imx93-jaguar-eink",
+            Self::Imx8mmJaguarSentai => "imx8mm-jaguar-sentai",
+            Self::RaspberryPiCm4 => "raspberry-pi-cm4",
+            Self::RaspberryPiCm5 => "raspberry-pi-cm5",
+        }
+    }
+
+    /// Machine for a configured or detected name; `auto` and `unknown` are none
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::value_variants()
+            .iter()
+            .find(|machine| machine.as_str() == name)
+            .cloned()
+    }
+}
+
+/// The i.MX platforms, whose boot chain includes OP-TEE and TF-A
+pub const IMX_MACHINES: &[MachineType] = &[
+    MachineType::Imx93JaguarEink,
+    MachineType::Imx8mmJaguarSentai,
+];
+
+/// Parse a duration such as `90`, `30s`, `2m` or `1h` into whole seconds
+pub fn parse_duration_secs(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((i, 's')) => (&value[..i], 1),
+        Some((i, 'm')) => (&value[..i], 60),
+        Some((i, 'h')) => (&value[..i], 3600),
+        _ => (value, 1),
+    };
+    let number = number
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| format!("invalid duration '{}' (expected e.g. 90, 30s, 2m)", value))?;
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("duration '{}' is too large", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_secs() {
+        assert_eq!(parse_duration_secs("90"), Ok(90));
+        assert_eq!(parse_duration_secs("30s"), Ok(30));
+        assert_eq!(parse_duration_secs("2m"), Ok(120));
+        assert_eq!(parse_duration_secs("1h"), Ok(3600));
+        assert!(parse_duration_secs("soon").is_err());
+        assert_eq!(
+            parse_duration_secs("99999999999999999h"),
+            Err("duration '99999999999999999h' is too large".to_string())
+        );
+    }
 }
@@ -55,6 +55,22 @@ pub trait CommunicationChannel: Send + Sync {
             "File download not supported by this communication channel".to_string(),
         ))
     }
+
+    /// Open another handle to the same target for concurrent work. Handles
+    /// must not block each other, so one may open its own connection (optional)
+    fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+        None
+    }
+
+    /// Whether `pooled_session` would return a handle, without opening one
+    fn supports_pooling(&self) -> bool {
+        false
+    }
+
+    /// Number of times a dropped connection has been re-established (optional)
+    fn reconnect_count(&self) -> u32 {
+        0
+    }
 }
 
 /// Abstract communication channel for executing commands on target systems (Windows version)
@@ -96,6 +112,22 @@ pub trait CommunicationChannel: Send {
             "File download not supported by this communication channel".to_string(),
         ))
     }
+
+    /// Open another handle to the same target for concurrent work. Handles
+    /// must not block each other, so one may open its own connection (optional)
+    fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+        None
+    }
+
+    /// Whether `pooled_session` would return a handle, without opening one
+    fn supports_pooling(&self) -> bool {
+        false
+    }
+
+    /// Number of times a dropped connection has been re-established (optional)
+    fn reconnect_count(&self) -> u32 {
+        0
+    }
 }
 
 /// Configuration for different communication channel types
@@ -108,7 +140,10 @@ pub enum ChannelConfig {
         password: String,
         ssh_key_path: Option<String>,
         timeout: u32,
-        ssh_multiplex: bool,
+        ssh_session_pool: bool,
+        bind_address: Option<String>,
+        keepalive_secs: u32,
+        reconnect_attempts: u32,
     },
     Serial {
         device: String,
@@ -120,6 +155,16 @@ pub enum ChannelConfig {
         username: Option<String>,
         password: Option<String>,
     },
+    Local {
+        timeout: u32,
+    },
+    Telnet {
+        host: String,
+        port: u16,
+        user: String,
+        password: String,
+        timeout: u32,
+    },
 }
 
 impl ChannelConfig {
@@ -127,6 +172,8 @@ impl ChannelConfig {
         match self {
             ChannelConfig::Ssh { .. } => "ssh",
             ChannelConfig::Serial { .. } => "serial",
+            ChannelConfig::Local { .. } => "local",
+            ChannelConfig::Telnet { .. } => "telnet",
         }
     }
 }
@@ -4,11 +4,14 @@
  * Licensed under GPLv3 - see LICENSE file for details
  */
 
+use crate::cli::{ComplianceFormat, ComplianceFramework};
+use crate::output::csv_row;
+use crate::target::SystemInfo;
 use crate::tests::{TestStatus, TestSuiteResults};
 use chrono::{DateTime, Utc};
 use printpdf::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::BufWriter;
 
@@ -17,6 +20,8 @@ pub struct ComplianceReport {
     pub report_type: String,
     pub generated_at: DateTime<Utc>,
     pub product_info: ProductInfo,
+    #[serde(default)]
+    pub system_under_test: SystemInfo,
     pub compliance_summary: ComplianceSummary,
     pub test_results: Vec<ComplianceTestResult>,
     pub recommendations: Vec<String>,
@@ -32,6 +37,55 @@ pub struct ProductInfo {
     pub description: String,
 }
 
+impl ProductInfo {
+    /// Identify the product by the `--product-*` values given for the run,
+    /// then by the build the results came from, falling back to the report's
+    /// generic name for anything neither provides
+    pub fn for_results(results: &TestSuiteResults, fallback_name: &str, description: &str) -> Self {
+        let known = |value: &str| !value.is_empty() && !value.eq_ignore_ascii_case("unknown");
+        let system_info = &results.system_info;
+        let product = &results.product;
+
+        let name = product.name.clone().unwrap_or_else(|| {
+            let os_name = system_info.os_name();
+            if known(&os_name) {
+                os_name
+            } else {
+                fallback_name.to_string()
+            }
+        });
+        let version = product.version.clone().unwrap_or_else(|| {
+            let mut version = if known(&system_info.os_version_id) {
+                system_info.os_version_id.clone()
+            } else {
+                "Unknown".to_string()
+            };
+            if known(&system_info.ostree_commit) {
+                version.push_str(&format!(" (OSTree {})", system_info.ostree_commit));
+            }
+            version
+        });
+        let model = product.model.clone().unwrap_or_else(|| {
+            if known(&system_info.machine_type) {
+                system_info.machine_type.clone()
+            } else {
+                "Security Compliance Device".to_string()
+            }
+        });
+
+        Self {
+            name,
+            version,
+            manufacturer: product
+                .manufacturer
+                .clone()
+                .unwrap_or_else(|| "Dynamic Devices Ltd".to_string()),
+            model,
+            description: description.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplianceSummary {
     pub total_requirements: usize,
@@ -40,6 +94,19 @@ pub struct ComplianceSummary {
     pub warning_requirements: usize,
     pub compliance_percentage: f64,
     pub overall_status: String,
+    /// Compliance broken down by the category of the originating tests
+    #[serde(default)]
+    pub category_scores: Vec<CategoryScore>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryScore {
+    pub category: String,
+    pub total_requirements: usize,
+    pub passed_requirements: usize,
+    pub failed_requirements: usize,
+    pub warning_requirements: usize,
+    pub compliance_percentage: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +115,9 @@ pub struct ComplianceTestResult {
     pub requirement_title: String,
     pub requirement_description: String,
     pub test_id: String,
+    /// Category of the test the requirement was mapped from
+    #[serde(default)]
+    pub category: String,
     pub status: String,
     pub evidence: String,
     pub remediation: Option<String>,
@@ -97,6 +167,7 @@ impl CraComplianceReporter {
                     requirement_title: cra_req.title.clone(),
                     requirement_description: cra_req.description.clone(),
                     test_id: result.test_id.clone(),
+                    category: result.category.clone(),
                     status: status.to_string(),
                     evidence: result.details.clone().unwrap_or_default(),
                     remediation: cra_req.remediation.clone(),
@@ -123,13 +194,12 @@ impl CraComplianceReporter {
         ComplianceReport {
             report_type: "EU_CYBER_RESILIENCE_ACT".to_string(),
             generated_at: Utc::now(),
-            product_info: ProductInfo {
-                name: "Embedded Linux Device".to_string(),
-                version: "1.0.0".to_string(),
-                manufacturer: "Dynamic Devices Ltd".to_string(),
-                model: "Security Compliance Device".to_string(),
-                description: "Embedded Linux device with security compliance testing".to_string(),
-            },
+            product_info: ProductInfo::for_results(
+                results,
+                "Embedded Linux Device",
+                "Embedded Linux device with security compliance testing",
+            ),
+            system_under_test: results.system_info.clone(),
             compliance_summary: ComplianceSummary {
                 total_requirements: total,
                 passed_requirements: passed,
@@ -137,6 +207,7 @@ impl CraComplianceReporter {
                 warning_requirements: warnings,
                 compliance_percentage,
                 overall_status: overall_status.to_string(),
+                category_scores: category_scores(&compliance_results),
             },
             test_results: compliance_results,
             recommendations: Self::generate_recommendations(failed, warnings),
@@ -314,6 +385,7 @@ impl RedComplianceReporter {
                     requirement_title: red_req.title.clone(),
                     requirement_description: red_req.description.clone(),
                     test_id: result.test_id.clone(),
+                    category: result.category.clone(),
                     status: status.to_string(),
                     evidence: result.details.clone().unwrap_or_default(),
                     remediation: red_req.remediation.clone(),
@@ -340,13 +412,12 @@ impl RedComplianceReporter {
         ComplianceReport {
             report_type: "UK_CE_RED_DIRECTIVE".to_string(),
             generated_at: Utc::now(),
-            product_info: ProductInfo {
-                name: "Radio Equipment Device".to_string(),
-                version: "1.0.0".to_string(),
-                manufacturer: "Dynamic Devices Ltd".to_string(),
-                model: "Radio Compliance Device".to_string(),
-                description: "Radio equipment with RED compliance features".to_string(),
-            },
+            product_info: ProductInfo::for_results(
+                results,
+                "Radio Equipment Device",
+                "Radio equipment with RED compliance features",
+            ),
+            system_under_test: results.system_info.clone(),
             compliance_summary: ComplianceSummary {
                 total_requirements: total,
                 passed_requirements: passed,
@@ -354,6 +425,7 @@ impl RedComplianceReporter {
                 warning_requirements: warnings,
                 compliance_percentage,
                 overall_status: overall_status.to_string(),
+                category_scores: category_scores(&compliance_results),
             },
             test_results: compliance_results,
             recommendations: Self::generate_recommendations(failed, warnings),
@@ -480,153 +552,1322 @@ impl RedComplianceReporter {
     }
 }
 
-#[derive(Debug, Clone)]
-struct CraRequirement {
-    requirement_id: String,
-    title: String,
-    description: String,
-    remediation: Option<String>,
-    risk_level: String,
-}
-
-#[derive(Debug, Clone)]
-struct RedRequirement {
-    requirement_id: String,
-    title: String,
-    description: String,
-    remediation: Option<String>,
-    risk_level: String,
-}
-
-pub fn format_compliance_report_as_markdown(report: &ComplianceReport) -> String {
-    let mut output = String::new();
-
-    output.push_str(&format!("# {} Compliance Report\n\n", report.report_type));
-    output.push_str(&format!(
-        "**Generated:** {}\n\n",
-        report.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
-    ));
+pub struct NistComplianceReporter;
 
-    // Product Information
-    output.push_str("## Product Information\n\n");
-    output.push_str(&format!("- **Name:** {}\n", report.product_info.name));
-    output.push_str(&format!("- **Version:** {}\n", report.product_info.version));
-    output.push_str(&format!(
-        "- **Manufacturer:** {}\n",
-        report.product_info.manufacturer
-    ));
-    output.push_str(&format!("- **Model:** {}\n", report.product_info.model));
-    output.push_str(&format!(
-        "- **Description:** {}\n\n",
-        report.product_info.description
-    ));
+impl NistComplianceReporter {
+    pub fn generate_report(results: &TestSuiteResults) -> ComplianceReport {
+        let nist_mapping = Self::get_nist_test_mapping();
+        let mut compliance_results = Vec::new();
+        let mut passed = 0;
+        let mut failed = 0;
+        let mut warnings = 0;
 
-    // Compliance Summary
-    output.push_str("## Compliance Summary\n\n");
-    output.push_str(&format!(
-        "- **Overall Status:** {}\n",
-        report.compliance_summary.overall_status
-    ));
-    output.push_str(&format!(
-        "- **Compliance Percentage:** {:.1}%\n",
-        report.compliance_summary.compliance_percentage
-    ));
-    output.push_str(&format!(
-        "- **Total Requirements:** {}\n",
-        report.compliance_summary.total_requirements
-    ));
-    output.push_str(&format!(
-        "- **Passed:** {}\n",
-        report.compliance_summary.passed_requirements
-    ));
-    output.push_str(&format!(
-        "- **Failed:** {}\n",
-        report.compliance_summary.failed_requirements
-    ));
-    output.push_str(&format!(
-        "- **Warnings:** {}\n\n",
-        report.compliance_summary.warning_requirements
-    ));
+        // Map test results to NIST IoT device cybersecurity capabilities
+        for result in &results.results {
+            if let Some(nist_req) = nist_mapping.get(&result.test_id) {
+                let status = match result.status {
+                    TestStatus::Passed => {
+                        passed += 1;
+                        "COMPLIANT"
+                    }
+                    TestStatus::Failed => {
+                        failed += 1;
+                        "NON_COMPLIANT"
+                    }
+                    TestStatus::Warning => {
+                        warnings += 1;
+                        "PARTIAL_COMPLIANCE"
+                    }
+                    TestStatus::Skipped => "NOT_TESTED",
+                    TestStatus::Error => "ERROR",
+                };
 
-    // Test Results
-    output.push_str("## Detailed Test Results\n\n");
-    output.push_str("| Requirement ID | Title | Status | Risk Level |\n");
-    output.push_str("|---|---|---|---|\n");
+                compliance_results.push(ComplianceTestResult {
+                    requirement_id: nist_req.requirement_id.clone(),
+                    requirement_title: nist_req.title.clone(),
+                    requirement_description: nist_req.description.clone(),
+                    test_id: result.test_id.clone(),
+                    category: result.category.clone(),
+                    status: status.to_string(),
+                    evidence: result.details.clone().unwrap_or_default(),
+                    remediation: nist_req.remediation.clone(),
+                    risk_level: nist_req.risk_level.clone(),
+                });
+            }
+        }
 
-    for result in &report.test_results {
-        let status_emoji = match result.status.as_str() {
-            "COMPLIANT" => "✅",
-            "NON_COMPLIANT" => "❌",
-            "PARTIAL_COMPLIANCE" => "⚠️",
-            _ => "❓",
+        let total = compliance_results.len();
+        let compliance_percentage = if total > 0 {
+            (passed as f64 / total as f64) * 100.0
+        } else {
+            0.0
         };
-        output.push_str(&format!(
-            "| {} | {} | {} {} | {} |\n",
-            result.requirement_id,
-            result.requirement_title,
-            status_emoji,
-            result.status,
-            result.risk_level
-        ));
-    }
-
-    // Certification Status
-    output.push_str("\n## Certification Readiness\n\n");
-    let ready_emoji = if report.certification_status.ready_for_certification {
-        "✅"
-    } else {
-        "❌"
-    };
-    output.push_str(&format!(
-        "**Ready for Certification:** {} {}\n\n",
-        ready_emoji, report.certification_status.ready_for_certification
-    ));
 
-    if !report.certification_status.blocking_issues.is_empty() {
-        output.push_str("### Blocking Issues\n\n");
-        for issue in &report.certification_status.blocking_issues {
-            output.push_str(&format!("- ❌ {}\n", issue));
-        }
-        output.push('\n');
-    }
+        let overall_status = if failed == 0 && warnings == 0 {
+            "FULLY_COMPLIANT"
+        } else if failed == 0 {
+            "COMPLIANT_WITH_WARNINGS"
+        } else {
+            "NON_COMPLIANT"
+        };
 
-    if !report.certification_status.warnings.is_empty() {
-        output.push_str("### Warnings\n\n");
-        for warning in &report.certification_status.warnings {
-            output.push_str(&format!("- ⚠️ {}\n", warning));
+        ComplianceReport {
+            report_type: "NIST_IR_8259_IOT_BASELINE".to_string(),
+            generated_at: Utc::now(),
+            product_info: ProductInfo::for_results(
+                results,
+                "IoT Device",
+                "IoT device assessed against the NIST IR 8259A core baseline",
+            ),
+            system_under_test: results.system_info.clone(),
+            compliance_summary: ComplianceSummary {
+                total_requirements: total,
+                passed_requirements: passed,
+                failed_requirements: failed,
+                warning_requirements: warnings,
+                compliance_percentage,
+                overall_status: overall_status.to_string(),
+                category_scores: category_scores(&compliance_results),
+            },
+            test_results: compliance_results,
+            recommendations: Self::generate_recommendations(failed, warnings),
+            certification_status: Self::assess_certification_readiness(failed, warnings),
         }
-        output.push('\n');
     }
 
-    // Next Steps
-    output.push_str("### Next Steps\n\n");
-    for step in &report.certification_status.next_steps {
-        output.push_str(&format!("1. {}\n", step));
-    }
+    fn get_nist_test_mapping() -> HashMap<String, NistRequirement> {
+        let mut mapping = HashMap::new();
 
-    // Recommendations
-    output.push_str("\n## Recommendations\n\n");
-    for recommendation in &report.recommendations {
-        output.push_str(&format!("- {}\n", recommendation));
-    }
+        // Device Identification
+        mapping.insert(
+            "hardware_003".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-DI-001".to_string(),
+                title: "Unique Device Identity".to_string(),
+                description: "The device must be uniquely identifiable logically and physically, anchored in hardware".to_string(),
+                remediation: Some("Provision a hardware-backed device identity from the root of trust".to_string()),
+                risk_level: "MEDIUM".to_string(),
+            },
+        );
+        mapping.insert(
+            "certificate_006".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-DI-002".to_string(),
+                title: "Protected Identity Credentials".to_string(),
+                description: "Credentials identifying the device must be stored so they cannot be read or cloned".to_string(),
+                remediation: Some("Keep device keys in a secure element or TPM-backed keystore".to_string()),
+                risk_level: "HIGH".to_string(),
+            },
+        );
 
-    output
-}
+        // Device Configuration
+        mapping.insert(
+            "production_004".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-DC-001".to_string(),
+                title: "Minimal Service Configuration".to_string(),
+                description: "Only services needed for the device's function may be enabled"
+                    .to_string(),
+                remediation: Some(
+                    "Disable or remove services that are not required in the field".to_string(),
+                ),
+                risk_level: "MEDIUM".to_string(),
+            },
+        );
+        mapping.insert(
+            "runtime_007".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-DC-002".to_string(),
+                title: "Secure Kernel Configuration".to_string(),
+                description:
+                    "The device configuration must enable the platform's exploit mitigations"
+                        .to_string(),
+                remediation: Some(
+                    "Enable ASLR, kptr_restrict, dmesg_restrict and related hardening sysctls"
+                        .to_string(),
+                ),
+                risk_level: "MEDIUM".to_string(),
+            },
+        );
 
-pub fn generate_pdf_report(
-    report: &ComplianceReport,
-    output_path: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Create a new PDF document
-    let (doc, page1, layer1) =
-        PdfDocument::new("Compliance Report", Mm(210.0), Mm(297.0), "Layer 1");
-    let current_layer = doc.get_page(page1).get_layer(layer1);
+        // Data Protection
+        mapping.insert(
+            "runtime_001".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-DP-001".to_string(),
+                title: "Data at Rest Protection".to_string(),
+                description: "Data stored on the device must be protected from unauthorized access and modification".to_string(),
+                remediation: Some("Encrypt data partitions with keys bound to the hardware".to_string()),
+                risk_level: "HIGH".to_string(),
+            },
+        );
+        mapping.insert(
+            "network_005".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-DP-002".to_string(),
+                title: "Data in Transit Protection".to_string(),
+                description: "Data sent by the device must be protected with cryptography"
+                    .to_string(),
+                remediation: Some(
+                    "Use TLS or SSH for all remote communication and disable plaintext protocols"
+                        .to_string(),
+                ),
+                risk_level: "HIGH".to_string(),
+            },
+        );
 
-    // Load fonts
-    let font_bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
-    let font_regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        // Logical Access to Interfaces
+        mapping.insert(
+            "production_003".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-LA-001".to_string(),
+                title: "Authenticated Interface Access".to_string(),
+                description: "Local and network interfaces must require authentication and not use default credentials".to_string(),
+                remediation: Some("Remove default passwords and require per-device credentials".to_string()),
+                risk_level: "HIGH".to_string(),
+            },
+        );
+        mapping.insert(
+            "production_001".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-LA-002".to_string(),
+                title: "Debug Interface Restriction".to_string(),
+                description:
+                    "Debug and diagnostic interfaces must be disabled or access controlled"
+                        .to_string(),
+                remediation: Some(
+                    "Disable JTAG, debug consoles and debugfs in production images".to_string(),
+                ),
+                risk_level: "HIGH".to_string(),
+            },
+        );
+        mapping.insert(
+            "network_001".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-LA-003".to_string(),
+                title: "Minimal Network Exposure".to_string(),
+                description:
+                    "The device must only expose network interfaces needed for its function"
+                        .to_string(),
+                remediation: Some(
+                    "Close unused ports and restrict the rest with the firewall".to_string(),
+                ),
+                risk_level: "MEDIUM".to_string(),
+            },
+        );
 
-    let mut y_position = Mm(270.0); // Start near top of page
+        // Software Update
+        mapping.insert(
+            "production_008".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-SU-001".to_string(),
+                title: "Secure Software Update".to_string(),
+                description: "The device's software must be updatable through a secure, configurable mechanism".to_string(),
+                remediation: Some("Enable an authenticated over-the-air update service".to_string()),
+                risk_level: "HIGH".to_string(),
+            },
+        );
+        mapping.insert(
+            "boot_001".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-SU-002".to_string(),
+                title: "Verified Software Integrity".to_string(),
+                description: "The device must verify the integrity and authenticity of software before running it".to_string(),
+                remediation: Some("Enable secure boot so that only signed images are executed".to_string()),
+                risk_level: "HIGH".to_string(),
+            },
+        );
+
+        // Cybersecurity State Awareness
+        mapping.insert(
+            "compliance_005".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-CSA-001".to_string(),
+                title: "Security Event Logging".to_string(),
+                description: "The device must record cybersecurity events for later review"
+                    .to_string(),
+                remediation: Some(
+                    "Enable audit logging and protect the logs from tampering".to_string(),
+                ),
+                risk_level: "MEDIUM".to_string(),
+            },
+        );
+        mapping.insert(
+            "production_006".to_string(),
+            NistRequirement {
+                requirement_id: "NIST-CSA-002".to_string(),
+                title: "Device State Monitoring".to_string(),
+                description:
+                    "The device's cybersecurity state must be observable by authorized entities"
+                        .to_string(),
+                remediation: Some(
+                    "Report device health and security state to a monitoring service".to_string(),
+                ),
+                risk_level: "MEDIUM".to_string(),
+            },
+        );
+
+        mapping
+    }
+
+    fn generate_recommendations(failed: usize, warnings: usize) -> Vec<String> {
+        let mut recommendations = Vec::new();
+
+        if failed > 0 {
+            recommendations.push(
+                "Address all failing NIST IR 8259A capabilities before deployment".to_string(),
+            );
+        }
+
+        if warnings > 0 {
+            recommendations
+                .push("Review warning conditions to fully meet the IoT core baseline".to_string());
+        }
+
+        recommendations.push(
+            "Document the non-technical supporting capabilities from NIST IR 8259B".to_string(),
+        );
+        recommendations
+            .push("Map device capabilities to the customer's SP 800-213 requirements".to_string());
+
+        recommendations
+    }
+
+    fn assess_certification_readiness(failed: usize, warnings: usize) -> CertificationStatus {
+        let ready = failed == 0;
+        let mut blocking_issues = Vec::new();
+        let mut warnings_list = Vec::new();
+        let mut next_steps = Vec::new();
+
+        if failed > 0 {
+            blocking_issues.push(format!("{} NIST device capabilities failing", failed));
+            next_steps.push("Resolve all failing tests before federal deployment".to_string());
+        }
+
+        if warnings > 0 {
+            warnings_list.push(format!("{} capabilities have warnings", warnings));
+            next_steps.push("Address warning conditions for full baseline coverage".to_string());
+        }
+
+        if ready {
+            next_steps.push("Device appears to meet the NIST IR 8259A core baseline".to_string());
+            next_steps
+                .push("Prepare SP 800-213 documentation for the acquiring agency".to_string());
+        }
+
+        CertificationStatus {
+            ready_for_certification: ready,
+            blocking_issues,
+            warnings: warnings_list,
+            next_steps,
+        }
+    }
+}
+
+pub struct Iec62443Reporter;
+
+impl Iec62443Reporter {
+    pub fn generate_report(results: &TestSuiteResults) -> ComplianceReport {
+        let iec_mapping = Self::get_iec62443_test_mapping();
+        let mut compliance_results = Vec::new();
+        let mut passed = 0;
+        let mut failed = 0;
+        let mut warnings = 0;
+
+        // Map test results to IEC 62443-4-2 component requirements
+        for result in &results.results {
+            if let Some(iec_req) = iec_mapping.get(&result.test_id) {
+                let status = match result.status {
+                    TestStatus::Passed => {
+                        passed += 1;
+                        "COMPLIANT"
+                    }
+                    TestStatus::Failed => {
+                        failed += 1;
+                        "NON_COMPLIANT"
+                    }
+                    TestStatus::Warning => {
+                        warnings += 1;
+                        "PARTIAL_COMPLIANCE"
+                    }
+                    TestStatus::Skipped => "NOT_TESTED",
+                    TestStatus::Error => "ERROR",
+                };
+
+                compliance_results.push(ComplianceTestResult {
+                    requirement_id: iec_req.requirement_id.clone(),
+                    requirement_title: iec_req.title.clone(),
+                    requirement_description: iec_req.description.clone(),
+                    test_id: result.test_id.clone(),
+                    category: result.category.clone(),
+                    status: status.to_string(),
+                    evidence: result.details.clone().unwrap_or_default(),
+                    remediation: iec_req.remediation.clone(),
+                    risk_level: iec_req.target_security_level(),
+                });
+            }
+        }
+
+        let total = compliance_results.len();
+        let compliance_percentage = if total > 0 {
+            (passed as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let overall_status = if failed == 0 && warnings == 0 {
+            "FULLY_COMPLIANT"
+        } else if failed == 0 {
+            "COMPLIANT_WITH_WARNINGS"
+        } else {
+            "NON_COMPLIANT"
+        };
+
+        ComplianceReport {
+            report_type: "IEC_62443_4_2".to_string(),
+            generated_at: Utc::now(),
+            product_info: ProductInfo::for_results(
+                results,
+                "Industrial Automation Component",
+                "Embedded device assessed against IEC 62443-4-2 component requirements",
+            ),
+            system_under_test: results.system_info.clone(),
+            compliance_summary: ComplianceSummary {
+                total_requirements: total,
+                passed_requirements: passed,
+                failed_requirements: failed,
+                warning_requirements: warnings,
+                compliance_percentage,
+                overall_status: overall_status.to_string(),
+                category_scores: category_scores(&compliance_results),
+            },
+            test_results: compliance_results,
+            recommendations: Self::generate_recommendations(failed, warnings),
+            certification_status: Self::assess_certification_readiness(failed, warnings),
+        }
+    }
+
+    fn get_iec62443_test_mapping() -> HashMap<String, Iec62443Requirement> {
+        let mut mapping = HashMap::new();
+
+        // Identification and authentication (CR 1.x)
+        mapping.insert(
+            "runtime_004".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR1.1".to_string(),
+                title: "Human User Identification and Authentication".to_string(),
+                description: "Interactive access to the component must identify and authenticate every human user".to_string(),
+                remediation: Some("Require key-based, per-user SSH authentication and disable root password login".to_string()),
+                security_level: 2,
+            },
+        );
+        mapping.insert(
+            "production_003".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR1.5".to_string(),
+                title: "Authenticator Management".to_string(),
+                description: "Default authenticators must be changed and authenticators protected from disclosure".to_string(),
+                remediation: Some("Replace factory default passwords with unique per-device credentials".to_string()),
+                security_level: 1,
+            },
+        );
+        mapping.insert(
+            "certificate_001".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR1.8".to_string(),
+                title: "Public Key Infrastructure Certificates".to_string(),
+                description: "Certificates used for authentication must be validated and managed through a PKI".to_string(),
+                remediation: Some("Issue device certificates from a managed CA and validate them on use".to_string()),
+                security_level: 2,
+            },
+        );
+        mapping.insert(
+            "hardware_003".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR1.9".to_string(),
+                title: "Strength of Public Key-Based Authentication".to_string(),
+                description:
+                    "Private keys must be protected by hardware so they cannot be extracted"
+                        .to_string(),
+                remediation: Some("Store private keys in the secure element or TPM".to_string()),
+                security_level: 3,
+            },
+        );
+
+        // System integrity (CR 3.x)
+        mapping.insert(
+            "runtime_008".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR3.4".to_string(),
+                title: "Software and Information Integrity".to_string(),
+                description:
+                    "The component must detect unauthorized changes to software and configuration"
+                        .to_string(),
+                remediation: Some(
+                    "Mount system partitions read-only and verify them at runtime".to_string(),
+                ),
+                security_level: 1,
+            },
+        );
+        mapping.insert(
+            "boot_001".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR3.14".to_string(),
+                title: "Integrity of the Boot Process".to_string(),
+                description: "The component must verify the integrity of firmware and software before use at boot".to_string(),
+                remediation: Some("Enable secure boot for every stage of the boot chain".to_string()),
+                security_level: 1,
+            },
+        );
+        mapping.insert(
+            "boot_007".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR3.14-RE1".to_string(),
+                title: "Authenticity of the Boot Process".to_string(),
+                description: "The component must verify the authenticity of the boot chain using a hardware root of trust".to_string(),
+                remediation: Some("Sign every boot stage and fuse the verification keys".to_string()),
+                security_level: 2,
+            },
+        );
+
+        // Data confidentiality (CR 4.x)
+        mapping.insert(
+            "runtime_001".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR4.1".to_string(),
+                title: "Information Confidentiality".to_string(),
+                description:
+                    "Information at rest must be protected where confidentiality is required"
+                        .to_string(),
+                remediation: Some("Encrypt data partitions with hardware-bound keys".to_string()),
+                security_level: 1,
+            },
+        );
+        mapping.insert(
+            "network_005".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR4.3".to_string(),
+                title: "Use of Cryptography".to_string(),
+                description: "Cryptography must use recognised algorithms and key sizes"
+                    .to_string(),
+                remediation: Some(
+                    "Use TLS 1.2+ and strong SSH ciphers for all remote communication".to_string(),
+                ),
+                security_level: 1,
+            },
+        );
+
+        // Resource availability (CR 7.x)
+        mapping.insert(
+            "runtime_002".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR7.1".to_string(),
+                title: "Denial of Service Protection".to_string(),
+                description:
+                    "The component must keep essential functions running under network flooding"
+                        .to_string(),
+                remediation: Some(
+                    "Enable the firewall with rate limiting on exposed services".to_string(),
+                ),
+                security_level: 1,
+            },
+        );
+        mapping.insert(
+            "production_007".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR7.3".to_string(),
+                title: "Control System Backup".to_string(),
+                description: "User and system level information must be backed up without affecting operation".to_string(),
+                remediation: Some("Schedule backups of configuration and data".to_string()),
+                security_level: 1,
+            },
+        );
+        mapping.insert(
+            "production_004".to_string(),
+            Iec62443Requirement {
+                requirement_id: "IEC62443-CR7.7".to_string(),
+                title: "Least Functionality".to_string(),
+                description: "The component must restrict itself to the functions, ports and services it needs".to_string(),
+                remediation: Some("Disable services that are not required for the component's function".to_string()),
+                security_level: 1,
+            },
+        );
+
+        mapping
+    }
+
+    fn generate_recommendations(failed: usize, warnings: usize) -> Vec<String> {
+        let mut recommendations = Vec::new();
+
+        if failed > 0 {
+            recommendations.push(
+                "Address all failing component requirements before IEC 62443 assessment"
+                    .to_string(),
+            );
+        }
+
+        if warnings > 0 {
+            recommendations
+                .push("Review warning conditions against the targeted security level".to_string());
+        }
+
+        recommendations.push(
+            "Confirm the target security level (SL-C) agreed with the asset owner".to_string(),
+        );
+        recommendations.push(
+            "Provide IEC 62443-4-1 secure development lifecycle evidence alongside this report"
+                .to_string(),
+        );
+
+        recommendations
+    }
+
+    fn assess_certification_readiness(failed: usize, warnings: usize) -> CertificationStatus {
+        let ready = failed == 0;
+        let mut blocking_issues = Vec::new();
+        let mut warnings_list = Vec::new();
+        let mut next_steps = Vec::new();
+
+        if failed > 0 {
+            blocking_issues.push(format!("{} component requirements failing", failed));
+            next_steps.push("Resolve all failing tests before component assessment".to_string());
+        }
+
+        if warnings > 0 {
+            warnings_list.push(format!("{} requirements have warnings", warnings));
+            next_steps.push("Address warning conditions for the targeted SL".to_string());
+        }
+
+        if ready {
+            next_steps.push("Component appears ready for IEC 62443-4-2 assessment".to_string());
+            next_steps.push("Hand the traceability matrix to the certification body".to_string());
+        }
+
+        CertificationStatus {
+            ready_for_certification: ready,
+            blocking_issues,
+            warnings: warnings_list,
+            next_steps,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CraRequirement {
+    requirement_id: String,
+    title: String,
+    description: String,
+    remediation: Option<String>,
+    risk_level: String,
+}
+
+#[derive(Debug, Clone)]
+struct RedRequirement {
+    requirement_id: String,
+    title: String,
+    description: String,
+    remediation: Option<String>,
+    risk_level: String,
+}
+
+#[derive(Debug, Clone)]
+struct NistRequirement {
+    requirement_id: String,
+    title: String,
+    description: String,
+    remediation: Option<String>,
+    risk_level: String,
+}
+
+#[derive(Debug, Clone)]
+struct Iec62443Requirement {
+    requirement_id: String,
+    title: String,
+    description: String,
+    remediation: Option<String>,
+    /// Lowest IEC 62443 security level (1-4) that demands this requirement
+    security_level: u8,
+}
+
+impl Iec62443Requirement {
+    /// The targeted security level, reported where other frameworks give a risk level
+    fn target_security_level(&self) -> String {
+        format!("SL{}", self.security_level)
+    }
+}
+
+/// Map a result set onto a framework's requirements with the current mappings
+pub fn generate_compliance_report(
+    framework: &ComplianceFramework,
+    results: &TestSuiteResults,
+) -> ComplianceReport {
+    match framework {
+        ComplianceFramework::Cra => CraComplianceReporter::generate_report(results),
+        ComplianceFramework::Red => RedComplianceReporter::generate_report(results),
+        ComplianceFramework::Nist => NistComplianceReporter::generate_report(results),
+        ComplianceFramework::Iec62443 => Iec62443Reporter::generate_report(results),
+    }
+}
+
+/// A framework requirement and the tests whose results feed it
+#[derive(Debug, Clone, Serialize)]
+pub struct RequirementMapping {
+    pub requirement_id: String,
+    pub title: String,
+    pub description: String,
+    pub risk_level: String,
+    pub remediation: Option<String>,
+    pub test_ids: Vec<String>,
+}
+
+/// The full test-ID to requirement mapping of a framework, one entry per
+/// requirement and ordered by requirement ID
+pub fn framework_mapping(framework: &ComplianceFramework) -> Vec<RequirementMapping> {
+    let entries: Vec<(String, RequirementMapping)> = match framework {
+        ComplianceFramework::Cra => CraComplianceReporter::get_cra_test_mapping()
+            .into_iter()
+            .map(|(test_id, req)| {
+                (
+                    test_id,
+                    RequirementMapping {
+                        requirement_id: req.requirement_id,
+                        title: req.title,
+                        description: req.description,
+                        risk_level: req.risk_level,
+                        remediation: req.remediation,
+                        test_ids: Vec::new(),
+                    },
+                )
+            })
+            .collect(),
+        ComplianceFramework::Red => RedComplianceReporter::get_red_test_mapping()
+            .into_iter()
+            .map(|(test_id, req)| {
+                (
+                    test_id,
+                    RequirementMapping {
+                        requirement_id: req.requirement_id,
+                        title: req.title,
+                        description: req.description,
+                        risk_level: req.risk_level,
+                        remediation: req.remediation,
+                        test_ids: Vec::new(),
+                    },
+                )
+            })
+            .collect(),
+        ComplianceFramework::Nist => NistComplianceReporter::get_nist_test_mapping()
+            .into_iter()
+            .map(|(test_id, req)| {
+                (
+                    test_id,
+                    RequirementMapping {
+                        requirement_id: req.requirement_id,
+                        title: req.title,
+                        description: req.description,
+                        risk_level: req.risk_level,
+                        remediation: req.remediation,
+                        test_ids: Vec::new(),
+                    },
+                )
+            })
+            .collect(),
+        ComplianceFramework::Iec62443 => Iec62443Reporter::get_iec62443_test_mapping()
+            .into_iter()
+            .map(|(test_id, req)| {
+                (
+                    test_id,
+                    RequirementMapping {
+                        risk_level: req.target_security_level(),
+                        requirement_id: req.requirement_id,
+                        title: req.title,
+                        description: req.description,
+                        remediation: req.remediation,
+                        test_ids: Vec::new(),
+                    },
+                )
+            })
+            .collect(),
+    };
+
+    let mut requirements: BTreeMap<String, RequirementMapping> = BTreeMap::new();
+    for (test_id, mapping) in entries {
+        requirements
+            .entry(mapping.requirement_id.clone())
+            .or_insert(mapping)
+            .test_ids
+            .push(test_id);
+    }
+    requirements
+        .into_values()
+        .map(|mut mapping| {
+            mapping.test_ids.sort();
+            mapping
+        })
+        .collect()
+}
+
+/// Every requirement of each framework as `(requirement ID, title)`, whether or
+/// not a test maps to it, so the mapping explanation can name the gaps
+const CRA_REQUIREMENTS: &[(&str, &str)] = &[
+    ("CRA-ART11-001", "Data Protection by Design and Default"),
+    ("CRA-ART11-002", "Vulnerability Management Process"),
+    ("CRA-ART11-003", "Security Audit Logging"),
+    ("CRA-ART11-004", "Secure Boot Implementation"),
+    ("CRA-ART11-005", "Hardware Root of Trust"),
+    ("CRA-ART11-006", "Software Bill of Materials"),
+    (
+        "CRA-ART11-007",
+        "Coordinated Vulnerability Disclosure Policy",
+    ),
+    ("CRA-ART11-008", "Security Updates for the Support Period"),
+];
+
+const RED_REQUIREMENTS: &[(&str, &str)] = &[
+    ("RED-ER3.3-001", "Cybersecurity Features"),
+    ("RED-ER3.3-002", "Network Security Controls"),
+    ("RED-ER3.3-003", "Wireless Communication Security"),
+    ("RED-ER3.3-004", "Default Credentials Management"),
+    ("RED-ER3.3-005", "Protection of Personal Data and Privacy"),
+    ("RED-ER3.3-006", "Protection from Fraud"),
+];
+
+const NIST_REQUIREMENTS: &[(&str, &str)] = &[
+    ("NIST-CSA-001", "Security Event Logging"),
+    ("NIST-CSA-002", "Device State Monitoring"),
+    ("NIST-DC-001", "Minimal Service Configuration"),
+    ("NIST-DC-002", "Secure Kernel Configuration"),
+    ("NIST-DC-003", "Restore to Secure Default Configuration"),
+    ("NIST-DI-001", "Unique Device Identity"),
+    ("NIST-DI-002", "Protected Identity Credentials"),
+    ("NIST-DP-001", "Data at Rest Protection"),
+    ("NIST-DP-002", "Data in Transit Protection"),
+    ("NIST-DP-003", "Secure Data Deletion"),
+    ("NIST-LA-001", "Authenticated Interface Access"),
+    ("NIST-LA-002", "Debug Interface Restriction"),
+    ("NIST-LA-003", "Minimal Network Exposure"),
+    ("NIST-SU-001", "Secure Software Update"),
+    ("NIST-SU-002", "Verified Software Integrity"),
+];
+
+const IEC62443_REQUIREMENTS: &[(&str, &str)] = &[
+    (
+        "IEC62443-CR1.1",
+        "Human User Identification and Authentication",
+    ),
+    ("IEC62443-CR1.5", "Authenticator Management"),
+    ("IEC62443-CR1.8", "Public Key Infrastructure Certificates"),
+    (
+        "IEC62443-CR1.9",
+        "Strength of Public Key-Based Authentication",
+    ),
+    ("IEC62443-CR2.1", "Authorization Enforcement"),
+    ("IEC62443-CR2.8", "Auditable Events"),
+    ("IEC62443-CR3.1", "Communication Integrity"),
+    ("IEC62443-CR3.4", "Software and Information Integrity"),
+    ("IEC62443-CR3.14", "Integrity of the Boot Process"),
+    ("IEC62443-CR3.14-RE1", "Authenticity of the Boot Process"),
+    ("IEC62443-CR4.1", "Information Confidentiality"),
+    ("IEC62443-CR4.3", "Use of Cryptography"),
+    ("IEC62443-CR5.1", "Network Segmentation"),
+    ("IEC62443-CR6.1", "Audit Log Accessibility"),
+    ("IEC62443-CR7.1", "Denial of Service Protection"),
+    ("IEC62443-CR7.3", "Control System Backup"),
+    ("IEC62443-CR7.7", "Least Functionality"),
+];
+
+/// The requirement catalogue of a framework as `(requirement ID, title)` pairs
+pub fn requirement_catalogue(
+    framework: &ComplianceFramework,
+) -> &'static [(&'static str, &'static str)] {
+    match framework {
+        ComplianceFramework::Cra => CRA_REQUIREMENTS,
+        ComplianceFramework::Red => RED_REQUIREMENTS,
+        ComplianceFramework::Nist => NIST_REQUIREMENTS,
+        ComplianceFramework::Iec62443 => IEC62443_REQUIREMENTS,
+    }
+}
+
+/// Explain how a framework's verdict is derived: every requirement with the tests
+/// that feed it, plus the coverage gaps against the tests that actually exist
+pub fn format_mapping_explanation(
+    framework: &ComplianceFramework,
+    registered_tests: &[&str],
+) -> String {
+    let mapping = framework_mapping(framework);
+    let is_registered = |test_id: &str| registered_tests.contains(&test_id);
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "# {} Compliance Mapping\n\n{} requirements mapped from {} tests\n\n",
+        format!("{:?}", framework).to_uppercase(),
+        mapping.len(),
+        mapping.iter().map(|req| req.test_ids.len()).sum::<usize>()
+    ));
+
+    for req in &mapping {
+        output.push_str(&format!(
+            "## {} - {} [{}]\n\n{}\n\n",
+            req.requirement_id, req.title, req.risk_level, req.description
+        ));
+        if let Some(remediation) = &req.remediation {
+            output.push_str(&format!("- **Remediation:** {}\n", remediation));
+        }
+        output.push_str("- **Tests:**");
+        for test_id in &req.test_ids {
+            if is_registered(test_id) {
+                output.push_str(&format!(" {}", test_id));
+            } else {
+                output.push_str(&format!(" {} (no such test)", test_id));
+            }
+        }
+        output.push_str("\n\n");
+    }
+
+    let untested: Vec<&(&str, &str)> = requirement_catalogue(framework)
+        .iter()
+        .filter(|(requirement_id, _)| {
+            !mapping.iter().any(|req| {
+                req.requirement_id == *requirement_id
+                    && req.test_ids.iter().any(|id| is_registered(id))
+            })
+        })
+        .collect();
+    output.push_str("## Requirements Without a Test\n\n");
+    if untested.is_empty() {
+        output.push_str("None - every requirement is fed by at least one test.\n");
+    }
+    for (requirement_id, title) in untested {
+        output.push_str(&format!("- {} - {}\n", requirement_id, title));
+    }
+
+    let unmapped: Vec<&str> = registered_tests
+        .iter()
+        .copied()
+        .filter(|test_id| {
+            !mapping
+                .iter()
+                .any(|req| req.test_ids.iter().any(|id| id == test_id))
+        })
+        .collect();
+    output.push_str(&format!(
+        "\n## Tests Not Mapped to a Requirement ({} of {})\n\n",
+        unmapped.len(),
+        registered_tests.len()
+    ));
+    for test_id in unmapped {
+        output.push_str(&format!("- {}\n", test_id));
+    }
+
+    output
+}
+
+/// Group mapped requirements by their source test category and score each group
+pub fn category_scores(results: &[ComplianceTestResult]) -> Vec<CategoryScore> {
+    let mut scores: BTreeMap<&str, CategoryScore> = BTreeMap::new();
+
+    for result in results {
+        let score = scores
+            .entry(result.category.as_str())
+            .or_insert_with(|| CategoryScore {
+                category: result.category.clone(),
+                total_requirements: 0,
+                passed_requirements: 0,
+                failed_requirements: 0,
+                warning_requirements: 0,
+                compliance_percentage: 0.0,
+            });
+        score.total_requirements += 1;
+        match result.status.as_str() {
+            "COMPLIANT" => score.passed_requirements += 1,
+            "NON_COMPLIANT" => score.failed_requirements += 1,
+            "PARTIAL_COMPLIANCE" => score.warning_requirements += 1,
+            _ => {}
+        }
+    }
+
+    scores
+        .into_values()
+        .map(|mut score| {
+            score.compliance_percentage =
+                (score.passed_requirements as f64 / score.total_requirements as f64) * 100.0;
+            score
+        })
+        .collect()
+}
+
+/// Ten-cell text bar for a percentage, used where a chart isn't available
+fn score_bar(percentage: f64) -> String {
+    let filled = ((percentage / 10.0).round() as usize).min(10);
+    format!("{}{}", "█".repeat(filled), "░".repeat(10 - filled))
+}
+
+pub fn format_compliance_report_as_markdown(report: &ComplianceReport) -> String {
+    let mut output = String::new();
+
+    output.push_str(&format!("# {} Compliance Report\n\n", report.report_type));
+    output.push_str(&format!(
+        "**Generated:** {}\n\n",
+        report.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+    ));
+
+    // Product Information
+    output.push_str("## Product Information\n\n");
+    output.push_str(&format!("- **Name:** {}\n", report.product_info.name));
+    output.push_str(&format!("- **Version:** {}\n", report.product_info.version));
+    output.push_str(&format!(
+        "- **Manufacturer:** {}\n",
+        report.product_info.manufacturer
+    ));
+    output.push_str(&format!("- **Model:** {}\n", report.product_info.model));
+    output.push_str(&format!(
+        "- **Description:** {}\n\n",
+        report.product_info.description
+    ));
+
+    // System Under Test
+    output.push_str("## System Under Test\n\n");
+    for (label, value) in report.system_under_test.system_under_test() {
+        output.push_str(&format!("- **{}:** {}\n", label, value));
+    }
+    output.push('\n');
+
+    // Compliance Summary
+    output.push_str("## Compliance Summary\n\n");
+    output.push_str(&format!(
+        "- **Overall Status:** {}\n",
+        report.compliance_summary.overall_status
+    ));
+    output.push_str(&format!(
+        "- **Compliance Percentage:** {:.1}%\n",
+        report.compliance_summary.compliance_percentage
+    ));
+    output.push_str(&format!(
+        "- **Total Requirements:** {}\n",
+        report.compliance_summary.total_requirements
+    ));
+    output.push_str(&format!(
+        "- **Passed:** {}\n",
+        report.compliance_summary.passed_requirements
+    ));
+    output.push_str(&format!(
+        "- **Failed:** {}\n",
+        report.compliance_summary.failed_requirements
+    ));
+    output.push_str(&format!(
+        "- **Warnings:** {}\n\n",
+        report.compliance_summary.warning_requirements
+    ));
+
+    if !report.compliance_summary.category_scores.is_empty() {
+        output.push_str("### Compliance by Category\n\n");
+        output.push_str("| Category | Score | Passed | Failed | Warnings |  |\n");
+        output.push_str("|---|---|---|---|---|---|\n");
+        for score in &report.compliance_summary.category_scores {
+            output.push_str(&format!(
+                "| {} | {:.1}% | {} | {} | {} | `{}` |\n",
+                score.category,
+                score.compliance_percentage,
+                score.passed_requirements,
+                score.failed_requirements,
+                score.warning_requirements,
+                score_bar(score.compliance_percentage)
+            ));
+        }
+        output.push('\n');
+    }
+
+    // Test Results
+    output.push_str("## Detailed Test Results\n\n");
+    output.push_str("| Requirement ID | Title | Status | Risk Level |\n");
+    output.push_str("|---|---|---|---|\n");
+
+    for result in &report.test_results {
+        output.push_str(&format!(
+            "| {} | {} | {} {} | {} |\n",
+            result.requirement_id,
+            result.requirement_title,
+            status_emoji(&result.status),
+            result.status,
+            result.risk_level
+        ));
+    }
+
+    // Certification Status
+    output.push_str("\n## Certification Readiness\n\n");
+    let ready_emoji = if report.certification_status.ready_for_certification {
+        "✅"
+    } else {
+        "❌"
+    };
+    output.push_str(&format!(
+        "**Ready for Certification:** {} {}\n\n",
+        ready_emoji, report.certification_status.ready_for_certification
+    ));
+
+    if !report.certification_status.blocking_issues.is_empty() {
+        output.push_str("### Blocking Issues\n\n");
+        for issue in &report.certification_status.blocking_issues {
+            output.push_str(&format!("- ❌ {}\n", issue));
+        }
+        output.push('\n');
+    }
+
+    if !report.certification_status.warnings.is_empty() {
+        output.push_str("### Warnings\n\n");
+        for warning in &report.certification_status.warnings {
+            output.push_str(&format!("- ⚠️ {}\n", warning));
+        }
+        output.push('\n');
+    }
+
+    // Next Steps
+    output.push_str("### Next Steps\n\n");
+    for step in &report.certification_status.next_steps {
+        output.push_str(&format!("1. {}\n", step));
+    }
+
+    // Recommendations
+    output.push_str("\n## Recommendations\n\n");
+    for recommendation in &report.recommendations {
+        output.push_str(&format!("- {}\n", recommendation));
+    }
+
+    output
+}
+
+fn status_emoji(status: &str) -> &'static str {
+    match status {
+        "COMPLIANT" => "✅",
+        "NON_COMPLIANT" => "❌",
+        "PARTIAL_COMPLIANCE" => "⚠️",
+        _ => "❓",
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+const HTML_REPORT_STYLE: &str =
+    "body{font-family:sans-serif;margin:2em auto;max-width:1100px;color:#222}\
+h1{border-bottom:2px solid #444}\
+table{border-collapse:collapse;width:100%;margin-bottom:1.5em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#eee}\
+.status-COMPLIANT{background:#e3f6e3}\
+.status-NON_COMPLIANT{background:#fbe3e3}\
+.status-PARTIAL_COMPLIANCE{background:#fff4d6}\
+.status-NOT_TESTED,.status-ERROR{background:#eee}\
+details{margin:0.5em 0;border:1px solid #ccc;padding:0.4em 0.8em}\
+summary{cursor:pointer;font-weight:bold}\
+pre{background:#f6f6f6;padding:0.6em;max-height:40em;overflow:auto;white-space:pre-wrap}";
+
+/// Self-contained HTML report: inline styles, a summary table and one
+/// expandable section per requirement holding the captured evidence
+pub fn format_compliance_report_as_html(report: &ComplianceReport) -> String {
+    let summary = &report.compliance_summary;
+    let mut output = String::new();
+
+    output.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    output.push_str(&format!(
+        "<title>{} Compliance Report</title>\n<style>{}</style>\n</head>\n<body>\n",
+        html_escape(&report.report_type),
+        HTML_REPORT_STYLE
+    ));
+    output.push_str(&format!(
+        "<h1>{} Compliance Report</h1>\n<p><strong>Generated:</strong> {}</p>\n",
+        html_escape(&report.report_type),
+        report.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+    ));
+
+    // Product Information and System Under Test
+    output.push_str("<h2>Product Information</h2>\n<table>\n");
+    for (label, value) in [
+        ("Name", &report.product_info.name),
+        ("Version", &report.product_info.version),
+        ("Manufacturer", &report.product_info.manufacturer),
+        ("Model", &report.product_info.model),
+        ("Description", &report.product_info.description),
+    ] {
+        output.push_str(&format!(
+            "<tr><th>{}</th><td>{}</td></tr>\n",
+            label,
+            html_escape(value)
+        ));
+    }
+    output.push_str("</table>\n<h2>System Under Test</h2>\n<table>\n");
+    for (label, value) in report.system_under_test.system_under_test() {
+        output.push_str(&format!(
+            "<tr><th>{}</th><td>{}</td></tr>\n",
+            html_escape(label),
+            html_escape(&value)
+        ));
+    }
+    output.push_str("</table>\n");
+
+    // Compliance Summary
+    output.push_str("<h2>Compliance Summary</h2>\n<table>\n");
+    output.push_str(&format!(
+        "<tr><th>Overall Status</th><td class=\"status-{0}\">{1} {0}</td></tr>\n",
+        html_escape(&summary.overall_status),
+        if summary.failed_requirements > 0 {
+            "❌"
+        } else if summary.warning_requirements > 0 {
+            "⚠️"
+        } else {
+            "✅"
+        }
+    ));
+    output.push_str(&format!(
+        "<tr><th>Compliance Percentage</th><td>{:.1}%</td></tr>\n",
+        summary.compliance_percentage
+    ));
+    output.push_str(&format!(
+        "<tr><th>Requirements</th><td>{} total, {} passed, {} failed, {} warnings</td></tr>\n</table>\n",
+        summary.total_requirements,
+        summary.passed_requirements,
+        summary.failed_requirements,
+        summary.warning_requirements
+    ));
+
+    if !summary.category_scores.is_empty() {
+        output.push_str("<h3>Compliance by Category</h3>\n<table>\n");
+        output.push_str(
+            "<tr><th>Category</th><th>Score</th><th>Passed</th><th>Failed</th><th>Warnings</th><th></th></tr>\n",
+        );
+        for score in &summary.category_scores {
+            output.push_str(&format!(
+                "<tr><td>{}</td><td>{:.1}%</td><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>\n",
+                html_escape(&score.category),
+                score.compliance_percentage,
+                score.passed_requirements,
+                score.failed_requirements,
+                score.warning_requirements,
+                score_bar(score.compliance_percentage)
+            ));
+        }
+        output.push_str("</table>\n");
+    }
+
+    // Requirement overview
+    output.push_str(
+        "<table>\n<tr><th>Requirement ID</th><th>Title</th><th>Test</th><th>Status</th><th>Risk Level</th></tr>\n",
+    );
+    for result in &report.test_results {
+        output.push_str(&format!(
+            "<tr class=\"status-{}\"><td><a href=\"#{}\">{}</a></td><td>{}</td><td>{}</td><td>{} {}</td><td>{}</td></tr>\n",
+            html_escape(&result.status),
+            html_escape(&result.test_id),
+            html_escape(&result.requirement_id),
+            html_escape(&result.requirement_title),
+            html_escape(&result.test_id),
+            status_emoji(&result.status),
+            html_escape(&result.status),
+            html_escape(&result.risk_level)
+        ));
+    }
+    output.push_str("</table>\n");
+
+    // Evidence per requirement
+    output.push_str("<h2>Evidence</h2>\n");
+    for result in &report.test_results {
+        output.push_str(&format!(
+            "<details id=\"{}\" class=\"status-{}\">\n<summary>{} {} &mdash; {} ({})</summary>\n<p>{}</p>\n",
+            html_escape(&result.test_id),
+            html_escape(&result.status),
+            status_emoji(&result.status),
+            html_escape(&result.requirement_id),
+            html_escape(&result.requirement_title),
+            html_escape(&result.test_id),
+            html_escape(&result.requirement_description)
+        ));
+        if let Some(remediation) = &result.remediation {
+            output.push_str(&format!(
+                "<p><strong>Remediation:</strong> {}</p>\n",
+                html_escape(remediation)
+            ));
+        }
+        if result.evidence.is_empty() {
+            output.push_str("<p><em>No evidence captured.</em></p>\n");
+        } else {
+            output.push_str(&format!("<pre>{}</pre>\n", html_escape(&result.evidence)));
+        }
+        output.push_str("</details>\n");
+    }
+
+    // Certification Status
+    let certification = &report.certification_status;
+    output.push_str(&format!(
+        "<h2>Certification Readiness</h2>\n<p><strong>Ready for Certification:</strong> {} {}</p>\n",
+        if certification.ready_for_certification {
+            "✅"
+        } else {
+            "❌"
+        },
+        certification.ready_for_certification
+    ));
+    for (heading, items, emoji) in [
+        ("Blocking Issues", &certification.blocking_issues, "❌ "),
+        ("Warnings", &certification.warnings, "⚠️ "),
+        ("Next Steps", &certification.next_steps, ""),
+        ("Recommendations", &report.recommendations, ""),
+    ] {
+        if items.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("<h3>{}</h3>\n<ul>\n", heading));
+        for item in items {
+            output.push_str(&format!("<li>{}{}</li>\n", emoji, html_escape(item)));
+        }
+        output.push_str("</ul>\n");
+    }
+
+    output.push_str("</body>\n</html>\n");
+    output
+}
+
+/// Render a compliance report in the chosen text format
+pub fn format_compliance_report(report: &ComplianceReport, format: ComplianceFormat) -> String {
+    match format {
+        ComplianceFormat::Markdown => format_compliance_report_as_markdown(report),
+        ComplianceFormat::Html => format_compliance_report_as_html(report),
+        ComplianceFormat::Csv => format_compliance_report_as_csv(report),
+    }
+}
+
+/// One CSV row per requirement result, for tracking spreadsheets
+pub fn format_compliance_report_as_csv(report: &ComplianceReport) -> String {
+    let mut output = String::from("requirement_id,title,status,risk_level\n");
+    for result in &report.test_results {
+        output.push_str(&csv_row(&[
+            &result.requirement_id,
+            &result.requirement_title,
+            &result.status,
+            &result.risk_level,
+        ]));
+    }
+    output
+}
+
+pub fn generate_pdf_report(
+    report: &ComplianceReport,
+    output_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Create a new PDF document
+    let (doc, page1, layer1) =
+        PdfDocument::new("Compliance Report", Mm(210.0), Mm(297.0), "Layer 1");
+    let current_layer = doc.get_page(page1).get_layer(layer1);
+
+    // Load fonts
+    let font_bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+    let font_regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+
+    let mut y_position = Mm(270.0); // Start near top of page
     let left_margin = Mm(20.0);
     let right_margin = Mm(190.0);
 
@@ -677,6 +1918,28 @@ pub fn generate_pdf_report(
     }
     y_position -= Mm(10.0);
 
+    // System Under Test Section
+    current_layer.use_text(
+        "System Under Test",
+        14.0,
+        left_margin,
+        y_position,
+        &font_bold,
+    );
+    y_position -= Mm(10.0);
+
+    for (label, value) in report.system_under_test.system_under_test() {
+        current_layer.use_text(
+            format!("{}: {}", label, value),
+            10.0,
+            left_margin + Mm(5.0),
+            y_position,
+            &font_regular,
+        );
+        y_position -= Mm(6.0);
+    }
+    y_position -= Mm(10.0);
+
     // Compliance Summary Section
     current_layer.use_text(
         "Compliance Summary",
@@ -712,6 +1975,55 @@ pub fn generate_pdf_report(
         current_layer.use_text(info, 10.0, left_margin + Mm(5.0), y_position, &font_regular);
         y_position -= Mm(6.0);
     }
+
+    if !report.compliance_summary.category_scores.is_empty() {
+        y_position -= Mm(4.0);
+        current_layer.use_text(
+            "By Category",
+            11.0,
+            left_margin + Mm(5.0),
+            y_position,
+            &font_bold,
+        );
+        y_position -= Mm(6.0);
+
+        for score in &report.compliance_summary.category_scores {
+            current_layer.use_text(
+                format!(
+                    "{}: {:.1}% ({}/{} passed)",
+                    score.category,
+                    score.compliance_percentage,
+                    score.passed_requirements,
+                    score.total_requirements
+                ),
+                10.0,
+                left_margin + Mm(5.0),
+                y_position,
+                &font_regular,
+            );
+
+            // Filled bar proportional to the score over a 50mm track
+            let bar_left = left_margin + Mm(80.0);
+            let bar_width = 50.0 * score.compliance_percentage / 100.0;
+            let track = Line {
+                points: vec![
+                    (Point::new(bar_left, y_position + Mm(1.0)), false),
+                    (Point::new(bar_left + Mm(50.0), y_position + Mm(1.0)), false),
+                ],
+                is_closed: false,
+            };
+            current_layer.add_line(track);
+            if bar_width > 0.0 {
+                current_layer.add_rect(Rect::new(
+                    bar_left,
+                    y_position,
+                    bar_left + Mm(bar_width as f32),
+                    y_position + Mm(2.5),
+                ));
+            }
+            y_position -= Mm(6.0);
+        }
+    }
     y_position -= Mm(10.0);
 
     // Test Results Section
@@ -874,3 +2186,165 @@ pub fn generate_pdf_report(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapped(category: &str, status: &str) -> ComplianceTestResult {
+        ComplianceTestResult {
+            requirement_id: "REQ".to_string(),
+            requirement_title: "Requirement".to_string(),
+            requirement_description: String::new(),
+            test_id: format!("{}_001", category),
+            category: category.to_string(),
+            status: status.to_string(),
+            evidence: String::new(),
+            remediation: None,
+            risk_level: "HIGH".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_category_scores_group_by_source_category() {
+        let scores = category_scores(&[
+            mapped("network", "COMPLIANT"),
+            mapped("boot", "COMPLIANT"),
+            mapped("network", "NON_COMPLIANT"),
+            mapped("network", "PARTIAL_COMPLIANCE"),
+        ]);
+
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].category, "boot");
+        assert_eq!(scores[0].compliance_percentage, 100.0);
+        assert_eq!(scores[1].category, "network");
+        assert_eq!(scores[1].total_requirements, 3);
+        assert_eq!(scores[1].failed_requirements, 1);
+        assert_eq!(scores[1].warning_requirements, 1);
+        assert!((scores[1].compliance_percentage - 100.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mapping_explanation_lists_catalogue_requirements_without_a_test() {
+        let registry = crate::tests::TestRegistry::new();
+        let test_ids = registry.test_ids();
+        for framework in [
+            ComplianceFramework::Cra,
+            ComplianceFramework::Red,
+            ComplianceFramework::Nist,
+            ComplianceFramework::Iec62443,
+        ] {
+            let catalogue = requirement_catalogue(&framework);
+            for requirement in framework_mapping(&framework) {
+                assert!(
+                    catalogue
+                        .iter()
+                        .any(|(id, _)| *id == requirement.requirement_id),
+                    "{} missing from the catalogue",
+                    requirement.requirement_id
+                );
+            }
+        }
+
+        let explanation = format_mapping_explanation(&ComplianceFramework::Cra, &test_ids);
+        let gaps = explanation
+            .split("## Requirements Without a Test")
+            .nth(1)
+            .unwrap();
+        assert!(gaps.contains("- CRA-ART11-006 - Software Bill of Materials"));
+        assert!(!gaps.contains("CRA-ART11-001"));
+    }
+
+    #[test]
+    fn test_nist_mapping_covers_all_capabilities_with_registered_tests() {
+        let registry = crate::tests::TestRegistry::new();
+        let test_ids = registry.test_ids();
+        let mapping = framework_mapping(&ComplianceFramework::Nist);
+
+        for capability in ["DI", "DC", "DP", "LA", "SU", "CSA"] {
+            let prefix = format!("NIST-{}-", capability);
+            assert!(mapping
+                .iter()
+                .any(|m| m.requirement_id.starts_with(&prefix)));
+        }
+        for requirement in &mapping {
+            for test_id in &requirement.test_ids {
+                assert!(test_ids.contains(&test_id.as_str()), "{}", test_id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_product_info_prefers_given_values_then_device_build() {
+        let mut results = TestSuiteResults::sample(vec![crate::tests::create_test_result(
+            "boot_001",
+            "Secure Boot Enabled",
+            "boot",
+            TestStatus::Passed,
+            "Secure boot enabled",
+            None,
+            std::time::Duration::from_millis(5),
+        )]);
+        results.system_info.os_release = "PRETTY_NAME=\"Linux-microPlatform\"\n".to_string();
+        results.system_info.os_version_id = "4.0.20".to_string();
+        results.system_info.ostree_commit = "3f2e9a0c".to_string();
+        results.system_info.machine_type = "imx93-jaguar-eink".to_string();
+
+        let report = CraComplianceReporter::generate_report(&results);
+        assert_eq!(report.product_info.name, "Linux-microPlatform");
+        assert_eq!(report.product_info.version, "4.0.20 (OSTree 3f2e9a0c)");
+        assert_eq!(report.product_info.model, "imx93-jaguar-eink");
+
+        results.product.name = Some("E-Ink Gateway".to_string());
+        results.product.manufacturer = Some("Acme".to_string());
+        let report = RedComplianceReporter::generate_report(&results);
+        assert_eq!(report.product_info.name, "E-Ink Gateway");
+        assert_eq!(report.product_info.manufacturer, "Acme");
+        assert_eq!(report.product_info.version, "4.0.20 (OSTree 3f2e9a0c)");
+    }
+
+    #[test]
+    fn test_iec62443_report_carries_security_levels() {
+        let results = TestSuiteResults::sample(vec![crate::tests::create_test_result(
+            "boot_007",
+            "Boot Chain Verification",
+            "boot",
+            TestStatus::Failed,
+            "Unsigned boot stage",
+            None,
+            std::time::Duration::from_millis(5),
+        )]);
+
+        let report = generate_compliance_report(&ComplianceFramework::Iec62443, &results);
+        assert_eq!(report.report_type, "IEC_62443_4_2");
+        assert_eq!(report.test_results.len(), 1);
+        assert_eq!(report.test_results[0].requirement_id, "IEC62443-CR3.14-RE1");
+        assert_eq!(report.test_results[0].risk_level, "SL2");
+        assert!(framework_mapping(&ComplianceFramework::Iec62443)
+            .iter()
+            .all(|m| m.risk_level.starts_with("SL")));
+    }
+
+    #[test]
+    fn test_html_report_escapes_evidence_in_details() {
+        let results = TestSuiteResults::sample(vec![crate::tests::create_test_result(
+            "boot_001",
+            "Secure Boot Enabled",
+            "boot",
+            TestStatus::Failed,
+            "Secure boot disabled",
+            Some("<script>alert(1)</script> & more".to_string()),
+            std::time::Duration::from_millis(5),
+        )]);
+        let report = CraComplianceReporter::generate_report(&results);
+        let html = format_compliance_report_as_html(&report);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt; &amp; more"));
+        assert!(html.contains("<details id=\"boot_001\" class=\"status-NON_COMPLIANT\">"));
+        assert!(html.contains("❌ NON_COMPLIANT"));
+        assert!(html.contains("<h3>Compliance by Category</h3>"));
+        assert!(html.contains("<tr><td>boot</td><td>0.0%</td>"));
+    }
+}
@@ -1,29 +1,124 @@
-use crate::cli::{Cli, MachineType, OutputFormat};
+use crate::cli::{
+    Cli, ComplianceFormat, DetailLevel, Environment, FailOnStatus, MachineType, NotifyOn,
+    OutputFormat, Severity,
+};
 use crate::communication::ChannelConfig;
+use crate::output::JsonProfile;
+use crate::secret::Redactor;
+use crate::tests::{certificate::split_tls_endpoint, TestRegistry};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     pub communication: CommunicationConfig,
     pub output: OutputConfig,
     pub tests: TestConfig,
     pub thresholds: ThresholdConfig,
     pub machine: Option<MachineConfig>,
+    #[serde(default)]
+    pub hooks: HookConfig,
+    #[serde(default)]
+    pub product: ProductConfig,
+    /// Test IDs run by `--test-suite custom`, in order
+    #[serde(default)]
+    pub custom_tests: Vec<String>,
 }
 
+/// Product identity for compliance report headers. Anything left unset is
+/// taken from the device (os-release, OSTree commit, detected machine).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProductConfig {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub manufacturer: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl ProductConfig {
+    /// Replace the fields that `other` sets, keeping the rest
+    pub fn apply(&mut self, other: &ProductConfig) {
+        for (field, value) in [
+            (&mut self.name, &other.name),
+            (&mut self.version, &other.version),
+            (&mut self.manufacturer, &other.manufacturer),
+            (&mut self.model, &other.model),
+        ] {
+            if value.is_some() {
+                field.clone_from(value);
+            }
+        }
+    }
+}
+
+/// Local commands run around a test suite
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HookConfig {
+    /// Shell command run on this machine after the suite completes
+    #[serde(default)]
+    pub post_run: Option<String>,
+    /// Seconds the hook may run before it is killed
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Fail the run when the hook fails instead of only warning
+    #[serde(default)]
+    pub require: bool,
+    /// URL the run summary is POSTed to after the suite completes
+    #[serde(default)]
+    pub notify_webhook: Option<String>,
+    /// Which runs send the webhook notification
+    #[serde(default)]
+    pub notify_on: NotifyOn,
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self {
+            post_run: None,
+            timeout_secs: default_hook_timeout_secs(),
+            require: false,
+            notify_webhook: None,
+            notify_on: NotifyOn::default(),
+        }
+    }
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CommunicationConfig {
-    pub channel_type: String, // "ssh" or "serial"
-    // SSH fields
+    pub channel_type: String, // "ssh", "serial", "local" or "telnet"
+    // SSH fields (host, port, user and password are also used by telnet)
     pub host: Option<String>,
     pub port: Option<u16>,
     pub user: Option<String>,
     pub password: Option<String>,
     pub ssh_key_path: Option<String>,
-    pub ssh_multiplex: Option<bool>,
+    /// Open a pool of SSH sessions so `--jobs` can run tests concurrently
+    #[serde(alias = "ssh_multiplex")]
+    pub ssh_session_pool: Option<bool>,
+    pub ssh_max_sessions: Option<usize>,
+    /// Seconds of idle time before an SSH keep-alive is sent (0 disables)
+    #[serde(default)]
+    pub ssh_keepalive_secs: Option<u32>,
+    /// Attempts to re-establish a dropped SSH session before a command fails
+    #[serde(default)]
+    pub ssh_reconnect_attempts: Option<u32>,
+    /// Local source IP (or interface name on Linux) for outbound SSH connections
+    pub bind_address: Option<String>,
     // Serial fields
     pub serial_device: Option<String>,
     pub baud_rate: Option<u32>,
@@ -34,9 +129,47 @@ pub struct CommunicationConfig {
     pub serial_shell_prompt: Option<String>,
     // Common fields
     pub timeout: u64,
+    /// Extra attempts for a command that errors, times out or returns cut-off output
+    #[serde(default)]
+    pub command_retries: Option<u32>,
+    /// Delay before the first retry, doubled for each further attempt
+    #[serde(default)]
+    pub command_retry_delay_ms: Option<u64>,
+    /// Save every command and its output to this snapshot file (`--record`)
+    #[serde(default)]
+    pub record_snapshot: Option<String>,
+    /// Answer commands from this snapshot file instead of a device (`--replay`)
+    #[serde(default)]
+    pub replay_snapshot: Option<String>,
 }
 
+/// Values `communication.channel_type` accepts
+pub const CHANNEL_TYPES: &[&str] = &["ssh", "serial", "telnet", "local"];
+
+/// Default number of concurrent sessions considered safe for an SSH target
+pub const DEFAULT_SSH_MAX_SESSIONS: usize = 4;
+/// Default idle interval between SSH keep-alives, in seconds
+pub const DEFAULT_SSH_KEEPALIVE_SECS: u32 = 15;
+/// Default number of reconnection attempts for a dropped SSH session
+pub const DEFAULT_SSH_RECONNECT_ATTEMPTS: u32 = 2;
+
 impl CommunicationConfig {
+    /// Maximum number of tests that may safely run concurrently over this channel.
+    /// Serial consoles are strictly single-session; interleaved commands would
+    /// corrupt the session.
+    pub fn max_concurrency(&self) -> usize {
+        match self.channel_type.as_str() {
+            "ssh" => self
+                .ssh_max_sessions
+                .unwrap_or(DEFAULT_SSH_MAX_SESSIONS)
+                .max(1),
+            "local" => std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            _ => 1,
+        }
+    }
+
     pub fn to_channel_config(&self) -> Result<ChannelConfig> {
         match self.channel_type.as_str() {
             "ssh" => Ok(ChannelConfig::Ssh {
@@ -46,7 +179,14 @@ impl CommunicationConfig {
                 password: self.password.clone().unwrap_or_default(),
                 ssh_key_path: self.ssh_key_path.clone(),
                 timeout: self.timeout as u32,
-                ssh_multiplex: self.ssh_multiplex.unwrap_or(false),
+                ssh_session_pool: self.ssh_session_pool.unwrap_or(false),
+                bind_address: self.bind_address.clone(),
+                keepalive_secs: self
+                    .ssh_keepalive_secs
+                    .unwrap_or(DEFAULT_SSH_KEEPALIVE_SECS),
+                reconnect_attempts: self
+                    .ssh_reconnect_attempts
+                    .unwrap_or(DEFAULT_SSH_RECONNECT_ATTEMPTS),
             }),
             "serial" => Ok(ChannelConfig::Serial {
                 device: self.serial_device.clone().ok_or_else(|| {
@@ -60,6 +200,19 @@ impl CommunicationConfig {
                 username: self.serial_username.clone(),
                 password: self.serial_password.clone(),
             }),
+            "local" => Ok(ChannelConfig::Local {
+                timeout: self.timeout as u32,
+            }),
+            "telnet" => Ok(ChannelConfig::Telnet {
+                host: self
+                    .host
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("Host is required for telnet communication"))?,
+                port: self.port.unwrap_or(23),
+                user: self.user.clone().unwrap_or_else(|| "root".to_string()),
+                password: self.password.clone().unwrap_or_default(),
+                timeout: self.timeout as u32,
+            }),
             _ => Err(anyhow::anyhow!(
                 "Unsupported communication channel type: {}",
                 self.channel_type
@@ -69,24 +222,288 @@ impl CommunicationConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct OutputConfig {
+    /// One format, or several separated by commas
     pub format: String,
     pub file: Option<String>,
     pub verbose: u8,
     pub colors: bool,
+    /// Details included in human and markdown reports; unset follows verbosity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<DetailLevel>,
+    /// Full results, or the minimal profile for high-volume fleet storage
+    #[serde(default)]
+    pub json_profile: JsonProfile,
+    /// Report warnings as JUnit failures rather than passing test cases
+    #[serde(default)]
+    pub junit_warnings_as_failures: bool,
+    /// Markdown or HTML for the framework compliance reports
+    #[serde(default)]
+    pub compliance_format: ComplianceFormat,
+    /// No progress display, per-test lines or timing summary in human output
+    #[serde(default)]
+    pub quiet: bool,
+    /// Number of slowest tests listed after a run (0 disables)
+    #[serde(default = "default_slowest_tests")]
+    pub slowest_tests: usize,
+    /// Mask password hashes, private keys and `redact_patterns` in results
+    #[serde(default = "default_redact")]
+    pub redact: bool,
+    /// Extra regular expressions whose matches are masked in results
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+    /// OpenSSH private key that signs evidence bundle manifests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+    /// Only write `file`, printing nothing, for runs that report elsewhere
+    /// such as each device of a fleet run
+    #[serde(skip)]
+    pub silent: bool,
+}
+
+fn default_slowest_tests() -> usize {
+    5
+}
+
+fn default_redact() -> bool {
+    true
+}
+
+impl OutputConfig {
+    pub fn formats(&self) -> Vec<String> {
+        let formats: Vec<String> = self
+            .format
+            .split(',')
+            .map(|format| format.trim().to_lowercase())
+            .filter(|format| !format.is_empty())
+            .collect();
+        if formats.is_empty() {
+            vec!["human".to_string()]
+        } else {
+            formats
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct TestConfig {
     pub suite: String,
     pub mode: String,
     pub continue_on_failure: bool,
     pub parallel: bool,
+    /// Seconds a single test may run before it is abandoned as an error (0 disables)
     pub timeout_per_test: u64,
+    /// Reuse the output of read-only commands (dmesg, /proc reads, os-release)
+    /// that several tests run, for slow links
+    #[serde(default)]
+    pub cache_commands: bool,
     pub retries: u32,
+    /// Requested number of concurrent tests, capped by the channel's limit
+    #[serde(default)]
+    pub jobs: Option<usize>,
+    /// Allow tests to reach external services (e.g. OCSP responders) from the target
+    #[serde(default)]
+    pub allow_external_network: bool,
+    /// Test categories to leave out of the selected suite
+    #[serde(default)]
+    pub exclude_categories: Vec<String>,
+    /// Test IDs to leave out of the selected suite
+    #[serde(default)]
+    pub exclude_tests: Vec<String>,
+    /// Ports the product is expected to listen on; network_001 flags any other
+    /// port bound to a non-loopback address
+    #[serde(default)]
+    pub allowed_ports: Vec<u16>,
+    /// `host:port` TLS endpoints whose presented certificates certificate_008 validates
+    /// and certificate_011 checks for revocation status
+    #[serde(default)]
+    pub tls_endpoints: Vec<String>,
+    /// Only run tests carrying at least one of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Report tests that error out without failing the run
+    #[serde(default)]
+    pub errors_non_gating: bool,
+    /// Categories in which a warning fails the run like a failure would
+    #[serde(default)]
+    pub fail_on_warning_categories: Vec<String>,
+    /// Only failures and warnings at or above this severity fail the run
+    #[serde(default)]
+    pub fail_on: Option<Severity>,
+    /// Fail the run on any test at or above this status, ignoring severities
+    #[serde(default)]
+    pub fail_on_status: Option<FailOnStatus>,
+    /// Warnings never fail the run
+    #[serde(default)]
+    pub exit_zero_on_warning: bool,
+    /// Devices up for less than this many seconds get a fresh-boot warning (0 disables)
+    #[serde(default = "default_min_uptime_secs")]
+    pub min_uptime_secs: u64,
+    /// Fewer loaded audit rules than this is reported as a warning
+    #[serde(default = "default_min_audit_rules")]
+    pub min_audit_rules: usize,
+    /// Have hardware_005 run a quick statistical sanity check on a sample read
+    /// from /dev/random
+    #[serde(default)]
+    pub rng_sample_test: bool,
+    /// How far back compliance_004 and compliance_005 look for security events,
+    /// in any form `journalctl --since` accepts
+    #[serde(default = "default_log_since")]
+    pub log_since: String,
+    /// Keep complete command output in test details instead of the first few lines
+    #[serde(default)]
+    pub verbose_evidence: bool,
+    /// Directories in which runtime_016 reports every world-readable file
+    #[serde(default = "default_sensitive_dirs")]
+    pub sensitive_dirs: Vec<String>,
+    /// Hardening directives a systemd service needs to count as sandboxed
+    #[serde(default = "default_min_sandbox_directives")]
+    pub min_sandbox_directives: usize,
+    /// Certificates expiring within this many days fail certificate_003
+    #[serde(default = "default_cert_expiry_fail_days")]
+    pub cert_expiry_fail_days: i64,
+    /// Certificates expiring within this many days are reported as a warning
+    #[serde(default = "default_cert_expiry_warn_days")]
+    pub cert_expiry_warn_days: i64,
+    /// Pipe the target password to `sudo -S` for privileged probes. Turn off where
+    /// passwords must not travel over stdin; kernel-level tests are then skipped.
+    #[serde(default = "default_allow_sudo_password")]
+    pub allow_sudo_password: bool,
+    /// Command that runs probes as root, e.g. `doas` or `sudo -E`. sudo is fed
+    /// the target password on stdin; other tools must not need one.
+    #[serde(default = "default_privilege_escalation_cmd")]
+    pub privilege_escalation_cmd: String,
+    /// Extra sudo passwords to try after the configured target password.
+    /// Empty by default: the tool never guesses passwords unless asked to.
+    #[serde(default)]
+    pub sudo_candidate_passwords: Vec<String>,
+    /// Interactively apply remediation for failed tests and re-test them
+    #[serde(skip)]
+    pub fix_and_verify: bool,
+    /// Record completed tests here and skip those already recorded
+    #[serde(skip)]
+    pub checkpoint: Option<PathBuf>,
+    /// Replacement probe commands keyed by `<test_id>.<probe>`
+    #[serde(default)]
+    pub commands: HashMap<String, String>,
+}
+
+fn default_min_uptime_secs() -> u64 {
+    300
+}
+
+fn default_min_audit_rules() -> usize {
+    10
+}
+
+fn default_log_since() -> String {
+    "24 hours ago".to_string()
+}
+
+fn default_sensitive_dirs() -> Vec<String> {
+    [
+        "/etc/ssl/private",
+        "/var/sota",
+        "/etc/wireguard",
+        "/etc/ipsec.d/private",
+    ]
+    .iter()
+    .map(|dir| dir.to_string())
+    .collect()
+}
+
+fn default_min_sandbox_directives() -> usize {
+    3
+}
+
+fn default_cert_expiry_fail_days() -> i64 {
+    30
+}
+
+fn default_cert_expiry_warn_days() -> i64 {
+    90
+}
+
+fn default_allow_sudo_password() -> bool {
+    true
+}
+
+fn default_privilege_escalation_cmd() -> String {
+    "sudo".to_string()
+}
+
+/// Categories whose warnings fail a run in the prod environment
+pub const SECURITY_CRITICAL_CATEGORIES: &[&str] = &["boot", "hardware", "runtime", "network"];
+
+impl TestConfig {
+    /// Apply the gating defaults for a deployment environment. Explicit
+    /// gating options are applied afterwards and take precedence.
+    pub fn apply_environment(&mut self, environment: &Environment) {
+        match environment {
+            Environment::Prod => {
+                self.errors_non_gating = false;
+                self.fail_on_warning_categories = SECURITY_CRITICAL_CATEGORIES
+                    .iter()
+                    .map(|category| category.to_string())
+                    .collect();
+            }
+            Environment::Staging => {
+                self.errors_non_gating = false;
+                self.fail_on_warning_categories.clear();
+            }
+            Environment::Dev => {
+                self.errors_non_gating = true;
+                self.fail_on_warning_categories.clear();
+            }
+        }
+    }
+}
+
+impl Default for TestConfig {
+    fn default() -> Self {
+        Self {
+            suite: "all".to_string(),
+            mode: "pre-production".to_string(),
+            continue_on_failure: false,
+            parallel: false,
+            timeout_per_test: 60,
+            cache_commands: false,
+            retries: 1,
+            jobs: None,
+            allow_external_network: false,
+            exclude_categories: Vec::new(),
+            exclude_tests: Vec::new(),
+            allowed_ports: Vec::new(),
+            tls_endpoints: Vec::new(),
+            tags: Vec::new(),
+            errors_non_gating: false,
+            fail_on_warning_categories: Vec::new(),
+            fail_on: None,
+            fail_on_status: None,
+            exit_zero_on_warning: false,
+            min_uptime_secs: default_min_uptime_secs(),
+            min_audit_rules: default_min_audit_rules(),
+            rng_sample_test: false,
+            log_since: default_log_since(),
+            verbose_evidence: false,
+            sensitive_dirs: default_sensitive_dirs(),
+            min_sandbox_directives: default_min_sandbox_directives(),
+            cert_expiry_fail_days: default_cert_expiry_fail_days(),
+            cert_expiry_warn_days: default_cert_expiry_warn_days(),
+            allow_sudo_password: default_allow_sudo_password(),
+            privilege_escalation_cmd: default_privilege_escalation_cmd(),
+            sudo_candidate_passwords: Vec::new(),
+            fix_and_verify: false,
+            checkpoint: None,
+            commands: HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ThresholdConfig {
     pub boot_time_max_ms: u64,
     pub memory_usage_max_mb: u64,
@@ -94,6 +511,7 @@ pub struct ThresholdConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct MachineConfig {
     pub machine_type: String,
     pub auto_detect: bool,
@@ -118,11 +536,18 @@ impl Config {
             || cli.identity_file.is_some();
 
         // Determine if we should override the communication config
-        let should_override_comm = cli_has_serial || (cli.config.is_none() && cli_has_ssh);
+        let should_override_comm =
+            cli.local || cli_has_serial || (cli.config.is_none() && cli_has_ssh);
 
         if should_override_comm {
             // Determine communication channel type
-            let channel_type = if cli_has_serial { "serial" } else { "ssh" };
+            let channel_type = if cli.local {
+                "local"
+            } else if cli_has_serial {
+                "serial"
+            } else {
+                "ssh"
+            };
 
             // Configure communication based on channel type
             config.communication = CommunicationConfig {
@@ -152,11 +577,15 @@ impl Config {
                     .identity_file
                     .as_ref()
                     .map(|p| p.to_string_lossy().to_string()),
-                ssh_multiplex: if channel_type == "ssh" {
+                ssh_session_pool: if channel_type == "ssh" {
                     Some(true)
                 } else {
                     None
                 },
+                ssh_max_sessions: config.communication.ssh_max_sessions,
+                ssh_keepalive_secs: config.communication.ssh_keepalive_secs,
+                ssh_reconnect_attempts: config.communication.ssh_reconnect_attempts,
+                bind_address: config.communication.bind_address.clone(),
                 // Serial fields
                 serial_device: cli.serial_device.clone(),
                 baud_rate: if channel_type == "serial" {
@@ -183,18 +612,71 @@ impl Config {
                 },
                 // Common fields
                 timeout: cli.timeout,
+                command_retries: config.communication.command_retries,
+                command_retry_delay_ms: config.communication.command_retry_delay_ms,
+                record_snapshot: config.communication.record_snapshot.clone(),
+                replay_snapshot: config.communication.replay_snapshot.clone(),
             };
         }
+        if let Some(path) = &cli.record {
+            config.communication.record_snapshot = Some(path.to_string_lossy().to_string());
+        }
+        if let Some(path) = &cli.replay {
+            config.communication.replay_snapshot = Some(path.to_string_lossy().to_string());
+        }
+        if cli.bind_address.is_some() {
+            config.communication.bind_address = cli.bind_address.clone();
+        }
         config.output.verbose = cli.verbose;
-        config.output.format = match cli.format {
-            OutputFormat::Human => "human".to_string(),
-            OutputFormat::Json => "json".to_string(),
-            OutputFormat::Junit => "junit".to_string(),
-            OutputFormat::Markdown => "markdown".to_string(),
-            OutputFormat::Cra => "cra".to_string(),
-            OutputFormat::Red => "red".to_string(),
-            OutputFormat::Pdf => "pdf".to_string(),
-        };
+        if cli.details.is_some() {
+            config.output.details = cli.details;
+        }
+        if let Some(timeout_per_test) = cli.timeout_per_test {
+            config.tests.timeout_per_test = timeout_per_test;
+        }
+        if cli.junit_warnings_as_failures {
+            config.output.junit_warnings_as_failures = true;
+        }
+        if cli.quiet {
+            config.output.quiet = true;
+        }
+        if let Some(slowest) = cli.slowest {
+            config.output.slowest_tests = slowest;
+        }
+        if let Some(format) = cli.compliance_format {
+            config.output.compliance_format = format;
+        }
+        if cli.no_details {
+            config.output.json_profile = JsonProfile::Minimal;
+        }
+        if cli.no_redact {
+            config.output.redact = false;
+        }
+        config.product.apply(&ProductConfig {
+            name: cli.product_name.clone(),
+            version: cli.product_version.clone(),
+            manufacturer: cli.manufacturer.clone(),
+            model: cli.model.clone(),
+        });
+        config.output.format = cli
+            .format
+            .iter()
+            .map(|format| match format {
+                OutputFormat::Human => "human",
+                OutputFormat::Json => "json",
+                OutputFormat::Junit => "junit",
+                OutputFormat::Markdown => "markdown",
+                OutputFormat::Cra => "cra",
+                OutputFormat::Red => "red",
+                OutputFormat::Nist => "nist",
+                OutputFormat::Iec62443 => "iec62443",
+                OutputFormat::Pdf => "pdf",
+                OutputFormat::Prometheus => "prometheus",
+                OutputFormat::Sarif => "sarif",
+                OutputFormat::Csv => "csv",
+            })
+            .collect::<Vec<_>>()
+            .join(",");
 
         if let Some(output_file) = &cli.output {
             config.output.file = Some(output_file.to_string_lossy().to_string());
@@ -202,13 +684,8 @@ impl Config {
 
         // Handle machine configuration
         if let Some(machine_type) = &cli.machine {
-            let machine_type_str = match machine_type {
-                MachineType::Imx93JaguarEink => "imx93-jaguar-eink".to_string(),
-                MachineType::Imx8mmJaguarSentai => "imx8mm-jaguar-sentai".to_string(),
-            };
-
             config.machine = Some(MachineConfig {
-                machine_type: machine_type_str,
+                machine_type: machine_type.as_str().to_string(),
                 auto_detect: false, // Explicitly set via CLI
                 hardware_features: get_machine_features(machine_type),
             });
@@ -227,13 +704,124 @@ impl Config {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read_to_string(path).context("Failed to read configuration file")?;
 
-        let config: Self = toml::from_str(&content)
-            .or_else(|_| serde_json::from_str(&content))
-            .context("Failed to parse configuration file (expected TOML or JSON)")?;
+        let config = Self::parse(&content)?;
+        config.validate()?;
 
         Ok(config)
     }
 
+    /// Parse a TOML or JSON configuration. Errors name the offending field,
+    /// what was expected there and, for a misspelt key, the likely intended one.
+    pub fn parse(content: &str) -> Result<Self> {
+        if content.trim_start().starts_with('{') {
+            return serde_json::from_str(content).map_err(|e| {
+                anyhow::anyhow!(
+                    "Invalid JSON configuration: {}{}",
+                    e,
+                    suggest_field(&e.to_string())
+                )
+            });
+        }
+        toml::from_str(content).map_err(|e| {
+            let message = e.message().trim_end();
+            match e.span() {
+                Some(span) => {
+                    let path = toml_key_path(content, span.start);
+                    anyhow::anyhow!(
+                        "Invalid configuration at {} (line {}): {}{}",
+                        if path.is_empty() {
+                            "the top level".to_string()
+                        } else {
+                            format!("`{}`", path)
+                        },
+                        content[..span.start].matches('\n').count() + 1,
+                        message,
+                        suggest_field(message)
+                    )
+                }
+                None => anyhow::anyhow!("Invalid configuration: {}", message),
+            }
+        })
+    }
+
+    /// Check settings that parse but refer to things that don't exist
+    pub fn validate(&self) -> Result<()> {
+        if !CHANNEL_TYPES.contains(&self.communication.channel_type.as_str()) {
+            anyhow::bail!(
+                "communication.channel_type must be one of {} (got \"{}\")",
+                CHANNEL_TYPES.join(", "),
+                self.communication.channel_type
+            );
+        }
+        let registry = TestRegistry::new();
+        if let Some(unknown) = registry.unknown_test_id(&self.custom_tests) {
+            anyhow::bail!(
+                "Unknown test ID '{}' in custom_tests (run the 'list' command to see available tests)",
+                unknown
+            );
+        }
+        if let Some(unknown) = registry.unknown_test_id(&self.tests.exclude_tests) {
+            anyhow::bail!(
+                "Unknown test ID '{}' in tests.exclude_tests (run the 'list' command to see available tests)",
+                unknown
+            );
+        }
+        if self.tests.privilege_escalation_cmd.trim().is_empty() {
+            anyhow::bail!("tests.privilege_escalation_cmd must name a command, e.g. \"sudo\"");
+        }
+        if let Err(e) = Redactor::new(&self.output.redact_patterns) {
+            anyhow::bail!("Invalid pattern in output.redact_patterns: {}", e);
+        }
+        if let Some(invalid) = self
+            .tests
+            .tls_endpoints
+            .iter()
+            .find(|endpoint| split_tls_endpoint(endpoint).is_none())
+        {
+            anyhow::bail!(
+                "Invalid TLS endpoint '{}' in tests.tls_endpoints (expected host:port)",
+                invalid
+            );
+        }
+        Ok(())
+    }
+
+    /// What `validate` checked on this configuration, one line per item, for
+    /// the `validate` command
+    pub fn validation_checklist(&self) -> Vec<String> {
+        let count = |items: usize, what: &str| match items {
+            0 => "none set".to_string(),
+            n => format!("{} set, {}", n, what),
+        };
+        vec![
+            "Syntax and value types, no unknown keys".to_string(),
+            format!(
+                "communication.channel_type: {}",
+                self.communication.channel_type
+            ),
+            format!(
+                "custom_tests: {}",
+                count(self.custom_tests.len(), "all known test IDs")
+            ),
+            format!(
+                "tests.exclude_tests: {}",
+                count(self.tests.exclude_tests.len(), "all known test IDs")
+            ),
+            format!(
+                "tests.tls_endpoints: {}",
+                count(self.tests.tls_endpoints.len(), "all host:port")
+            ),
+            format!(
+                "tests.privilege_escalation_cmd: {}",
+                self.tests.privilege_escalation_cmd
+            ),
+            format!(
+                "output.redact_patterns: {}",
+                count(self.output.redact_patterns.len(), "all valid regexes")
+            ),
+        ]
+    }
+
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let content = toml::to_string_pretty(self).context("Failed to serialize configuration")?;
 
@@ -251,10 +839,7 @@ impl Config {
         if let Some(machine_config) = &mut self.machine {
             if machine_config.auto_detect {
                 if let Some(detected_type) = machine_type {
-                    machine_config.machine_type = match detected_type {
-                        MachineType::Imx93JaguarEink => "imx93-jaguar-eink".to_string(),
-                        MachineType::Imx8mmJaguarSentai => "imx8mm-jaguar-sentai".to_string(),
-                    };
+                    machine_config.machine_type = detected_type.as_str().to_string();
                 } else {
                     machine_config.machine_type = "unknown".to_string();
                 }
@@ -274,7 +859,11 @@ impl Default for Config {
                 user: Some("fio".to_string()),
                 password: Some("fio".to_string()),
                 ssh_key_path: None,
-                ssh_multiplex: Some(true),
+                ssh_session_pool: Some(true),
+                ssh_max_sessions: None,
+                ssh_keepalive_secs: None,
+                ssh_reconnect_attempts: None,
+                bind_address: None,
                 serial_device: None,
                 baud_rate: None,
                 serial_username: None,
@@ -283,21 +872,28 @@ impl Default for Config {
                 serial_password_prompt: None,
                 serial_shell_prompt: None,
                 timeout: 30,
+                command_retries: None,
+                command_retry_delay_ms: None,
+                record_snapshot: None,
+                replay_snapshot: None,
             },
             output: OutputConfig {
                 format: "human".to_string(),
                 file: None,
                 verbose: 0,
                 colors: true,
+                details: None,
+                json_profile: JsonProfile::Full,
+                junit_warnings_as_failures: false,
+                compliance_format: ComplianceFormat::Markdown,
+                quiet: false,
+                slowest_tests: default_slowest_tests(),
+                redact: default_redact(),
+                redact_patterns: Vec::new(),
+                signing_key: None,
+                silent: false,
             },
-            tests: TestConfig {
-                suite: "all".to_string(),
-                mode: "pre-production".to_string(),
-                continue_on_failure: false,
-                parallel: false,
-                timeout_per_test: 60,
-                retries: 1,
-            },
+            tests: TestConfig::default(),
             thresholds: ThresholdConfig {
                 boot_time_max_ms: 30000,
                 memory_usage_max_mb: 512,
@@ -308,6 +904,9 @@ impl Default for Config {
                 auto_detect: true,
                 hardware_features: vec![],
             }),
+            hooks: HookConfig::default(),
+            product: ProductConfig::default(),
+            custom_tests: Vec::new(),
         }
     }
 }
@@ -324,6 +923,7 @@ fn get_machine_features(machine_type: &MachineType) -> Vec<String> {
             "tf-a".to_string(),
             "eink-display".to_string(),
             "pcf2131-rtc".to_string(),
+            "hwrng".to_string(),
         ],
         MachineType::Imx8mmJaguarSentai => vec![
             "imx8mm".to_string(),
@@ -333,6 +933,136 @@ fn get_machine_features(machine_type: &MachineType) -> Vec<String> {
             "op-tee".to_string(),
             "tf-a".to_string(),
             "hab".to_string(),
+            "hwrng".to_string(),
         ],
+        MachineType::RaspberryPiCm4 => vec![
+            "raspberry-pi".to_string(),
+            "bcm2711".to_string(),
+            "hwrng".to_string(),
+        ],
+        MachineType::RaspberryPiCm5 => vec![
+            "raspberry-pi".to_string(),
+            "bcm2712".to_string(),
+            "hwrng".to_string(),
+        ],
+    }
+}
+
+/// Dotted path of the key at `offset` in a TOML document, e.g.
+/// `communication.chanel_type`, from the enclosing table header and the
+/// nearest key assignment
+fn toml_key_path(content: &str, offset: usize) -> String {
+    let mut table = String::new();
+    let mut key = None;
+    for line in content[..offset].split('\n') {
+        let line = line.trim();
+        if line.starts_with('[') {
+            table = line
+                .trim_matches(|c| c == '[' || c == ']')
+                .trim()
+                .to_string();
+            key = None;
+        } else if let Some((name, _)) = line.split_once('=') {
+            key = Some(name.trim().trim_matches('"').to_string());
+        }
+    }
+    // The offending key or table itself starts at the offset
+    let rest = content[offset..].lines().next().unwrap_or("").trim();
+    if rest.starts_with('[') {
+        table = rest
+            .trim_matches(|c| c == '[' || c == ']')
+            .trim()
+            .to_string();
+        key = None;
+    } else if let Some((name, _)) = rest.split_once('=') {
+        key = Some(name.trim().trim_matches('"').to_string());
+    }
+    match (table.is_empty(), key) {
+        (true, Some(key)) => key,
+        (false, Some(key)) => format!("{}.{}", table, key),
+        (_, None) => table,
+    }
+}
+
+/// " (did you mean `x`?)" for an unknown field error whose expected fields
+/// include one within two edits of it
+fn suggest_field(message: &str) -> String {
+    let Some(rest) = message.strip_prefix("unknown field `") else {
+        return String::new();
+    };
+    let Some((unknown, expected)) = rest.split_once('`') else {
+        return String::new();
+    };
+    expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(|field| (edit_distance(unknown, field), field))
+        .filter(|(distance, _)| *distance <= 2)
+        .min()
+        .map(|(_, field)| format!(" (did you mean `{}`?)", field))
+        .unwrap_or_default()
+}
+
+/// Levenshtein distance between two field names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_errors_name_the_field() {
+        let mut content = toml::to_string_pretty(&Config::default()).unwrap();
+        assert!(Config::parse(&content).is_ok());
+
+        content = content.replacen("channel_type =", "chanel_type =", 1);
+        let error = Config::parse(&content).unwrap_err().to_string();
+        assert!(
+            error.contains("at `communication.chanel_type`"),
+            "{}",
+            error
+        );
+        assert!(error.contains("did you mean `channel_type`?"), "{}", error);
+
+        let content = toml::to_string_pretty(&Config::default())
+            .unwrap()
+            .replacen("timeout_per_test = 60", "timeout_per_test = \"60\"", 1);
+        let error = Config::parse(&content).unwrap_err().to_string();
+        assert!(error.contains("at `tests.timeout_per_test`"), "{}", error);
+        assert!(error.contains("expected u64"), "{}", error);
+
+        let mut config = Config::default();
+        config.communication.channel_type = "usb".to_string();
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("must be one of ssh, serial, telnet, local"));
+
+        let content = toml::to_string_pretty(&Config::default())
+            .unwrap()
+            .replacen("auto_detect = true\n", "", 1);
+        let error = Config::parse(&content).unwrap_err().to_string();
+        assert!(error.contains("at `machine`"), "{}", error);
+        assert!(error.contains("missing field `auto_detect`"), "{}", error);
+    }
+
+    #[test]
+    fn test_ssh_multiplex_is_read_as_session_pool() {
+        let content = toml::to_string_pretty(&Config::default())
+            .unwrap()
+            .replacen("ssh_session_pool = true", "ssh_multiplex = false", 1);
+        let config = Config::parse(&content).unwrap();
+        assert_eq!(config.communication.ssh_session_pool, Some(false));
     }
 }
@@ -0,0 +1,310 @@
+/*
+ * Security Compliance CLI - Result Comparison
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+use crate::tests::{TestStatus, TestSuiteResults};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Outcome of a single test on both sides of a comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestDiff {
+    pub test_id: String,
+    pub test_name: String,
+    pub reference: Option<TestStatus>,
+    pub candidate: Option<TestStatus>,
+    pub reference_message: Option<String>,
+    pub candidate_message: Option<String>,
+}
+
+/// How a test's outcome moved between a baseline and a later run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Change {
+    /// Got worse: e.g. Passed → Warning or Warning → Failed
+    Regressed,
+    /// Got better: e.g. Failed → Passed
+    Improved,
+    /// Only in the later run
+    Added,
+    /// Only in the baseline
+    Removed,
+    /// Moved to or from Skipped or Error, which says nothing about posture
+    Changed,
+    /// Same outcome in both runs
+    Unchanged,
+}
+
+impl TestDiff {
+    /// True when the two sides disagree, including a test that only ran on one side
+    pub fn is_divergent(&self) -> bool {
+        self.reference != self.candidate
+    }
+
+    /// Classify the move from the reference (baseline) to the candidate
+    pub fn change(&self) -> Change {
+        match (&self.reference, &self.candidate) {
+            (None, _) => Change::Added,
+            (_, None) => Change::Removed,
+            (Some(before), Some(after)) if before == after => Change::Unchanged,
+            (Some(before), Some(after)) => match (posture_rank(before), posture_rank(after)) {
+                (Some(before), Some(after)) if after < before => Change::Regressed,
+                (Some(_), Some(_)) => Change::Improved,
+                _ => Change::Changed,
+            },
+        }
+    }
+}
+
+/// Ordering of outcomes that reflect the device's security posture, best first
+fn posture_rank(status: &TestStatus) -> Option<u8> {
+    match status {
+        TestStatus::Passed => Some(2),
+        TestStatus::Warning => Some(1),
+        TestStatus::Failed => Some(0),
+        TestStatus::Skipped | TestStatus::Error => None,
+    }
+}
+
+/// Per-test comparison of a candidate run against a reference run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultsDiff {
+    pub reference_label: String,
+    pub candidate_label: String,
+    pub entries: Vec<TestDiff>,
+}
+
+impl ResultsDiff {
+    pub fn divergent(&self) -> impl Iterator<Item = &TestDiff> {
+        self.entries.iter().filter(|entry| entry.is_divergent())
+    }
+
+    pub fn has_divergence(&self) -> bool {
+        self.divergent().next().is_some()
+    }
+
+    pub fn has_regression(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.change() == Change::Regressed)
+    }
+}
+
+/// Compare two result sets test by test, ordered by test ID
+pub fn diff_results(
+    reference_label: &str,
+    reference: &TestSuiteResults,
+    candidate_label: &str,
+    candidate: &TestSuiteResults,
+) -> ResultsDiff {
+    let mut entries: BTreeMap<&str, TestDiff> = BTreeMap::new();
+
+    for (is_reference, results) in [(true, reference), (false, candidate)] {
+        for result in &results.results {
+            let entry = entries
+                .entry(result.test_id.as_str())
+                .or_insert_with(|| TestDiff {
+                    test_id: result.test_id.clone(),
+                    test_name: result.test_name.clone(),
+                    reference: None,
+                    candidate: None,
+                    reference_message: None,
+                    candidate_message: None,
+                });
+            if is_reference {
+                entry.reference = Some(result.status.clone());
+                entry.reference_message = Some(result.message.clone());
+            } else {
+                entry.candidate = Some(result.status.clone());
+                entry.candidate_message = Some(result.message.clone());
+            }
+        }
+    }
+
+    ResultsDiff {
+        reference_label: reference_label.to_string(),
+        candidate_label: candidate_label.to_string(),
+        entries: entries.into_values().collect(),
+    }
+}
+
+/// Render a diff for the console, listing only the tests that diverge
+pub fn render_diff(diff: &ResultsDiff) -> String {
+    let mut output = String::new();
+    let divergent: Vec<&TestDiff> = diff.divergent().collect();
+
+    output.push_str(&format!(
+        "\n{}\n",
+        format!(
+            "🔍 Comparison: {} (reference) vs {}",
+            diff.reference_label, diff.candidate_label
+        )
+        .bold()
+    ));
+
+    if divergent.is_empty() {
+        output.push_str(&format!(
+            "  {} All {} tests match the reference\n",
+            "✅".green(),
+            diff.entries.len()
+        ));
+        return output;
+    }
+
+    output.push_str(&format!(
+        "  {} {} of {} tests diverge from the reference\n\n",
+        "⚠️".yellow(),
+        divergent.len(),
+        diff.entries.len()
+    ));
+
+    for entry in divergent {
+        output.push_str(&format!(
+            "  • {} - {}: {} → {}\n",
+            entry.test_id,
+            entry.test_name,
+            status_text(entry.reference.as_ref()),
+            status_text(entry.candidate.as_ref()).bold()
+        ));
+        if let Some(message) = &entry.reference_message {
+            output.push_str(&format!("      {}: {}\n", diff.reference_label, message));
+        }
+        if let Some(message) = &entry.candidate_message {
+            output.push_str(&format!("      {}: {}\n", diff.candidate_label, message));
+        }
+    }
+
+    output
+}
+
+/// Render a baseline comparison for the console, grouped by kind of change
+pub fn render_baseline_diff(diff: &ResultsDiff) -> String {
+    let mut output = format!(
+        "\n{}\n",
+        format!("📉 Baseline comparison: {}", diff.reference_label).bold()
+    );
+
+    let groups = [
+        (Change::Regressed, "❌ Regressed"),
+        (Change::Improved, "✅ Improved"),
+        (Change::Added, "➕ Added"),
+        (Change::Removed, "➖ Removed"),
+        (Change::Changed, "🔄 Changed"),
+    ];
+    let mut any_change = false;
+    for (change, heading) in groups {
+        let entries: Vec<&TestDiff> = diff
+            .entries
+            .iter()
+            .filter(|entry| entry.change() == change)
+            .collect();
+        if entries.is_empty() {
+            continue;
+        }
+        any_change = true;
+        output.push_str(&format!("  {} ({}):\n", heading, entries.len()));
+        for entry in entries {
+            output.push_str(&format!(
+                "    • {} - {}: {} → {}\n",
+                entry.test_id,
+                entry.test_name,
+                status_text(entry.reference.as_ref()),
+                status_text(entry.candidate.as_ref())
+            ));
+        }
+    }
+
+    if !any_change {
+        output.push_str(&format!(
+            "  {} No changes across {} tests\n",
+            "✅".green(),
+            diff.entries.len()
+        ));
+    }
+    output
+}
+
+fn status_text(status: Option<&TestStatus>) -> String {
+    match status {
+        Some(status) => format!("{:?}", status).to_uppercase(),
+        None => "NOT RUN".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{create_test_result, TestResult};
+    use std::time::Duration;
+
+    fn result(test_id: &str, status: TestStatus) -> TestResult {
+        create_test_result(
+            test_id,
+            test_id,
+            "runtime",
+            status,
+            "message",
+            None,
+            Duration::ZERO,
+        )
+    }
+
+    #[test]
+    fn test_diff_results_flags_status_changes_and_missing_tests() {
+        let reference = TestSuiteResults::sample(vec![
+            result("runtime_001", TestStatus::Passed),
+            result("runtime_002", TestStatus::Passed),
+        ]);
+        let candidate = TestSuiteResults::sample(vec![
+            result("runtime_001", TestStatus::Passed),
+            result("runtime_002", TestStatus::Failed),
+            result("runtime_003", TestStatus::Warning),
+        ]);
+
+        let diff = diff_results("golden", &reference, "dut", &candidate);
+        let divergent: Vec<&str> = diff.divergent().map(|e| e.test_id.as_str()).collect();
+
+        assert_eq!(diff.entries.len(), 3);
+        assert_eq!(divergent, vec!["runtime_002", "runtime_003"]);
+        assert!(diff.has_divergence());
+    }
+
+    #[test]
+    fn test_baseline_changes_are_classified() {
+        let baseline = TestSuiteResults::sample(vec![
+            result("runtime_001", TestStatus::Passed),
+            result("runtime_002", TestStatus::Failed),
+            result("runtime_003", TestStatus::Warning),
+            result("runtime_004", TestStatus::Passed),
+            result("runtime_005", TestStatus::Failed),
+        ]);
+        let current = TestSuiteResults::sample(vec![
+            result("runtime_001", TestStatus::Warning),
+            result("runtime_002", TestStatus::Passed),
+            result("runtime_003", TestStatus::Skipped),
+            result("runtime_005", TestStatus::Failed),
+            result("runtime_006", TestStatus::Failed),
+        ]);
+
+        let diff = diff_results("baseline.json", &baseline, "current", &current);
+        let changes: Vec<Change> = diff.entries.iter().map(TestDiff::change).collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                Change::Regressed,
+                Change::Improved,
+                Change::Changed,
+                Change::Removed,
+                Change::Unchanged,
+                Change::Added,
+            ]
+        );
+        assert!(diff.has_regression());
+
+        let diff = diff_results("baseline.json", &baseline, "baseline", &baseline);
+        assert!(!diff.has_regression());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::tests::SkipReason;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,6 +18,14 @@ pub enum Error {
     #[error("Operation not supported: {0}")]
     Unsupported(String),
 
+    /// A test that could not or need not run here, reported as Skipped
+    #[error("Skipped: {message}")]
+    Skipped {
+        reason: SkipReason,
+        message: String,
+        details: Option<String>,
+    },
+
     #[error("Command execution failed: {0}")]
     CommandExecution(String),
 
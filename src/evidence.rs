@@ -0,0 +1,413 @@
+/*
+ * Security Compliance CLI - Certification Evidence Bundle
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+use crate::{
+    compliance::{
+        format_compliance_report_as_markdown, generate_pdf_report, ComplianceReport,
+        CraComplianceReporter, Iec62443Reporter, NistComplianceReporter, RedComplianceReporter,
+    },
+    error::{Error, Result},
+    output::write_atomically,
+    secret::Redactor,
+    tests::TestSuiteResults,
+};
+use chrono::Utc;
+use flate2::{write::GzEncoder, Compression};
+use serde::Serialize;
+use ssh_key::{
+    sha2::{Digest, Sha256},
+    HashAlg, LineEnding, PrivateKey,
+};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tracing::warn;
+
+pub use crate::secret::REDACTED;
+
+/// Version of the bundle layout, bumped whenever files move or change meaning
+pub const BUNDLE_FORMAT_VERSION: u32 = 2;
+
+/// SSH signature namespace of `manifest.json.sig`, for
+/// `ssh-keygen -Y verify -n security-compliance-evidence`
+pub const SIGNATURE_NAMESPACE: &str = "security-compliance-evidence";
+
+/// Size of a tar header and of the blocks file contents are padded to
+const TAR_BLOCK: usize = 512;
+
+/// Top-level index of an evidence bundle, written as `manifest.json`
+#[derive(Debug, Serialize)]
+pub struct BundleManifest {
+    pub bundle_format: u32,
+    pub tool: ToolInfo,
+    pub created_at: chrono::DateTime<Utc>,
+    pub run: RunMetadata,
+    /// SHA-256 fingerprint of the key that signed this manifest, if any
+    pub signed_by: Option<String>,
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolInfo {
+    pub name: String,
+    pub version: String,
+    /// Commit the binary was built from, recorded by `build.rs`
+    pub commit: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunMetadata {
+    pub suite_name: String,
+    pub test_mode: String,
+    pub timestamp: chrono::DateTime<Utc>,
+    pub duration_secs: f64,
+    pub total_tests: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub warnings: usize,
+    pub skipped: usize,
+    pub errors: usize,
+    pub overall_passed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleFile {
+    pub path: String,
+    pub bytes: u64,
+    pub sha256: String,
+    pub description: String,
+}
+
+/// Package a run into a single `.tar.gz` for submission to a notified body.
+///
+/// The bundle holds the JSON results, the CRA, RED, NIST and IEC 62443
+/// reports, per-test transcripts, system information and a manifest with tool
+/// and run metadata and the SHA-256 of every file. With a `signing_key`
+/// (an unencrypted OpenSSH private key) the manifest is signed into
+/// `manifest.json.sig`. Captured text is masked with `redactor` before
+/// anything is written.
+pub fn write_evidence_bundle(
+    results: &TestSuiteResults,
+    redactor: &Redactor,
+    signing_key: Option<&Path>,
+    path: &Path,
+) -> Result<BundleManifest> {
+    let signing_key = signing_key.map(load_signing_key).transpose()?;
+    let results = &redact_results(results, redactor);
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut files = Vec::new();
+
+    stage_file(
+        &mut entries,
+        &mut files,
+        "results.json",
+        serde_json::to_string_pretty(results)?.into_bytes(),
+        "Full test results as produced by --format json",
+    );
+
+    let system_info = redactor.redact(&serde_json::to_string_pretty(&results.system_info)?);
+    stage_file(
+        &mut entries,
+        &mut files,
+        "system-info.json",
+        system_info.into_bytes(),
+        "System information collected from the device under test",
+    );
+
+    let reports: [(&str, ComplianceReport); 4] = [
+        ("cra", CraComplianceReporter::generate_report(results)),
+        ("red", RedComplianceReporter::generate_report(results)),
+        ("nist", NistComplianceReporter::generate_report(results)),
+        ("iec62443", Iec62443Reporter::generate_report(results)),
+    ];
+    let pdf_dir = tempfile::tempdir()?;
+    for (framework, report) in &reports {
+        stage_file(
+            &mut entries,
+            &mut files,
+            &format!("reports/{}-report.md", framework),
+            format_compliance_report_as_markdown(report).into_bytes(),
+            &format!("{} compliance report", report.report_type),
+        );
+
+        let pdf_path = pdf_dir.path().join(format!("{}-report.pdf", framework));
+        match generate_pdf_report(report, &pdf_path.to_string_lossy()) {
+            Ok(()) => stage_file(
+                &mut entries,
+                &mut files,
+                &format!("reports/{}-report.pdf", framework),
+                fs::read(&pdf_path)?,
+                &format!("{} compliance report (PDF)", report.report_type),
+            ),
+            Err(e) => warn!("⚠️  Skipping {} PDF report in bundle: {}", framework, e),
+        }
+    }
+
+    for result in &results.results {
+        stage_file(
+            &mut entries,
+            &mut files,
+            &format!("transcripts/{}.txt", result.test_id),
+            format_transcript(result).into_bytes(),
+            &format!("Captured output of {}", result.test_name),
+        );
+    }
+
+    let manifest = BundleManifest {
+        bundle_format: BUNDLE_FORMAT_VERSION,
+        tool: ToolInfo {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            commit: option_env!("GIT_COMMIT").map(str::to_string),
+        },
+        created_at: Utc::now(),
+        run: RunMetadata {
+            suite_name: results.suite_name.clone(),
+            test_mode: results.test_mode.clone(),
+            timestamp: results.timestamp,
+            duration_secs: results.duration.as_secs_f64(),
+            total_tests: results.total_tests,
+            passed: results.passed,
+            failed: results.failed,
+            warnings: results.warnings,
+            skipped: results.skipped,
+            errors: results.errors,
+            overall_passed: results.overall_passed(),
+        },
+        signed_by: signing_key
+            .as_ref()
+            .map(|key| key.fingerprint(HashAlg::Sha256).to_string()),
+        files,
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest)?.into_bytes();
+    if let Some(key) = &signing_key {
+        let signature = key
+            .sign(SIGNATURE_NAMESPACE, HashAlg::Sha512, &manifest_json)
+            .and_then(|signature| signature.to_pem(LineEnding::LF))
+            .map_err(|e| Error::Config(format!("Failed to sign the bundle manifest: {}", e)))?;
+        entries.push(("manifest.json.sig".to_string(), signature.into_bytes()));
+    }
+    entries.push(("manifest.json".to_string(), manifest_json));
+
+    let mut archive = GzEncoder::new(Vec::new(), Compression::default());
+    let mtime = manifest.created_at.timestamp().max(0) as u64;
+    for (name, contents) in &entries {
+        append_tar_entry(&mut archive, name, contents, mtime)?;
+    }
+    // End of archive: two zero blocks
+    archive.write_all(&[0; 2 * TAR_BLOCK])?;
+    write_atomically(path, &archive.finish()?)?;
+
+    Ok(manifest)
+}
+
+/// Read the OpenSSH private key bundles are signed with
+fn load_signing_key(path: &Path) -> Result<PrivateKey> {
+    let key = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| PrivateKey::from_openssh(content).map_err(|e| e.to_string()))
+        .map_err(|e| Error::Config(format!("Cannot read signing key {}: {}", path.display(), e)))?;
+    if key.is_encrypted() {
+        return Err(Error::Config(format!(
+            "Signing key {} is passphrase-protected; use an unencrypted key",
+            path.display()
+        )));
+    }
+    Ok(key)
+}
+
+/// A copy of `results` with every captured message, detail and remediation
+/// command masked
+fn redact_results(results: &TestSuiteResults, redactor: &Redactor) -> TestSuiteResults {
+    let mut redacted = results.clone();
+    for result in &mut redacted.results {
+        result.message = redactor.redact(&result.message);
+        result.details = result.details.as_deref().map(|d| redactor.redact(d));
+        result.remediation = result.remediation.as_ref().map(|commands| {
+            commands
+                .iter()
+                .map(|command| redactor.redact(command))
+                .collect()
+        });
+    }
+    redacted
+}
+
+fn stage_file(
+    entries: &mut Vec<(String, Vec<u8>)>,
+    files: &mut Vec<BundleFile>,
+    name: &str,
+    contents: Vec<u8>,
+    description: &str,
+) {
+    files.push(BundleFile {
+        path: name.to_string(),
+        bytes: contents.len() as u64,
+        sha256: format!("{:x}", Sha256::digest(&contents)),
+        description: description.to_string(),
+    });
+    entries.push((name.to_string(), contents));
+}
+
+/// Write one regular file as a ustar entry, so the bundle is built without an
+/// external `tar`
+fn append_tar_entry(
+    archive: &mut impl Write,
+    name: &str,
+    contents: &[u8],
+    mtime: u64,
+) -> Result<()> {
+    if name.len() > 100 {
+        return Err(Error::Config(format!(
+            "Bundle file name too long for tar: {}",
+            name
+        )));
+    }
+
+    let mut header = [0u8; TAR_BLOCK];
+    let mut field = |offset: usize, value: &[u8]| {
+        header[offset..offset + value.len()].copy_from_slice(value);
+    };
+    field(0, name.as_bytes());
+    field(100, b"0000644\0");
+    field(108, b"0000000\0");
+    field(116, b"0000000\0");
+    field(124, format!("{:011o}\0", contents.len()).as_bytes());
+    field(136, format!("{:011o}\0", mtime).as_bytes());
+    field(148, b"        ");
+    field(156, b"0");
+    field(257, b"ustar\0");
+    field(263, b"00");
+
+    let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+    archive.write_all(&header)?;
+    archive.write_all(contents)?;
+    let padding = (TAR_BLOCK - contents.len() % TAR_BLOCK) % TAR_BLOCK;
+    archive.write_all(&vec![0; padding])?;
+    Ok(())
+}
+
+fn format_transcript(result: &crate::tests::TestResult) -> String {
+    let mut transcript = format!(
+        "Test: {} - {}\nCategory: {}\nStatus: {:?}\nDuration: {:.3}s\nTimestamp: {}\n\nMessage:\n{}\n",
+        result.test_id,
+        result.test_name,
+        result.category,
+        result.status,
+        result.duration.as_secs_f64(),
+        result.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+        result.message
+    );
+    if let Some(details) = &result.details {
+        transcript.push_str(&format!("\nDetails:\n{}\n", details));
+    }
+    if let Some(commands) = &result.remediation {
+        transcript.push_str("\nSuggested remediation:\n");
+        for command in commands {
+            transcript.push_str(&format!("  {}\n", command));
+        }
+    }
+    transcript
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{create_test_result, TestStatus};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use std::time::Duration;
+
+    /// Name and contents of every file in a `.tar.gz`
+    fn read_bundle(path: &Path) -> Vec<(String, String)> {
+        let mut tar = Vec::new();
+        GzDecoder::new(fs::File::open(path).unwrap())
+            .read_to_end(&mut tar)
+            .unwrap();
+
+        let mut files = Vec::new();
+        let mut offset = 0;
+        while tar[offset] != 0 {
+            let header = &tar[offset..offset + TAR_BLOCK];
+            let name = String::from_utf8_lossy(&header[..100])
+                .trim_end_matches('\0')
+                .to_string();
+            let size_field = String::from_utf8_lossy(&header[124..135]).to_string();
+            let size = usize::from_str_radix(&size_field, 8).unwrap();
+            let start = offset + TAR_BLOCK;
+            let contents = String::from_utf8_lossy(&tar[start..start + size]).to_string();
+            files.push((name, contents));
+            offset = start + size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+        }
+        files
+    }
+
+    #[test]
+    fn test_bundle_is_built_in_process_and_masks_credentials_only() {
+        let results = TestSuiteResults::sample(vec![create_test_result(
+            "runtime_001",
+            "Root Account",
+            "runtime",
+            TestStatus::Warning,
+            "Logged in as fio",
+            Some("echo 'fio' | sudo -S cat /etc/shadow\nfio:$6$salt$hash:19000::::::".to_string()),
+            Duration::ZERO,
+        )]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("evidence.tar.gz");
+
+        let key = PrivateKey::random(&mut rand::rngs::OsRng, ssh_key::Algorithm::Ed25519).unwrap();
+        let key_path = dir.path().join("signing_key");
+        fs::write(&key_path, key.to_openssh(LineEnding::LF).unwrap()).unwrap();
+
+        let manifest = write_evidence_bundle(
+            &results,
+            &Redactor::new(&[]).unwrap(),
+            Some(&key_path),
+            &path,
+        )
+        .unwrap();
+
+        let files = read_bundle(&path);
+        let file = |name: &str| {
+            files
+                .iter()
+                .find(|(path, _)| path == name)
+                .map(|(_, contents)| contents.clone())
+                .unwrap()
+        };
+        let transcript = file("transcripts/runtime_001.txt");
+        assert!(transcript.contains("Logged in as fio"));
+        assert!(transcript.contains("echo '[REDACTED]' | sudo -S cat /etc/shadow"));
+        assert!(transcript.contains("fio:[REDACTED]:19000"));
+        assert!(!file("results.json").contains("$6$salt$hash"));
+        assert!(file("manifest.json").contains("\"bundle_format\": 2"));
+        assert!(file("reports/nist-report.md").contains("NIST"));
+        assert!(files
+            .iter()
+            .any(|(path, _)| path == "reports/iec62443-report.md"));
+
+        let results_entry = manifest
+            .files
+            .iter()
+            .find(|entry| entry.path == "results.json")
+            .unwrap();
+        assert_eq!(
+            results_entry.sha256,
+            format!("{:x}", Sha256::digest(file("results.json").as_bytes()))
+        );
+        let signature: ssh_key::SshSig = file("manifest.json.sig").parse().unwrap();
+        key.public_key()
+            .verify(
+                SIGNATURE_NAMESPACE,
+                file("manifest.json").as_bytes(),
+                &signature,
+            )
+            .unwrap();
+    }
+}
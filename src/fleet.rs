@@ -0,0 +1,299 @@
+/*
+ * Security Compliance CLI - Fleet Runs
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+//! Running one suite against many identical devices with `--targets`.
+//!
+//! The targets file lists the SSH devices to test, in TOML:
+//!
+//! ```toml
+//! [[targets]]
+//! name = "lab-01"
+//! host = "192.168.0.36"
+//!
+//! [[targets]]
+//! host = "192.168.0.37"
+//! user = "root"
+//! ```
+//!
+//! or the same structure in YAML (`.yaml`/`.yml`) or JSON. Anything a target
+//! leaves out is taken from the `[communication]` settings of the run.
+
+use crate::config::CommunicationConfig;
+use crate::tests::{CategorySummary, TestSuiteResults};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Default number of devices tested at the same time
+pub const DEFAULT_TARGET_JOBS: usize = 4;
+
+/// One device of a fleet run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TargetConfig {
+    /// Name the device is reported under; defaults to its host
+    #[serde(default)]
+    pub name: Option<String>,
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TargetsFile {
+    targets: Vec<TargetConfig>,
+}
+
+impl TargetConfig {
+    /// Name for reports, also used for the per-target results file
+    pub fn label(&self) -> String {
+        let label = self.name.clone().unwrap_or_else(|| match self.port {
+            Some(port) => format!("{}:{}", self.host, port),
+            None => self.host.clone(),
+        });
+        label
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || "._-".contains(c) {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    /// Point a copy of the run's communication settings at this device
+    pub fn apply(&self, communication: &mut CommunicationConfig) {
+        communication.channel_type = "ssh".to_string();
+        communication.host = Some(self.host.clone());
+        if self.port.is_some() {
+            communication.port = self.port;
+        }
+        if self.user.is_some() {
+            communication.user.clone_from(&self.user);
+        }
+        if self.password.is_some() {
+            communication.password.clone_from(&self.password);
+        }
+        if self.ssh_key_path.is_some() {
+            communication.ssh_key_path.clone_from(&self.ssh_key_path);
+        }
+    }
+}
+
+/// Read a TOML, YAML or JSON targets file. YAML is recognised by its
+/// extension, JSON by its opening brace.
+pub fn load_targets(path: &Path) -> Result<Vec<TargetConfig>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read targets file {}", path.display()))?;
+    let yaml = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| matches!(extension.to_ascii_lowercase().as_str(), "yaml" | "yml"));
+    let file = read_targets_file(&content, yaml)
+        .with_context(|| format!("Invalid targets file {}", path.display()))?;
+    parse_targets(file.targets).with_context(|| format!("Invalid targets file {}", path.display()))
+}
+
+fn read_targets_file(content: &str, yaml: bool) -> Result<TargetsFile> {
+    Ok(if yaml {
+        serde_yaml::from_str(content)?
+    } else if content.trim_start().starts_with('{') {
+        serde_json::from_str(content)?
+    } else {
+        toml::from_str(content)?
+    })
+}
+
+fn parse_targets(targets: Vec<TargetConfig>) -> Result<Vec<TargetConfig>> {
+    if targets.is_empty() {
+        anyhow::bail!("no targets listed");
+    }
+    let mut labels: Vec<String> = Vec::new();
+    for target in &targets {
+        let label = target.label();
+        if labels.contains(&label) {
+            anyhow::bail!("target '{}' is listed more than once", label);
+        }
+        labels.push(label);
+    }
+    Ok(targets)
+}
+
+/// Outcome of the suite on one device of the fleet
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetTargetResult {
+    pub host: String,
+    pub passed: bool,
+    /// Why the suite could not be run, e.g. the device was unreachable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results: Option<TestSuiteResults>,
+}
+
+impl FleetTargetResult {
+    pub fn completed(host: &str, results: TestSuiteResults) -> Self {
+        Self {
+            host: host.to_string(),
+            passed: results.overall_passed(),
+            error: None,
+            results: Some(results),
+        }
+    }
+
+    pub fn failed(host: &str, error: String) -> Self {
+        Self {
+            host: host.to_string(),
+            passed: false,
+            error: Some(error),
+            results: None,
+        }
+    }
+}
+
+/// Combined report of a fleet run, keyed by target
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetReport {
+    pub suite_name: String,
+    pub timestamp: DateTime<Utc>,
+    pub total_targets: usize,
+    pub passed_targets: usize,
+    pub targets: BTreeMap<String, FleetTargetResult>,
+}
+
+impl FleetReport {
+    pub fn new(suite_name: &str, targets: BTreeMap<String, FleetTargetResult>) -> Self {
+        Self {
+            suite_name: suite_name.to_string(),
+            timestamp: Utc::now(),
+            total_targets: targets.len(),
+            passed_targets: targets.values().filter(|target| target.passed).count(),
+            targets,
+        }
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.passed_targets == self.total_targets
+    }
+}
+
+/// Render which devices passed, one line per target
+pub fn render_fleet_summary(report: &FleetReport) -> String {
+    let mut output = format!(
+        "\n{}\n",
+        format!(
+            "🚚 Fleet summary: {}/{} targets passed",
+            report.passed_targets, report.total_targets
+        )
+        .bold()
+    );
+    for (label, target) in &report.targets {
+        let (icon, outcome) = match (&target.results, &target.error) {
+            (Some(results), _) => {
+                let counts = CategorySummary {
+                    category: label.clone(),
+                    total: results.total_tests,
+                    passed: results.passed,
+                    failed: results.failed,
+                    warnings: results.warnings,
+                    skipped: results.skipped,
+                    errors: results.errors,
+                };
+                let icon = if target.passed {
+                    "✅".green()
+                } else {
+                    "❌".red()
+                };
+                (icon, counts.to_string())
+            }
+            (None, error) => ("💥".red(), error.clone().unwrap_or_default()),
+        };
+        output.push_str(&format!(
+            "  {} {} ({}): {}\n",
+            icon, label, target.host, outcome
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{create_test_result, TestStatus};
+    use std::time::Duration;
+
+    #[test]
+    fn test_targets_file_and_fleet_summary() {
+        let file: TargetsFile = toml::from_str(
+            "[[targets]]\nname = \"lab-01\"\nhost = \"192.168.0.36\"\n\n[[targets]]\nhost = \"192.168.0.37\"\nport = 2222\nuser = \"root\"\n",
+        )
+        .unwrap();
+        let targets = parse_targets(file.targets).unwrap();
+        assert_eq!(targets[0].label(), "lab-01");
+        assert_eq!(targets[1].label(), "192.168.0.37_2222");
+
+        let mut communication = crate::config::Config::default().communication;
+        communication.user = Some("fio".to_string());
+        targets[1].apply(&mut communication);
+        assert_eq!(communication.host.as_deref(), Some("192.168.0.37"));
+        assert_eq!(communication.port, Some(2222));
+        assert_eq!(communication.user.as_deref(), Some("root"));
+
+        let duplicate = vec![targets[0].clone(), targets[0].clone()];
+        assert!(parse_targets(duplicate).is_err());
+
+        let yaml = read_targets_file(
+            "targets:\n  - name: lab-01\n    host: 192.168.0.36\n  - host: 192.168.0.37\n    port: 2222\n",
+            true,
+        )
+        .unwrap();
+        let yaml_targets = parse_targets(yaml.targets).unwrap();
+        assert_eq!(yaml_targets[0].label(), "lab-01");
+        assert_eq!(yaml_targets[1].label(), "192.168.0.37_2222");
+
+        let result = |test_id: &str, status: TestStatus| {
+            create_test_result(test_id, test_id, "boot", status, "", None, Duration::ZERO)
+        };
+        let results = TestSuiteResults::sample(vec![
+            result("boot_001", TestStatus::Passed),
+            result("boot_002", TestStatus::Passed),
+            result("boot_003", TestStatus::Failed),
+        ]);
+        let report = FleetReport::new(
+            "All",
+            BTreeMap::from([
+                (
+                    "lab-01".to_string(),
+                    FleetTargetResult {
+                        passed: false,
+                        ..FleetTargetResult::completed("192.168.0.36", results)
+                    },
+                ),
+                (
+                    "lab-02".to_string(),
+                    FleetTargetResult::failed("192.168.0.37", "connection refused".to_string()),
+                ),
+            ]),
+        );
+        assert!(!report.all_passed());
+        let summary = render_fleet_summary(&report);
+        assert!(summary.contains("0/2 targets passed"));
+        assert!(summary.contains("lab-01 (192.168.0.36): 2/3 passed, 1 failed"));
+        assert!(summary.contains("lab-02 (192.168.0.37): connection refused"));
+    }
+}
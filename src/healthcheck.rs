@@ -0,0 +1,297 @@
+/*
+ * Security Compliance CLI - Environment Health Check
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+use crate::target::Target;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Target tools that many tests rely on
+pub const CORE_TOOLS: &[&str] = &["dmesg", "openssl", "systemctl", "ss"];
+
+/// Optional target tools that individual tests rely on
+pub const OPTIONAL_TOOLS: &[&str] = &["iptables", "nft", "docker", "ostree"];
+
+/// Tests that report reduced results without each core tool
+const TOOL_DEPENDENT_TESTS: &[(&str, &[&str])] = &[
+    (
+        "dmesg",
+        &[
+            "boot_001",
+            "boot_002",
+            "boot_003",
+            "boot_004",
+            "boot_005",
+            "boot_006",
+            "boot_007",
+            "compliance_001",
+            "hardware_001",
+            "hardware_003",
+            "hardware_006",
+            "hardware_007",
+            "hardware_008",
+            "hardware_009",
+            "runtime_007",
+            "runtime_009",
+        ],
+    ),
+    (
+        "openssl",
+        &[
+            "certificate_001",
+            "certificate_003",
+            "certificate_004",
+            "certificate_005",
+            "certificate_007",
+            "certificate_008",
+            "certificate_010",
+            "certificate_011",
+            "certificate_012",
+            "compliance_001",
+            "compliance_007",
+            "network_005",
+        ],
+    ),
+    (
+        "systemctl",
+        &[
+            "certificate_003",
+            "certificate_009",
+            "compliance_004",
+            "compliance_005",
+            "hardware_002",
+            "network_002",
+            "network_006",
+            "production_001",
+            "production_005",
+            "production_006",
+            "production_007",
+            "production_008",
+            "production_009",
+            "runtime_002",
+            "runtime_004",
+            "runtime_006",
+            "runtime_009",
+            "runtime_010",
+            "runtime_013",
+            "runtime_015",
+        ],
+    ),
+    (
+        "ss",
+        &[
+            "certificate_011",
+            "network_001",
+            "network_006",
+            "network_007",
+        ],
+    ),
+];
+
+/// Tests that need root, directly or through sudo, for their privileged probes
+const PRIVILEGED_TESTS: &[&str] = &[
+    "boot_008",
+    "compliance_004",
+    "compliance_005",
+    "compliance_006",
+    "compliance_007",
+    "compliance_008",
+    "container_008",
+    "hardware_010",
+    "network_006",
+    "runtime_002",
+    "runtime_005",
+    "runtime_016",
+];
+
+const ECHO_MARKER: &str = "security-compliance-healthcheck";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SudoAccess {
+    Passwordless,
+    WithPassword,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub connected: bool,
+    pub connection_error: Option<String>,
+    pub echo_ok: bool,
+    pub shell: Option<String>,
+    /// Login user reported by `whoami`
+    pub user: Option<String>,
+    /// Operating system reported by `uname -s`
+    pub os_type: Option<String>,
+    pub sudo: SudoAccess,
+    pub tools: Vec<(String, bool)>,
+}
+
+impl HealthReport {
+    /// True when the target is reachable, can run commands at all and runs Linux
+    pub fn is_testable(&self) -> bool {
+        self.connected && self.echo_ok && self.is_linux()
+    }
+
+    /// False only when the target positively reported a non-Linux OS
+    pub fn is_linux(&self) -> bool {
+        self.os_type
+            .as_deref()
+            .is_none_or(|os| os.eq_ignore_ascii_case("linux"))
+    }
+
+    pub fn missing_tools(&self) -> Vec<&str> {
+        self.tools
+            .iter()
+            .filter(|(_, present)| !present)
+            .map(|(tool, _)| tool.as_str())
+            .collect()
+    }
+
+    /// Whether privileged probes can run, as root or through sudo
+    pub fn is_privileged(&self) -> bool {
+        self.user.as_deref() == Some("root") || self.sudo != SudoAccess::Unavailable
+    }
+
+    /// Tests that will report reduced results, with the missing tool or
+    /// privilege that degrades them
+    pub fn degraded_tests(&self) -> Vec<(String, &'static [&'static str])> {
+        let missing = self.missing_tools();
+        let mut degraded: Vec<(String, &'static [&'static str])> = TOOL_DEPENDENT_TESTS
+            .iter()
+            .filter(|(tool, _)| missing.contains(tool))
+            .map(|(tool, tests)| (format!("{} not found", tool), *tests))
+            .collect();
+        if !self.is_privileged() {
+            degraded.push(("no root or sudo access".to_string(), PRIVILEGED_TESTS));
+        }
+        degraded
+    }
+}
+
+/// Preflight check that the target can be tested, without running any security tests
+pub async fn run_healthcheck(target: &mut Target) -> HealthReport {
+    let mut report = HealthReport {
+        connected: false,
+        connection_error: None,
+        echo_ok: false,
+        shell: None,
+        user: None,
+        os_type: None,
+        sudo: SudoAccess::Unavailable,
+        tools: Vec::new(),
+    };
+
+    if let Err(e) = target.connect().await {
+        report.connection_error = Some(e.to_string());
+        return report;
+    }
+    report.connected = true;
+
+    match target
+        .execute_command(&format!("echo {}", ECHO_MARKER))
+        .await
+    {
+        Ok(result) => report.echo_ok = result.stdout.contains(ECHO_MARKER),
+        Err(e) => debug!("Echo check failed: {}", e),
+    }
+
+    if !report.echo_ok {
+        return report;
+    }
+
+    if let Ok(result) = target.execute_command("uname -s").await {
+        let os = result.stdout.trim();
+        if result.success() && !os.is_empty() {
+            report.os_type = Some(os.to_string());
+        }
+    }
+    if !report.is_linux() {
+        return report;
+    }
+
+    if let Ok(result) = target
+        .execute_command("readlink /proc/$$/exe 2>/dev/null || echo \"$SHELL\"")
+        .await
+    {
+        let shell = result.stdout.trim();
+        if !shell.is_empty() {
+            report.shell = Some(shell.to_string());
+        }
+    }
+
+    if let Ok(result) = target.execute_command("whoami 2>/dev/null || id -un").await {
+        let user = result.stdout.trim();
+        if result.success() && !user.is_empty() {
+            report.user = Some(user.to_string());
+        }
+    }
+
+    report.sudo = check_sudo(target).await;
+
+    for tool in CORE_TOOLS.iter().chain(OPTIONAL_TOOLS) {
+        let present = target.has_command(tool).await;
+        report.tools.push((tool.to_string(), present));
+    }
+
+    report
+}
+
+async fn check_sudo(target: &mut Target) -> SudoAccess {
+    let command = format!(
+        "{} true 2>/dev/null",
+        target.elevation_prefix_non_interactive()
+    );
+    if let Ok(result) = target.execute_command(&command).await {
+        if result.success() {
+            return SudoAccess::Passwordless;
+        }
+    }
+
+    if target.elevation_reads_password() && !target.get_password().is_empty() {
+        let command = format!("{} -p '' true 2>/dev/null", target.elevation_pipe());
+        if let Ok(result) = target.execute_command(&command).await {
+            if result.success() {
+                return SudoAccess::WithPassword;
+            }
+        }
+    }
+
+    SudoAccess::Unavailable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degraded_tests_follow_missing_tools_and_privileges() {
+        let mut report = HealthReport {
+            connected: true,
+            connection_error: None,
+            echo_ok: true,
+            shell: Some("/bin/sh".to_string()),
+            user: Some("fio".to_string()),
+            os_type: Some("Linux".to_string()),
+            sudo: SudoAccess::WithPassword,
+            tools: vec![
+                ("dmesg".to_string(), true),
+                ("ss".to_string(), false),
+                ("docker".to_string(), false),
+            ],
+        };
+
+        let degraded = report.degraded_tests();
+        assert_eq!(degraded.len(), 1);
+        assert_eq!(degraded[0].0, "ss not found");
+        assert!(degraded[0].1.contains(&"network_001"));
+
+        report.sudo = SudoAccess::Unavailable;
+        assert_eq!(report.degraded_tests().len(), 2);
+
+        report.user = Some("root".to_string());
+        assert_eq!(report.degraded_tests().len(), 1);
+    }
+}
@@ -0,0 +1,283 @@
+/*
+ * Security Compliance CLI - Post-Run Hook
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+use crate::{
+    cli::NotifyOn,
+    config::HookConfig,
+    error::{Error, Result},
+    tests::{TestStatus, TestSuiteResults},
+};
+use serde::Serialize;
+use std::io::Write;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// Run the configured post-run command locally once a suite has finished.
+///
+/// The command runs through `sh -c` with the results and outcome exposed as
+/// environment variables:
+/// * `SECURITY_COMPLIANCE_RESULTS_FILE` - JSON results of this run
+/// * `SECURITY_COMPLIANCE_EXIT_STATUS` - exit status the tool is about to return
+/// * `SECURITY_COMPLIANCE_SUITE`, `_PASSED`, `_FAILED`, `_WARNINGS`, `_ERRORS`
+/// * `SECURITY_COMPLIANCE_OUTPUT` - the `--output` path, when one was given
+///
+/// A hook that fails or outruns its timeout is reported as an error; callers
+/// decide whether that is fatal (`--require-hook`).
+pub async fn run_post_run_hook(
+    hook: &HookConfig,
+    results: &TestSuiteResults,
+    exit_status: i32,
+    output: Option<&str>,
+) -> Result<()> {
+    let Some(command) = hook.post_run.as_deref().filter(|c| !c.trim().is_empty()) else {
+        return Ok(());
+    };
+
+    // The hook always gets a JSON copy of the results, whatever --format was
+    let mut results_file = tempfile::Builder::new()
+        .prefix("security-compliance-results-")
+        .suffix(".json")
+        .tempfile()?;
+    results_file.write_all(serde_json::to_string_pretty(results)?.as_bytes())?;
+    results_file.flush()?;
+
+    info!("🪝 Running post-run hook: {}", command);
+    let mut process = Command::new("sh");
+    process
+        .arg("-c")
+        .arg(command)
+        .env("SECURITY_COMPLIANCE_RESULTS_FILE", results_file.path())
+        .env("SECURITY_COMPLIANCE_EXIT_STATUS", exit_status.to_string())
+        .env("SECURITY_COMPLIANCE_SUITE", &results.suite_name)
+        .env("SECURITY_COMPLIANCE_PASSED", results.passed.to_string())
+        .env("SECURITY_COMPLIANCE_FAILED", results.failed.to_string())
+        .env("SECURITY_COMPLIANCE_WARNINGS", results.warnings.to_string())
+        .env("SECURITY_COMPLIANCE_ERRORS", results.errors.to_string())
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    if let Some(output) = output {
+        process.env("SECURITY_COMPLIANCE_OUTPUT", output);
+    }
+
+    let mut child = process
+        .spawn()
+        .map_err(|e| Error::CommandExecution(format!("Failed to start post-run hook: {}", e)))?;
+
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) if status.success() => {
+            info!("✅ Post-run hook completed");
+            Ok(())
+        }
+        Ok(Ok(status)) => Err(Error::CommandExecution(format!(
+            "Post-run hook exited with {}",
+            status
+        ))),
+        Ok(Err(e)) => Err(Error::CommandExecution(format!(
+            "Post-run hook failed: {}",
+            e
+        ))),
+        Err(_) => {
+            if let Err(e) = child.kill().await {
+                warn!("⚠️  Could not stop post-run hook: {}", e);
+            }
+            Err(Error::CommandExecution(format!(
+                "Post-run hook timed out after {}s",
+                hook.timeout_secs
+            )))
+        }
+    }
+}
+
+/// Run summary POSTed to `--notify-webhook`
+#[derive(Debug, Serialize)]
+pub struct WebhookSummary {
+    /// Slack-style incoming webhooks display this field
+    pub text: String,
+    pub host: String,
+    pub suite: String,
+    pub overall_passed: bool,
+    pub total_tests: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub warnings: usize,
+    pub skipped: usize,
+    pub errors: usize,
+    pub success_rate: f64,
+    pub failed_tests: Vec<String>,
+}
+
+impl WebhookSummary {
+    pub fn new(host: &str, results: &TestSuiteResults) -> Self {
+        let overall_passed = results.overall_passed();
+        let failed_tests: Vec<String> = results
+            .results
+            .iter()
+            .filter(|r| r.status == TestStatus::Failed)
+            .map(|r| r.test_id.clone())
+            .collect();
+        let mut text = format!(
+            "{} Security compliance {} on {}: {}/{} passed ({:.1}%)",
+            if overall_passed { "✅" } else { "❌" },
+            if overall_passed { "passed" } else { "failed" },
+            host,
+            results.passed,
+            results.total_tests,
+            results.success_rate()
+        );
+        if !failed_tests.is_empty() {
+            text.push_str(&format!(", failed: {}", failed_tests.join(", ")));
+        }
+
+        Self {
+            text,
+            host: host.to_string(),
+            suite: results.suite_name.clone(),
+            overall_passed,
+            total_tests: results.total_tests,
+            passed: results.passed,
+            failed: results.failed,
+            warnings: results.warnings,
+            skipped: results.skipped,
+            errors: results.errors,
+            success_rate: results.success_rate(),
+            failed_tests,
+        }
+    }
+}
+
+/// POST the run summary to the configured webhook, if any. Returns whether a
+/// notification was sent; `notify_on = failure` keeps passing runs quiet.
+pub async fn send_webhook_notification(
+    hook: &HookConfig,
+    host: &str,
+    results: &TestSuiteResults,
+) -> Result<bool> {
+    let Some(url) = hook
+        .notify_webhook
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())
+    else {
+        return Ok(false);
+    };
+    if hook.notify_on == NotifyOn::Failure && results.overall_passed() {
+        return Ok(false);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(hook.timeout_secs))
+        .build()
+        .map_err(|e| Error::Communication(format!("Failed to create HTTP client: {}", e)))?;
+    let response = client
+        .post(url)
+        .json(&WebhookSummary::new(host, results))
+        .send()
+        .await
+        .map_err(|e| Error::Communication(format!("Webhook delivery failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(Error::Communication(format!(
+            "Webhook returned {}",
+            response.status()
+        )));
+    }
+    info!("📣 Sent run summary to webhook");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> TestSuiteResults {
+        TestSuiteResults::sample(vec![crate::tests::create_test_result(
+            "boot_001",
+            "Secure Boot",
+            "boot",
+            TestStatus::Failed,
+            "Secure boot disabled",
+            None,
+            Duration::ZERO,
+        )])
+    }
+
+    fn hook(command: &str, timeout_secs: u64) -> HookConfig {
+        HookConfig {
+            post_run: Some(command.to_string()),
+            timeout_secs,
+            require: false,
+            notify_webhook: None,
+            notify_on: NotifyOn::Always,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_run_hook_environment_and_timeout() {
+        let check_env = hook(
+            "test \"$SECURITY_COMPLIANCE_EXIT_STATUS\" = 1 \
+             && test \"$SECURITY_COMPLIANCE_FAILED\" = 1 \
+             && grep -q suite_name \"$SECURITY_COMPLIANCE_RESULTS_FILE\"",
+            10,
+        );
+        assert!(run_post_run_hook(&check_env, &results(), 1, None)
+            .await
+            .is_ok());
+
+        assert!(run_post_run_hook(&hook("exit 3", 10), &results(), 0, None)
+            .await
+            .is_err());
+        assert!(run_post_run_hook(&hook("sleep 5", 0), &results(), 0, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_posts_summary_and_respects_notify_on() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !String::from_utf8_lossy(&request).contains("failed_tests") {
+                let n = stream.read(&mut buf).await.unwrap();
+                assert!(n > 0, "connection closed before the body arrived");
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let mut results = results();
+        let mut config = hook("true", 10);
+        config.notify_webhook = Some(url);
+        config.notify_on = NotifyOn::Failure;
+
+        assert!(send_webhook_notification(&config, "192.168.0.36", &results)
+            .await
+            .unwrap());
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /hook"));
+        assert!(request.contains("\"host\":\"192.168.0.36\""));
+        assert!(request.contains("\"failed_tests\":[\"boot_001\"]"));
+
+        // Passing runs stay quiet with notify_on = failure
+        results.failed = 0;
+        results.results.clear();
+        assert!(
+            !send_webhook_notification(&config, "192.168.0.36", &results)
+                .await
+                .unwrap()
+        );
+    }
+}
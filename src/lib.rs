@@ -19,21 +19,31 @@
  * Support: info@dynamicdevices.co.uk
  */
 
+pub mod checkpoint;
 pub mod cli;
 pub mod communication;
 pub mod compliance;
 pub mod config;
+pub mod diff;
 pub mod error;
+pub mod evidence;
+pub mod fleet;
+pub mod healthcheck;
+pub mod hook;
+pub mod local_channel;
 pub mod machine;
 pub mod output;
 pub mod runner;
+pub mod secret;
 #[cfg(not(target_os = "windows"))]
 pub mod serial_channel;
 #[cfg(target_os = "windows")]
 pub mod serial_channel_windows;
+pub mod snapshot;
 pub mod ssh_channel;
 pub mod ssh_key;
 pub mod target;
+pub mod telnet_channel;
 pub mod tests;
 
 pub use error::{Error, Result};
@@ -0,0 +1,151 @@
+/*
+ * Security Compliance CLI - Local Execution Channel
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+use crate::communication::{ChannelConfig, CommandOutput, CommunicationChannel};
+use crate::error::{Error, Result};
+use crate::secret::mask_sudo_password;
+use async_trait::async_trait;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::{debug, info};
+
+/// Runs commands on the machine the CLI itself runs on, for testing a device
+/// from its own shell (e.g. on the manufacturing line) without looping back
+/// through SSH
+pub struct LocalChannel {
+    timeout: u32,
+    connected: bool,
+}
+
+impl LocalChannel {
+    pub fn new(timeout: u32) -> Self {
+        Self {
+            timeout,
+            connected: false,
+        }
+    }
+
+    pub fn from_channel_config(config: ChannelConfig) -> Result<Self> {
+        match config {
+            ChannelConfig::Local { timeout } => Ok(Self::new(timeout)),
+            _ => Err(Error::Config(
+                "Invalid channel config for local execution".to_string(),
+            )),
+        }
+    }
+
+    async fn run(&self, command: &str, timeout: Duration) -> Result<CommandOutput> {
+        debug!("Executing local command: {}", mask_sudo_password(command));
+
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| Error::CommandExecution(format!("Failed to start shell: {}", e)))?;
+
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .map_err(|_| Error::CommandExecution(format!("Command timed out after {:?}", timeout)))?
+            .map_err(|e| Error::CommandExecution(format!("Failed to run command: {}", e)))?;
+
+        // A command killed by a signal has no exit code; report it like a shell would
+        let exit_code = output.status.code().unwrap_or(-1);
+        debug!("Command completed with exit code: {}", exit_code);
+
+        Ok(CommandOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code,
+        })
+    }
+}
+
+#[async_trait]
+impl CommunicationChannel for LocalChannel {
+    async fn connect(&mut self) -> Result<()> {
+        info!("Using local shell on this machine");
+
+        // Nothing to connect to; just make sure commands can be run
+        let probe = self.run("true", Duration::from_secs(5)).await?;
+        if probe.exit_code != 0 {
+            return Err(Error::Communication(format!(
+                "Local shell is not usable (exit code {})",
+                probe.exit_code
+            )));
+        }
+
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+
+    async fn execute_command(&mut self, command: &str) -> Result<CommandOutput> {
+        self.run(command, Duration::from_secs(self.timeout as u64))
+            .await
+    }
+
+    async fn execute_command_with_timeout(
+        &mut self,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<CommandOutput> {
+        self.run(command, timeout).await
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn description(&self) -> String {
+        "Local shell on this machine".to_string()
+    }
+
+    /// Every local command is its own process, so concurrent handles are independent
+    fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+        Some(Box::new(Self {
+            timeout: self.timeout,
+            connected: self.connected,
+        }))
+    }
+
+    fn supports_pooling(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_channel_runs_shell_commands() {
+        let mut channel = LocalChannel::new(5);
+        channel.connect().await.unwrap();
+        assert!(channel.is_connected().await);
+
+        let output = channel
+            .execute_command("echo out; echo err >&2; exit 3")
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "out\n");
+        assert_eq!(output.stderr, "err\n");
+        assert_eq!(output.exit_code, 3);
+
+        let timed_out = channel
+            .execute_command_with_timeout("sleep 5", Duration::from_millis(50))
+            .await;
+        assert!(timed_out.is_err());
+    }
+}
@@ -90,17 +90,64 @@ impl<'a> MachineDetector<'a> {
             features.push("pcf2131-rtc".to_string());
         }
 
+        // Hardware RNG exposed through the kernel hw_random framework
+        if self.check_feature_exists("/dev/hwrng").await {
+            features.push("hwrng".to_string());
+        }
+
         // Check for specific SoC types
         let cpu_info = self.get_cpu_info().await?;
         if cpu_info.contains("i.MX93") || cpu_info.contains("imx93") {
             features.push("imx93".to_string());
         } else if cpu_info.contains("i.MX8MM") || cpu_info.contains("imx8mm") {
             features.push("imx8mm".to_string());
+        } else if let Some(revision) = self.get_pi_revision().await {
+            features.push("raspberry-pi".to_string());
+            match revision.processor {
+                PI_PROCESSOR_BCM2711 => features.push("bcm2711".to_string()),
+                PI_PROCESSOR_BCM2712 => features.push("bcm2712".to_string()),
+                _ => {}
+            }
+            if self.check_pi_otp_secure_boot().await {
+                features.push("otp-secure-boot".to_string());
+                if !features.contains(&"secure-boot".to_string()) {
+                    features.push("secure-boot".to_string());
+                }
+            }
         }
 
         Ok(features)
     }
 
+    /// Raspberry Pi board revision from the `Revision` line of /proc/cpuinfo
+    async fn get_pi_revision(&mut self) -> Option<PiRevision> {
+        let output = self
+            .comm_channel
+            .execute_command("grep -m1 '^Revision' /proc/cpuinfo")
+            .await
+            .ok()?;
+        let (_, code) = output.stdout.split_once(':')?;
+        PiRevision::parse(code)
+    }
+
+    /// Signed boot is enforced once the hash of the customer boot key has been
+    /// programmed into OTP rows 47-54
+    async fn check_pi_otp_secure_boot(&mut self) -> bool {
+        match self
+            .comm_channel
+            .execute_command("vcgencmd otp_dump 2>/dev/null")
+            .await
+        {
+            Ok(output) => output.stdout.lines().any(|line| {
+                line.split_once(':').is_some_and(|(row, value)| {
+                    matches!(row.trim().parse::<u32>(), Ok(47..=54))
+                        && !value.trim().trim_start_matches('0').is_empty()
+                })
+            }),
+            Err(_) => false,
+        }
+    }
+
     async fn check_feature_exists(&mut self, path: &str) -> bool {
         let command = format!("ls {} >/dev/null 2>&1", path);
         if let Ok(output) = self.comm_channel.execute_command(&command).await {
@@ -155,7 +202,7 @@ impl<'a> MachineDetector<'a> {
 
     fn determine_machine_type(
         &self,
-        _cpu_info: &str,
+        cpu_info: &str,
         board_info: &Option<String>,
         features: &[String],
     ) -> Option<MachineType> {
@@ -177,6 +224,11 @@ impl<'a> MachineDetector<'a> {
             return Some(MachineType::Imx93JaguarEink);
         }
 
+        // Check for Raspberry Pi Compute Modules
+        if features.contains(&"raspberry-pi".to_string()) {
+            return raspberry_pi_machine(board_info.as_deref(), cpu_info);
+        }
+
         // Check for i.MX8MM Jaguar Sentai
         if features.contains(&"imx8mm".to_string()) {
             if let Some(board) = board_info {
@@ -194,6 +246,73 @@ impl<'a> MachineDetector<'a> {
     }
 }
 
+/// Processor field of a new-style Raspberry Pi revision code
+const PI_PROCESSOR_BCM2711: u32 = 3;
+const PI_PROCESSOR_BCM2712: u32 = 4;
+
+/// Type field of a new-style Raspberry Pi revision code
+const PI_TYPE_CM4: u32 = 0x14;
+const PI_TYPE_CM4S: u32 = 0x15;
+const PI_TYPE_CM5: u32 = 0x18;
+const PI_TYPE_CM5_LITE: u32 = 0x1a;
+
+/// A new-style Raspberry Pi revision code (`NOQuuuWuFMMMCCCCPPPPTTTTTTTTRRRR`).
+///
+/// Bit 23 marks the new style; bits 4-11 are the board type and bits 12-15
+/// the processor. Mapping used for detection:
+///
+/// | Type | Board        | Processor     |
+/// |------|--------------|---------------|
+/// | 0x14 | CM4          | 3 = BCM2711   |
+/// | 0x15 | CM4S         | 3 = BCM2711   |
+/// | 0x18 | CM5          | 4 = BCM2712   |
+/// | 0x1a | CM5 Lite     | 4 = BCM2712   |
+///
+/// e.g. `b03140` is a CM4 and `d04180` a CM5.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PiRevision {
+    pub board_type: u32,
+    pub processor: u32,
+}
+
+impl PiRevision {
+    pub fn parse(code: &str) -> Option<Self> {
+        let code = u32::from_str_radix(code.trim().trim_start_matches("0x"), 16).ok()?;
+        // Old-style codes predate the compute modules we support
+        if code & (1 << 23) == 0 {
+            return None;
+        }
+        Some(Self {
+            board_type: (code >> 4) & 0xff,
+            processor: (code >> 12) & 0xf,
+        })
+    }
+}
+
+/// Which compute module a Raspberry Pi is, from its device tree model
+/// (e.g. "Raspberry Pi Compute Module 4 Rev 1.1") or cpuinfo revision code
+fn raspberry_pi_machine(board_info: Option<&str>, cpu_info: &str) -> Option<MachineType> {
+    if let Some(board) = board_info {
+        if board.contains("Compute Module 4") {
+            return Some(MachineType::RaspberryPiCm4);
+        }
+        if board.contains("Compute Module 5") {
+            return Some(MachineType::RaspberryPiCm5);
+        }
+    }
+
+    let revision = cpu_info
+        .lines()
+        .find(|line| line.starts_with("Revision"))
+        .and_then(|line| line.split_once(':'))
+        .and_then(|(_, code)| PiRevision::parse(code))?;
+    match revision.board_type {
+        PI_TYPE_CM4 | PI_TYPE_CM4S => Some(MachineType::RaspberryPiCm4),
+        PI_TYPE_CM5 | PI_TYPE_CM5_LITE => Some(MachineType::RaspberryPiCm5),
+        _ => None,
+    }
+}
+
 /// Filter tests based on machine compatibility
 pub fn filter_tests_for_machine(
     test_names: &[String],
@@ -224,7 +343,7 @@ fn is_test_compatible_with_machine(test_name: &str, machine_features: &[String])
         ("hardware_002", vec!["trustzone", "op-tee"]), // Secure Enclave Status
         ("hardware_003", vec!["secure-boot"]),      // Hardware Root of Trust
         ("hardware_004", vec!["caam"]),             // Crypto Hardware Acceleration
-        ("hardware_005", vec!["caam"]),             // Hardware RNG
+        ("hardware_005", vec!["hwrng"]),            // Hardware RNG
         ("hardware_006", vec!["pcf2131-rtc"]),      // PCF2131 RTC functionality - i.MX93 E-Ink only
         // Boot tests that may be SoC-specific
         ("boot_001", vec!["secure-boot"]), // Secure Boot Enabled
@@ -299,4 +418,31 @@ mod tests {
         assert!(filtered.contains(&"hardware_002".to_string())); // Should include TrustZone test
         assert!(filtered.contains(&"runtime_001".to_string())); // Should include generic test
     }
+
+    #[test]
+    fn test_raspberry_pi_compute_module_detection() {
+        let cm4 = PiRevision::parse("b03140").unwrap();
+        assert_eq!(cm4.board_type, PI_TYPE_CM4);
+        assert_eq!(cm4.processor, PI_PROCESSOR_BCM2711);
+        let cm5 = PiRevision::parse("d04180").unwrap();
+        assert_eq!(cm5.board_type, PI_TYPE_CM5);
+        assert_eq!(cm5.processor, PI_PROCESSOR_BCM2712);
+        // Old-style code (Pi 1 Model B)
+        assert_eq!(PiRevision::parse("000e"), None);
+
+        assert!(matches!(
+            raspberry_pi_machine(Some("Raspberry Pi Compute Module 4 Rev 1.1"), ""),
+            Some(MachineType::RaspberryPiCm4)
+        ));
+        assert!(matches!(
+            raspberry_pi_machine(None, "Hardware\t: BCM2835\nRevision\t: d04180\n"),
+            Some(MachineType::RaspberryPiCm5)
+        ));
+        // Raspberry Pi 4 Model B is not a compute module
+        assert!(raspberry_pi_machine(
+            Some("Raspberry Pi 4 Model B Rev 1.4"),
+            "Revision\t: c03114\n"
+        )
+        .is_none());
+    }
 }
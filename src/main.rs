@@ -21,15 +21,37 @@
 
 use anyhow::Result;
 use clap::Parser;
+use colored::Colorize;
 use security_compliance_cli::{
-    cli::{Cli, Commands},
-    config::Config,
+    cli::{Cli, Commands, DetailLevel, RemapFormat, TestMode, TestSuite},
+    compliance::{
+        format_compliance_report_as_csv, format_compliance_report_as_html,
+        format_compliance_report_as_markdown, format_mapping_explanation,
+        generate_compliance_report, generate_pdf_report,
+    },
+    config::{Config, HookConfig},
+    diff::{diff_results, render_baseline_diff, render_diff},
+    evidence::write_evidence_bundle,
+    fleet::{
+        load_targets, render_fleet_summary, FleetReport, FleetTargetResult, TargetConfig,
+        DEFAULT_TARGET_JOBS,
+    },
+    healthcheck::{run_healthcheck, HealthReport, SudoAccess, OPTIONAL_TOOLS},
+    hook::{run_post_run_hook, send_webhook_notification},
     machine::MachineDetector,
-    runner::TestRunner,
+    output::{format_remediation_script, write_atomically},
+    runner::{select_tests, validate_test_selection, TestRunner},
+    secret::Redactor,
     ssh_key::{KeyRemovalCriteria, SshKeyInstaller},
-    target::Target,
+    target::{RunContext, Target},
+    tests::{SecurityTest, TestRegistry, TestSuiteResults},
 };
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tracing::{error, info, warn};
 
 #[tokio::main]
@@ -45,78 +67,332 @@ async fn main() -> Result<()> {
     let mut config = Config::from_cli(&cli)?;
 
     info!("Security Compliance CLI v{}", env!("CARGO_PKG_VERSION"));
-    let description = match config.communication.channel_type.as_str() {
-        "ssh" => format!(
-            "SSH {}:{}",
-            config.communication.host.as_deref().unwrap_or("unknown"),
-            config.communication.port.unwrap_or(22)
-        ),
-        "serial" => format!(
-            "Serial {}",
-            config
-                .communication
-                .serial_device
-                .as_deref()
-                .unwrap_or("unknown")
-        ),
-        _ => "Unknown communication channel".to_string(),
+    let description = match config.communication.replay_snapshot.as_deref() {
+        Some(path) => format!("Snapshot replay {}", path),
+        None => describe_channel(&config),
     };
     info!("Target: {}", description);
 
     match cli.command {
         Commands::Test {
-            test_suite, mode, ..
+            test_suite,
+            mode,
+            jobs,
+            exclude_category,
+            exclude_tests,
+            fail_on,
+            fail_on_status,
+            exit_zero_on_warning,
+            tag,
+            compare_device,
+            baseline,
+            targets,
+            target_jobs,
+            fleet_dir,
+            emit_remediation,
+            errors_non_gating,
+            cache_commands,
+            verbose_evidence,
+            environment,
+            fix_and_verify,
+            checkpoint,
+            dump_evidence_bundle,
+            signing_key,
+            min_uptime,
+            since,
+            post_run_hook: hook_command,
+            require_hook,
+            notify_webhook,
+            notify_on,
+            dry_run,
+            ..
         } => {
-            let mut target = Target::new(config.communication.clone())?;
-            target.connect().await?;
-
-            // Perform machine detection if auto-detect is enabled
-            if let Some(machine_config) = &config.machine {
-                if machine_config.auto_detect {
-                    info!("🔍 Auto-detecting target machine type...");
-                    let comm_channel = target.get_communication_channel();
-                    let mut detector = MachineDetector::new(comm_channel);
-
-                    match detector.detect_machine().await {
-                        Ok(machine_info) => {
-                            config.update_machine_config(
-                                machine_info.machine_type.clone(),
-                                machine_info.detected_features.clone(),
-                            );
-
-                            if let Some(detected_type) = &machine_info.machine_type {
-                                info!("✅ Detected machine: {:?}", detected_type);
-                            } else {
-                                info!("❓ Could not determine specific machine type, using generic tests");
-                            }
-                        }
-                        Err(e) => {
-                            warn!("⚠️  Machine detection failed: {}. Using generic tests.", e);
-                        }
-                    }
+            if let Some(environment) = &environment {
+                info!("🌍 Applying {:?} environment gating", environment);
+                config.tests.apply_environment(environment);
+            }
+            if jobs.is_some() {
+                config.tests.jobs = jobs;
+            }
+            if !exclude_category.is_empty() {
+                config.tests.exclude_categories = exclude_category;
+            }
+            if !exclude_tests.is_empty() {
+                if let Some(unknown) = TestRegistry::new().unknown_test_id(&exclude_tests) {
+                    error!(
+                        "❌ Unknown test ID '{}' in --exclude-tests (run the 'list' command to see available tests)",
+                        unknown
+                    );
+                    process::exit(1);
                 }
+                config.tests.exclude_tests = exclude_tests;
+            }
+            if !tag.is_empty() {
+                config.tests.tags = tag;
             }
+            if errors_non_gating {
+                config.tests.errors_non_gating = true;
+            }
+            if cache_commands {
+                config.tests.cache_commands = true;
+            }
+            if verbose_evidence {
+                config.tests.verbose_evidence = true;
+                // Show the evidence in reports unless --details asks for less
+                config.output.details.get_or_insert(DetailLevel::Full);
+            }
+            if fail_on.is_some() {
+                config.tests.fail_on = fail_on;
+            }
+            if fail_on_status.is_some() {
+                config.tests.fail_on_status = fail_on_status;
+            }
+            if exit_zero_on_warning {
+                config.tests.exit_zero_on_warning = true;
+            }
+            if matches!(test_suite, TestSuite::Custom) && config.custom_tests.is_empty() {
+                error!("❌ The custom suite runs the tests listed in custom_tests in the config file, and none are set");
+                process::exit(1);
+            }
+            if dry_run {
+                print_test_plan(&config, &test_suite, &mode)?;
+                return Ok(());
+            }
+            config.tests.fix_and_verify = fix_and_verify;
+            config.tests.checkpoint = checkpoint;
+            if let Some(min_uptime) = min_uptime {
+                config.tests.min_uptime_secs = min_uptime;
+            }
+            if let Some(since) = since {
+                config.tests.log_since = since;
+            }
+            let redactor = Redactor::new(&config.output.redact_patterns)?;
+            if let Some(signing_key) = signing_key {
+                config.output.signing_key = Some(signing_key.to_string_lossy().to_string());
+            }
+            let signing_key = config.output.signing_key.clone().map(PathBuf::from);
+            if hook_command.is_some() {
+                config.hooks.post_run = hook_command;
+            }
+            if require_hook {
+                config.hooks.require = true;
+            }
+            if notify_webhook.is_some() {
+                config.hooks.notify_webhook = notify_webhook;
+            }
+            if let Some(notify_on) = notify_on {
+                config.hooks.notify_on = notify_on;
+            }
+            let hooks = config.hooks.clone();
+            let host = config
+                .communication
+                .host
+                .clone()
+                .unwrap_or_else(|| description.clone());
+            let output_file = config.output.file.clone();
+            // Read the baseline up front so a bad path fails before the device is tested
+            let baseline = match &baseline {
+                Some(path) => Some((path.display().to_string(), load_results(path)?)),
+                None => None,
+            };
+
+            if let Some(targets_file) = targets {
+                let targets = load_targets(&targets_file)?;
+                let jobs = target_jobs.unwrap_or(DEFAULT_TARGET_JOBS).max(1);
+                info!(
+                    "🚚 Running {:?} suite on {} targets, {} at a time",
+                    test_suite,
+                    targets.len(),
+                    jobs
+                );
+                let report =
+                    run_fleet(config, &test_suite, mode, targets, jobs, &fleet_dir).await?;
+                println!("{}", render_fleet_summary(&report));
+                process::exit(if report.all_passed() { 0 } else { 1 });
+            }
+
+            if let Some(reference_host) = compare_device {
+                if config.communication.channel_type != "ssh" {
+                    error!("❌ --compare-device requires an SSH connection");
+                    process::exit(1);
+                }
 
-            let mut runner =
-                TestRunner::new(target, config.output.clone(), mode, config.machine.clone())?;
+                let mut reference_config = config.clone();
+                reference_config.communication.host = Some(reference_host.clone());
+                reference_config.output.file =
+                    config.output.file.as_deref().map(reference_output_file);
+                reference_config.tests.checkpoint = None;
+                let candidate_host = config
+                    .communication
+                    .host
+                    .clone()
+                    .unwrap_or_else(|| "target".to_string());
+
+                info!("🏅 Running reference suite on {}", reference_host);
+                let reference = run_suite(reference_config, &test_suite, mode.clone()).await?;
+                info!("🔧 Running suite on unit under test {}", candidate_host);
+                let results = run_suite(config, &test_suite, mode).await?;
+                if let Some(path) = &emit_remediation {
+                    write_remediation_script(path, &results)?;
+                }
+                if let Some(path) = &dump_evidence_bundle {
+                    dump_bundle(path, &results, &redactor, signing_key.as_deref())?;
+                }
 
-            let results = runner.run_tests(&test_suite).await?;
+                let diff = diff_results(&reference_host, &reference, &candidate_host, &results);
+                println!("{}", render_diff(&diff));
 
-            if results.overall_passed() {
+                let exit_status = if results.overall_passed() && !diff.has_divergence() {
+                    info!("✅ Unit under test matches the reference device");
+                    0
+                } else {
+                    error!("❌ Unit under test diverges from the reference device or failed");
+                    1
+                };
+                process::exit(
+                    post_run_hook(
+                        &hooks,
+                        &candidate_host,
+                        &results,
+                        exit_status,
+                        output_file.as_deref(),
+                    )
+                    .await,
+                );
+            }
+
+            let results = run_suite(config, &test_suite, mode).await?;
+            if let Some(path) = &emit_remediation {
+                write_remediation_script(path, &results)?;
+            }
+            if let Some(path) = &dump_evidence_bundle {
+                dump_bundle(path, &results, &redactor, signing_key.as_deref())?;
+            }
+
+            let exit_status = if let Some((label, baseline)) = &baseline {
+                let diff = diff_results(label, baseline, "this run", &results);
+                println!("{}", render_baseline_diff(&diff));
+                if diff.has_regression() {
+                    error!("❌ Security posture regressed against the baseline");
+                    1
+                } else {
+                    info!("✅ No regressions against the baseline");
+                    0
+                }
+            } else if results.overall_passed() {
                 info!("✅ All security compliance tests PASSED");
-                process::exit(0);
+                0
             } else {
                 error!("❌ Security compliance tests FAILED");
-                process::exit(1);
-            }
+                1
+            };
+            process::exit(
+                post_run_hook(&hooks, &host, &results, exit_status, output_file.as_deref()).await,
+            );
         }
         Commands::List => {
             security_compliance_cli::tests::list_available_tests();
         }
-        Commands::Validate { config_file } => {
+        Commands::Validate { config_file, check } => {
             let config = Config::from_file(&config_file)?;
             println!("✅ Configuration file is valid");
+            for item in config.validation_checklist() {
+                println!("  {} {}", "✔".green(), item.green());
+            }
+            if !config.custom_tests.is_empty() {
+                println!(
+                    "🧪 custom_tests: {} tests for --test-suite custom: {}",
+                    config.custom_tests.len(),
+                    config.custom_tests.join(", ")
+                );
+            }
             println!("{:#?}", config);
+
+            if check {
+                let mut target = Target::new(config.communication.clone())?;
+                target.set_context(RunContext {
+                    tests: config.tests.clone(),
+                    ..Default::default()
+                });
+
+                info!("🩺 Checking the configured target...");
+                let report = run_healthcheck(&mut target).await;
+                println!();
+                print_health_report(&report);
+                let _ = target.disconnect().await;
+
+                if !report.is_testable() {
+                    println!("\n❌ Device cannot be tested - check connection settings");
+                    process::exit(1);
+                }
+                println!("\n✅ Device is ready for testing");
+            }
+        }
+        Commands::EvidenceBundle {
+            results_file,
+            output,
+            signing_key,
+        } => {
+            let mut results = load_results(&results_file)?;
+            results.product.apply(&config.product);
+            dump_bundle(
+                &output,
+                &results,
+                &Redactor::new(&config.output.redact_patterns)?,
+                signing_key
+                    .or_else(|| config.output.signing_key.as_ref().map(PathBuf::from))
+                    .as_deref(),
+            )?;
+        }
+        Commands::Remap {
+            results_file,
+            framework,
+            report_format,
+        } => {
+            let mut results = load_results(&results_file)?;
+            results.product.apply(&config.product);
+            let report = generate_compliance_report(&framework, &results);
+            info!(
+                "🗺️  Re-mapped {} stored results to {} ({} requirements)",
+                results.results.len(),
+                report.report_type,
+                report.compliance_summary.total_requirements
+            );
+
+            let output = cli.output.as_deref();
+            match report_format {
+                RemapFormat::Pdf => {
+                    let path = output.unwrap_or(Path::new("compliance-report.pdf"));
+                    generate_pdf_report(&report, &path.to_string_lossy())
+                        .map_err(|e| anyhow::anyhow!("PDF generation failed: {}", e))?;
+                    info!("📄 Report written to {}", path.display());
+                }
+                RemapFormat::Markdown
+                | RemapFormat::Html
+                | RemapFormat::Json
+                | RemapFormat::Csv => {
+                    let content = match report_format {
+                        RemapFormat::Json => serde_json::to_string_pretty(&report)?,
+                        RemapFormat::Html => format_compliance_report_as_html(&report),
+                        RemapFormat::Csv => format_compliance_report_as_csv(&report),
+                        _ => format_compliance_report_as_markdown(&report),
+                    };
+                    match output {
+                        Some(path) => {
+                            std::fs::write(path, content)?;
+                            info!("📄 Report written to {}", path.display());
+                        }
+                        None => println!("{}", content),
+                    }
+                }
+            }
+        }
+        Commands::ExplainCompliance { framework } => {
+            let registry = TestRegistry::new();
+            print!(
+                "{}",
+                format_mapping_explanation(&framework, &registry.test_ids())
+            );
         }
         Commands::Detect => {
             let mut target = Target::new(config.communication)?;
@@ -154,33 +430,68 @@ async fn main() -> Result<()> {
                 println!("  (No specific hardware features detected)");
             }
         }
+        Commands::Healthcheck => {
+            let mut target = Target::new(config.communication)?;
+
+            info!("🩺 Running target health check...");
+            let report = run_healthcheck(&mut target).await;
+
+            print_health_report(&report);
+
+            let _ = target.disconnect().await;
+
+            if report.is_testable() {
+                println!("\n✅ Device is ready for testing");
+            } else {
+                println!("\n❌ Device cannot be tested - check connection settings");
+                process::exit(1);
+            }
+        }
         Commands::InstallSshKey {
             public_key_file,
             key_validity_hours,
             save_private_key,
             test_connection,
             target_user,
+            install_cleanup_timer,
         } => {
-            // Ensure we're using serial communication for key installation
-            if config.communication.channel_type != "serial" {
-                error!("❌ SSH key installation requires serial console connection");
-                error!("💡 Use --serial-device /dev/ttyUSB0 (or appropriate device) to connect via serial");
-                process::exit(1);
-            }
+            // Serial bootstraps a device without keys; an SSH login that already
+            // works can rotate in a new key
+            let over_serial = match config.communication.channel_type.as_str() {
+                "serial" => true,
+                "ssh" => false,
+                other => {
+                    error!(
+                        "❌ SSH key installation requires a serial console or SSH connection (got {})",
+                        other
+                    );
+                    error!("💡 Use --serial-device /dev/ttyUSB0 to bootstrap over serial, or --host with working SSH credentials");
+                    process::exit(1);
+                }
+            };
 
-            info!("🔑 Installing SSH key via serial console...");
+            info!(
+                "🔑 Installing SSH key via {}...",
+                if over_serial { "serial console" } else { "SSH" }
+            );
 
             let mut target = Target::new(config.communication.clone())?;
             target.connect().await?;
 
-            // Determine target user - use provided value, serial username, or default to 'root'
+            // Determine target user - use provided value, the login user, or default to 'root'
+            let login_user = if over_serial {
+                &config.communication.serial_username
+            } else {
+                &config.communication.user
+            };
             let target_username = target_user
-                .or_else(|| config.communication.serial_username.clone())
+                .or_else(|| login_user.clone())
                 .unwrap_or_else(|| "root".to_string());
 
             info!("👤 Installing SSH key for user: {}", target_username);
 
-            let installer = SshKeyInstaller::new(target_username, test_connection);
+            let mut installer = SshKeyInstaller::new(target_username, test_connection);
+            installer.install_cleanup_timer = install_cleanup_timer;
 
             // Get host and port for connection testing
             let host = config
@@ -271,6 +582,29 @@ async fn main() -> Result<()> {
             }
         }
 
+        Commands::AuditSshKeys => {
+            let mut target = Target::new(config.communication.clone())?;
+            target.connect().await?;
+
+            let audit = SshKeyInstaller::audit_ssh_keys(target.get_communication_channel()).await?;
+            let _ = target.disconnect().await;
+
+            if config
+                .output
+                .formats()
+                .iter()
+                .any(|format| format == "json")
+            {
+                let json = serde_json::to_string_pretty(&audit)?;
+                match &config.output.file {
+                    Some(path) => write_atomically(Path::new(path), json.as_bytes())?,
+                    None => println!("{}", json),
+                }
+            } else {
+                SshKeyInstaller::display_audit(&audit);
+            }
+        }
+
         Commands::UninstallSshKey {
             public_key_file,
             private_key_file,
@@ -314,9 +648,9 @@ async fn main() -> Result<()> {
             } else if let Some(pub_key_file) = public_key_file {
                 let public_key = SshKeyInstaller::load_public_key_from_file(&pub_key_file)?;
                 KeyRemovalCriteria::PublicKey(public_key)
-            } else if let Some(priv_key_file) = private_key_file {
+            } else if let Some(priv_key_file) = &private_key_file {
                 // Try to extract public key from private key
-                match SshKeyInstaller::extract_public_key_from_private(&priv_key_file) {
+                match SshKeyInstaller::extract_public_key_from_private(priv_key_file) {
                     Ok(public_key) => KeyRemovalCriteria::PublicKey(public_key),
                     Err(e) => {
                         error!("❌ Failed to extract public key from private key: {}", e);
@@ -348,8 +682,50 @@ async fn main() -> Result<()> {
                         // Verify removal if requested
                         if verify_removal && !removed_keys.is_empty() {
                             info!("🔍 Verifying key removal...");
-                            // This is a placeholder - in a real implementation, you'd test SSH connection
-                            // to ensure the removed keys no longer work
+                            let mut verified = true;
+
+                            let still_present = installer
+                                .keys_still_present(comm_channel, &removed_keys)
+                                .await?;
+                            if still_present.is_empty() {
+                                info!("✅ No removed key remains in authorized_keys");
+                            } else {
+                                verified = false;
+                                error!(
+                                    "❌ {} removed key(s) still in authorized_keys:",
+                                    still_present.len()
+                                );
+                                for key in &still_present {
+                                    error!("  {}", installer.truncate_key_for_display(key));
+                                }
+                            }
+
+                            // With the private key we can also check sshd turns it away
+                            if let (Some(private_key), Some(host)) =
+                                (&private_key_file, &config.communication.host)
+                            {
+                                let port = config.communication.port.unwrap_or(22);
+                                match installer.ssh_login_accepted(host, port, private_key) {
+                                    Ok(false) => {
+                                        info!("✅ SSH login with the removed key is rejected")
+                                    }
+                                    Ok(true) => {
+                                        verified = false;
+                                        error!("❌ SSH login with the removed key still succeeds");
+                                    }
+                                    Err(e) => {
+                                        warn!(
+                                            "⚠️  Could not test SSH login with the removed key: {}",
+                                            e
+                                        )
+                                    }
+                                }
+                            }
+
+                            if !verified {
+                                error!("❌ Key removal verification failed");
+                                process::exit(1);
+                            }
                             info!("✅ Key removal verified");
                         }
                     }
@@ -364,3 +740,356 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// List the tests a run would execute, for --dry-run
+fn print_test_plan(config: &Config, test_suite: &TestSuite, mode: &TestMode) -> Result<()> {
+    let mut registry = TestRegistry::new();
+    validate_test_selection(&registry, &config.tests)?;
+    registry.set_custom_tests(config.custom_tests.clone());
+    let test_ids = select_tests(
+        &registry,
+        test_suite,
+        &config.tests.exclude_categories,
+        &config.tests.exclude_tests,
+        &config.tests.tags,
+        mode,
+        &config.machine,
+    );
+
+    println!(
+        "🧾 Dry run: {} tests would run ({:?} suite, {:?} mode); no connection made",
+        test_ids.len(),
+        test_suite,
+        mode
+    );
+    if config.machine.as_ref().is_some_and(|m| m.auto_detect) {
+        println!(
+            "   Hardware-specific tests depend on machine detection at run time; pass --machine to preview a device"
+        );
+    }
+    println!();
+    for test_id in test_ids {
+        if let Some(test) = registry.get_test(test_id) {
+            println!(
+                "  {:<16} {:<45} [{}]",
+                test.test_id(),
+                test.test_name(),
+                test.category()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Results file for the reference device of a comparison, `report.json` becoming
+/// `report.reference.json`, so the unit under test does not overwrite it
+fn reference_output_file(path: &str) -> String {
+    let path = Path::new(path);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{}.reference.{}", stem, ext.to_string_lossy()),
+        None => format!("{}.reference", stem),
+    };
+    path.with_file_name(name).to_string_lossy().to_string()
+}
+
+/// Connect to the configured target, detect its machine type if enabled and run a suite on it
+async fn run_suite(
+    mut config: Config,
+    test_suite: &TestSuite,
+    mode: TestMode,
+) -> Result<TestSuiteResults> {
+    let mut target = Target::new(config.communication.clone())?;
+    target.connect().await?;
+
+    // Perform machine detection if auto-detect is enabled
+    if let Some(machine_config) = &config.machine {
+        if machine_config.auto_detect {
+            info!("🔍 Auto-detecting target machine type...");
+            let comm_channel = target.get_communication_channel();
+            let mut detector = MachineDetector::new(comm_channel);
+
+            match detector.detect_machine().await {
+                Ok(machine_info) => {
+                    config.update_machine_config(
+                        machine_info.machine_type.clone(),
+                        machine_info.detected_features.clone(),
+                    );
+
+                    if let Some(detected_type) = &machine_info.machine_type {
+                        info!("✅ Detected machine: {:?}", detected_type);
+                    } else {
+                        info!("❓ Could not determine specific machine type, using generic tests");
+                    }
+                }
+                Err(e) => {
+                    warn!("⚠️  Machine detection failed: {}. Using generic tests.", e);
+                }
+            }
+        }
+    }
+
+    let mut runner = TestRunner::new(target, config.output, config.tests, mode, config.machine)?;
+    runner.set_custom_tests(config.custom_tests);
+    runner.set_product(config.product);
+
+    Ok(runner.run_tests(test_suite).await?)
+}
+
+/// Run the suite on every device of a fleet, `jobs` devices at a time, writing
+/// each device's JSON results and the aggregate `fleet.json` to `dir`. Each
+/// device gets its own checkpoint file and post-run hook; fix-and-verify is
+/// interactive and so never used.
+async fn run_fleet(
+    config: Config,
+    test_suite: &TestSuite,
+    mode: TestMode,
+    targets: Vec<TargetConfig>,
+    jobs: usize,
+    dir: &Path,
+) -> Result<FleetReport> {
+    std::fs::create_dir_all(dir)?;
+    let semaphore = Arc::new(Semaphore::new(jobs));
+    let mut tasks = JoinSet::new();
+
+    for target in targets {
+        let label = target.label();
+        let mut config = config.clone();
+        target.apply(&mut config.communication);
+        config.output.format = "json".to_string();
+        config.output.file = Some(
+            dir.join(format!("{}.json", label))
+                .to_string_lossy()
+                .to_string(),
+        );
+        config.output.silent = true;
+        config.tests.fix_and_verify = false;
+        if config.tests.checkpoint.is_some() {
+            config.tests.checkpoint = Some(dir.join(format!("{}.checkpoint.jsonl", label)));
+        }
+        let hooks = config.hooks.clone();
+        let output_file = config.output.file.clone();
+
+        let semaphore = Arc::clone(&semaphore);
+        let test_suite = test_suite.clone();
+        let mode = mode.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("target semaphore is never closed");
+            info!("🔧 Testing {} ({})", label, target.host);
+            let outcome = match run_suite(config, &test_suite, mode).await {
+                Ok(results) => {
+                    let exit_status = if results.overall_passed() { 0 } else { 1 };
+                    let exit_status = post_run_hook(
+                        &hooks,
+                        &target.host,
+                        &results,
+                        exit_status,
+                        output_file.as_deref(),
+                    )
+                    .await;
+                    FleetTargetResult {
+                        passed: exit_status == 0,
+                        ..FleetTargetResult::completed(&target.host, results)
+                    }
+                }
+                Err(e) => {
+                    error!("❌ Could not test {}: {}", label, e);
+                    FleetTargetResult::failed(&target.host, e.to_string())
+                }
+            };
+            (label, outcome)
+        });
+    }
+
+    let mut outcomes = BTreeMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (label, outcome) = joined?;
+        outcomes.insert(label, outcome);
+    }
+
+    let report = FleetReport::new(&format!("{:?}", test_suite), outcomes);
+    let path = dir.join("fleet.json");
+    write_atomically(&path, serde_json::to_string_pretty(&report)?.as_bytes())?;
+    info!("Fleet results written to: {}", path.display());
+    Ok(report)
+}
+
+fn write_remediation_script(path: &Path, results: &TestSuiteResults) -> Result<()> {
+    std::fs::write(path, format_remediation_script(results))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    info!(
+        "🩹 Remediation script written to {} - review it before running",
+        path.display()
+    );
+    Ok(())
+}
+
+fn dump_bundle(
+    path: &Path,
+    results: &TestSuiteResults,
+    redactor: &Redactor,
+    signing_key: Option<&Path>,
+) -> Result<()> {
+    let manifest = write_evidence_bundle(results, redactor, signing_key, path)?;
+    info!(
+        "📦 Evidence bundle written to {} ({} files{})",
+        path.display(),
+        manifest.files.len() + 1 + usize::from(manifest.signed_by.is_some()),
+        if manifest.signed_by.is_some() {
+            ", signed"
+        } else {
+            ""
+        }
+    );
+    Ok(())
+}
+
+/// Load a `TestSuiteResults` previously saved with `--format json`
+fn load_results(path: &Path) -> Result<TestSuiteResults> {
+    let contents = std::fs::read_to_string(path)?;
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("{} is not a JSON results file: {}", path.display(), e))
+}
+
+/// Send the webhook notification and run the post-run hook, if any, and return
+/// the exit status to use. A failing hook only changes the outcome when it is
+/// required; a failed notification never does.
+async fn post_run_hook(
+    hooks: &HookConfig,
+    host: &str,
+    results: &TestSuiteResults,
+    exit_status: i32,
+    output: Option<&str>,
+) -> i32 {
+    if let Err(e) = send_webhook_notification(hooks, host, results).await {
+        warn!("⚠️  {}", e);
+    }
+    match run_post_run_hook(hooks, results, exit_status, output).await {
+        Ok(()) => exit_status,
+        Err(e) if hooks.require => {
+            error!("❌ {} (hook is required)", e);
+            1
+        }
+        Err(e) => {
+            warn!("⚠️  {}", e);
+            exit_status
+        }
+    }
+}
+
+/// Print a target health check for the healthcheck and `validate --check` commands
+fn print_health_report(report: &HealthReport) {
+    println!("🩺 Health Check Results");
+    println!("================================");
+
+    if report.connected {
+        println!("✅ Connection: OK");
+    } else {
+        println!(
+            "❌ Connection: FAILED ({})",
+            report
+                .connection_error
+                .as_deref()
+                .unwrap_or("unknown error")
+        );
+    }
+
+    if report.connected {
+        if report.echo_ok {
+            println!("✅ Command execution: OK");
+        } else {
+            println!("❌ Command execution: FAILED (echo returned unexpected output)");
+        }
+    }
+
+    if let Some(os_type) = &report.os_type {
+        if report.is_linux() {
+            println!("✅ Operating system: {}", os_type);
+        } else {
+            println!(
+                "❌ Operating system: {} (security tests require embedded Linux)",
+                os_type
+            );
+        }
+    }
+
+    if report.is_testable() {
+        println!("🐚 Shell: {}", report.shell.as_deref().unwrap_or("unknown"));
+        println!("👤 User: {}", report.user.as_deref().unwrap_or("unknown"));
+        match report.sudo {
+            _ if report.user.as_deref() == Some("root") => {
+                println!("✅ sudo: not needed (logged in as root)")
+            }
+            SudoAccess::Passwordless => println!("✅ sudo: available (passwordless)"),
+            SudoAccess::WithPassword => println!("✅ sudo: available (with password)"),
+            SudoAccess::Unavailable => {
+                println!("⚠️  sudo: unavailable (privileged checks may be incomplete)")
+            }
+        }
+
+        println!("\n🔧 Target tools:");
+        for (tool, present) in &report.tools {
+            if *present {
+                println!("  ✅ {}", tool);
+            } else {
+                println!("  ❌ {} (not found)", tool);
+            }
+        }
+
+        let degraded = report.degraded_tests();
+        if !degraded.is_empty() {
+            println!("\n💡 Tests that will report reduced results:");
+            for (reason, tests) in degraded {
+                println!("  • {}: {}", reason, tests.join(", "));
+            }
+        }
+
+        let missing: Vec<&str> = report
+            .missing_tools()
+            .into_iter()
+            .filter(|tool| OPTIONAL_TOOLS.contains(tool))
+            .collect();
+        if !missing.is_empty() {
+            println!(
+                "\n💡 Tests relying on {} will report reduced results",
+                missing.join(", ")
+            );
+        }
+    }
+}
+
+/// Connection target for the startup log
+fn describe_channel(config: &Config) -> String {
+    match config.communication.channel_type.as_str() {
+        "ssh" => format!(
+            "SSH {}:{}",
+            config.communication.host.as_deref().unwrap_or("unknown"),
+            config.communication.port.unwrap_or(22)
+        ),
+        "serial" => format!(
+            "Serial {}",
+            config
+                .communication
+                .serial_device
+                .as_deref()
+                .unwrap_or("unknown")
+        ),
+        "local" => "Local machine".to_string(),
+        "telnet" => format!(
+            "Telnet {}:{}",
+            config.communication.host.as_deref().unwrap_or("unknown"),
+            config.communication.port.unwrap_or(23)
+        ),
+        _ => "Unknown communication channel".to_string(),
+    }
+}
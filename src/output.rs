@@ -1,20 +1,29 @@
 use crate::{
+    cli::{ComplianceFormat, DetailLevel},
     compliance::{
-        format_compliance_report_as_markdown, generate_pdf_report, CraComplianceReporter,
-        RedComplianceReporter,
+        format_compliance_report, generate_pdf_report, CraComplianceReporter, Iec62443Reporter,
+        NistComplianceReporter, RedComplianceReporter,
     },
     config::OutputConfig,
     error::Result,
-    tests::{TestResult, TestStatus, TestSuiteResults},
+    tests::{
+        CategorySummary, SecurityTest, SkipReason, TestRegistry, TestResult, TestStatus,
+        TestSuiteResults,
+    },
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
 use serde_json;
 use std::fs;
+use std::io::{stdout, IsTerminal};
+use std::path::Path;
+use std::time::Duration;
 
 pub struct OutputHandler {
     config: OutputConfig,
+    formats: Vec<String>,
     progress_bar: Option<ProgressBar>,
     current_test: usize,
     total_tests: usize,
@@ -23,6 +32,7 @@ pub struct OutputHandler {
 impl OutputHandler {
     pub fn new(config: OutputConfig) -> Result<Self> {
         Ok(Self {
+            formats: config.formats(),
             config,
             progress_bar: None,
             current_test: 0,
@@ -33,47 +43,57 @@ impl OutputHandler {
     pub async fn start_test_suite(&mut self, suite_name: &str, total_tests: usize) -> Result<()> {
         self.total_tests = total_tests;
         self.current_test = 0;
+        if self.config.silent {
+            return Ok(());
+        }
 
-        match self.config.format.as_str() {
-            "human" => {
-                println!("{}", "🔒 Security Compliance Testing".bold().blue());
-                println!("{}", "================================".blue());
-                println!("Suite: {}", suite_name.bold());
-                println!("Tests: {}", total_tests);
-                println!();
-
-                if self.config.verbose == 0 {
-                    let pb = ProgressBar::new(total_tests as u64);
-                    pb.set_style(
-                        ProgressStyle::default_bar()
-                            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-                            .unwrap()
-                            .progress_chars("#>-"),
+        let to_directory = self.writes_directory();
+        for format in self.formats.clone() {
+            if to_directory && format != "human" {
+                continue;
+            }
+            match format.as_str() {
+                "human" => {
+                    println!("{}", "🔒 Security Compliance Testing".bold().blue());
+                    println!("{}", "================================".blue());
+                    println!("Suite: {}", suite_name.bold());
+                    println!("Tests: {}", total_tests);
+                    println!();
+
+                    // Without a terminal the bar is invisible, so plain progress lines are printed
+                    if self.config.verbose == 0 && !self.config.quiet && stdout().is_terminal() {
+                        let pb = ProgressBar::new(total_tests as u64);
+                        pb.set_style(
+                            ProgressStyle::default_bar()
+                                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                                .unwrap()
+                                .progress_chars("#>-"),
+                        );
+                        self.progress_bar = Some(pb);
+                    }
+                }
+                "json" => {
+                    // JSON output will be at the end
+                }
+                "junit" => {
+                    // JUnit XML will be at the end
+                }
+                "markdown" => {
+                    println!("# Security Compliance Test Report");
+                    println!();
+                    println!("**Suite:** {}", suite_name);
+                    println!("**Tests:** {}", total_tests);
+                    println!(
+                        "**Started:** {}",
+                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
                     );
-                    self.progress_bar = Some(pb);
+                    println!();
                 }
+                "cra" | "red" | "nist" | "iec62443" | "pdf" => {
+                    // Compliance reports will be generated at the end
+                }
+                _ => {}
             }
-            "json" => {
-                // JSON output will be at the end
-            }
-            "junit" => {
-                // JUnit XML will be at the end
-            }
-            "markdown" => {
-                println!("# Security Compliance Test Report");
-                println!();
-                println!("**Suite:** {}", suite_name);
-                println!("**Tests:** {}", total_tests);
-                println!(
-                    "**Started:** {}",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-                );
-                println!();
-            }
-            "cra" | "red" | "pdf" => {
-                // Compliance reports will be generated at the end
-            }
-            _ => {}
         }
 
         Ok(())
@@ -82,7 +102,7 @@ impl OutputHandler {
     pub async fn start_test(&mut self, test_id: &str, test_name: &str) -> Result<()> {
         self.current_test += 1;
 
-        if self.config.format.as_str() == "human" {
+        if self.has_format("human") && !self.config.silent {
             if let Some(pb) = &self.progress_bar {
                 pb.set_message(format!("{}: {}", test_id, test_name));
                 pb.set_position(self.current_test as u64);
@@ -91,6 +111,11 @@ impl OutputHandler {
                     "🔍 [{}/{}] Running: {} - {}",
                     self.current_test, self.total_tests, test_id, test_name
                 );
+            } else if !self.config.quiet {
+                println!(
+                    "[{}/{}] {} {}...",
+                    self.current_test, self.total_tests, test_id, test_name
+                );
             }
         }
 
@@ -98,7 +123,9 @@ impl OutputHandler {
     }
 
     pub async fn complete_test(&mut self, result: &TestResult) -> Result<()> {
-        if self.config.format.as_str() == "human"
+        if self.has_format("human")
+            && !self.config.quiet
+            && !self.config.silent
             && (self.progress_bar.is_none() || self.config.verbose > 0)
         {
             let status_icon = match result.status {
@@ -114,8 +141,12 @@ impl OutputHandler {
                 status_icon, result.test_id, result.test_name, result.message
             );
 
-            if self.config.verbose > 1 && result.details.is_some() {
-                println!("   Details: {}", result.details.as_ref().unwrap());
+            if let Some(details) = result
+                .details
+                .as_deref()
+                .and_then(|details| shape_details(details, self.detail_level()))
+            {
+                println!("   Details: {}", details);
             }
         }
 
@@ -128,25 +159,66 @@ impl OutputHandler {
             println!();
         }
 
-        match self.config.format.as_str() {
-            "human" => self.output_human_summary(results).await?,
-            "json" => self.output_json(results).await?,
-            "junit" => self.output_junit(results).await?,
-            "markdown" => self.output_markdown(results).await?,
-            "cra" => self.output_cra_compliance(results).await?,
-            "red" => self.output_red_compliance(results).await?,
-            "pdf" => self.output_pdf_report(results).await?,
-            _ => {}
+        // With several formats written to a directory, only human output goes to the console
+        let to_directory = self.writes_directory();
+        for format in &self.formats {
+            if (to_directory && format != "human") || self.config.silent {
+                continue;
+            }
+            match format.as_str() {
+                "human" => self.output_human_summary(results).await?,
+                "json" => self.output_json(results).await?,
+                "junit" => self.output_junit(results).await?,
+                "markdown" => self.output_markdown(results).await?,
+                "cra" => self.output_cra_compliance(results).await?,
+                "red" => self.output_red_compliance(results).await?,
+                "nist" => self.output_nist_compliance(results).await?,
+                "iec62443" => self.output_iec62443_compliance(results).await?,
+                "pdf" => self.output_pdf_report(results).await?,
+                "prometheus" => self.output_prometheus(results).await?,
+                "sarif" => println!("{}", format_sarif_results(results)?),
+                "csv" => print!("{}", format_csv_results(results)),
+                _ => {}
+            }
         }
 
         // Write to file if specified
         if let Some(output_file) = &self.config.file {
-            self.write_to_file(results, output_file).await?;
+            if to_directory {
+                fs::create_dir_all(output_file)?;
+                for format in &self.formats {
+                    let path = Path::new(output_file)
+                        .join(report_file_name(format, self.config.compliance_format));
+                    self.write_to_file(results, format, &path.to_string_lossy())
+                        .await?;
+                }
+            } else {
+                self.write_to_file(results, &self.formats[0], output_file)
+                    .await?;
+            }
         }
 
         Ok(())
     }
 
+    /// Explicit --details level, or full details at -vv and above as before
+    fn detail_level(&self) -> DetailLevel {
+        self.config.details.unwrap_or(if self.config.verbose > 1 {
+            DetailLevel::Full
+        } else {
+            DetailLevel::Off
+        })
+    }
+
+    fn has_format(&self, format: &str) -> bool {
+        self.formats.iter().any(|f| f == format)
+    }
+
+    /// Several formats with an output path write one report per format into that directory
+    fn writes_directory(&self) -> bool {
+        self.formats.len() > 1 && self.config.file.is_some()
+    }
+
     async fn output_human_summary(&self, results: &TestSuiteResults) -> Result<()> {
         println!("{}", "📊 Test Results Summary".bold().blue());
         println!("{}", "======================".blue());
@@ -171,16 +243,50 @@ impl OutputHandler {
         println!("  {} Warnings: {}", "⚠️ ".yellow(), results.warnings);
         println!("  {} Skipped: {}", "⏭️ ".blue(), results.skipped);
         println!("  {} Errors: {}", "💥".red(), results.errors);
+        if let Some(threshold) = results.gate.fail_on {
+            println!(
+                "  🎚️  Findings at {:?} severity or above: {} (--fail-on)",
+                threshold,
+                results.findings_at_or_above(threshold)
+            );
+        }
         println!();
 
         // Duration
         println!("⏱️  Duration: {:?}", results.duration);
+        if results.reconnects > 0 {
+            println!("🔌 Reconnections: {}", results.reconnects);
+        }
         println!();
 
+        let slowest = slowest_tests(results, self.config.slowest_tests);
+        if !self.config.quiet && !slowest.is_empty() {
+            println!("🐢 Slowest Tests:");
+            for result in slowest {
+                println!(
+                    "  {:>8.2}s  {} - {}",
+                    result.duration.as_secs_f64(),
+                    result.test_id,
+                    result.test_name
+                );
+            }
+            println!();
+        }
+
         // System info
-        println!("🖥️  Target System:");
+        println!("🖥️  System Under Test:");
+        println!("  Hostname: {}", results.system_info.hostname);
+        println!("  OS Type: {}", results.system_info.os_type);
         println!("  Kernel: {}", results.system_info.kernel_version);
+        println!("  Architecture: {}", results.system_info.architecture);
+        println!("  Machine Type: {}", results.system_info.machine_type);
         println!("  Uptime: {}", results.system_info.uptime);
+        if !results.system_info.serial_number.is_empty() {
+            println!("  Serial Number: {}", results.system_info.serial_number);
+        }
+        if !results.system_info.ostree_commit.is_empty() {
+            println!("  OSTree Commit: {}", results.system_info.ostree_commit);
+        }
 
         // Display CPU information
         if !results.system_info.cpu_info.is_empty() {
@@ -296,6 +402,7 @@ impl OutputHandler {
                         "  • {} - {}: {}",
                         result.test_id, result.test_name, result.message
                     );
+                    self.print_summary_details(result);
                 }
             }
             println!();
@@ -307,9 +414,10 @@ impl OutputHandler {
             for result in &results.results {
                 if matches!(result.status, TestStatus::Failed | TestStatus::Error) {
                     println!(
-                        "  • {} - {}: {}",
-                        result.test_id, result.test_name, result.message
+                        "  • {} - {} [{:?}]: {}",
+                        result.test_id, result.test_name, result.severity, result.message
                     );
+                    self.print_summary_details(result);
                 }
             }
             println!();
@@ -318,129 +426,70 @@ impl OutputHandler {
         Ok(())
     }
 
+    fn print_summary_details(&self, result: &TestResult) {
+        if let Some(details) = result
+            .details
+            .as_deref()
+            .and_then(|details| shape_details(details, self.detail_level()))
+        {
+            for line in details.lines() {
+                println!("      {}", line);
+            }
+        }
+    }
+
     async fn output_json(&self, results: &TestSuiteResults) -> Result<()> {
-        let json = serde_json::to_string_pretty(results)?;
+        let json = format_json_results(results, self.config.json_profile)?;
         println!("{}", json);
         Ok(())
     }
 
     async fn output_junit(&self, results: &TestSuiteResults) -> Result<()> {
-        println!(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
-        println!(
-            r#"<testsuites name="SecurityCompliance" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
-            results.total_tests,
-            results.failed,
-            results.errors,
-            results.duration.as_secs_f64()
-        );
-
         println!(
-            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{:.3}">"#,
-            results.suite_name,
-            results.total_tests,
-            results.failed,
-            results.errors,
-            results.skipped,
-            results.duration.as_secs_f64()
+            "{}",
+            format_junit_results(results, self.config.junit_warnings_as_failures)
         );
-
-        for result in &results.results {
-            println!(
-                r#"    <testcase name="{}" classname="{}" time="{:.3}">"#,
-                result.test_name,
-                result.category,
-                result.duration.as_secs_f64()
-            );
-
-            match result.status {
-                TestStatus::Failed => {
-                    println!(
-                        r#"      <failure message="{}">{}</failure>"#,
-                        xml_escape(&result.message),
-                        xml_escape(result.details.as_deref().unwrap_or(""))
-                    );
-                }
-                TestStatus::Error => {
-                    println!(
-                        r#"      <error message="{}">{}</error>"#,
-                        xml_escape(&result.message),
-                        xml_escape(result.details.as_deref().unwrap_or(""))
-                    );
-                }
-                TestStatus::Skipped => {
-                    println!(
-                        r#"      <skipped message="{}"/>"#,
-                        xml_escape(&result.message)
-                    );
-                }
-                _ => {}
-            }
-
-            println!(r#"    </testcase>"#);
-        }
-
-        println!(r#"  </testsuite>"#);
-        println!(r#"</testsuites>"#);
         Ok(())
     }
 
     async fn output_markdown(&self, results: &TestSuiteResults) -> Result<()> {
-        println!("## Results");
-        println!();
-        println!("| Metric | Value |");
-        println!("| ------ | ----- |");
-        println!(
-            "| **Overall Status** | {} |",
-            if results.overall_passed() {
-                "✅ PASSED"
-            } else {
-                "❌ FAILED"
-            }
-        );
-        println!("| **Success Rate** | {:.1}% |", results.success_rate());
-        println!("| **Total Tests** | {} |", results.total_tests);
-        println!("| **Passed** | ✅ {} |", results.passed);
-        println!("| **Failed** | ❌ {} |", results.failed);
-        println!("| **Warnings** | ⚠️ {} |", results.warnings);
-        println!("| **Skipped** | ⏭️ {} |", results.skipped);
-        println!("| **Errors** | 💥 {} |", results.errors);
-        println!("| **Duration** | {:?} |", results.duration);
-        println!();
-
-        println!("## Test Details");
-        println!();
-        println!("| Test ID | Test Name | Status | Message |");
-        println!("| ------- | --------- | ------ | ------- |");
-
-        for result in &results.results {
-            let status_icon = match result.status {
-                TestStatus::Passed => "✅",
-                TestStatus::Failed => "❌",
-                TestStatus::Warning => "⚠️",
-                TestStatus::Skipped => "⏭️",
-                TestStatus::Error => "💥",
-            };
+        print!("{}", format_markdown_results(results, self.detail_level()));
+        Ok(())
+    }
 
-            println!(
-                "| {} | {} | {} | {} |",
-                result.test_id, result.test_name, status_icon, result.message
-            );
+    async fn output_prometheus(&self, results: &TestSuiteResults) -> Result<()> {
+        // Metrics go to the .prom file when one is given, keeping stdout clean
+        if self.config.file.is_none() {
+            print!("{}", format_prometheus_metrics(results));
         }
-
         Ok(())
     }
 
     async fn output_cra_compliance(&self, results: &TestSuiteResults) -> Result<()> {
         let compliance_report = CraComplianceReporter::generate_report(results);
-        let markdown_report = format_compliance_report_as_markdown(&compliance_report);
-        println!("{}", markdown_report);
+        let report = format_compliance_report(&compliance_report, self.config.compliance_format);
+        println!("{}", report);
         Ok(())
     }
 
     async fn output_red_compliance(&self, results: &TestSuiteResults) -> Result<()> {
         let compliance_report = RedComplianceReporter::generate_report(results);
-        let markdown_report = format_compliance_report_as_markdown(&compliance_report);
-        println!("{}", markdown_report);
+        let report = format_compliance_report(&compliance_report, self.config.compliance_format);
+        println!("{}", report);
+        Ok(())
+    }
+
+    async fn output_nist_compliance(&self, results: &TestSuiteResults) -> Result<()> {
+        let compliance_report = NistComplianceReporter::generate_report(results);
+        let report = format_compliance_report(&compliance_report, self.config.compliance_format);
+        println!("{}", report);
+        Ok(())
+    }
+
+    async fn output_iec62443_compliance(&self, results: &TestSuiteResults) -> Result<()> {
+        let compliance_report = Iec62443Reporter::generate_report(results);
+        let report = format_compliance_report(&compliance_report, self.config.compliance_format);
+        println!("{}", report);
         Ok(())
     }
 
@@ -477,16 +526,39 @@ impl OutputHandler {
         Ok(())
     }
 
-    async fn write_to_file(&self, results: &TestSuiteResults, file_path: &str) -> Result<()> {
-        let content = match self.config.format.as_str() {
-            "json" => serde_json::to_string_pretty(results)?,
+    async fn write_to_file(
+        &self,
+        results: &TestSuiteResults,
+        format: &str,
+        file_path: &str,
+    ) -> Result<()> {
+        let content = match format {
+            "json" => format_json_results(results, self.config.json_profile)?,
+            "prometheus" => format_prometheus_metrics(results),
+            "sarif" => format_sarif_results(results)?,
+            "csv" => format_csv_results(results),
+            "markdown" => format!(
+                "# Security Compliance Test Report\n\n**Suite:** {}\n**Tests:** {}\n**Completed:** {}\n\n{}",
+                results.suite_name,
+                results.total_tests,
+                results.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+                format_markdown_results(results, self.detail_level())
+            ),
             "cra" => {
                 let compliance_report = CraComplianceReporter::generate_report(results);
-                format_compliance_report_as_markdown(&compliance_report)
+                format_compliance_report(&compliance_report, self.config.compliance_format)
             }
             "red" => {
                 let compliance_report = RedComplianceReporter::generate_report(results);
-                format_compliance_report_as_markdown(&compliance_report)
+                format_compliance_report(&compliance_report, self.config.compliance_format)
+            }
+            "nist" => {
+                let compliance_report = NistComplianceReporter::generate_report(results);
+                format_compliance_report(&compliance_report, self.config.compliance_format)
+            }
+            "iec62443" => {
+                let compliance_report = Iec62443Reporter::generate_report(results);
+                format_compliance_report(&compliance_report, self.config.compliance_format)
             }
             "pdf" => {
                 // For PDF, we generate the file directly instead of returning content
@@ -504,47 +576,702 @@ impl OutputHandler {
                     }
                 }
             }
-            "junit" => {
-                // Generate JUnit XML content
-                format!(
-                    r#"<?xml version="1.0" encoding="UTF-8"?>
-<testsuites name="SecurityCompliance" tests="{}" failures="{}" errors="{}" time="{:.3}">
-  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{:.3}">
-{}
-  </testsuite>
-</testsuites>"#,
-                    results.total_tests,
-                    results.failed,
-                    results.errors,
-                    results.duration.as_secs_f64(),
-                    results.suite_name,
-                    results.total_tests,
-                    results.failed,
-                    results.errors,
-                    results.skipped,
-                    results.duration.as_secs_f64(),
-                    results
-                        .results
-                        .iter()
-                        .map(|r| format!(
-                            r#"    <testcase name="{}" classname="{}" time="{:.3}"/>"#,
-                            r.test_name,
-                            r.category,
-                            r.duration.as_secs_f64()
-                        ))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                )
-            }
+            "junit" => format_junit_results(results, self.config.junit_warnings_as_failures),
             _ => format!("Security Compliance Test Results\n{:#?}", results),
         };
 
-        fs::write(file_path, content)?;
-        println!("Results written to: {}", file_path);
+        write_atomically(Path::new(file_path), content.as_bytes())?;
+        if !self.config.silent {
+            println!("Results written to: {}", file_path);
+        }
         Ok(())
     }
 }
 
+/// Write a report through a temporary file in the same directory and rename it
+/// into place, so readers never see a partially written file.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    std::io::Write::write_all(&mut file, contents)?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// How much of each test result goes into JSON output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonProfile {
+    /// Everything, as `TestSuiteResults` serializes
+    #[default]
+    Full,
+    /// Outcomes only: no details, messages or metadata
+    Minimal,
+}
+
+/// Full results plus the per-category breakdown dashboards render
+#[derive(Serialize)]
+struct FullSuiteResults<'a> {
+    #[serde(flatten)]
+    results: &'a TestSuiteResults,
+    category_summary: Vec<CategorySummary>,
+}
+
+/// Compact view of a run for storing results from many devices
+#[derive(Serialize)]
+struct MinimalSuiteResults<'a> {
+    suite_name: &'a str,
+    test_mode: &'a str,
+    total_tests: usize,
+    passed: usize,
+    failed: usize,
+    warnings: usize,
+    skipped: usize,
+    errors: usize,
+    reconnects: u32,
+    #[serde(with = "crate::tests::duration_ms")]
+    duration: Duration,
+    timestamp: DateTime<Utc>,
+    system_info: &'a crate::target::SystemInfo,
+    category_summary: Vec<CategorySummary>,
+    results: Vec<MinimalTestResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct MinimalTestResult<'a> {
+    test_id: &'a str,
+    status: &'a TestStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skip_reason: Option<SkipReason>,
+    #[serde(with = "crate::tests::duration_ms")]
+    duration: Duration,
+    timestamp: DateTime<Utc>,
+}
+
+/// Serialize results as JSON in the requested profile
+pub fn format_json_results(results: &TestSuiteResults, profile: JsonProfile) -> Result<String> {
+    let json = match profile {
+        JsonProfile::Full => serde_json::to_string_pretty(&FullSuiteResults {
+            results,
+            category_summary: results.category_summary(),
+        })?,
+        JsonProfile::Minimal => serde_json::to_string_pretty(&MinimalSuiteResults {
+            suite_name: &results.suite_name,
+            test_mode: &results.test_mode,
+            total_tests: results.total_tests,
+            passed: results.passed,
+            failed: results.failed,
+            warnings: results.warnings,
+            skipped: results.skipped,
+            errors: results.errors,
+            reconnects: results.reconnects,
+            duration: results.duration,
+            timestamp: results.timestamp,
+            system_info: &results.system_info,
+            category_summary: results.category_summary(),
+            results: results
+                .results
+                .iter()
+                .map(|result| MinimalTestResult {
+                    test_id: &result.test_id,
+                    status: &result.status,
+                    skip_reason: result.skip_reason,
+                    duration: result.duration,
+                    timestamp: result.timestamp,
+                })
+                .collect(),
+        })?,
+    };
+    Ok(json)
+}
+
+/// Number of detail lines kept for each test at `--details summary`
+pub const SUMMARY_DETAIL_LINES: usize = 5;
+
+/// Shape a test's details for a report: nothing, the leading non-blank lines, or everything
+pub fn shape_details(details: &str, level: DetailLevel) -> Option<String> {
+    match level {
+        DetailLevel::Off => None,
+        DetailLevel::Full => Some(details.to_string()).filter(|d| !d.trim().is_empty()),
+        DetailLevel::Summary => {
+            let lines: Vec<&str> = details
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .collect();
+            if lines.is_empty() {
+                return None;
+            }
+            let mut summary = lines
+                .iter()
+                .take(SUMMARY_DETAIL_LINES)
+                .copied()
+                .collect::<Vec<_>>()
+                .join("\n");
+            if lines.len() > SUMMARY_DETAIL_LINES {
+                summary.push_str(&format!(
+                    "\n… ({} more lines, use --details full)",
+                    lines.len() - SUMMARY_DETAIL_LINES
+                ));
+            }
+            Some(summary)
+        }
+    }
+}
+
+/// Findings with more lines than this are folded into a collapsible block
+pub const FOLDED_DETAIL_LINES: usize = 20;
+
+/// Markdown results tables, followed by each test's details at the requested level
+pub fn format_markdown_results(results: &TestSuiteResults, level: DetailLevel) -> String {
+    let mut out = String::new();
+
+    out.push_str("## Results\n\n");
+    out.push_str("| Metric | Value |\n");
+    out.push_str("| ------ | ----- |\n");
+    out.push_str(&format!(
+        "| **Overall Status** | {} |\n",
+        if results.overall_passed() {
+            "✅ PASSED"
+        } else {
+            "❌ FAILED"
+        }
+    ));
+    out.push_str(&format!(
+        "| **Success Rate** | {:.1}% |\n",
+        results.success_rate()
+    ));
+    out.push_str(&format!("| **Total Tests** | {} |\n", results.total_tests));
+    out.push_str(&format!("| **Passed** | ✅ {} |\n", results.passed));
+    out.push_str(&format!("| **Failed** | ❌ {} |\n", results.failed));
+    out.push_str(&format!("| **Warnings** | ⚠️ {} |\n", results.warnings));
+    out.push_str(&format!("| **Skipped** | ⏭️ {} |\n", results.skipped));
+    out.push_str(&format!("| **Errors** | 💥 {} |\n", results.errors));
+    out.push_str(&format!("| **Duration** | {:?} |\n\n", results.duration));
+
+    out.push_str("## System Under Test\n\n");
+    out.push_str("| Property | Value |\n");
+    out.push_str("| -------- | ----- |\n");
+    for (label, value) in results.system_info.system_under_test() {
+        out.push_str(&format!("| **{}** | {} |\n", label, value));
+    }
+    out.push('\n');
+
+    out.push_str("## Test Details\n\n");
+    out.push_str("| Test ID | Test Name | Status | Message |\n");
+    out.push_str("| ------- | --------- | ------ | ------- |\n");
+    for result in &results.results {
+        let status_icon = match result.status {
+            TestStatus::Passed => "✅",
+            TestStatus::Failed => "❌",
+            TestStatus::Warning => "⚠️",
+            TestStatus::Skipped => "⏭️",
+            TestStatus::Error => "💥",
+        };
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            result.test_id, result.test_name, status_icon, result.message
+        ));
+    }
+
+    let findings: Vec<(&TestResult, String)> = results
+        .results
+        .iter()
+        .filter_map(|result| {
+            result
+                .details
+                .as_deref()
+                .and_then(|details| shape_details(details, level))
+                .map(|details| (result, details))
+        })
+        .collect();
+    if !findings.is_empty() {
+        out.push_str("\n## Findings\n");
+        for (result, details) in findings {
+            out.push_str(&format!(
+                "\n### {} - {}\n\n",
+                result.test_id, result.test_name
+            ));
+            let lines = details.lines().count();
+            if lines > FOLDED_DETAIL_LINES {
+                out.push_str(&format!(
+                    "<details>\n<summary>{} lines of evidence</summary>\n\n```\n{}\n```\n\n</details>\n",
+                    lines, details
+                ));
+            } else {
+                out.push_str(&format!("```\n{}\n```\n", details));
+            }
+        }
+    }
+
+    out
+}
+
+/// The `count` longest-running tests, slowest first
+fn slowest_tests(results: &TestSuiteResults, count: usize) -> Vec<&TestResult> {
+    let mut sorted: Vec<&TestResult> = results.results.iter().collect();
+    sorted.sort_by_key(|result| std::cmp::Reverse(result.duration));
+    sorted.truncate(count);
+    sorted
+}
+
+/// File name for one format's report when several are written to a directory
+fn report_file_name(format: &str, compliance_format: ComplianceFormat) -> String {
+    if matches!(format, "cra" | "red" | "nist" | "iec62443") {
+        let extension = match compliance_format {
+            ComplianceFormat::Markdown => "md",
+            ComplianceFormat::Html => "html",
+            ComplianceFormat::Csv => "csv",
+        };
+        return format!("security-compliance-report.{}.{}", format, extension);
+    }
+    let extension = match format {
+        "json" => "json",
+        "junit" => "xml",
+        "markdown" => "md",
+        "pdf" => "pdf",
+        "prometheus" => "prom",
+        "sarif" => "sarif",
+        "csv" => "csv",
+        _ => "txt",
+    };
+    format!("security-compliance-report.{}", extension)
+}
+
+/// One CSV row per test result, for spreadsheet review
+pub fn format_csv_results(results: &TestSuiteResults) -> String {
+    let mut output =
+        String::from("test_id,test_name,category,status,message,duration_ms,timestamp\n");
+    for result in &results.results {
+        output.push_str(&csv_row(&[
+            &result.test_id,
+            &result.test_name,
+            &result.category,
+            &format!("{:?}", result.status),
+            &result.message,
+            &result.duration.as_millis().to_string(),
+            &result.timestamp.to_rfc3339(),
+        ]));
+    }
+    output
+}
+
+/// One CSV record. Fields holding commas, quotes or line breaks are quoted,
+/// with embedded quotes doubled (RFC 4180).
+pub fn csv_row(fields: &[&str]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    format!("{}\n", fields.join(","))
+}
+
+/// SARIF 2.1.0 log of the failed, warning and errored tests, for GitHub code scanning.
+///
+/// Each reported test becomes a rule keyed by its test id, and every result
+/// carries the target host so findings from different devices stay apart.
+/// Code scanning rejects results without a location, so each one points at
+/// `<host>/<category>/<test id>`, as an artifact and a logical location.
+/// Skipped tests are included with kind `review` when they could not run and
+/// `notApplicable` when they don't apply, along with their skip reason.
+pub fn format_sarif_results(results: &TestSuiteResults) -> Result<String> {
+    let registry = TestRegistry::new();
+    let target_host = if results.system_info.hostname.is_empty() {
+        "unknown"
+    } else {
+        results.system_info.hostname.as_str()
+    };
+
+    let mut rules = Vec::new();
+    let mut findings = Vec::new();
+    for result in &results.results {
+        // Skipped tests are reported without a level so consumers can tell
+        // tests that could not run from ones that don't apply
+        let (kind, level) = match (&result.status, result.skip_reason) {
+            (TestStatus::Failed, _) => ("fail", "error"),
+            (TestStatus::Warning, _) => ("fail", "warning"),
+            (TestStatus::Error, _) => ("fail", "error"),
+            (TestStatus::Skipped, Some(reason)) if reason.is_inconclusive() => ("review", "none"),
+            (TestStatus::Skipped, _) => ("notApplicable", "none"),
+            (TestStatus::Passed, _) => continue,
+        };
+
+        let rule_index = match rules
+            .iter()
+            .position(|rule: &serde_json::Value| rule["id"] == result.test_id.as_str())
+        {
+            Some(index) => index,
+            None => {
+                let description = registry
+                    .get_test(&result.test_id)
+                    .map(|test| test.description().to_string())
+                    .unwrap_or_else(|| result.test_name.clone());
+                rules.push(serde_json::json!({
+                    "id": result.test_id,
+                    "name": result.test_name,
+                    "shortDescription": { "text": result.test_name },
+                    "fullDescription": { "text": description },
+                    "properties": { "category": result.category },
+                }));
+                rules.len() - 1
+            }
+        };
+
+        let mut properties = serde_json::json!({
+            "target_host": target_host,
+            "status": status_label(&result.status),
+        });
+        if let Some(reason) = result.skip_reason {
+            properties["skip_reason"] = serde_json::json!(reason);
+        }
+        let location = format!("{}/{}/{}", target_host, result.category, result.test_id);
+        findings.push(serde_json::json!({
+            "ruleId": result.test_id,
+            "ruleIndex": rule_index,
+            "kind": kind,
+            "level": level,
+            "message": { "text": result.message },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": location },
+                    "region": { "startLine": 1 },
+                },
+                "logicalLocations": [{
+                    "name": result.test_id,
+                    "fullyQualifiedName": location,
+                    "kind": "member",
+                }],
+            }],
+            "properties": properties,
+        }));
+    }
+
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": findings,
+            "properties": {
+                "target_host": target_host,
+                "machine_type": results.system_info.machine_type,
+                "suite_name": results.suite_name,
+                "test_mode": results.test_mode,
+            },
+        }],
+    });
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+/// Render results in the Prometheus text exposition format, suitable for the
+/// node_exporter textfile collector
+pub fn format_prometheus_metrics(results: &TestSuiteResults) -> String {
+    let mut out = String::new();
+    let suite = prometheus_escape(&results.suite_name);
+
+    out.push_str("# HELP security_compliance_test Security compliance test result as a status label (security_compliance_test_status has the same outcome as one numeric value)\n");
+    out.push_str("# TYPE security_compliance_test gauge\n");
+    for result in &results.results {
+        out.push_str(&format!(
+            "security_compliance_test{{suite=\"{}\",test_id=\"{}\",category=\"{}\",status=\"{}\"}} 1\n",
+            suite,
+            prometheus_escape(&result.test_id),
+            prometheus_escape(&result.category),
+            status_label(&result.status)
+        ));
+    }
+
+    out.push_str(
+        "# HELP security_compliance_test_status Test outcome (0=pass, 1=warn, 2=fail, 3=error, 4=skipped), one series per test for threshold alerts\n",
+    );
+    out.push_str("# TYPE security_compliance_test_status gauge\n");
+    for result in &results.results {
+        out.push_str(&format!(
+            "security_compliance_test_status{{suite=\"{}\",test_id=\"{}\",category=\"{}\"}} {}\n",
+            suite,
+            prometheus_escape(&result.test_id),
+            prometheus_escape(&result.category),
+            prometheus_status_code(&result.status)
+        ));
+    }
+
+    let info = &results.system_info;
+    out.push_str("# HELP security_compliance_system_info System under test\n");
+    out.push_str("# TYPE security_compliance_system_info gauge\n");
+    out.push_str(&format!(
+        "security_compliance_system_info{{suite=\"{}\",hostname=\"{}\",os=\"{}\",kernel=\"{}\",arch=\"{}\",machine_type=\"{}\"}} 1\n",
+        suite,
+        prometheus_escape(&info.hostname),
+        prometheus_escape(&info.os_name()),
+        prometheus_escape(&info.kernel_version),
+        prometheus_escape(&info.architecture),
+        prometheus_escape(&info.machine_type)
+    ));
+
+    out.push_str("# HELP security_compliance_tests Number of tests by status\n");
+    out.push_str("# TYPE security_compliance_tests gauge\n");
+    for (status, count) in [
+        ("passed", results.passed),
+        ("failed", results.failed),
+        ("warning", results.warnings),
+        ("skipped", results.skipped),
+        ("error", results.errors),
+    ] {
+        out.push_str(&format!(
+            "security_compliance_tests{{suite=\"{}\",status=\"{}\"}} {}\n",
+            suite, status, count
+        ));
+    }
+
+    out.push_str("# HELP security_compliance_pass_rate Ratio of passed tests (0-1); security_compliance_success_rate is the same as a percentage\n");
+    out.push_str("# TYPE security_compliance_pass_rate gauge\n");
+    out.push_str(&format!(
+        "security_compliance_pass_rate{{suite=\"{}\"}} {:.4}\n",
+        suite,
+        results.success_rate() / 100.0
+    ));
+
+    out.push_str("# HELP security_compliance_success_rate Percentage of passed tests (0-100), security_compliance_pass_rate scaled for percentage dashboards\n");
+    out.push_str("# TYPE security_compliance_success_rate gauge\n");
+    out.push_str(&format!(
+        "security_compliance_success_rate{{suite=\"{}\"}} {:.2}\n",
+        suite,
+        results.success_rate()
+    ));
+
+    out.push_str(
+        "# HELP security_compliance_suite_passed Whether the run passed its gate (1 = passed)\n",
+    );
+    out.push_str("# TYPE security_compliance_suite_passed gauge\n");
+    out.push_str(&format!(
+        "security_compliance_suite_passed{{suite=\"{}\"}} {}\n",
+        suite,
+        u8::from(results.overall_passed())
+    ));
+
+    out.push_str("# HELP security_compliance_duration_seconds Wall time of the test run\n");
+    out.push_str("# TYPE security_compliance_duration_seconds gauge\n");
+    out.push_str(&format!(
+        "security_compliance_duration_seconds{{suite=\"{}\"}} {:.3}\n",
+        suite,
+        results.duration.as_secs_f64()
+    ));
+
+    out.push_str(
+        "# HELP security_compliance_weighted_risk Weighted risk score (0 = no findings, 1 = all failed)\n",
+    );
+    out.push_str("# TYPE security_compliance_weighted_risk gauge\n");
+    out.push_str(&format!(
+        "security_compliance_weighted_risk{{suite=\"{}\"}} {:.4}\n",
+        suite,
+        weighted_risk(results)
+    ));
+
+    out.push_str(
+        "# HELP security_compliance_last_run_timestamp_seconds Unix time of the test run\n",
+    );
+    out.push_str("# TYPE security_compliance_last_run_timestamp_seconds gauge\n");
+    out.push_str(&format!(
+        "security_compliance_last_run_timestamp_seconds{{suite=\"{}\"}} {}\n",
+        suite,
+        results.timestamp.timestamp()
+    ));
+
+    out
+}
+
+/// Collect the remediation commands from failed and warning results into a shell
+/// script the operator can review and run by hand
+pub fn format_remediation_script(results: &TestSuiteResults) -> String {
+    let mut out = String::new();
+    out.push_str("#!/bin/sh\n");
+    out.push_str("# Security compliance remediation script\n");
+    out.push_str(&format!(
+        "# Generated {} for {} ({} suite)\n",
+        results.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+        if results.system_info.hostname.is_empty() {
+            "unknown host"
+        } else {
+            results.system_info.hostname.as_str()
+        },
+        results.suite_name
+    ));
+    out.push_str("#\n");
+    out.push_str("# REVIEW BEFORE RUNNING. These commands change system configuration on the\n");
+    out.push_str("# target and must be run there as root. Remove anything not appropriate for\n");
+    out.push_str("# this product before applying it.\n\n");
+    out.push_str("set -e\n");
+
+    let mut any = false;
+    for result in &results.results {
+        let Some(commands) = result.remediation.as_ref().filter(|c| !c.is_empty()) else {
+            continue;
+        };
+        any = true;
+        out.push_str(&format!(
+            "\n# {} - {} ({}): {}\n",
+            result.test_id,
+            result.test_name,
+            status_label(&result.status),
+            result.message
+        ));
+        for command in commands {
+            out.push_str(command);
+            out.push('\n');
+        }
+    }
+
+    if !any {
+        out.push_str("\n# No remediation commands were suggested by this run\n");
+    }
+    out
+}
+
+/// JUnit XML for CI test panels: one `<testcase>` per test, classed by category.
+///
+/// Failures and errors carry the message and details; warnings pass with their
+/// message as output unless `warnings_as_failures` is set.
+pub fn format_junit_results(results: &TestSuiteResults, warnings_as_failures: bool) -> String {
+    let failures = results.failed
+        + if warnings_as_failures {
+            results.warnings
+        } else {
+            0
+        };
+    let time = results.duration.as_secs_f64();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"SecurityCompliance\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+        results.total_tests, failures, results.errors, time
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\" timestamp=\"{}\">\n",
+        xml_escape(&results.suite_name),
+        results.total_tests,
+        failures,
+        results.errors,
+        results.skipped,
+        time,
+        results.timestamp.format("%Y-%m-%dT%H:%M:%S")
+    ));
+    out.push_str(&junit_properties(results));
+
+    for result in &results.results {
+        out.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n",
+            xml_escape(&result.test_name),
+            xml_escape(&result.category),
+            result.duration.as_secs_f64()
+        ));
+        let message = xml_escape(&result.message);
+        let details = xml_escape(result.details.as_deref().unwrap_or(""));
+        match result.status {
+            TestStatus::Failed => out.push_str(&format!(
+                "      <failure message=\"{}\">{}</failure>\n",
+                message, details
+            )),
+            TestStatus::Error => out.push_str(&format!(
+                "      <error message=\"{}\">{}</error>\n",
+                message, details
+            )),
+            TestStatus::Skipped => {
+                out.push_str(&format!("      <skipped message=\"{}\"/>\n", message))
+            }
+            TestStatus::Warning if warnings_as_failures => out.push_str(&format!(
+                "      <failure type=\"warning\" message=\"{}\">{}</failure>\n",
+                message, details
+            )),
+            TestStatus::Warning => out.push_str(&format!(
+                "      <system-out>Warning: {}</system-out>\n",
+                message
+            )),
+            TestStatus::Passed => {}
+        }
+        out.push_str("    </testcase>\n");
+    }
+
+    out.push_str("  </testsuite>\n</testsuites>");
+    out
+}
+
+/// System under test as JUnit testsuite properties
+fn junit_properties(results: &TestSuiteResults) -> String {
+    let mut out = String::from("    <properties>\n");
+    for (label, value) in results.system_info.system_under_test() {
+        out.push_str(&format!(
+            "      <property name=\"{}\" value=\"{}\"/>\n",
+            label.to_lowercase().replace(' ', "_"),
+            xml_escape(&value)
+        ));
+    }
+    out.push_str("    </properties>\n");
+    out
+}
+
+/// Numeric test outcome for alerting rules: higher is worse, skipped sorts last
+fn prometheus_status_code(status: &TestStatus) -> u8 {
+    match status {
+        TestStatus::Passed => 0,
+        TestStatus::Warning => 1,
+        TestStatus::Failed => 2,
+        TestStatus::Error => 3,
+        TestStatus::Skipped => 4,
+    }
+}
+
+/// Average risk across tests: failures count fully, errors and warnings partially
+fn weighted_risk(results: &TestSuiteResults) -> f64 {
+    if results.results.is_empty() {
+        return 0.0;
+    }
+
+    let total: f64 = results
+        .results
+        .iter()
+        .map(|r| match r.status {
+            TestStatus::Failed => 1.0,
+            TestStatus::Error => 0.75,
+            TestStatus::Warning => 0.5,
+            TestStatus::Passed | TestStatus::Skipped => 0.0,
+        })
+        .sum();
+
+    total / results.results.len() as f64
+}
+
+fn status_label(status: &TestStatus) -> &'static str {
+    match status {
+        TestStatus::Passed => "passed",
+        TestStatus::Failed => "failed",
+        TestStatus::Warning => "warning",
+        TestStatus::Skipped => "skipped",
+        TestStatus::Error => "error",
+    }
+}
+
+fn prometheus_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
 fn xml_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -552,3 +1279,258 @@ fn xml_escape(s: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&apos;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_test_result;
+
+    #[test]
+    fn test_shape_details_levels() {
+        let details = "line 1\n\nline 2\nline 3\nline 4\nline 5\nline 6\nline 7";
+
+        assert_eq!(shape_details(details, DetailLevel::Off), None);
+        assert_eq!(
+            shape_details(details, DetailLevel::Full).as_deref(),
+            Some(details)
+        );
+
+        let summary = shape_details(details, DetailLevel::Summary).unwrap();
+        assert!(summary.starts_with("line 1\nline 2\nline 3\nline 4\nline 5\n"));
+        assert!(summary.ends_with("(2 more lines, use --details full)"));
+        assert_eq!(shape_details("  \n", DetailLevel::Summary), None);
+    }
+
+    #[test]
+    fn test_markdown_folds_long_evidence() {
+        let evidence: Vec<String> = (1..=30).map(|n| format!("line {}", n)).collect();
+        let results = TestSuiteResults::sample(vec![
+            create_test_result(
+                "boot_001",
+                "Secure Boot Enabled",
+                "boot",
+                TestStatus::Failed,
+                "Secure boot disabled",
+                Some(evidence.join("\n")),
+                Duration::from_millis(5),
+            ),
+            create_test_result(
+                "runtime_002",
+                "Firewall Configuration",
+                "runtime",
+                TestStatus::Passed,
+                "Firewall rules configured",
+                Some("INPUT DROP".to_string()),
+                Duration::from_millis(5),
+            ),
+        ]);
+
+        let markdown = format_markdown_results(&results, DetailLevel::Full);
+        assert!(markdown.contains(
+            "### boot_001 - Secure Boot Enabled\n\n<details>\n<summary>30 lines of evidence</summary>\n\n```\nline 1\n"
+        ));
+        assert!(markdown.contains("line 30\n```\n\n</details>\n"));
+        assert!(
+            markdown.contains("### runtime_002 - Firewall Configuration\n\n```\nINPUT DROP\n```\n")
+        );
+    }
+
+    #[test]
+    fn test_prometheus_status_codes_and_suite_metrics() {
+        let failed = create_test_result(
+            "runtime_004",
+            "SSH Security Configuration",
+            "runtime",
+            TestStatus::Failed,
+            "Root login permitted",
+            None,
+            Duration::from_millis(10),
+        );
+        let mut results = TestSuiteResults::sample(vec![failed]);
+        results.duration = Duration::from_secs(1);
+        let metrics = format_prometheus_metrics(&results);
+
+        assert!(metrics.contains(
+            "security_compliance_test_status{suite=\"All\",test_id=\"runtime_004\",category=\"runtime\"} 2\n"
+        ));
+        assert!(metrics.contains("security_compliance_suite_passed{suite=\"All\"} 0\n"));
+        assert!(metrics.contains("security_compliance_duration_seconds{suite=\"All\"} 1.000\n"));
+        assert!(metrics.contains("# TYPE security_compliance_success_rate gauge\n"));
+    }
+
+    #[test]
+    fn test_slowest_tests_sorted_by_duration() {
+        let timed = |id: &str, millis: u64| {
+            create_test_result(
+                id,
+                id,
+                "runtime",
+                TestStatus::Passed,
+                "ok",
+                None,
+                Duration::from_millis(millis),
+            )
+        };
+        let results = TestSuiteResults::sample(vec![
+            timed("runtime_001", 20),
+            timed("certificate_001", 900),
+            timed("network_001", 150),
+        ]);
+
+        let ids: Vec<&str> = slowest_tests(&results, 2)
+            .iter()
+            .map(|result| result.test_id.as_str())
+            .collect();
+        assert_eq!(ids, ["certificate_001", "network_001"]);
+        assert!(slowest_tests(&results, 0).is_empty());
+    }
+
+    #[test]
+    fn test_minimal_json_profile_omits_details_and_metadata() {
+        let result = TestResult {
+            test_id: "boot_001".to_string(),
+            test_name: "Secure Boot Enabled".to_string(),
+            category: "boot".to_string(),
+            status: TestStatus::Passed,
+            message: "Secure boot enabled".to_string(),
+            details: Some("verbose probe output".to_string()),
+            duration: Duration::from_millis(120),
+            timestamp: Utc::now(),
+            metadata: [("uptime_secs".to_string(), "42".to_string())].into(),
+            remediation: None,
+            severity: Default::default(),
+            skip_reason: None,
+        };
+        let results = TestSuiteResults::sample(vec![result]);
+
+        let full = format_json_results(&results, JsonProfile::Full).unwrap();
+        assert!(full.contains("verbose probe output") && full.contains("uptime_secs"));
+        let value: serde_json::Value = serde_json::from_str(&full).unwrap();
+        assert_eq!(value["category_summary"][0]["category"], "boot");
+        assert_eq!(value["category_summary"][0]["passed"], 1);
+        // Stored full results still load for remap, baselines and bundles
+        let reloaded: TestSuiteResults = serde_json::from_str(&full).unwrap();
+        assert_eq!(reloaded.results.len(), 1);
+
+        let minimal = format_json_results(&results, JsonProfile::Minimal).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&minimal).unwrap();
+        let test = &value["results"][0];
+        assert_eq!(test["test_id"], "boot_001");
+        assert_eq!(test["status"], "Passed");
+        assert!(test.get("details").is_none() && test.get("metadata").is_none());
+    }
+
+    #[test]
+    fn test_junit_escapes_details_and_maps_warnings() {
+        let failed = create_test_result(
+            "runtime_001",
+            "File Permissions <critical>",
+            "runtime",
+            TestStatus::Failed,
+            "World-writable files found",
+            Some("/tmp/a & /tmp/<b>".to_string()),
+            Duration::from_millis(10),
+        );
+        let warning = create_test_result(
+            "network_001",
+            "Open Ports",
+            "network",
+            TestStatus::Warning,
+            "Telnet port open",
+            None,
+            Duration::from_millis(10),
+        );
+        let results = TestSuiteResults::sample(vec![failed, warning]);
+
+        let lenient = format_junit_results(&results, false);
+        assert!(lenient.contains("File Permissions &lt;critical&gt;"));
+        assert!(lenient.contains("/tmp/a &amp; /tmp/&lt;b&gt;</failure>"));
+        assert!(lenient.contains("<system-out>Warning: Telnet port open</system-out>"));
+        assert!(lenient.contains(r#"<testsuite name="All" tests="2" failures="1""#));
+
+        let strict = format_junit_results(&results, true);
+        assert!(strict.contains(r#"<failure type="warning" message="Telnet port open">"#));
+        assert!(strict.contains(r#"<testsuite name="All" tests="2" failures="2""#));
+    }
+
+    #[test]
+    fn test_sarif_reports_findings_with_rules() {
+        let result = |test_id: &str, status: TestStatus| {
+            create_test_result(
+                test_id,
+                "Firewall Active",
+                "network",
+                status,
+                "No firewall rules",
+                None,
+                Duration::ZERO,
+            )
+        };
+        let mut results = TestSuiteResults::sample(vec![
+            result("network_002", TestStatus::Failed),
+            result("network_002", TestStatus::Failed),
+            result("network_001", TestStatus::Passed),
+            result("network_003", TestStatus::Warning),
+            result("network_004", TestStatus::Skipped).with_skip_reason(SkipReason::MissingTool),
+            result("network_005", TestStatus::Skipped).with_skip_reason(SkipReason::NotPresent),
+            result("network_006", TestStatus::Error),
+        ]);
+        results.system_info.hostname = "imx93-jaguar-eink".to_string();
+
+        let sarif: serde_json::Value =
+            serde_json::from_str(&format_sarif_results(&results).unwrap()).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 5);
+        assert_eq!(run["results"].as_array().unwrap().len(), 6);
+        assert_eq!(run["results"][0]["ruleId"], "network_002");
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(run["results"][2]["level"], "warning");
+        assert_eq!(run["results"][2]["ruleIndex"], 1);
+        assert_eq!(run["results"][3]["kind"], "review");
+        assert_eq!(
+            run["results"][3]["properties"]["skip_reason"],
+            "MissingTool"
+        );
+        assert_eq!(run["results"][4]["kind"], "notApplicable");
+        assert_eq!(run["results"][4]["level"], "none");
+        assert_eq!(run["results"][5]["level"], "error");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "imx93-jaguar-eink/network/network_002"
+        );
+        assert_eq!(
+            run["results"][0]["locations"][0]["logicalLocations"][0]["name"],
+            "network_002"
+        );
+        assert_eq!(
+            run["results"][0]["properties"]["target_host"],
+            "imx93-jaguar-eink"
+        );
+    }
+
+    #[test]
+    fn test_csv_quotes_fields_with_commas_and_newlines() {
+        let mut result = create_test_result(
+            "network_001",
+            "Open Ports",
+            "network",
+            TestStatus::Warning,
+            "Ports 22, 80 open\nsee \"details\"",
+            None,
+            Duration::from_millis(42),
+        );
+        result.timestamp = "2025-01-02T03:04:05Z".parse().unwrap();
+
+        let csv = format_csv_results(&TestSuiteResults::sample(vec![result]));
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("test_id,test_name,category,status,message,duration_ms,timestamp")
+        );
+        assert_eq!(
+            csv.split_once('\n').unwrap().1,
+            "network_001,Open Ports,network,Warning,\"Ports 22, 80 open\nsee \"\"details\"\"\",42,2025-01-02T03:04:05+00:00\n"
+        );
+    }
+}
@@ -5,18 +5,27 @@
  */
 
 use crate::{
-    cli::{TestMode, TestSuite},
-    config::{MachineConfig, OutputConfig},
-    error::Result,
+    checkpoint::{Checkpoint, CheckpointRun},
+    cli::{MachineType, TestMode, TestSuite},
+    config::{CommunicationConfig, MachineConfig, OutputConfig, ProductConfig, TestConfig},
+    error::{Error, Result},
     machine::filter_tests_for_machine,
     output::OutputHandler,
+    secret::{set_output_redactor, Redactor},
     ssh_key::SshKeyInstaller,
-    target::Target,
-    tests::{SecurityTest, TestRegistry, TestStatus, TestSuiteResults},
+    target::{RunContext, Target},
+    tests::{
+        create_test_result, GatePolicy, SecurityTest, SkipReason, TestRegistry, TestResult,
+        TestStatus, TestSuiteResults,
+    },
 };
 use chrono::Utc;
-use std::time::Instant;
-use tracing::{error, info, warn};
+use std::io::{IsTerminal, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn};
 
 pub struct TestRunner {
     target: Target,
@@ -25,18 +34,69 @@ pub struct TestRunner {
     test_mode: TestMode,
     verbose: u8,
     machine_config: Option<MachineConfig>,
+    jobs: usize,
+    exclude_categories: Vec<String>,
+    exclude_tests: Vec<String>,
+    tags: Vec<String>,
+    gate: GatePolicy,
+    fix_and_verify: bool,
+    min_uptime_secs: u64,
+    product: ProductConfig,
+    checkpoint: Option<Checkpoint>,
 }
 
+/// Tests whose results depend on state that builds up after boot (entropy
+/// pool, log files, audit trail) and can be misleading on a fresh device
+const SETTLE_SENSITIVE_TESTS: &[&str] = &[
+    "compliance_004",
+    "compliance_005",
+    "hardware_005",
+    "production_005",
+];
+
 impl TestRunner {
     pub fn new(
-        target: Target,
+        mut target: Target,
         output_config: OutputConfig,
+        test_config: TestConfig,
         test_mode: TestMode,
         machine_config: Option<MachineConfig>,
     ) -> Result<Self> {
+        let jobs = resolve_jobs(test_config.jobs, target.communication_config());
+        let registry = TestRegistry::new();
+
+        validate_test_selection(&registry, &test_config)?;
+        let exclude_categories = test_config.exclude_categories.clone();
+        let exclude_tests = test_config.exclude_tests.clone();
+        let tags = test_config.tags.clone();
+        let fix_and_verify = test_config.fix_and_verify;
+        let min_uptime_secs = test_config.min_uptime_secs;
+        let gate = GatePolicy {
+            errors_non_gating: test_config.errors_non_gating,
+            fail_on_warning_categories: test_config.fail_on_warning_categories.clone(),
+            fail_on: test_config.fail_on,
+            fail_on_status: test_config.fail_on_status,
+            exit_zero_on_warning: test_config.exit_zero_on_warning,
+        };
+
+        let redactor = if output_config.redact {
+            Some(Redactor::new(&output_config.redact_patterns)?)
+        } else {
+            None
+        };
+        if let Some(redactor) = &redactor {
+            set_output_redactor(redactor.clone());
+        }
+        target.set_context(RunContext {
+            mode: test_mode.clone(),
+            tests: test_config,
+            machine: machine_config
+                .as_ref()
+                .and_then(|machine| MachineType::from_name(&machine.machine_type)),
+            redactor,
+        });
         let verbose = output_config.verbose;
         let output_handler = OutputHandler::new(output_config)?;
-        let registry = TestRegistry::new();
 
         Ok(Self {
             target,
@@ -45,9 +105,28 @@ impl TestRunner {
             test_mode,
             verbose,
             machine_config,
+            jobs,
+            exclude_categories,
+            exclude_tests,
+            tags,
+            gate,
+            fix_and_verify,
+            min_uptime_secs,
+            product: ProductConfig::default(),
+            checkpoint: None,
         })
     }
 
+    /// Tests to run for `--test-suite custom`
+    pub fn set_custom_tests(&mut self, test_ids: Vec<String>) {
+        self.registry.set_custom_tests(test_ids);
+    }
+
+    /// Product identity recorded with the results for report headers
+    pub fn set_product(&mut self, product: ProductConfig) {
+        self.product = product;
+    }
+
     pub async fn run_tests(&mut self, test_suite: &TestSuite) -> Result<TestSuiteResults> {
         info!(
             "Starting security compliance test suite: {:?} in {:?} mode",
@@ -58,27 +137,39 @@ impl TestRunner {
 
         // Connect to target
         self.target.connect().await?;
+        // Security evidence read later leaves out what the run itself logs,
+        // such as sudo password probing
+        if let Err(e) = self.target.mark_run_started().await {
+            debug!("Could not read the target clock: {}", e);
+        }
 
         // Get system information
-        let system_info = self.target.get_system_info().await?;
+        let mut system_info = self.target.get_system_info().await?;
+        if let Some(machine_config) = &self.machine_config {
+            system_info.machine_type = machine_config.machine_type.clone();
+        }
         info!("Target system: {}", system_info.kernel_version);
 
-        // Get tests for the suite, filtered by mode
-        let test_ids_raw = self
-            .registry
-            .get_tests_for_suite_and_mode(test_suite, &self.test_mode);
-
-        // Convert to Vec<String> for machine filtering
-        let test_ids_strings: Vec<String> = test_ids_raw.iter().map(|s| s.to_string()).collect();
+        // Every test assumes Linux; refuse early rather than report a wall of errors
+        if !system_info.is_linux() {
+            let _ = self.target.disconnect().await;
+            return Err(Error::Unsupported(format!(
+                "target reports '{}' (uname -s) but the security tests require embedded Linux",
+                system_info.os_type
+            )));
+        }
 
-        // Apply machine-specific filtering
-        let filtered_test_ids = filter_tests_for_machine(&test_ids_strings, &self.machine_config);
+        let fresh_boot_uptime = self.check_uptime().await;
 
-        // Convert back to Vec<&str> for compatibility with existing code
-        let test_ids: Vec<&str> = test_ids_raw
-            .into_iter()
-            .filter(|id| filtered_test_ids.contains(&id.to_string()))
-            .collect();
+        let test_ids = select_tests(
+            &self.registry,
+            test_suite,
+            &self.exclude_categories,
+            &self.exclude_tests,
+            &self.tags,
+            &self.test_mode,
+            &self.machine_config,
+        );
 
         if let Some(machine_config) = &self.machine_config {
             if !machine_config.auto_detect || machine_config.machine_type != "auto" {
@@ -95,76 +186,118 @@ impl TestRunner {
             }
         }
 
-        info!("Running {} tests", test_ids.len());
+        // Results an interrupted earlier attempt already recorded are reused
+        if let Some(path) = self.target.context().tests.checkpoint.clone() {
+            let config = self.target.communication_config();
+            let run = CheckpointRun {
+                host: config
+                    .host
+                    .clone()
+                    .or_else(|| config.serial_device.clone())
+                    .unwrap_or_else(|| config.channel_type.clone()),
+                suite: format!("{:?}", test_suite),
+                mode: format!("{:?}", self.test_mode),
+            };
+            self.checkpoint = Some(Checkpoint::open(&path, &run)?);
+        }
+        let resumed: Vec<TestResult> = match &self.checkpoint {
+            Some(checkpoint) => test_ids
+                .iter()
+                .filter_map(|test_id| checkpoint.completed(test_id).cloned())
+                .collect(),
+            None => Vec::new(),
+        };
+        let remaining: Vec<&str> = test_ids
+            .iter()
+            .copied()
+            .filter(|test_id| !resumed.iter().any(|result| result.test_id == *test_id))
+            .collect();
+        if !resumed.is_empty() {
+            info!(
+                "⏯️  Skipping {} test(s) completed before the checkpoint, {} left to run",
+                resumed.len(),
+                remaining.len()
+            );
+        }
 
-        let mut results = Vec::new();
-        let mut passed = 0;
-        let mut failed = 0;
-        let mut warnings = 0;
-        let mut skipped = 0;
-        let mut errors = 0;
+        info!(
+            "Running {} tests (up to {} concurrently)",
+            remaining.len(),
+            self.jobs
+        );
 
         // Initialize progress reporting
         self.output_handler
-            .start_test_suite(&format!("{:?}", test_suite), test_ids.len())
+            .start_test_suite(&format!("{:?}", test_suite), remaining.len())
             .await?;
 
-        // Run each test
-        for (index, test_id) in test_ids.iter().enumerate() {
-            if let Some(test) = self.registry.get_test(test_id) {
-                if self.verbose > 0 {
-                    info!(
-                        "Running test {}/{}: {} - {}",
-                        index + 1,
-                        test_ids.len(),
-                        test.test_id(),
-                        test.test_name()
-                    );
-                    info!("📋 Purpose: {}", test.description());
-                    if self.verbose > 1 {
-                        info!("🏷️  Category: {}", test.category());
-                    }
-                } else {
-                    info!(
-                        "Running test {}/{}: {} - {}",
-                        index + 1,
-                        test_ids.len(),
-                        test.test_id(),
-                        test.test_name()
-                    );
-                }
+        let mut tests = Vec::new();
+        for test_id in &remaining {
+            match self.registry.get_test(test_id) {
+                Some(test) => tests.push(test.clone()),
+                None => error!("Test not found: {}", test_id),
+            }
+        }
 
-                self.output_handler
-                    .start_test(test.test_id(), test.test_name())
-                    .await?;
+        let mut results = if self.jobs > 1 && self.target.supports_pooling() {
+            run_concurrently(
+                tests,
+                &self.target,
+                self.jobs,
+                &mut self.output_handler,
+                self.checkpoint.as_mut(),
+            )
+            .await?
+        } else {
+            if self.jobs > 1 {
+                warn!(
+                    "⚠️  {} cannot run commands concurrently; running tests one at a time",
+                    self.target.communication_config().channel_type
+                );
+            }
+            run_sequentially(
+                &tests,
+                &mut self.target,
+                self.verbose,
+                &mut self.output_handler,
+                self.checkpoint.as_mut(),
+            )
+            .await?
+        };
 
-                let result = test.run(&mut self.target).await?;
+        if !resumed.is_empty() {
+            results.extend(resumed);
+            results.sort_by_key(|result| test_ids.iter().position(|id| *id == result.test_id));
+        }
 
-                match result.status {
-                    TestStatus::Passed => {
-                        passed += 1;
-                    }
-                    TestStatus::Failed => {
-                        failed += 1;
-                    }
-                    TestStatus::Warning => {
-                        warnings += 1;
-                    }
-                    TestStatus::Skipped => {
-                        skipped += 1;
-                    }
-                    TestStatus::Error => {
-                        errors += 1;
-                    }
+        if let Some(uptime) = fresh_boot_uptime {
+            for result in &mut results {
+                if SETTLE_SENSITIVE_TESTS.contains(&result.test_id.as_str()) {
+                    annotate_fresh_boot(result, uptime);
                 }
-
-                self.output_handler.complete_test(&result).await?;
-                results.push(result);
-            } else {
-                error!("Test not found: {}", test_id);
             }
         }
 
+        let total_tests = test_ids.len();
+        if self.fix_and_verify {
+            self.fix_and_verify(&mut results).await;
+        }
+
+        let count = |status: TestStatus| results.iter().filter(|r| r.status == status).count();
+        let passed = count(TestStatus::Passed);
+        let failed = count(TestStatus::Failed);
+        let warnings = count(TestStatus::Warning);
+        let skipped = count(TestStatus::Skipped);
+        let errors = count(TestStatus::Error);
+
+        if self.target.context().tests.cache_commands {
+            let (hits, entries) = self.target.command_cache_stats();
+            debug!(
+                "Command cache: {} hits across {} cached commands",
+                hits, entries
+            );
+        }
+
         // Disconnect from target
         self.target.disconnect().await?;
 
@@ -173,7 +306,7 @@ impl TestRunner {
         let suite_results = TestSuiteResults {
             suite_name: format!("{:?}", test_suite),
             test_mode: format!("{:?}", self.test_mode),
-            total_tests: test_ids.len(),
+            total_tests,
             passed,
             failed,
             warnings,
@@ -183,6 +316,9 @@ impl TestRunner {
             timestamp: Utc::now(),
             system_info,
             results,
+            gate: self.gate.clone(),
+            reconnects: self.target.reconnect_count(),
+            product: self.product.clone(),
         };
 
         // Complete test suite reporting
@@ -190,6 +326,10 @@ impl TestRunner {
             .complete_test_suite(&suite_results)
             .await?;
 
+        if let Some(checkpoint) = self.checkpoint.take() {
+            checkpoint.finish()?;
+        }
+
         // Check for remaining test keys and warn user
         self.check_for_remaining_test_keys().await;
 
@@ -198,10 +338,145 @@ impl TestRunner {
             "Results: {} passed, {} failed, {} warnings, {} skipped, {} errors",
             passed, failed, warnings, skipped, errors
         );
+        let categories = suite_results.category_summary();
+        if categories.len() > 1 {
+            info!("Results by category:");
+            for category in &categories {
+                info!("  {:<12} {}", category.category, category);
+            }
+        }
+        if errors > 0 && self.gate.errors_non_gating {
+            warn!(
+                "⚠️  {} test(s) could not complete and are NOT counted as failures (--errors-non-gating)",
+                errors
+            );
+        }
+        if warnings > 0 && self.gate.exit_zero_on_warning {
+            info!(
+                "{} warning(s) reported but not failing the run (--exit-zero-on-warning)",
+                warnings
+            );
+        }
 
         Ok(suite_results)
     }
 
+    /// Warn when the device booted too recently for state-dependent tests to be
+    /// trusted; returns the uptime if it is below the configured threshold
+    async fn check_uptime(&mut self) -> Option<f64> {
+        if self.min_uptime_secs == 0 {
+            return None;
+        }
+
+        match self.target.get_uptime_secs().await {
+            Ok(uptime) if uptime < self.min_uptime_secs as f64 => {
+                warn!(
+                    "⚠️  Device has only been up for {:.0}s (threshold {}s); entropy, log and audit results may be unreliable",
+                    uptime, self.min_uptime_secs
+                );
+                warn!("💡 Let the device settle before testing, or lower --min-uptime");
+                Some(uptime)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("⚠️  Could not read device uptime: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Walk the operator through each failed or warning test that has remediation
+    /// commands: show them, apply them on confirmation, then re-run just that
+    /// test so the report records whether the fix actually worked
+    async fn fix_and_verify(&mut self, results: &mut [TestResult]) {
+        if !std::io::stdin().is_terminal() {
+            warn!("⚠️  --fix-and-verify needs an interactive terminal, skipping remediation");
+            return;
+        }
+
+        let is_root = matches!(
+            self.target.execute_command("id -u").await,
+            Ok(output) if output.stdout.trim() == "0"
+        );
+        let read_only = match self.target.execute_command("cat /proc/mounts").await {
+            Ok(output) => read_only_mounts(&output.stdout),
+            Err(_) => Vec::new(),
+        };
+
+        for result in results.iter_mut() {
+            let Some(commands) = result.remediation.clone() else {
+                continue;
+            };
+
+            println!();
+            println!(
+                "🩹 {} - {} ({:?}): {}",
+                result.test_id, result.test_name, result.status, result.message
+            );
+            println!("   Suggested remediation:");
+            for command in &commands {
+                println!("     {}", command);
+            }
+
+            if let Some(mount) = read_only_mount_for(&commands, &read_only) {
+                println!(
+                    "   🔒 Skipped: {} is mounted read-only on this device",
+                    mount
+                );
+                continue;
+            }
+
+            match confirm("   Apply these commands and re-test? [y/N/q] ") {
+                Some(true) => {}
+                Some(false) => continue,
+                None => break,
+            }
+
+            let mut applied = true;
+            for command in &commands {
+                let command = if is_root {
+                    command.clone()
+                } else {
+                    format!(
+                        "{} sh -c {}",
+                        self.target.elevation_pipe(),
+                        shell_quote(command)
+                    )
+                };
+                match self.target.execute_command(&command).await {
+                    Ok(output) if output.success() => {}
+                    Ok(output) => {
+                        warn!("❌ Remediation command failed: {}", output.stderr.trim());
+                        applied = false;
+                        break;
+                    }
+                    Err(e) => {
+                        warn!("❌ Remediation command failed: {}", e);
+                        applied = false;
+                        break;
+                    }
+                }
+            }
+            self.target.clear_command_cache();
+            if !applied {
+                continue;
+            }
+
+            if let Some(test) = self.registry.get_test(&result.test_id) {
+                let verified = execute_test(test, &mut self.target).await;
+                if verified.status == TestStatus::Passed {
+                    println!("   ✅ Verified: {} now passes", verified.test_id);
+                } else {
+                    println!(
+                        "   ⚠️  Still {:?} after remediation: {}",
+                        verified.status, verified.message
+                    );
+                }
+                *result = verified;
+            }
+        }
+    }
+
     /// Check if temporary test keys remain on the device and warn the user
     async fn check_for_remaining_test_keys(&mut self) {
         // Determine the target user - try to get from the current connection
@@ -245,3 +520,891 @@ impl TestRunner {
         }
     }
 }
+
+/// Resolve the requested job count against the channel's safe concurrency limit
+pub fn resolve_jobs(requested: Option<usize>, config: &CommunicationConfig) -> usize {
+    let limit = config.max_concurrency();
+
+    match requested {
+        None | Some(0) => 1,
+        Some(jobs) if jobs > limit => {
+            warn!(
+                "⚠️  Requested {} jobs but {} channel supports at most {}; using {}",
+                jobs, config.channel_type, limit, limit
+            );
+            limit
+        }
+        Some(jobs) => jobs,
+    }
+}
+
+/// Mark a result as taken on a device that had not settled after boot
+fn annotate_fresh_boot(result: &mut TestResult, uptime: f64) {
+    result
+        .metadata
+        .insert("uptime_secs".to_string(), format!("{:.0}", uptime));
+    result.message = format!(
+        "{} (device up only {:.0}s - result may be unreliable)",
+        result.message, uptime
+    );
+}
+
+/// Ask a yes/no question on the terminal; `None` means the operator chose to quit
+fn confirm(prompt: &str) -> Option<bool> {
+    print!("{}", prompt);
+    let _ = std::io::stdout().flush();
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return None;
+    }
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Some(true),
+        "q" | "quit" => None,
+        _ => Some(false),
+    }
+}
+
+/// Mount points mounted read-only in `/proc/mounts` output, such as /usr on
+/// OSTree images; a later entry for the same mount point takes precedence
+fn read_only_mounts(mounts: &str) -> Vec<String> {
+    let mut read_only: Vec<String> = Vec::new();
+    for line in mounts.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (Some(mount_point), Some(options)) = (fields.get(1), fields.get(3)) else {
+            continue;
+        };
+        read_only.retain(|mount| mount != mount_point);
+        if options.split(',').any(|option| option == "ro") {
+            read_only.push(mount_point.to_string());
+        }
+    }
+    read_only
+}
+
+/// Read-only mount that would make remediation commands fail: the root
+/// filesystem for any command, otherwise one whose path a command names
+fn read_only_mount_for<'a>(commands: &[String], read_only: &'a [String]) -> Option<&'a str> {
+    read_only
+        .iter()
+        .find(|mount| {
+            mount.as_str() == "/"
+                || commands
+                    .iter()
+                    .any(|command| command.contains(&format!("{}/", mount)))
+        })
+        .map(String::as_str)
+}
+
+/// Quote a command so it survives as a single `sh -c` argument
+pub(crate) fn shell_quote(command: &str) -> String {
+    format!("'{}'", command.replace('\'', "'\\''"))
+}
+
+/// Reject excluded categories and tags that no registered test has, so a typo
+/// fails loudly instead of silently selecting nothing or everything
+pub fn validate_test_selection(registry: &TestRegistry, test_config: &TestConfig) -> Result<()> {
+    let known_categories = registry.categories();
+    for category in &test_config.exclude_categories {
+        if !known_categories
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(category))
+        {
+            return Err(Error::Config(format!(
+                "Unknown test category '{}' (expected one of: {})",
+                category,
+                known_categories.join(", ")
+            )));
+        }
+    }
+    let known_tags = registry.tags();
+    for tag in &test_config.tags {
+        if !known_tags
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(tag))
+        {
+            return Err(Error::Config(format!(
+                "Unknown test tag '{}' (expected one of: {})",
+                tag,
+                known_tags.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Tests a run would execute: the suite filtered by mode, excluded categories
+/// and test IDs, tags and the machine's hardware features, in execution order
+pub fn select_tests<'a>(
+    registry: &'a TestRegistry,
+    test_suite: &TestSuite,
+    exclude_categories: &[String],
+    exclude_tests: &[String],
+    tags: &[String],
+    test_mode: &TestMode,
+    machine_config: &Option<MachineConfig>,
+) -> Vec<&'a str> {
+    // Get tests for the suite, filtered by mode
+    let test_ids_raw = registry.get_tests_for_suite_and_mode(
+        test_suite,
+        exclude_categories,
+        exclude_tests,
+        tags,
+        test_mode,
+    );
+
+    // Convert to Vec<String> for machine filtering
+    let test_ids_strings: Vec<String> = test_ids_raw.iter().map(|s| s.to_string()).collect();
+
+    // Apply machine-specific filtering
+    let filtered_test_ids = filter_tests_for_machine(&test_ids_strings, machine_config);
+
+    test_ids_raw
+        .into_iter()
+        .filter(|id| filtered_test_ids.contains(&id.to_string()))
+        .collect()
+}
+
+/// Run tests one after another on the target. A test that errors is recorded
+/// as an error result and the remaining tests still run.
+async fn run_sequentially<T: SecurityTest + Sync>(
+    tests: &[T],
+    target: &mut Target,
+    verbose: u8,
+    output_handler: &mut OutputHandler,
+    mut checkpoint: Option<&mut Checkpoint>,
+) -> Result<Vec<TestResult>> {
+    let mut results = Vec::new();
+    for (index, test) in tests.iter().enumerate() {
+        info!(
+            "Running test {}/{}: {} - {}",
+            index + 1,
+            tests.len(),
+            test.test_id(),
+            test.test_name()
+        );
+        if verbose > 0 {
+            info!("📋 Purpose: {}", test.description());
+            if verbose > 1 {
+                info!("🏷️  Category: {}", test.category());
+            }
+        }
+
+        output_handler
+            .start_test(test.test_id(), test.test_name())
+            .await?;
+
+        let result = execute_test(test, target).await;
+        output_handler.complete_test(&result).await?;
+        if let Some(checkpoint) = checkpoint.as_deref_mut() {
+            checkpoint.record(&result)?;
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Run tests up to `jobs` at a time and return their results in the order given.
+///
+/// Tests share a pool of `jobs` handles opened with `Target::pooled_session`. Over
+/// SSH each handle is its own session, because libssh2 serializes all channels
+/// on one session, and its blocking I/O runs on tokio's blocking pool, so a slow
+/// command holds up neither the other tests nor the runtime. The device sees up
+/// to `jobs` connections. Each test times itself once it holds a handle, so
+/// durations exclude time spent queued.
+async fn run_concurrently<T>(
+    tests: Vec<T>,
+    target: &Target,
+    jobs: usize,
+    output_handler: &mut OutputHandler,
+    mut checkpoint: Option<&mut Checkpoint>,
+) -> Result<Vec<TestResult>>
+where
+    T: SecurityTest + Send + Sync + 'static,
+{
+    let total = tests.len();
+    let mut workers = Vec::new();
+    for _ in 0..jobs.min(total) {
+        workers.push(target.pooled_session().ok_or_else(|| {
+            Error::Communication("connection no longer supports concurrent channels".to_string())
+        })?);
+    }
+    let semaphore = Arc::new(Semaphore::new(workers.len()));
+    let pool = Arc::new(std::sync::Mutex::new(workers));
+    let mut tasks = JoinSet::new();
+
+    for (index, test) in tests.into_iter().enumerate() {
+        let semaphore = Arc::clone(&semaphore);
+        let pool = Arc::clone(&pool);
+        tasks.spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("test semaphore is never closed");
+            // One permit per pooled handle, so a handle is always free here
+            let mut worker = pool
+                .lock()
+                .expect("worker pool lock poisoned")
+                .pop()
+                .expect("a worker for every permit");
+            info!(
+                "Running test {}/{}: {} - {}",
+                index + 1,
+                total,
+                test.test_id(),
+                test.test_name()
+            );
+            let result = execute_test(&test, &mut worker).await;
+            pool.lock().expect("worker pool lock poisoned").push(worker);
+            (index, result)
+        });
+    }
+
+    let mut ordered: Vec<Option<TestResult>> = vec![None; total];
+    while let Some(joined) = tasks.join_next().await {
+        let (index, result) = joined
+            .map_err(|e| Error::CommandExecution(format!("Test task did not complete: {}", e)))?;
+        output_handler
+            .start_test(&result.test_id, &result.test_name)
+            .await?;
+        output_handler.complete_test(&result).await?;
+        if let Some(checkpoint) = checkpoint.as_deref_mut() {
+            checkpoint.record(&result)?;
+        }
+        ordered[index] = Some(result);
+    }
+
+    let workers = std::mem::take(&mut *pool.lock().expect("worker pool lock poisoned"));
+    for mut worker in workers {
+        if let Err(e) = worker.disconnect().await {
+            debug!("Failed to close pooled connection: {}", e);
+        }
+    }
+
+    Ok(ordered.into_iter().flatten().collect())
+}
+
+/// Run a single test, converting any error that escapes it into an `Error`
+/// result so one misbehaving test can never abort the rest of the suite
+pub async fn execute_test<T: SecurityTest + Sync + ?Sized>(
+    test: &T,
+    target: &mut Target,
+) -> TestResult {
+    let start_time = Instant::now();
+    let not_applicable = target.context().machine.as_ref().and_then(|machine| {
+        let applicable = test.applicable_machines();
+        if applicable.is_empty() || applicable.contains(machine) {
+            return None;
+        }
+        let machines: Vec<&str> = applicable.iter().map(MachineType::as_str).collect();
+        Some(format!(
+            "Not applicable to {} (applies to {})",
+            machine.as_str(),
+            machines.join(", ")
+        ))
+    });
+    target.take_command_attempts();
+
+    let mut result = match not_applicable {
+        Some(message) => create_test_result(
+            test.test_id(),
+            test.test_name(),
+            test.category(),
+            TestStatus::Skipped,
+            &message,
+            None,
+            start_time.elapsed(),
+        )
+        .with_skip_reason(SkipReason::NotApplicableToMachine),
+        None => run_with_timeout(test, target, start_time).await,
+    };
+    result.severity = test.severity();
+    if let Some(redactor) = &target.context().redactor {
+        result.message = redactor.redact(&result.message);
+        result.details = result.details.map(|details| redactor.redact(&details));
+        for value in result.metadata.values_mut() {
+            *value = redactor.redact(value);
+        }
+        if let Some(commands) = &mut result.remediation {
+            for command in commands.iter_mut() {
+                *command = redactor.redact(command);
+            }
+        }
+    }
+    // Recorded for every test so flaky links show up against a baseline of 1
+    let attempts = target.take_command_attempts();
+    result
+        .metadata
+        .insert("command_attempts".to_string(), attempts.to_string());
+    result
+}
+
+/// Run a test under `timeout_per_test`, turning a timeout or an escaped error
+/// into an `Error` result
+async fn run_with_timeout<T: SecurityTest + Sync + ?Sized>(
+    test: &T,
+    target: &mut Target,
+    start_time: Instant,
+) -> TestResult {
+    let limit = target.context().tests.timeout_per_test;
+    let outcome = if limit > 0 {
+        let limit = Duration::from_secs(limit);
+        target.set_deadline(Some(start_time + limit));
+        let outcome = tokio::time::timeout(limit, test.run(target)).await;
+        target.set_deadline(None);
+        outcome
+    } else {
+        Ok(test.run(target).await)
+    };
+
+    match outcome {
+        Err(_) => {
+            warn!("Test {} exceeded timeout of {}s", test.test_id(), limit);
+            create_test_result(
+                test.test_id(),
+                test.test_name(),
+                test.category(),
+                TestStatus::Error,
+                &format!("Test exceeded timeout of {}s (--timeout-per-test)", limit),
+                None,
+                start_time.elapsed(),
+            )
+        }
+        Ok(Ok(mut result)) => {
+            if result.is_finding() {
+                result.remediation = test.remediation(&result);
+            }
+            result
+        }
+        Ok(Err(e)) => {
+            warn!("Test {} returned an error: {}", test.test_id(), e);
+            create_test_result(
+                test.test_id(),
+                test.test_name(),
+                test.category(),
+                TestStatus::Error,
+                &format!("Test execution failed: {}", e),
+                None,
+                start_time.elapsed(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::communication::{CommandOutput, CommunicationChannel};
+    use crate::config::Config;
+    use crate::error::Error;
+    use async_trait::async_trait;
+    use std::time::Duration;
+
+    struct NullChannel;
+
+    #[async_trait]
+    impl CommunicationChannel for NullChannel {
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn execute_command(&mut self, _command: &str) -> Result<CommandOutput> {
+            Ok(CommandOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: 0,
+            })
+        }
+
+        async fn execute_command_with_timeout(
+            &mut self,
+            command: &str,
+            _timeout: Duration,
+        ) -> Result<CommandOutput> {
+            self.execute_command(command).await
+        }
+
+        async fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn description(&self) -> String {
+            "null channel".to_string()
+        }
+
+        fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+            Some(Box::new(NullChannel))
+        }
+
+        fn supports_pooling(&self) -> bool {
+            true
+        }
+    }
+
+    /// Blocks like libssh2 does: every command holds its session's lock on a
+    /// blocking thread for the whole time it runs
+    struct BlockingChannel {
+        session: Arc<std::sync::Mutex<()>>,
+        delay: Duration,
+    }
+
+    impl BlockingChannel {
+        fn new(delay: Duration) -> Self {
+            Self {
+                session: Arc::new(std::sync::Mutex::new(())),
+                delay,
+            }
+        }
+
+        async fn block(&self, limit: Duration) -> Result<CommandOutput> {
+            let session = Arc::clone(&self.session);
+            let delay = self.delay;
+            tokio::task::spawn_blocking(move || {
+                let _session = session.lock().unwrap();
+                std::thread::sleep(delay.min(limit));
+            })
+            .await
+            .unwrap();
+            if limit < delay {
+                return Err(Error::CommandExecution(format!(
+                    "Command timed out after {:?}",
+                    limit
+                )));
+            }
+            Ok(CommandOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: 0,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl CommunicationChannel for BlockingChannel {
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn execute_command(&mut self, _command: &str) -> Result<CommandOutput> {
+            self.block(Duration::MAX).await
+        }
+
+        async fn execute_command_with_timeout(
+            &mut self,
+            _command: &str,
+            timeout: Duration,
+        ) -> Result<CommandOutput> {
+            self.block(timeout).await
+        }
+
+        async fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn description(&self) -> String {
+            "blocking channel".to_string()
+        }
+
+        fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+            Some(Box::new(BlockingChannel::new(self.delay)))
+        }
+
+        fn supports_pooling(&self) -> bool {
+            true
+        }
+    }
+
+    /// Blocks the calling thread inside `execute_command` until its command's
+    /// timeout, or indefinitely when it has none
+    struct HangingChannel;
+
+    #[async_trait]
+    impl CommunicationChannel for HangingChannel {
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn execute_command(&mut self, _command: &str) -> Result<CommandOutput> {
+            std::thread::sleep(Duration::from_secs(30));
+            Err(Error::CommandExecution("no response".to_string()))
+        }
+
+        async fn execute_command_with_timeout(
+            &mut self,
+            _command: &str,
+            timeout: Duration,
+        ) -> Result<CommandOutput> {
+            std::thread::sleep(timeout.min(Duration::from_secs(30)));
+            Err(Error::CommandExecution(format!(
+                "Command timed out after {:?}",
+                timeout
+            )))
+        }
+
+        async fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn description(&self) -> String {
+            "hanging channel".to_string()
+        }
+    }
+
+    struct StubTest {
+        id: &'static str,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl SecurityTest for StubTest {
+        async fn run(&self, _target: &mut Target) -> Result<TestResult> {
+            if self.fail {
+                return Err(Error::CommandExecution("channel dropped".to_string()));
+            }
+            Ok(create_test_result(
+                self.id,
+                "Stub",
+                "stub",
+                TestStatus::Passed,
+                "ok",
+                None,
+                Duration::ZERO,
+            ))
+        }
+
+        fn test_id(&self) -> &str {
+            self.id
+        }
+
+        fn test_name(&self) -> &str {
+            "Stub"
+        }
+
+        fn category(&self) -> &str {
+            "stub"
+        }
+
+        fn description(&self) -> &str {
+            "Stub test"
+        }
+    }
+
+    struct SlowTest {
+        id: &'static str,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl SecurityTest for SlowTest {
+        async fn run(&self, _target: &mut Target) -> Result<TestResult> {
+            let start = Instant::now();
+            tokio::time::sleep(self.delay).await;
+            Ok(create_test_result(
+                self.id,
+                "Slow",
+                "stub",
+                TestStatus::Passed,
+                "ok",
+                None,
+                start.elapsed(),
+            ))
+        }
+
+        fn test_id(&self) -> &str {
+            self.id
+        }
+
+        fn test_name(&self) -> &str {
+            "Slow"
+        }
+
+        fn category(&self) -> &str {
+            "stub"
+        }
+
+        fn description(&self) -> &str {
+            "Slow stub test"
+        }
+    }
+
+    struct CommandTest {
+        id: &'static str,
+    }
+
+    #[async_trait]
+    impl SecurityTest for CommandTest {
+        async fn run(&self, target: &mut Target) -> Result<TestResult> {
+            let start = Instant::now();
+            target.execute_command("true").await?;
+            Ok(create_test_result(
+                self.id,
+                "Command",
+                "stub",
+                TestStatus::Passed,
+                "ok",
+                None,
+                start.elapsed(),
+            ))
+        }
+
+        fn test_id(&self) -> &str {
+            self.id
+        }
+
+        fn test_name(&self) -> &str {
+            "Command"
+        }
+
+        fn category(&self) -> &str {
+            "stub"
+        }
+
+        fn description(&self) -> &str {
+            "Stub test running one command"
+        }
+    }
+
+    struct ImxOnlyTest;
+
+    #[async_trait]
+    impl SecurityTest for ImxOnlyTest {
+        async fn run(&self, _target: &mut Target) -> Result<TestResult> {
+            Ok(create_test_result(
+                "imx_001",
+                "i.MX only",
+                "stub",
+                TestStatus::Failed,
+                "hardware missing",
+                None,
+                Duration::ZERO,
+            ))
+        }
+
+        fn test_id(&self) -> &str {
+            "imx_001"
+        }
+
+        fn test_name(&self) -> &str {
+            "i.MX only"
+        }
+
+        fn category(&self) -> &str {
+            "stub"
+        }
+
+        fn description(&self) -> &str {
+            "Stub test for i.MX hardware"
+        }
+
+        fn applicable_machines(&self) -> &[MachineType] {
+            crate::cli::IMX_MACHINES
+        }
+    }
+
+    #[tokio::test]
+    async fn test_test_for_other_machine_is_skipped_not_failed() {
+        let mut target =
+            Target::from_channel(Box::new(NullChannel), Config::default().communication);
+
+        // Machine not known: the test runs
+        let result = execute_test(&ImxOnlyTest, &mut target).await;
+        assert_eq!(result.status, TestStatus::Failed);
+
+        target.set_context(RunContext {
+            machine: Some(MachineType::RaspberryPiCm4),
+            ..Default::default()
+        });
+        let result = execute_test(&ImxOnlyTest, &mut target).await;
+        assert_eq!(result.status, TestStatus::Skipped);
+        assert_eq!(result.skip_reason, Some(SkipReason::NotApplicableToMachine));
+        assert_eq!(result.severity, ImxOnlyTest.severity());
+        assert_eq!(
+            result.metadata.get("command_attempts").map(String::as_str),
+            Some("0")
+        );
+        assert!(result
+            .message
+            .contains("Not applicable to raspberry-pi-cm4 (applies to imx93-jaguar-eink"));
+
+        target.set_context(RunContext {
+            machine: Some(MachineType::Imx8mmJaguarSentai),
+            ..Default::default()
+        });
+        let result = execute_test(&ImxOnlyTest, &mut target).await;
+        assert_eq!(result.status, TestStatus::Failed);
+    }
+
+    #[test]
+    fn test_resolve_jobs_respects_channel_limits() {
+        let mut config = Config::default().communication;
+        assert_eq!(resolve_jobs(None, &config), 1);
+        assert_eq!(resolve_jobs(Some(2), &config), 2);
+        assert_eq!(resolve_jobs(Some(16), &config), 4);
+
+        config.ssh_max_sessions = Some(8);
+        assert_eq!(resolve_jobs(Some(16), &config), 8);
+
+        config.channel_type = "serial".to_string();
+        assert_eq!(resolve_jobs(Some(4), &config), 1);
+    }
+
+    #[tokio::test]
+    async fn test_erroring_test_does_not_abort_suite() {
+        let mut target =
+            Target::from_channel(Box::new(NullChannel), Config::default().communication);
+        let tests = [
+            StubTest {
+                id: "stub_001",
+                fail: false,
+            },
+            StubTest {
+                id: "stub_002",
+                fail: true,
+            },
+            StubTest {
+                id: "stub_003",
+                fail: false,
+            },
+        ];
+
+        let mut output_handler = OutputHandler::new(Config::default().output).unwrap();
+
+        let results = run_sequentially(&tests, &mut target, 0, &mut output_handler, None)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].status, TestStatus::Passed);
+        assert_eq!(results[1].status, TestStatus::Error);
+        assert_eq!(results[1].test_id, "stub_002");
+        assert!(results[1].message.contains("channel dropped"));
+        assert_eq!(results[2].status, TestStatus::Passed);
+    }
+
+    #[tokio::test]
+    async fn test_hung_test_times_out_as_error() {
+        let mut target =
+            Target::from_channel(Box::new(NullChannel), Config::default().communication);
+        let mut context = RunContext::default();
+        context.tests.timeout_per_test = 1;
+        target.set_context(context);
+
+        let start = Instant::now();
+        let result = execute_test(
+            &SlowTest {
+                id: "slow_001",
+                delay: Duration::from_secs(30),
+            },
+            &mut target,
+        )
+        .await;
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(result.status, TestStatus::Error);
+        assert!(result.message.contains("exceeded timeout of 1s"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_interrupts_command_blocking_in_channel() {
+        let mut target =
+            Target::from_channel(Box::new(HangingChannel), Config::default().communication);
+        let mut context = RunContext::default();
+        context.tests.timeout_per_test = 1;
+        target.set_context(context);
+
+        let start = Instant::now();
+        let result = execute_test(&CommandTest { id: "cmd_001" }, &mut target).await;
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(result.status, TestStatus::Error);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_results_keep_suite_order() {
+        let target = Target::from_channel(Box::new(NullChannel), Config::default().communication);
+        let mut output_handler = OutputHandler::new(Config::default().output).unwrap();
+        let tests = vec![
+            SlowTest {
+                id: "slow_001",
+                delay: Duration::from_millis(150),
+            },
+            SlowTest {
+                id: "slow_002",
+                delay: Duration::from_millis(50),
+            },
+            SlowTest {
+                id: "slow_003",
+                delay: Duration::from_millis(10),
+            },
+        ];
+
+        let start = Instant::now();
+        let results = run_concurrently(tests, &target, 3, &mut output_handler, None)
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|r| r.test_id.as_str()).collect();
+        assert_eq!(ids, ["slow_001", "slow_002", "slow_003"]);
+        assert!(start.elapsed() < Duration::from_millis(200));
+        assert!(results[0].duration >= Duration::from_millis(150));
+        assert!(results[2].duration < Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_tests_do_not_wait_on_blocking_commands() {
+        let target = Target::from_channel(
+            Box::new(BlockingChannel::new(Duration::from_millis(200))),
+            Config::default().communication,
+        );
+        let mut output_handler = OutputHandler::new(Config::default().output).unwrap();
+        let tests = vec![
+            CommandTest { id: "cmd_001" },
+            CommandTest { id: "cmd_002" },
+            CommandTest { id: "cmd_003" },
+            CommandTest { id: "cmd_004" },
+        ];
+
+        let start = Instant::now();
+        let results = run_concurrently(tests, &target, 4, &mut output_handler, None)
+            .await
+            .unwrap();
+
+        assert!(results.iter().all(|r| r.status == TestStatus::Passed));
+        // Run one after another, four commands take at least 800ms
+        assert!(start.elapsed() < Duration::from_millis(600));
+    }
+
+    #[test]
+    fn test_fixes_writing_to_read_only_mounts_are_skipped() {
+        let mounts = "rootfs / rootfs rw 0 0\n\
+                      /dev/mmcblk0p2 / ext4 rw,relatime 0 0\n\
+                      /dev/mmcblk0p2 /sysroot ext4 ro,relatime 0 0\n\
+                      /dev/mmcblk0p2 /usr ext4 ro,relatime 0 0\n\
+                      proc /proc proc rw,nosuid 0 0\n";
+        let read_only = read_only_mounts(mounts);
+        assert_eq!(read_only, ["/sysroot", "/usr"]);
+
+        let usr_fix = vec!["chmod 0755 /usr/bin/newgrp".to_string()];
+        let etc_fix = vec![
+            "sed -i 's/^PermitRootLogin.*/PermitRootLogin no/' /etc/ssh/sshd_config".to_string(),
+        ];
+        assert_eq!(read_only_mount_for(&usr_fix, &read_only), Some("/usr"));
+        assert_eq!(read_only_mount_for(&etc_fix, &read_only), None);
+
+        // A read-only root filesystem blocks every fix
+        let read_only = read_only_mounts("/dev/root / ext4 ro,relatime 0 0\n");
+        assert_eq!(read_only_mount_for(&etc_fix, &read_only), Some("/"));
+    }
+}
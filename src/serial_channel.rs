@@ -6,6 +6,7 @@
 
 use crate::communication::{ChannelConfig, CommandOutput, CommunicationChannel};
 use crate::error::{Error, Result};
+use crate::secret::{mask_sudo_password, redact_output};
 use async_trait::async_trait;
 use bytes::BytesMut;
 use std::time::Duration;
@@ -40,6 +41,39 @@ fn strip_ansi_codes(text: &str) -> String {
     result
 }
 
+/// Number of wake-up attempts made before giving up on reaching a prompt
+const STABLE_PROMPT_ATTEMPTS: u32 = 5;
+/// How long the console must stay silent before its output is considered settled
+const PROMPT_QUIET_PERIOD: Duration = Duration::from_millis(500);
+/// How much recent serial output is kept for timeout diagnostics
+const RECENT_BYTES_LIMIT: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PromptKind {
+    Shell,
+    Login,
+}
+
+/// Classify the last non-empty line of settled console output as a shell or login prompt
+fn classify_prompt(text: &str, config: &SerialChannelConfig) -> Option<PromptKind> {
+    let line = text.lines().map(str::trim).rfind(|line| !line.is_empty())?;
+
+    let login_prompt = config.login_prompt.as_deref().unwrap_or("login:").trim();
+    if !login_prompt.is_empty() && line.ends_with(login_prompt) {
+        return Some(PromptKind::Login);
+    }
+
+    let shell_prompt = config.shell_prompt.as_deref().map(str::trim);
+    if shell_prompt.is_some_and(|prompt| !prompt.is_empty() && line.ends_with(prompt))
+        || line.ends_with('$')
+        || line.ends_with('#')
+    {
+        return Some(PromptKind::Shell);
+    }
+
+    None
+}
+
 pub struct SerialChannel {
     config: SerialChannelConfig,
     port: Option<SerialStream>,
@@ -121,8 +155,8 @@ impl SerialChannel {
 
                         debug!(
                             "Serial RX: {:?} -> {:?} (looking for: {:?})",
-                            raw_text.trim(),
-                            clean_text.trim(),
+                            redact_output(raw_text.trim()),
+                            redact_output(clean_text.trim()),
                             expected_prompt
                         );
 
@@ -152,16 +186,84 @@ impl SerialChannel {
         }
     }
 
+    /// Wake the console and wait until it settles on a shell or login prompt.
+    ///
+    /// Connecting while the target is still booting means the prompt may not have been
+    /// printed yet, or may be buried under kernel messages. Each attempt sends a newline
+    /// and reads until the line goes quiet; if the last line is not a prompt it backs off
+    /// and tries again, failing with the most recent serial output once attempts run out.
+    async fn wait_for_stable_prompt(&mut self) -> Result<PromptKind> {
+        let attempt_window = Duration::from_secs(
+            (self.config.timeout as u64 / STABLE_PROMPT_ATTEMPTS as u64).max(2),
+        );
+        let port = self
+            .port
+            .as_mut()
+            .ok_or_else(|| Error::Communication("Serial port not connected".to_string()))?;
+
+        let mut recent: Vec<u8> = Vec::new();
+
+        for attempt in 1..=STABLE_PROMPT_ATTEMPTS {
+            debug!(
+                "Waiting for stable prompt (attempt {}/{})",
+                attempt, STABLE_PROMPT_ATTEMPTS
+            );
+            port.write_all(b"\r\n")
+                .await
+                .map_err(|e| Error::SerialConnection(format!("Failed to send newline: {}", e)))?;
+            port.flush()
+                .await
+                .map_err(|e| Error::SerialConnection(format!("Failed to flush: {}", e)))?;
+
+            let mut buffer = BytesMut::new();
+            let deadline = tokio::time::Instant::now() + attempt_window;
+            while tokio::time::Instant::now() < deadline {
+                let mut temp_buf = [0u8; 1024];
+                match timeout(PROMPT_QUIET_PERIOD, port.read(&mut temp_buf)).await {
+                    Ok(Ok(n)) if n > 0 => {
+                        buffer.extend_from_slice(&temp_buf[..n]);
+                        recent.extend_from_slice(&temp_buf[..n]);
+                        if recent.len() > RECENT_BYTES_LIMIT {
+                            recent.drain(..recent.len() - RECENT_BYTES_LIMIT);
+                        }
+                    }
+                    // Nothing arrived within the quiet period, so the console has settled
+                    Ok(Ok(_)) | Err(_) => break,
+                    Ok(Err(e)) => {
+                        return Err(Error::SerialConnection(format!("Read error: {}", e)));
+                    }
+                }
+            }
+
+            let clean_text = strip_ansi_codes(&String::from_utf8_lossy(&buffer));
+            debug!(
+                "Serial RX while waiting for prompt: {:?}",
+                redact_output(clean_text.trim())
+            );
+            if let Some(kind) = classify_prompt(&clean_text, &self.config) {
+                debug!("Stable {:?} prompt after {} attempt(s)", kind, attempt);
+                return Ok(kind);
+            }
+
+            sleep(Duration::from_millis(250 * attempt as u64)).await;
+        }
+
+        let recent_text = strip_ansi_codes(&String::from_utf8_lossy(&recent));
+        Err(Error::SerialConnection(format!(
+            "Timed out waiting for a stable prompt on {} after {} attempts; recent serial output: {:?}",
+            self.config.device, STABLE_PROMPT_ATTEMPTS, recent_text
+        )))
+    }
+
     async fn send_command(&mut self, command: &str) -> Result<()> {
         let port = self
             .port
             .as_mut()
             .ok_or_else(|| Error::Communication("Serial port not connected".to_string()))?;
 
-        debug!("Serial TX: {:?}", command);
+        debug!("Serial TX: {:?}", mask_sudo_password(command));
 
         let command_with_newline = format!("{}\r\n", command);
-        debug!("Serial TX (with newline): {:?}", command_with_newline);
         port.write_all(command_with_newline.as_bytes())
             .await
             .map_err(|e| Error::SerialConnection(format!("Failed to send command: {}", e)))?;
@@ -178,61 +280,22 @@ impl SerialChannel {
             return Ok(());
         }
 
-        // Send Ctrl-C and newlines to break out of any running process and get a clean prompt
+        // Send Ctrl-C to break out of any running process, then wait for the console to settle
         if let Some(port) = &mut self.port {
             debug!("Sending Ctrl-C to break out of any running process");
             port.write_all(&[3]) // Ctrl-C
                 .await
                 .map_err(|e| Error::SerialConnection(format!("Failed to send Ctrl-C: {}", e)))?;
-
-            sleep(Duration::from_millis(100)).await;
-            // Try different line endings to wake up the device
-            for (i, line_ending) in [b"\r\n".as_slice(), b"\n".as_slice(), b"\r".as_slice()]
-                .iter()
-                .enumerate()
-            {
-                debug!("Sending line ending attempt {}: {:?}", i + 1, line_ending);
-                port.write_all(line_ending).await.map_err(|e| {
-                    Error::SerialConnection(format!("Failed to send newline: {}", e))
-                })?;
-                port.flush()
-                    .await
-                    .map_err(|e| Error::SerialConnection(format!("Failed to flush: {}", e)))?;
-
-                // Wait a bit and try to read any response
-                sleep(Duration::from_millis(500)).await;
-
-                // Try to read any immediate response
-                let mut temp_buf = [0u8; 1024];
-                match port.try_read(&mut temp_buf) {
-                    Ok(n) if n > 0 => {
-                        let raw_response = String::from_utf8_lossy(&temp_buf[..n]);
-                        let clean_response = strip_ansi_codes(&raw_response);
-                        info!(
-                            "Received after line ending {}: {:?} -> {:?}",
-                            i + 1,
-                            raw_response.trim(),
-                            clean_response.trim()
-                        );
-
-                        // Check if we can see any shell-like prompt ($ or #) - be flexible
-                        if clean_response.contains('$') || clean_response.contains('#') {
-                            info!("Shell prompt detected in response, assuming ready");
-                            self.logged_in = true;
-                            return Ok(());
-                        }
-                    }
-                    _ => {
-                        debug!("No immediate response to line ending {}", i + 1);
-                    }
-                }
-            }
         }
 
-        sleep(Duration::from_millis(500)).await;
+        let prompt = self.wait_for_stable_prompt().await?;
+        if prompt == PromptKind::Shell {
+            info!("Shell prompt detected, assuming ready");
+            self.logged_in = true;
+            return Ok(());
+        }
 
         // Check if we need to login
-        let login_prompt = self.config.login_prompt.clone();
         let username = self.config.username.clone();
         let password_prompt = self.config.password_prompt.clone();
         let password = self.config.password.clone();
@@ -245,43 +308,16 @@ impl SerialChannel {
             return Ok(());
         }
 
-        // Try to wait for shell prompt first (maybe we're already logged in)
-        if let Some(shell_prompt) = shell_prompt.clone() {
-            debug!("Checking if already at shell prompt: {}", shell_prompt);
-            match self.wait_for_prompt(&shell_prompt, 3).await {
-                Ok(_) => {
-                    info!("Already at shell prompt, no login needed");
-                    self.logged_in = true;
-                    return Ok(());
-                }
-                Err(_) => {
-                    debug!("Not at shell prompt, checking for login prompt");
-                }
-            }
-        }
+        // The stable prompt is a login prompt, so proceed straight to sending credentials
+        if let Some(username) = username {
+            info!("Login prompt found, proceeding with login");
+            self.send_command(&username).await?;
 
-        if let Some(login_prompt) = login_prompt {
-            if let Some(username) = username {
-                info!("Waiting for login prompt: {}", login_prompt);
-                // Use a shorter timeout since we might not need to login
-                match self.wait_for_prompt(&login_prompt, 5).await {
-                    Ok(_) => {
-                        info!("Login prompt found, proceeding with login");
-                        self.send_command(&username).await?;
-
-                        if let Some(password_prompt) = password_prompt {
-                            if let Some(password) = password {
-                                info!("Waiting for password prompt: {}", password_prompt);
-                                self.wait_for_prompt(&password_prompt, 10).await?;
-                                self.send_command(&password).await?;
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        info!("No login prompt found, assuming shell is ready");
-                        self.logged_in = true;
-                        return Ok(());
-                    }
+            if let Some(password_prompt) = password_prompt {
+                if let Some(password) = password {
+                    info!("Waiting for password prompt: {}", password_prompt);
+                    self.wait_for_prompt(&password_prompt, 10).await?;
+                    self.send_command(&password).await?;
                 }
             }
         }
@@ -367,7 +403,7 @@ impl CommunicationChannel for SerialChannel {
 
         debug!(
             "Executing serial command with timeout: {} (timeout: {}s)",
-            command,
+            mask_sudo_password(command),
             timeout_duration.as_secs()
         );
 
@@ -403,8 +439,8 @@ impl CommunicationChannel for SerialChannel {
 
                         debug!(
                             "Serial RX (command output): {:?} -> {:?}",
-                            raw_text.trim(),
-                            clean_text.trim()
+                            redact_output(raw_text.trim()),
+                            redact_output(clean_text.trim())
                         );
 
                         // Skip the command echo (first line)
@@ -426,7 +462,11 @@ impl CommunicationChannel for SerialChannel {
                                     {
                                         echo_line_found = true;
                                         start_index = i + 1; // Start collecting output from the next line
-                                        debug!("Found command echo at line {}: {:?}", i, line);
+                                        debug!(
+                                            "Found command echo at line {}: {:?}",
+                                            i,
+                                            redact_output(line)
+                                        );
                                         break;
                                     }
                                 }
@@ -434,7 +474,10 @@ impl CommunicationChannel for SerialChannel {
                                 if echo_line_found && start_index < lines.len() {
                                     stdout = lines[start_index..].join("\n");
                                     command_echo_seen = true;
-                                    debug!("Command echo stripped, remaining output: {:?}", stdout);
+                                    debug!(
+                                        "Command echo stripped, remaining output: {:?}",
+                                        redact_output(&stdout)
+                                    );
                                 } else if echo_line_found {
                                     // Echo found but no output yet, mark as seen and continue
                                     command_echo_seen = true;
@@ -501,7 +544,7 @@ impl CommunicationChannel for SerialChannel {
                                 stdout = new_content;
                             }
 
-                            debug!("Accumulated output: {:?}", stdout);
+                            debug!("Accumulated output: {:?}", redact_output(&stdout));
                         }
 
                         // Check if we've reached the shell prompt (command completed)
@@ -551,7 +594,7 @@ impl CommunicationChannel for SerialChannel {
                             }
 
                             stdout = cleaned_lines.join("\n").trim().to_string();
-                            debug!("Final cleaned output: {:?}", stdout);
+                            debug!("Final cleaned output: {:?}", redact_output(&stdout));
                             break;
                         }
                     }
@@ -609,3 +652,39 @@ impl CommunicationChannel for SerialChannel {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SerialChannelConfig {
+        SerialChannelConfig {
+            device: "/dev/ttyUSB0".to_string(),
+            baud_rate: 115200,
+            timeout: 30,
+            login_prompt: Some("login:".to_string()),
+            password_prompt: Some("Password:".to_string()),
+            shell_prompt: None,
+            username: Some("root".to_string()),
+            password: None,
+        }
+    }
+
+    #[test]
+    fn test_classify_prompt_uses_last_settled_line() {
+        let config = config();
+        assert_eq!(
+            classify_prompt("Welcome\r\nimx8mm-jaguar-sentai login: ", &config),
+            Some(PromptKind::Login)
+        );
+        assert_eq!(
+            classify_prompt("root@imx8mm:~# \r\n\r\nroot@imx8mm:~# ", &config),
+            Some(PromptKind::Shell)
+        );
+        // A prompt followed by late kernel messages has not settled yet
+        assert_eq!(
+            classify_prompt("login: \r\n[   12.345678] usb 1-1: new device\r\n", &config),
+            None
+        );
+    }
+}
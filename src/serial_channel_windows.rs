@@ -7,6 +7,7 @@
 use crate::{
     communication::{ChannelConfig, CommandOutput, CommunicationChannel},
     error::{Error, Result},
+    secret::{mask_sudo_password, redact_output},
 };
 use async_trait::async_trait;
 use bytes::BytesMut;
@@ -95,7 +96,7 @@ impl WindowsSerialChannel {
                 Error::SerialConnection(format!("Failed to flush serial port: {}", e))
             })?;
 
-            debug!("Sent command: {}", command);
+            debug!("Sent command: {}", mask_sudo_password(command));
             Ok(())
         } else {
             Err(Error::SerialConnection(
@@ -125,7 +126,10 @@ impl WindowsSerialChannel {
 
                         // Look for shell prompt patterns
                         if self.has_prompt(&cleaned_response) {
-                            debug!("Received response with prompt: {}", cleaned_response.trim());
+                            debug!(
+                                "Received response with prompt: {}",
+                                redact_output(cleaned_response.trim())
+                            );
                             return Ok(cleaned_response);
                         }
                     }
@@ -231,7 +235,10 @@ impl WindowsSerialChannel {
         // Try to read any immediate response
         if let Ok(response) = self.read_response(Duration::from_secs(2)) {
             let cleaned_response = self.strip_ansi_codes(&response);
-            debug!("Initial response: {}", cleaned_response.trim());
+            debug!(
+                "Initial response: {}",
+                redact_output(cleaned_response.trim())
+            );
 
             // Check if we already have a shell prompt
             if self.has_prompt(&cleaned_response) {
@@ -373,7 +380,7 @@ impl CommunicationChannel for WindowsSerialChannel {
             ));
         }
 
-        debug!("Executing command: {}", command);
+        debug!("Executing command: {}", mask_sudo_password(command));
 
         // Send the command
         self.send_command(command)?;
@@ -425,7 +432,7 @@ impl CommunicationChannel for WindowsSerialChannel {
 
         let stdout = stdout.trim().to_string();
 
-        debug!("Command output: {}", stdout);
+        debug!("Command output: {}", redact_output(&stdout));
 
         Ok(CommandOutput {
             stdout,
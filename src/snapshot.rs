@@ -0,0 +1,332 @@
+/*
+ * Security Compliance CLI - Command Snapshots
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+//! Recording and replay of the commands a run sends to the target.
+//!
+//! `--record` wraps the live channel and saves every command with its output;
+//! `--replay` serves a run from such a file without any device attached, so
+//! audits can be reproduced and the tests exercised offline.
+
+use crate::communication::{CommandOutput, CommunicationChannel};
+use crate::error::{Error, Result};
+use crate::secret::{mask_sudo_password, redact_output};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Snapshot file format version written by `--record`
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Commands captured from a device, in the order they were run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub recorded_at: DateTime<Utc>,
+    /// Description of the channel the commands were recorded over
+    pub target: String,
+    pub commands: Vec<SnapshotEntry>,
+}
+
+/// One command and what the device answered. Sudo passwords are masked in
+/// `command`, and replay masks incoming commands the same way before matching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub command: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+impl Snapshot {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let snapshot: Snapshot = serde_json::from_str(&content)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(Error::Config(format!(
+                "Unsupported snapshot version {} in {} (expected {})",
+                snapshot.version,
+                path.display(),
+                SNAPSHOT_VERSION
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Passes commands through to a live channel and records each one with its output
+pub struct RecordingChannel {
+    inner: Box<dyn CommunicationChannel>,
+    path: PathBuf,
+    /// Shared by every pooled handle opened from this channel
+    entries: Arc<Mutex<Vec<SnapshotEntry>>>,
+}
+
+impl RecordingChannel {
+    pub fn new(inner: Box<dyn CommunicationChannel>, path: PathBuf) -> Self {
+        Self {
+            inner,
+            path,
+            entries: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn record(&self, command: &str, output: &CommandOutput) {
+        let mut entries = self.entries.lock().expect("snapshot lock poisoned");
+        entries.push(SnapshotEntry {
+            command: mask_sudo_password(command),
+            stdout: redact_output(&output.stdout),
+            stderr: redact_output(&output.stderr),
+            exit_code: output.exit_code,
+        });
+    }
+
+    /// Write everything recorded so far to the snapshot file
+    fn save(&self) -> Result<()> {
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            recorded_at: Utc::now(),
+            target: self.inner.description(),
+            commands: self.entries.lock().expect("snapshot lock poisoned").clone(),
+        };
+        snapshot.save(&self.path)?;
+        info!(
+            "📼 Recorded {} commands to {}",
+            snapshot.commands.len(),
+            self.path.display()
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CommunicationChannel for RecordingChannel {
+    async fn connect(&mut self) -> Result<()> {
+        self.inner.connect().await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.save()?;
+        self.inner.disconnect().await
+    }
+
+    async fn execute_command(&mut self, command: &str) -> Result<CommandOutput> {
+        let output = self.inner.execute_command(command).await?;
+        self.record(command, &output);
+        Ok(output)
+    }
+
+    async fn execute_command_with_timeout(
+        &mut self,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<CommandOutput> {
+        let output = self
+            .inner
+            .execute_command_with_timeout(command, timeout)
+            .await?;
+        self.record(command, &output);
+        Ok(output)
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.inner.is_connected().await
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "{} (recording to {})",
+            self.inner.description(),
+            self.path.display()
+        )
+    }
+
+    async fn upload_file(&mut self, local_path: &str, remote_path: &str) -> Result<()> {
+        self.inner.upload_file(local_path, remote_path).await
+    }
+
+    async fn download_file(&mut self, remote_path: &str, local_path: &str) -> Result<()> {
+        self.inner.download_file(remote_path, local_path).await
+    }
+
+    fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+        Some(Box::new(Self {
+            inner: self.inner.pooled_session()?,
+            path: self.path.clone(),
+            entries: Arc::clone(&self.entries),
+        }))
+    }
+
+    fn supports_pooling(&self) -> bool {
+        self.inner.supports_pooling()
+    }
+
+    fn reconnect_count(&self) -> u32 {
+        self.inner.reconnect_count()
+    }
+}
+
+/// Recorded answers for each command, with the index of the next one to serve
+type RecordedResponses = HashMap<String, (Vec<SnapshotEntry>, usize)>;
+
+/// Answers commands from a snapshot instead of a device. A command recorded
+/// several times is answered with each recording in turn, then the last one.
+pub struct ReplayChannel {
+    path: PathBuf,
+    recorded_target: String,
+    responses: Arc<Mutex<RecordedResponses>>,
+    connected: bool,
+}
+
+impl ReplayChannel {
+    pub fn new(snapshot: Snapshot, path: PathBuf) -> Self {
+        let mut responses = RecordedResponses::new();
+        for entry in snapshot.commands {
+            responses
+                .entry(entry.command.clone())
+                .or_default()
+                .0
+                .push(entry);
+        }
+        Self {
+            path,
+            recorded_target: snapshot.target,
+            responses: Arc::new(Mutex::new(responses)),
+            connected: false,
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Ok(Self::new(Snapshot::load(path)?, path.to_path_buf()))
+    }
+
+    fn replay(&self, command: &str) -> Result<CommandOutput> {
+        let key = mask_sudo_password(command);
+        let mut responses = self.responses.lock().expect("snapshot lock poisoned");
+        let (entries, next) = responses.get_mut(&key).ok_or_else(|| {
+            Error::CommandExecution(format!(
+                "Command not in snapshot {}: {}",
+                self.path.display(),
+                key
+            ))
+        })?;
+
+        let entry = &entries[(*next).min(entries.len() - 1)];
+        *next += 1;
+        debug!("Replaying recorded output for: {}", key);
+        Ok(CommandOutput {
+            stdout: entry.stdout.clone(),
+            stderr: entry.stderr.clone(),
+            exit_code: entry.exit_code,
+        })
+    }
+}
+
+#[async_trait]
+impl CommunicationChannel for ReplayChannel {
+    async fn connect(&mut self) -> Result<()> {
+        info!(
+            "📼 Replaying commands from {} (recorded from {})",
+            self.path.display(),
+            self.recorded_target
+        );
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+
+    async fn execute_command(&mut self, command: &str) -> Result<CommandOutput> {
+        self.replay(command)
+    }
+
+    async fn execute_command_with_timeout(
+        &mut self,
+        command: &str,
+        _timeout: Duration,
+    ) -> Result<CommandOutput> {
+        self.replay(command)
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn description(&self) -> String {
+        format!("Replay of {}", self.path.display())
+    }
+
+    fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+        Some(Box::new(Self {
+            path: self.path.clone(),
+            recorded_target: self.recorded_target.clone(),
+            responses: Arc::clone(&self.responses),
+            connected: self.connected,
+        }))
+    }
+
+    fn supports_pooling(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local_channel::LocalChannel;
+
+    #[tokio::test]
+    async fn test_recorded_commands_replay_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.json");
+
+        let mut recorder = RecordingChannel::new(Box::new(LocalChannel::new(5)), path.clone());
+        recorder.connect().await.unwrap();
+        recorder.execute_command("echo first").await.unwrap();
+        recorder
+            .execute_command("echo 'secret' | sudo -S true; echo privileged; exit 4")
+            .await
+            .unwrap();
+        recorder
+            .execute_command("printf 'root:%s6%ssalt%shash:19000\\n' '$' '$' '$'")
+            .await
+            .unwrap();
+        recorder.disconnect().await.unwrap();
+
+        // Neither the password nor captured secrets end up in the snapshot
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("secret"));
+        assert!(!saved.contains("$6$salt$hash"));
+        assert!(saved.contains("root:[REDACTED]:19000"));
+
+        let mut replay = ReplayChannel::from_file(&path).unwrap();
+        replay.connect().await.unwrap();
+        let output = replay.execute_command("echo first").await.unwrap();
+        assert_eq!(output.stdout, "first\n");
+
+        // Replay matches on the masked command, whatever password is configured now
+        let output = replay
+            .execute_command("echo 'other' | sudo -S true; echo privileged; exit 4")
+            .await
+            .unwrap();
+        assert_eq!(output.exit_code, 4);
+        assert!(output.stdout.ends_with("privileged\n"));
+
+        assert!(replay.execute_command("uname -a").await.is_err());
+    }
+}
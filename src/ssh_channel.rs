@@ -6,18 +6,28 @@
 
 use crate::communication::{ChannelConfig, CommandOutput, CommunicationChannel};
 use crate::error::{Error, Result};
+use crate::secret::mask_sudo_password;
 use async_trait::async_trait;
 use ssh2::Session;
 use std::io::prelude::*;
-use std::net::TcpStream;
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::net::TcpSocket;
 use tracing::{debug, info};
 
 pub struct SshChannel {
     config: SshChannelConfig,
     session: Option<Session>,
     connected: bool,
+    /// Pooled handles open their own session on first use
+    connect_on_demand: bool,
+    /// Reconnections made by this handle or any handle pooled from it
+    reconnects: Arc<AtomicU32>,
+    /// Set to stop the keep-alive thread started by this handle's `connect`
+    keepalive_stop: Option<Arc<AtomicBool>>,
 }
 
 #[derive(Debug, Clone)]
@@ -28,7 +38,10 @@ pub struct SshChannelConfig {
     pub password: String,
     pub ssh_key_path: Option<String>,
     pub timeout: u32,
-    pub ssh_multiplex: bool,
+    pub ssh_session_pool: bool,
+    pub bind_address: Option<String>,
+    pub keepalive_secs: u32,
+    pub reconnect_attempts: u32,
 }
 
 impl SshChannel {
@@ -37,6 +50,9 @@ impl SshChannel {
             config,
             session: None,
             connected: false,
+            connect_on_demand: false,
+            reconnects: Arc::new(AtomicU32::new(0)),
+            keepalive_stop: None,
         }
     }
 
@@ -49,7 +65,10 @@ impl SshChannel {
                 password,
                 ssh_key_path,
                 timeout,
-                ssh_multiplex,
+                ssh_session_pool,
+                bind_address,
+                keepalive_secs,
+                reconnect_attempts,
             } => Ok(Self::new(SshChannelConfig {
                 host,
                 port,
@@ -57,14 +76,301 @@ impl SshChannel {
                 password,
                 ssh_key_path,
                 timeout,
-                ssh_multiplex,
+                ssh_session_pool,
+                bind_address,
+                keepalive_secs,
+                reconnect_attempts,
             })),
             _ => Err(Error::Config("Invalid channel config for SSH".to_string())),
         }
     }
 
-    fn try_key_auth(&self, session: &Session) -> Result<bool> {
-        let key_paths = if let Some(key_path) = &self.config.ssh_key_path {
+    /// Open the TCP connection from a specific source IP address, or on Linux
+    /// from a named interface, for multi-homed hosts where the default route
+    /// isn't the management path
+    async fn connect_from(&self, bind_address: &str) -> Result<TcpStream> {
+        let remote = (self.config.host.as_str(), self.config.port)
+            .to_socket_addrs()
+            .map_err(|e| Error::SshConnection(format!("Failed to resolve host: {}", e)))?
+            .collect::<Vec<SocketAddr>>();
+
+        let local_ip = bind_address.parse::<IpAddr>().ok();
+        let remote = remote
+            .into_iter()
+            .find(|addr| local_ip.is_none_or(|ip| ip.is_ipv4() == addr.is_ipv4()))
+            .ok_or_else(|| {
+                Error::SshConnection(format!(
+                    "No address for {} matches the address family of {}",
+                    self.config.host, bind_address
+                ))
+            })?;
+
+        let socket = if remote.is_ipv4() {
+            TcpSocket::new_v4()
+        } else {
+            TcpSocket::new_v6()
+        }
+        .map_err(|e| Error::SshConnection(format!("Failed to create socket: {}", e)))?;
+
+        match local_ip {
+            Some(ip) => socket.bind(SocketAddr::new(ip, 0)).map_err(|e| {
+                Error::SshConnection(format!("Failed to bind to {}: {}", bind_address, e))
+            })?,
+            #[cfg(target_os = "linux")]
+            None => socket
+                .bind_device(Some(bind_address.as_bytes()))
+                .map_err(|e| {
+                    Error::SshConnection(format!(
+                        "Failed to bind to interface {}: {}",
+                        bind_address, e
+                    ))
+                })?,
+            #[cfg(not(target_os = "linux"))]
+            None => {
+                return Err(Error::Config(format!(
+                    "Invalid bind address '{}': expected an IP address",
+                    bind_address
+                )))
+            }
+        }
+
+        info!("Binding SSH connection to {}", bind_address);
+
+        let stream = tokio::time::timeout(
+            Duration::from_secs(self.config.timeout as u64),
+            socket.connect(remote),
+        )
+        .await
+        .map_err(|_| Error::SshConnection("TCP connection timed out".to_string()))?
+        .map_err(|e| Error::SshConnection(format!("TCP connection failed: {}", e)))?;
+
+        let tcp = stream
+            .into_std()
+            .map_err(|e| Error::SshConnection(format!("TCP connection failed: {}", e)))?;
+        tcp.set_nonblocking(false)
+            .map_err(|e| Error::SshConnection(format!("TCP connection failed: {}", e)))?;
+
+        Ok(tcp)
+    }
+
+    /// Send keep-alives from a background thread whenever the session has been
+    /// idle for `keepalive_secs`, so NAT and WiFi links don't silently drop it
+    /// between slow tests
+    fn start_keepalive(&mut self, session: &Session) {
+        self.stop_keepalive();
+        if self.config.keepalive_secs == 0 {
+            return;
+        }
+
+        session.set_keepalive(true, self.config.keepalive_secs);
+        let stop = Arc::new(AtomicBool::new(false));
+        let session = session.clone();
+        let stopped = Arc::clone(&stop);
+        std::thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                // Only sends once the session has been idle for the interval
+                if let Err(e) = session.keepalive_send() {
+                    debug!("SSH keep-alive failed, stopping keep-alives: {}", e);
+                    break;
+                }
+                std::thread::sleep(Duration::from_secs(1));
+            }
+        });
+        self.keepalive_stop = Some(stop);
+    }
+
+    fn stop_keepalive(&mut self) {
+        if let Some(stop) = self.keepalive_stop.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Drop the current session and log in again with the same settings
+    async fn reconnect(&mut self) -> Result<()> {
+        self.stop_keepalive();
+        self.session = None;
+        self.connect().await?;
+        let total = self.reconnects.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!(
+            "Re-established SSH session to {}:{} ({} reconnection(s) so far)",
+            self.config.host, self.config.port, total
+        );
+        Ok(())
+    }
+
+    /// Run a command, re-establishing the session and retrying when the session
+    /// itself has dropped. An error on a session that is still alive, such as an
+    /// exec failure, and a command that ran out of time are returned as they
+    /// are: the command may already have run, and running it twice could repeat
+    /// a change.
+    async fn run_with_reconnect(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+    ) -> Result<CommandOutput> {
+        if self.session.is_none() && self.connect_on_demand {
+            self.connect().await?;
+        }
+
+        let started = std::time::Instant::now();
+        let mut result = self.run_command(command, timeout).await;
+        if let (Err(_), Some(timeout)) = (&result, timeout) {
+            if started.elapsed() >= timeout {
+                return Err(Error::CommandExecution(format!(
+                    "Command timed out after {:?}",
+                    timeout
+                )));
+            }
+        }
+
+        let mut attempt = 0;
+        while let Err(e) = &result {
+            if !self.connected
+                || attempt >= self.config.reconnect_attempts
+                || self.session_alive().await
+            {
+                break;
+            }
+            attempt += 1;
+            debug!(
+                "SSH command failed ({}), reconnecting to {}:{} (attempt {}/{})",
+                e, self.config.host, self.config.port, attempt, self.config.reconnect_attempts
+            );
+            tokio::time::sleep(Duration::from_secs(attempt as u64)).await;
+
+            match self.reconnect().await {
+                Ok(()) => result = self.run_command(command, timeout).await,
+                Err(e) => debug!("SSH reconnection attempt {} failed: {}", attempt, e),
+            }
+        }
+
+        result
+    }
+
+    /// Whether the session still carries channels: opening and closing one
+    /// runs nothing on the device, and fails once the transport has gone
+    async fn session_alive(&self) -> bool {
+        let Some(session) = self.session.clone() else {
+            return false;
+        };
+        let timeout_ms = self.config.timeout.saturating_mul(1000);
+        tokio::task::spawn_blocking(move || {
+            session.set_timeout(timeout_ms);
+            let alive = session
+                .channel_session()
+                .and_then(|mut channel| channel.close())
+                .is_ok();
+            session.set_timeout(0);
+            alive
+        })
+        .await
+        .unwrap_or(false)
+    }
+
+    /// Run a command on its own channel. libssh2 blocks, so the command runs on
+    /// tokio's blocking pool rather than stalling the async workers. A timeout
+    /// is applied both as the session timeout, which this handle owns, and on
+    /// the device, so a hung command is killed rather than left running.
+    async fn run_command(&self, command: &str, timeout: Option<Duration>) -> Result<CommandOutput> {
+        let session = self
+            .session
+            .clone()
+            .ok_or_else(|| Error::Communication("Not connected".to_string()))?;
+
+        debug!("Executing SSH command: {}", mask_sudo_password(command));
+
+        let command = match timeout {
+            Some(timeout) => bounded_command(command, timeout),
+            None => command.to_string(),
+        };
+        tokio::task::spawn_blocking(move || {
+            if let Some(timeout) = timeout {
+                session.set_timeout(timeout.as_millis().min(u32::MAX as u128) as u32);
+            }
+            let result = Self::run_on_session(&session, &command);
+            if timeout.is_some() {
+                session.set_timeout(0);
+            }
+            result
+        })
+        .await
+        .map_err(|e| Error::CommandExecution(format!("SSH command task failed: {}", e)))?
+    }
+
+    fn run_on_session(session: &Session, command: &str) -> Result<CommandOutput> {
+        let mut channel = session
+            .channel_session()
+            .map_err(|e| Error::CommandExecution(format!("Failed to create channel: {}", e)))?;
+
+        channel
+            .exec(command)
+            .map_err(|e| Error::CommandExecution(format!("Failed to execute command: {}", e)))?;
+
+        let mut stdout = String::new();
+        channel
+            .read_to_string(&mut stdout)
+            .map_err(|e| Error::CommandExecution(format!("Failed to read stdout: {}", e)))?;
+
+        let mut stderr = String::new();
+        channel
+            .stderr()
+            .read_to_string(&mut stderr)
+            .map_err(|e| Error::CommandExecution(format!("Failed to read stderr: {}", e)))?;
+
+        channel
+            .wait_close()
+            .map_err(|e| Error::CommandExecution(format!("Failed to close channel: {}", e)))?;
+
+        let exit_code = channel
+            .exit_status()
+            .map_err(|e| Error::CommandExecution(format!("Failed to get exit status: {}", e)))?;
+
+        debug!("Command completed with exit code: {}", exit_code);
+
+        Ok(CommandOutput {
+            stdout,
+            stderr,
+            exit_code,
+        })
+    }
+
+    /// Connect, handshake and authenticate. Blocks, so callers run it on
+    /// tokio's blocking pool.
+    fn open_session(config: &SshChannelConfig, tcp: Option<TcpStream>) -> Result<Session> {
+        let tcp = match tcp {
+            Some(tcp) => tcp,
+            None => TcpStream::connect(format!("{}:{}", config.host, config.port))
+                .map_err(|e| Error::SshConnection(format!("TCP connection failed: {}", e)))?,
+        };
+
+        tcp.set_read_timeout(Some(Duration::from_secs(config.timeout as u64)))
+            .map_err(|e| Error::SshConnection(format!("Failed to set read timeout: {}", e)))?;
+
+        let mut session = Session::new()
+            .map_err(|e| Error::SshConnection(format!("Failed to create SSH session: {}", e)))?;
+
+        session.set_tcp_stream(tcp);
+        session
+            .handshake()
+            .map_err(|e| Error::SshConnection(format!("SSH handshake failed: {}", e)))?;
+
+        // Try key-based authentication first
+        if !Self::try_key_auth(config, &session)? {
+            debug!("Key authentication failed, trying password authentication");
+            session
+                .userauth_password(&config.user, &config.password)
+                .map_err(|e| Error::SshAuth(format!("Password authentication failed: {}", e)))?;
+        }
+
+        if !session.authenticated() {
+            return Err(Error::SshAuth("Authentication failed".to_string()));
+        }
+
+        Ok(session)
+    }
+
+    fn try_key_auth(config: &SshChannelConfig, session: &Session) -> Result<bool> {
+        let key_paths = if let Some(key_path) = &config.ssh_key_path {
             // If a specific key is provided, only try that key to avoid "too many authentication failures"
             vec![key_path.clone()]
         } else {
@@ -87,18 +393,13 @@ impl SshChannel {
 
                 let result = if Path::new(&public_key_path).exists() {
                     session.userauth_pubkey_file(
-                        &self.config.user,
+                        &config.user,
                         Some(Path::new(&public_key_path)),
                         Path::new(&key_path),
                         None,
                     )
                 } else {
-                    session.userauth_pubkey_file(
-                        &self.config.user,
-                        None,
-                        Path::new(&key_path),
-                        None,
-                    )
+                    session.userauth_pubkey_file(&config.user, None, Path::new(&key_path), None)
                 };
 
                 match result {
@@ -111,7 +412,7 @@ impl SshChannel {
 
                         // If we have a specific key path and it fails, don't try others
                         // This prevents "too many authentication failures"
-                        if self.config.ssh_key_path.is_some() {
+                        if config.ssh_key_path.is_some() {
                             debug!("Specific key failed, not trying additional keys to avoid authentication failures");
                             break;
                         }
@@ -132,39 +433,26 @@ impl CommunicationChannel for SshChannel {
             self.config.host, self.config.port
         );
 
-        let tcp = TcpStream::connect(format!("{}:{}", self.config.host, self.config.port))
-            .map_err(|e| Error::SshConnection(format!("TCP connection failed: {}", e)))?;
-
-        tcp.set_read_timeout(Some(Duration::from_secs(self.config.timeout as u64)))
-            .map_err(|e| Error::SshConnection(format!("Failed to set read timeout: {}", e)))?;
-
-        let mut session = Session::new()
-            .map_err(|e| Error::SshConnection(format!("Failed to create SSH session: {}", e)))?;
-
-        session.set_tcp_stream(tcp);
-        session
-            .handshake()
-            .map_err(|e| Error::SshConnection(format!("SSH handshake failed: {}", e)))?;
-
-        // Try key-based authentication first
-        if !self.try_key_auth(&session)? {
-            debug!("Key authentication failed, trying password authentication");
-            session
-                .userauth_password(&self.config.user, &self.config.password)
-                .map_err(|e| Error::SshAuth(format!("Password authentication failed: {}", e)))?;
-        }
+        let tcp = match &self.config.bind_address {
+            Some(bind_address) => Some(self.connect_from(bind_address).await?),
+            None => None,
+        };
 
-        if !session.authenticated() {
-            return Err(Error::SshAuth("Authentication failed".to_string()));
-        }
+        let config = self.config.clone();
+        let session = tokio::task::spawn_blocking(move || Self::open_session(&config, tcp))
+            .await
+            .map_err(|e| Error::SshConnection(format!("SSH connection task failed: {}", e)))??;
 
         info!("SSH connection established successfully");
+        self.start_keepalive(&session);
         self.session = Some(session);
         self.connected = true;
         Ok(())
     }
 
     async fn disconnect(&mut self) -> Result<()> {
+        self.stop_keepalive();
+
         if let Some(session) = &mut self.session {
             session
                 .disconnect(None, "Closing connection", None)
@@ -177,56 +465,15 @@ impl CommunicationChannel for SshChannel {
     }
 
     async fn execute_command(&mut self, command: &str) -> Result<CommandOutput> {
-        self.execute_command_with_timeout(command, Duration::from_secs(60))
-            .await
+        self.run_with_reconnect(command, None).await
     }
 
     async fn execute_command_with_timeout(
         &mut self,
         command: &str,
-        _timeout: Duration,
+        timeout: Duration,
     ) -> Result<CommandOutput> {
-        let session = self
-            .session
-            .as_ref()
-            .ok_or_else(|| Error::Communication("Not connected".to_string()))?;
-
-        debug!("Executing SSH command: {}", command);
-
-        let mut channel = session
-            .channel_session()
-            .map_err(|e| Error::CommandExecution(format!("Failed to create channel: {}", e)))?;
-
-        channel
-            .exec(command)
-            .map_err(|e| Error::CommandExecution(format!("Failed to execute command: {}", e)))?;
-
-        let mut stdout = String::new();
-        channel
-            .read_to_string(&mut stdout)
-            .map_err(|e| Error::CommandExecution(format!("Failed to read stdout: {}", e)))?;
-
-        let mut stderr = String::new();
-        channel
-            .stderr()
-            .read_to_string(&mut stderr)
-            .map_err(|e| Error::CommandExecution(format!("Failed to read stderr: {}", e)))?;
-
-        channel
-            .wait_close()
-            .map_err(|e| Error::CommandExecution(format!("Failed to close channel: {}", e)))?;
-
-        let exit_code = channel
-            .exit_status()
-            .map_err(|e| Error::CommandExecution(format!("Failed to get exit status: {}", e)))?;
-
-        debug!("Command completed with exit code: {}", exit_code);
-
-        Ok(CommandOutput {
-            stdout,
-            stderr,
-            exit_code,
-        })
+        self.run_with_reconnect(command, Some(timeout)).await
     }
 
     async fn is_connected(&self) -> bool {
@@ -319,4 +566,48 @@ impl CommunicationChannel for SshChannel {
         );
         Ok(())
     }
+
+    /// A pooled handle with its own SSH session, opened on first use. libssh2
+    /// serializes every channel on a session behind one lock, so handles sharing
+    /// a session would still run their commands one at a time.
+    fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+        if !self.supports_pooling() {
+            return None;
+        }
+
+        debug!("Adding pooled SSH connection to {}", self.config.host);
+        Some(Box::new(SshChannel {
+            config: self.config.clone(),
+            session: None,
+            connected: false,
+            connect_on_demand: true,
+            reconnects: Arc::clone(&self.reconnects),
+            keepalive_stop: None,
+        }))
+    }
+
+    fn supports_pooling(&self) -> bool {
+        self.config.ssh_session_pool && self.connected
+    }
+
+    fn reconnect_count(&self) -> u32 {
+        self.reconnects.load(Ordering::Relaxed)
+    }
+}
+
+/// Wrap a command so the device kills it once `timeout` has passed, falling
+/// back to running it unbounded where `timeout` isn't installed
+fn bounded_command(command: &str, timeout: Duration) -> String {
+    let secs = timeout.as_secs_f64().ceil().max(1.0) as u64;
+    let quoted = format!("'{}'", command.replace('\'', "'\\''"));
+    format!(
+        "if command -v timeout >/dev/null 2>&1; then exec timeout -s KILL {} sh -c {}; else exec sh -c {}; fi",
+        secs, quoted, quoted
+    )
+}
+
+impl Drop for SshChannel {
+    fn drop(&mut self) {
+        self.stop_keepalive();
+    }
 }
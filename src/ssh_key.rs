@@ -4,10 +4,12 @@
  * Licensed under GPLv3 - see LICENSE file for details
  */
 
-use crate::communication::CommunicationChannel;
+use crate::communication::{CommandOutput, CommunicationChannel};
 use crate::error::{Error, Result};
+use crate::secret::{key_fields, key_material_matches};
 use chrono::{DateTime, Duration, Utc};
 use rand::rngs::OsRng;
+use serde::Serialize;
 use ssh_key::PrivateKey;
 use std::fs;
 use std::path::Path;
@@ -26,6 +28,46 @@ pub struct InstalledKeyInfo {
     pub is_temp_key: bool,
 }
 
+/// RSA keys shorter than this are flagged by `audit-ssh-keys`
+pub const MIN_RSA_BITS: u32 = 3072;
+
+/// One authorized_keys entry found by `audit-ssh-keys`
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditedKey {
+    pub user: String,
+    pub file: String,
+    pub line_number: usize,
+    pub key_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bits: Option<u32>,
+    /// SHA256 fingerprint as printed by `ssh-keygen -lf`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    pub comment: String,
+    /// Restriction options in front of the key, e.g. `from="10.0.0.0/8"`
+    pub options: Vec<String>,
+    /// Why the key deserves a second look; empty when nothing stands out
+    pub findings: Vec<String>,
+}
+
+/// Every authorized key on a device, for offboarding audits
+#[derive(Debug, Clone, Serialize)]
+pub struct SshKeyAudit {
+    pub total_keys: usize,
+    pub flagged_keys: usize,
+    pub keys: Vec<AuditedKey>,
+}
+
+impl SshKeyAudit {
+    pub fn new(keys: Vec<AuditedKey>) -> Self {
+        Self {
+            total_keys: keys.len(),
+            flagged_keys: keys.iter().filter(|key| !key.findings.is_empty()).count(),
+            keys,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SshKeyPair {
     pub private_key: String,
@@ -45,10 +87,71 @@ pub enum KeyRemovalCriteria {
     Pattern(String),
 }
 
+/// Where the expiry of an installed time-limited key is enforced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpiryEnforcement {
+    /// The installed line carries `expiry-time=`, so sshd refuses the key once it lapses
+    pub expiry_option: bool,
+    /// A systemd timer on the target deletes lapsed keys from authorized_keys
+    pub cleanup_timer: bool,
+}
+
+impl ExpiryEnforcement {
+    pub fn is_enforced(&self) -> bool {
+        self.expiry_option || self.cleanup_timer
+    }
+}
+
+impl std::fmt::Display for ExpiryEnforcement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.expiry_option, self.cleanup_timer) {
+            (true, true) => write!(
+                f,
+                "enforced by the target (sshd expiry-time option and cleanup timer)"
+            ),
+            (true, false) => write!(f, "enforced by the target (sshd expiry-time option)"),
+            (false, true) => write!(f, "enforced by the target (cleanup timer)"),
+            (false, false) => write!(
+                f,
+                "only tracked client-side: the target keeps accepting the key after it expires"
+            ),
+        }
+    }
+}
+
+/// First OpenSSH release that honours `expiry-time=` in authorized_keys. Older
+/// sshd rejects the whole line on an unknown option.
+const EXPIRY_TIME_MIN_OPENSSH: (u32, u32) = (8, 2);
+
+/// systemd unit name of the expired key cleanup timer and service
+const EXPIRY_CLEANUP_UNIT: &str = "ssh-key-expiry-cleanup";
+
+/// Script run by the cleanup timer; in /etc as /usr is read-only on OSTree systems
+const EXPIRY_CLEANUP_SCRIPT_PATH: &str = "/etc/security-compliance-cli/expire-ssh-keys.sh";
+
+/// Deletes keys whose `expires:` comment has passed, keeping each file's owner and mode
+const EXPIRY_CLEANUP_SCRIPT: &str = r#"#!/bin/sh
+# Installed by security-compliance-cli: removes lapsed keys from authorized_keys
+now=$(date -u +%Y%m%d%H%M%S)
+for f in /root/.ssh/authorized_keys /home/*/.ssh/authorized_keys; do
+    [ -f "$f" ] || continue
+    awk -v now="$now" '{
+        if (match($0, /expires:[0-9-]+ [0-9:]+ UTC/)) {
+            stamp = substr($0, RSTART + 8, 19)
+            gsub(/[^0-9]/, "", stamp)
+            if (stamp < now) next
+        }
+        print
+    }' "$f" > "$f.tmp" && cat "$f.tmp" > "$f"
+    rm -f "$f.tmp"
+done"#;
+
 #[derive(Debug)]
 pub struct SshKeyInstaller {
     pub target_user: String,
     pub test_connection: bool,
+    /// Install a systemd timer on the target that deletes expired keys
+    pub install_cleanup_timer: bool,
 }
 
 impl SshKeyInstaller {
@@ -56,7 +159,105 @@ impl SshKeyInstaller {
         Self {
             target_user,
             test_connection,
+            install_cleanup_timer: false,
+        }
+    }
+
+    /// The authorized_keys line for a key pair, with an `expiry-time=` option
+    /// when the key expires and the target's sshd honours it
+    pub fn authorized_keys_line(key_pair: &SshKeyPair, expiry_option: bool) -> String {
+        match key_pair.expires_at {
+            Some(expires_at) if expiry_option => format!(
+                "expiry-time=\"{}\" {}",
+                expires_at.format("%Y%m%d%H%M%SZ"),
+                key_pair.public_key.trim()
+            ),
+            _ => key_pair.public_key.trim().to_string(),
+        }
+    }
+
+    /// Whether the target's sshd is OpenSSH recent enough for `expiry-time=`
+    async fn sshd_supports_expiry_time(channel: &mut dyn CommunicationChannel) -> bool {
+        match channel.execute_command("sshd -V 2>&1 || ssh -V 2>&1").await {
+            Ok(result) => parse_openssh_version(&result.stdout)
+                .is_some_and(|version| version >= EXPIRY_TIME_MIN_OPENSSH),
+            Err(_) => false,
+        }
+    }
+
+    /// Run a command as root: directly, or through `sudo -n` for a non-root login
+    async fn execute_as_root(
+        channel: &mut dyn CommunicationChannel,
+        command: &str,
+    ) -> Result<CommandOutput> {
+        let result = channel.execute_command(command).await?;
+        if result.exit_code == 0 {
+            return Ok(result);
         }
+        let quoted = format!("'{}'", command.replace('\'', "'\\''"));
+        channel
+            .execute_command(&format!("sudo -n sh -c {}", quoted))
+            .await
+    }
+
+    /// Install and start a systemd timer that deletes expired keys every 15 minutes
+    pub async fn install_expiry_cleanup_timer(
+        &self,
+        channel: &mut dyn CommunicationChannel,
+    ) -> Result<()> {
+        info!("Installing {} timer on the target", EXPIRY_CLEANUP_UNIT);
+        let service = format!(
+            "[Unit]\nDescription=Remove expired SSH test keys\n\n[Service]\nType=oneshot\nExecStart=/bin/sh {}\n",
+            EXPIRY_CLEANUP_SCRIPT_PATH
+        );
+        let timer = "[Unit]\nDescription=Remove expired SSH test keys periodically\n\n[Timer]\nOnBootSec=1min\nOnUnitActiveSec=15min\nPersistent=true\n\n[Install]\nWantedBy=timers.target\n";
+        let command = format!(
+            "mkdir -p {dir} && cat > {script} << 'EOF'\n{script_body}\nEOF\nchmod 700 {script} && cat > /etc/systemd/system/{unit}.service << 'EOF'\n{service}EOF\ncat > /etc/systemd/system/{unit}.timer << 'EOF'\n{timer}EOF\nsystemctl daemon-reload && systemctl enable --now {unit}.timer",
+            dir = Path::new(EXPIRY_CLEANUP_SCRIPT_PATH)
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
+            script = EXPIRY_CLEANUP_SCRIPT_PATH,
+            script_body = EXPIRY_CLEANUP_SCRIPT,
+            unit = EXPIRY_CLEANUP_UNIT,
+            service = service,
+            timer = timer,
+        );
+        let result = Self::execute_as_root(channel, &command).await?;
+        if result.exit_code != 0 {
+            return Err(Error::Communication(format!(
+                "Failed to install the expired key cleanup timer (needs root or passwordless sudo): {}",
+                result.stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Read back the installed key and report whether the target enforces its expiry
+    pub async fn verify_expiry_enforcement(
+        &self,
+        channel: &mut dyn CommunicationChannel,
+        authorized_keys_path: &str,
+        public_key: &str,
+    ) -> Result<ExpiryEnforcement> {
+        let result = channel
+            .execute_command(&format!("cat {}", authorized_keys_path))
+            .await?;
+        let expiry_option = result
+            .stdout
+            .lines()
+            .filter(|line| key_material_matches(line, public_key))
+            .any(|line| expiry_time_option(line).is_some());
+        let timer = channel
+            .execute_command(&format!(
+                "systemctl is-active {}.timer 2>/dev/null",
+                EXPIRY_CLEANUP_UNIT
+            ))
+            .await?;
+        Ok(ExpiryEnforcement {
+            expiry_option,
+            cleanup_timer: timer.stdout.trim() == "active",
+        })
     }
 
     /// Generate a new Ed25519 SSH key pair with optional expiration
@@ -158,12 +359,13 @@ impl SshKeyInstaller {
         Ok(())
     }
 
-    /// Install SSH public key on target device via communication channel
+    /// Install SSH public key on target device via communication channel,
+    /// returning the path of the authorized_keys file it went into
     pub async fn install_public_key(
         &self,
         channel: &mut dyn CommunicationChannel,
         public_key: &str,
-    ) -> Result<()> {
+    ) -> Result<String> {
         info!("Installing SSH public key for user: {}", self.target_user);
 
         // Detect current user if target_user is root (default)
@@ -257,15 +459,27 @@ impl SshKeyInstaller {
 
         // Add the public key to authorized_keys (append to avoid overwriting)
         let authorized_keys_path = format!("{}/.ssh/authorized_keys", home_dir);
-        let add_key_command = format!("echo '{}' >> {}", public_key.trim(), authorized_keys_path);
-
-        debug!("Adding public key to authorized_keys");
-        let result = channel.execute_command(&add_key_command).await?;
-        if result.exit_code != 0 {
-            return Err(Error::Communication(format!(
-                "Failed to add public key to authorized_keys: {}",
-                result.stderr
-            )));
+        let existing = channel
+            .execute_command(&format!("cat {} 2>/dev/null", authorized_keys_path))
+            .await?;
+        if existing
+            .stdout
+            .lines()
+            .any(|line| key_material_matches(line, public_key.trim()))
+        {
+            info!("Key is already in authorized_keys, not adding it again");
+        } else {
+            let add_key_command =
+                format!("echo '{}' >> {}", public_key.trim(), authorized_keys_path);
+
+            debug!("Adding public key to authorized_keys");
+            let result = channel.execute_command(&add_key_command).await?;
+            if result.exit_code != 0 {
+                return Err(Error::Communication(format!(
+                    "Failed to add public key to authorized_keys: {}",
+                    result.stderr
+                )));
+            }
         }
 
         // Set proper permissions on authorized_keys
@@ -303,7 +517,11 @@ impl SshKeyInstaller {
 
         // Check if our public key is present in the file
         let public_key_content = public_key.trim();
-        if result.stdout.contains(public_key_content) {
+        if result
+            .stdout
+            .lines()
+            .any(|line| key_material_matches(line, public_key_content))
+        {
             info!(
                 "🔑 Key found in authorized_keys file for user: {}",
                 actual_user
@@ -322,7 +540,7 @@ impl SshKeyInstaller {
             "SSH public key installed successfully for user: {}",
             actual_user
         );
-        Ok(())
+        Ok(authorized_keys_path)
     }
 
     /// Test SSH connection using the installed key
@@ -376,7 +594,92 @@ impl SshKeyInstaller {
         Ok(())
     }
 
-    /// Remove SSH public keys from target device
+    /// The target user's authorized_keys file
+    fn authorized_keys_path(&self) -> String {
+        if self.target_user == "root" {
+            "/root/.ssh/authorized_keys".to_string()
+        } else {
+            format!("/home/{}/.ssh/authorized_keys", self.target_user)
+        }
+    }
+
+    /// Re-read authorized_keys after a removal, returning the removed keys that
+    /// are still there. The file is read with the same root fallback the removal
+    /// used; a missing file holds no keys, but one that can't be read is an
+    /// error rather than proof the keys are gone.
+    pub async fn keys_still_present(
+        &self,
+        channel: &mut dyn CommunicationChannel,
+        removed_keys: &[String],
+    ) -> Result<Vec<String>> {
+        let authorized_keys_path = self.authorized_keys_path();
+        let exists =
+            Self::execute_as_root(channel, &format!("test -f {}", authorized_keys_path)).await?;
+        if exists.exit_code != 0 {
+            // test -f also fails when the directory can't be searched; only a
+            // file that is really absent counts as no keys
+            let listing = channel
+                .execute_command(&format!("LC_ALL=C ls {}", authorized_keys_path))
+                .await?;
+            let output = format!("{}{}", listing.stdout, listing.stderr);
+            if output.contains("No such file or directory") {
+                return Ok(vec![]);
+            }
+            return Err(Error::Communication(format!(
+                "Cannot check {} (needs root or passwordless sudo): {}",
+                authorized_keys_path,
+                output.trim()
+            )));
+        }
+
+        let result =
+            Self::execute_as_root(channel, &format!("cat {}", authorized_keys_path)).await?;
+        if result.exit_code != 0 {
+            return Err(Error::Communication(format!(
+                "Failed to re-read authorized_keys (exit {}): {}",
+                result.exit_code,
+                result.stderr.trim()
+            )));
+        }
+        Ok(removed_keys
+            .iter()
+            .filter(|removed| {
+                result
+                    .stdout
+                    .lines()
+                    .any(|line| key_material_matches(line, removed))
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Try to log in with a private key. `Ok(false)` means sshd rejected the
+    /// key; connection failures are errors, since they prove nothing either way.
+    pub fn ssh_login_accepted(
+        &self,
+        host: &str,
+        port: u16,
+        private_key_path: &Path,
+    ) -> Result<bool> {
+        use ssh2::Session;
+        use std::net::TcpStream;
+
+        let tcp = TcpStream::connect(format!("{}:{}", host, port))
+            .map_err(|e| Error::Communication(format!("TCP connection failed: {}", e)))?;
+        let mut session = Session::new()
+            .map_err(|e| Error::Communication(format!("SSH session creation failed: {}", e)))?;
+        session.set_tcp_stream(tcp);
+        session
+            .handshake()
+            .map_err(|e| Error::Communication(format!("SSH handshake failed: {}", e)))?;
+
+        // An authentication error is the rejection we are looking for
+        let _ = session.userauth_pubkey_file(&self.target_user, None, private_key_path, None);
+        Ok(session.authenticated())
+    }
+
+    /// Remove SSH public keys from target device, through passwordless sudo
+    /// when the login user cannot access the target user's authorized_keys
     pub async fn remove_public_keys(
         &self,
         channel: &mut dyn CommunicationChannel,
@@ -384,11 +687,11 @@ impl SshKeyInstaller {
     ) -> Result<Vec<String>> {
         info!("Removing SSH public keys for user: {}", self.target_user);
 
-        let authorized_keys_path = format!("/home/{}/.ssh/authorized_keys", self.target_user);
+        let authorized_keys_path = self.authorized_keys_path();
 
         // First, check if authorized_keys file exists
         let check_file_cmd = format!("test -f {}", authorized_keys_path);
-        let result = channel.execute_command(&check_file_cmd).await?;
+        let result = Self::execute_as_root(channel, &check_file_cmd).await?;
         if result.exit_code != 0 {
             info!("No authorized_keys file found - nothing to remove");
             return Ok(vec![]);
@@ -396,7 +699,7 @@ impl SshKeyInstaller {
 
         // Read current authorized_keys content
         let read_keys_cmd = format!("cat {}", authorized_keys_path);
-        let result = channel.execute_command(&read_keys_cmd).await?;
+        let result = Self::execute_as_root(channel, &read_keys_cmd).await?;
         if result.exit_code != 0 {
             return Err(Error::Communication(format!(
                 "Failed to read authorized_keys: {}",
@@ -443,7 +746,7 @@ impl SshKeyInstaller {
         };
 
         debug!("Writing updated authorized_keys");
-        let result = channel.execute_command(&write_keys_cmd).await?;
+        let result = Self::execute_as_root(channel, &write_keys_cmd).await?;
         if result.exit_code != 0 {
             return Err(Error::Communication(format!(
                 "Failed to update authorized_keys: {}",
@@ -453,7 +756,7 @@ impl SshKeyInstaller {
 
         // Set proper permissions
         let chmod_cmd = format!("chmod 600 {}", authorized_keys_path);
-        let result = channel.execute_command(&chmod_cmd).await?;
+        let result = Self::execute_as_root(channel, &chmod_cmd).await?;
         if result.exit_code != 0 {
             warn!(
                 "Failed to set authorized_keys permissions: {}",
@@ -468,17 +771,7 @@ impl SshKeyInstaller {
     /// Check if a key should be removed based on criteria
     fn should_remove_key(&self, key_line: &str, criteria: &KeyRemovalCriteria) -> bool {
         match criteria {
-            KeyRemovalCriteria::PublicKey(public_key) => {
-                // Extract the key part (second field) for comparison
-                let key_parts: Vec<&str> = key_line.split_whitespace().collect();
-                let target_parts: Vec<&str> = public_key.split_whitespace().collect();
-
-                if key_parts.len() >= 2 && target_parts.len() >= 2 {
-                    key_parts[1] == target_parts[1] // Compare the base64 key part
-                } else {
-                    false
-                }
-            }
+            KeyRemovalCriteria::PublicKey(public_key) => key_material_matches(key_line, public_key),
             KeyRemovalCriteria::TempKeys => key_line.contains("security-compliance-cli-temp-key"),
             KeyRemovalCriteria::Pattern(pattern) => self.matches_pattern(key_line, pattern),
         }
@@ -537,7 +830,7 @@ impl SshKeyInstaller {
 
         // Check if authorized_keys file exists
         let check_file_cmd = format!("test -f {}", authorized_keys_path);
-        let result = channel.execute_command(&check_file_cmd).await?;
+        let result = Self::execute_as_root(channel, &check_file_cmd).await?;
         if result.exit_code != 0 {
             return Ok(vec![]);
         }
@@ -610,10 +903,49 @@ impl SshKeyInstaller {
             Self::generate_key_pair(validity_hours, None)?
         };
 
+        // Have sshd enforce the expiry where it can; older sshd would reject the line
+        let expiry_option =
+            key_pair.expires_at.is_some() && Self::sshd_supports_expiry_time(channel).await;
+        if key_pair.expires_at.is_some() && !expiry_option {
+            warn!(
+                "Target sshd does not support expiry-time (OpenSSH {}.{}+ needed)",
+                EXPIRY_TIME_MIN_OPENSSH.0, EXPIRY_TIME_MIN_OPENSSH.1
+            );
+        }
+
         // Install the public key
-        self.install_public_key(channel, &key_pair.public_key)
+        let authorized_keys_path = self
+            .install_public_key(
+                channel,
+                &Self::authorized_keys_line(&key_pair, expiry_option),
+            )
             .await?;
 
+        if let Some(expires_at) = key_pair.expires_at {
+            if self.install_cleanup_timer {
+                if let Err(e) = self.install_expiry_cleanup_timer(channel).await {
+                    warn!("{}", e);
+                }
+            }
+            let enforcement = self
+                .verify_expiry_enforcement(channel, &authorized_keys_path, &key_pair.public_key)
+                .await?;
+            if enforcement.is_enforced() {
+                info!(
+                    "⏰ Key expiry at {} is {}",
+                    expires_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    enforcement
+                );
+            } else {
+                warn!(
+                    "⚠️  Key expiry at {} is {}",
+                    expires_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    enforcement
+                );
+                warn!("💡 Use --install-cleanup-timer, or remove it with uninstall-ssh-key --remove-temp-keys");
+            }
+        }
+
         // Save private key if requested and available
         if let Some(save_path) = save_private_key_path {
             if !key_pair.private_key.is_empty() {
@@ -641,6 +973,104 @@ impl SshKeyInstaller {
         Ok(key_pair)
     }
 
+    /// List every authorized key of every user with a home directory. A non-root
+    /// login reads other users' keys through `sudo -n`; without it only the
+    /// keys it can read itself are listed.
+    pub async fn audit_ssh_keys(channel: &mut dyn CommunicationChannel) -> Result<SshKeyAudit> {
+        info!("🔍 Auditing authorized SSH keys of all users...");
+
+        let uid = channel.execute_command("id -u").await?;
+        let prefix = if uid.stdout.trim() == "0" {
+            ""
+        } else if channel.execute_command("sudo -n true").await?.exit_code == 0 {
+            "sudo -n "
+        } else {
+            warn!("⚠️  Not root and no passwordless sudo: keys in home directories the login user cannot read are missed");
+            ""
+        };
+
+        // "<user> <file>" for each authorized keys file in a passwd home directory
+        let list_command = "(getent passwd 2>/dev/null || cat /etc/passwd) | while IFS=: read -r user _ _ _ _ home _; do for f in \"$home/.ssh/authorized_keys\" \"$home/.ssh/authorized_keys2\"; do [ -n \"$home\" ] && [ -f \"$f\" ] && echo \"$user $f\"; done; done; true";
+        let files = channel
+            .execute_command(&format!(
+                "{}sh -c '{}'",
+                prefix,
+                list_command.replace('\'', "'\\''")
+            ))
+            .await?;
+
+        let mut keys = Vec::new();
+        let mut seen_files: Vec<&str> = Vec::new();
+        for line in files.stdout.lines() {
+            let Some((user, file)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            // Users sharing a home directory share its keys
+            if seen_files.contains(&file) {
+                continue;
+            }
+            seen_files.push(file);
+            let content = channel
+                .execute_command(&format!("{}cat '{}'", prefix, file.replace('\'', "'\\''")))
+                .await?;
+            if content.exit_code != 0 {
+                warn!("Failed to read {}: {}", file, content.stderr.trim());
+                continue;
+            }
+            keys.extend(audit_authorized_keys(&content.stdout, user, file));
+        }
+
+        Ok(SshKeyAudit::new(keys))
+    }
+
+    /// Print an audit grouped by user, flagged keys first within each user
+    pub fn display_audit(audit: &SshKeyAudit) {
+        if audit.keys.is_empty() {
+            info!("✅ No authorized SSH keys found on the device");
+            return;
+        }
+        let mut users: Vec<&str> = audit.keys.iter().map(|key| key.user.as_str()).collect();
+        users.dedup();
+        for user in users {
+            info!("👤 {}", user);
+            let mut user_keys: Vec<&AuditedKey> =
+                audit.keys.iter().filter(|key| key.user == user).collect();
+            user_keys.sort_by_key(|key| key.findings.is_empty());
+            for key in user_keys {
+                let icon = if key.findings.is_empty() {
+                    "🗝️"
+                } else {
+                    "⚠️"
+                };
+                info!(
+                    "  {} {}{} {} {}",
+                    icon,
+                    key.key_type,
+                    key.bits
+                        .map(|bits| format!(" ({} bits)", bits))
+                        .unwrap_or_default(),
+                    key.fingerprint.as_deref().unwrap_or("<unparseable key>"),
+                    if key.comment.is_empty() {
+                        "<no comment>"
+                    } else {
+                        &key.comment
+                    }
+                );
+                info!("     {}:{}", key.file, key.line_number);
+                if !key.options.is_empty() {
+                    info!("     Options: {}", key.options.join(","));
+                }
+                for finding in &key.findings {
+                    warn!("     - {}", finding);
+                }
+            }
+        }
+        info!(
+            "📊 {} key(s), {} flagged for review",
+            audit.total_keys, audit.flagged_keys
+        );
+    }
+
     /// Check for installed SSH test keys on the target device
     pub async fn check_ssh_keys(
         channel: &mut dyn CommunicationChannel,
@@ -749,19 +1179,20 @@ impl SshKeyInstaller {
                 continue;
             }
 
-            let parts: Vec<&str> = line.splitn(3, ' ').collect();
-            if parts.len() >= 2 {
-                let key_type = parts[0];
-                let key_data = parts[1];
-                let comment = parts.get(2).unwrap_or(&"").to_string();
+            if let Some((key_type, key_data)) = key_fields(line) {
+                let comment = line
+                    .split_once(key_data)
+                    .map(|(_, comment)| comment.trim().to_string())
+                    .unwrap_or_default();
 
                 // Filter for temp keys if requested
                 if temp_keys_only && !comment.contains("security-compliance-cli-temp-key") {
                     continue;
                 }
 
-                // Parse expiration from comment if present
-                let expiration = Self::parse_expiration_from_comment(&comment);
+                // Parse expiration from the expiry-time option or the comment
+                let expiration = expiry_time_option(line)
+                    .or_else(|| Self::parse_expiration_from_comment(&comment));
                 let is_expired = expiration.is_some_and(|exp| now > exp);
 
                 // Filter for expired keys if requested
@@ -851,10 +1282,230 @@ impl SshKeyInstaller {
     }
 }
 
+/// Options in front of the key type of an authorized_keys line, split on
+/// commas outside quotes
+fn key_options(line: &str, key_type: &str) -> Vec<String> {
+    let prefix = line.split(key_type).next().unwrap_or("").trim();
+    let mut options = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in prefix.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ',' if !quoted => options.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    options.push(current);
+    options
+        .into_iter()
+        .map(|option| option.trim().to_string())
+        .filter(|option| !option.is_empty())
+        .collect()
+}
+
+/// `SHA256:<base64>` fingerprint of a key blob, the form `ssh-keygen -lf` prints
+fn key_fingerprint(key_data: &str) -> Option<String> {
+    use base64::Engine;
+    use ssh_key::sha2::{Digest, Sha256};
+
+    let blob = base64::engine::general_purpose::STANDARD
+        .decode(key_data)
+        .ok()?;
+    Some(format!(
+        "SHA256:{}",
+        base64::engine::general_purpose::STANDARD_NO_PAD.encode(Sha256::digest(&blob))
+    ))
+}
+
+/// Key size in bits: the modulus of RSA and DSA keys, the curve of EC keys
+fn key_bits(key_type: &str, key_data: &str) -> Option<u32> {
+    let modulus_bits = |bytes: Option<&[u8]>| {
+        bytes.map(|bytes| {
+            bytes.len() as u32 * 8 - bytes.first().map_or(0, |byte| byte.leading_zeros())
+        })
+    };
+    match key_type {
+        "ssh-ed25519" | "sk-ssh-ed25519@openssh.com" => Some(256),
+        "ssh-rsa" | "ssh-dss" => {
+            let key =
+                ssh_key::PublicKey::from_openssh(&format!("{} {}", key_type, key_data)).ok()?;
+            match key.key_data() {
+                ssh_key::public::KeyData::Rsa(rsa) => modulus_bits(rsa.n.as_positive_bytes()),
+                ssh_key::public::KeyData::Dsa(dsa) => modulus_bits(dsa.p.as_positive_bytes()),
+                _ => None,
+            }
+        }
+        _ => ["256", "384", "521"]
+            .into_iter()
+            .find(|curve| key_type.contains(&format!("nistp{}", curve)))
+            .and_then(|curve| curve.parse().ok()),
+    }
+}
+
+/// Audit the keys of one authorized_keys file
+fn audit_authorized_keys(content: &str, user: &str, file: &str) -> Vec<AuditedKey> {
+    let mut keys = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key_type, key_data)) = key_fields(line) else {
+            continue;
+        };
+        let comment = line
+            .split_once(key_data)
+            .map(|(_, comment)| comment.trim().to_string())
+            .unwrap_or_default();
+        let options = key_options(line, key_type);
+        let fingerprint = key_fingerprint(key_data);
+        let bits = key_bits(key_type, key_data);
+
+        let mut findings = Vec::new();
+        if fingerprint.is_none() {
+            findings.push("Key data is not valid base64".to_string());
+        }
+        if comment.is_empty() {
+            findings.push("No comment identifying the key's owner".to_string());
+        }
+        if key_type == "ssh-dss" {
+            findings.push("Weak key type ssh-dss (DSA)".to_string());
+        }
+        if let (Some(bits), "ssh-rsa") = (bits, key_type) {
+            if bits < MIN_RSA_BITS {
+                findings.push(format!(
+                    "Weak {}-bit RSA key ({}+ bits recommended)",
+                    bits, MIN_RSA_BITS
+                ));
+            }
+        }
+        if !options
+            .iter()
+            .any(|option| option.starts_with("from=") || option.starts_with("expiry-time="))
+        {
+            findings.push("No source (from=) or expiry (expiry-time=) restriction".to_string());
+        }
+
+        keys.push(AuditedKey {
+            user: user.to_string(),
+            file: file.to_string(),
+            line_number: index + 1,
+            key_type: key_type.to_string(),
+            bits,
+            fingerprint,
+            comment,
+            options,
+            findings,
+        });
+    }
+    keys
+}
+
+/// (major, minor) of the first `OpenSSH_9.6p1` style version in `ssh -V` output
+fn parse_openssh_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.split("OpenSSH_").nth(1)?;
+    let mut numbers = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse::<u32>());
+    Some((numbers.next()?.ok()?, numbers.next()?.ok()?))
+}
+
+/// Expiry set by an `expiry-time="YYYYMMDD[HHMM[SS]][Z]"` option on an authorized_keys
+/// line. Times without `Z` are in the target's local time and read here as UTC.
+fn expiry_time_option(line: &str) -> Option<DateTime<Utc>> {
+    let (key_type, _) = key_fields(line)?;
+    let options = line.split(key_type).next()?;
+    let value = options.split("expiry-time=\"").nth(1)?.split('"').next()?;
+    let digits = value.trim_end_matches(['Z', 'z']);
+    let padded = match digits.len() {
+        8 => format!("{}000000", digits),
+        12 => format!("{}00", digits),
+        14 => digits.to_string(),
+        _ => return None,
+    };
+    chrono::NaiveDateTime::parse_from_str(&padded, "%Y%m%d%H%M%S")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_audit_authorized_keys_flags_weak_and_unrestricted_keys() {
+        let content = [
+            "# team keys",
+            "ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQCVyP70+kUukf2aT2oynIWDEV7xtpv50cdQjtUIhEbJB6AqlBmjbZsYELA1AiqUcfl6BknN4TWS7VfuF/isdaa5bIptZtGg2Sd/+2+gkzDX0e9tOWz7zRlb6OcJ2TzvWsmWZoe8HTLyXTfXa81pJcrpCFkvqfOiLzCPmOivD1ZcbmAuu6Z5rtqdsyG+bQTR0gMSp84AiyWTo20kH3Tnib5GEsfLVRy84FVoEwBkrNr2IAvg1puA/ZDLpgWSDA9bVZQCtNPokXSKS6fT/7YmZnLzD4Y82UCsAxPl8qJpr5lX72QvjIrCLp6tt6Slv+Epmm/FkTHQMlhw7G9YivEd1zyr",
+            "from=\"10.0.0.0/8\",command=\"echo a,b\" ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBCm9ZYzYgwHMoZB+XVvkNZ6fkYjwTD4EpoXaRKHwixaIzhfmHjt0Ms5oTrtv8Jd4t6RlzprHoEEvhVbjGIDcKwM= ops@example",
+            "ssh-dss AAAAB3NzaC1kc3M= legacy",
+        ]
+        .join("\n");
+        let keys = audit_authorized_keys(&content, "fio", "/home/fio/.ssh/authorized_keys");
+        assert_eq!(keys.len(), 3);
+
+        assert_eq!(keys[0].line_number, 2);
+        assert_eq!(keys[0].bits, Some(2048));
+        assert_eq!(
+            keys[0].fingerprint.as_deref(),
+            Some("SHA256:UDaOOrKGPFhfpNkn/TXKrE3zfk97DZVCThXHCslYIOY")
+        );
+        assert_eq!(keys[0].findings.len(), 3);
+        assert!(keys[0].findings[1].contains("2048-bit RSA"));
+
+        assert_eq!(keys[1].key_type, "ecdsa-sha2-nistp256");
+        assert_eq!(keys[1].bits, Some(256));
+        assert_eq!(
+            keys[1].fingerprint.as_deref(),
+            Some("SHA256:DJbbdvIcvDornh2KfDpl/tqoDWiJ6p/5DB+mJMWAM20")
+        );
+        assert_eq!(
+            keys[1].options,
+            ["from=\"10.0.0.0/8\"", "command=\"echo a,b\""]
+        );
+        assert_eq!(keys[1].comment, "ops@example");
+        assert!(keys[1].findings.is_empty());
+
+        assert!(keys[2].findings.iter().any(|f| f.contains("ssh-dss")));
+        assert_eq!(SshKeyAudit::new(keys).flagged_keys, 2);
+    }
+
+    #[test]
+    fn test_expiry_time_option_on_installed_line() {
+        let key_pair = SshKeyInstaller::generate_key_pair(2, None).unwrap();
+        let line = SshKeyInstaller::authorized_keys_line(&key_pair, true);
+        assert!(line.starts_with("expiry-time=\""));
+        assert!(key_material_matches(&line, &key_pair.public_key));
+        let expires_at = expiry_time_option(&line).unwrap();
+        assert_eq!(
+            expires_at.timestamp(),
+            key_pair.expires_at.unwrap().timestamp()
+        );
+        assert_eq!(
+            SshKeyInstaller::authorized_keys_line(&key_pair, false),
+            key_pair.public_key
+        );
+
+        let keys = SshKeyInstaller::parse_authorized_keys(&line, "fio", true, false);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key_type, "ssh-ed25519");
+        assert!(keys[0]
+            .comment
+            .starts_with("security-compliance-cli-temp-key-"));
+        assert!(expiry_time_option("ssh-ed25519 AAAA expiry-time=\"20300101\"").is_none());
+
+        assert_eq!(
+            parse_openssh_version("OpenSSH_9.6p1, OpenSSL 3.2.1"),
+            Some((9, 6))
+        );
+        assert!(parse_openssh_version("OpenSSH_7.4p1 Debian").unwrap() < EXPIRY_TIME_MIN_OPENSSH);
+        assert_eq!(parse_openssh_version("Dropbear v2022.83"), None);
+    }
+
     #[test]
     fn test_generate_key_pair() {
         let key_pair = SshKeyInstaller::generate_key_pair(1, Some("test-key".to_string()))
@@ -910,4 +1561,76 @@ mod tests {
         assert!(truncated.contains("67890"));
         assert!(truncated.contains("test-comment"));
     }
+
+    /// Channel whose `ls` reports `listing` and every other command fails
+    struct NoAccessChannel {
+        listing: &'static str,
+    }
+
+    #[async_trait::async_trait]
+    impl CommunicationChannel for NoAccessChannel {
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn execute_command(&mut self, command: &str) -> Result<CommandOutput> {
+            Ok(CommandOutput {
+                stdout: String::new(),
+                stderr: if command.starts_with("LC_ALL=C ls ") {
+                    self.listing.to_string()
+                } else {
+                    String::new()
+                },
+                exit_code: if command.starts_with("LC_ALL=C ls ") {
+                    2
+                } else {
+                    1
+                },
+            })
+        }
+
+        async fn execute_command_with_timeout(
+            &mut self,
+            command: &str,
+            _timeout: std::time::Duration,
+        ) -> Result<CommandOutput> {
+            self.execute_command(command).await
+        }
+
+        async fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn description(&self) -> String {
+            "no access channel".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_authorized_keys_does_not_verify_removal() {
+        let installer = SshKeyInstaller::new("fio".to_string(), false);
+        let removed = vec!["ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIB old".to_string()];
+
+        let mut denied = NoAccessChannel {
+            listing: "ls: cannot access '/home/fio/.ssh/authorized_keys': Permission denied",
+        };
+        assert!(installer
+            .keys_still_present(&mut denied, &removed)
+            .await
+            .is_err());
+
+        let mut missing = NoAccessChannel {
+            listing:
+                "ls: cannot access '/home/fio/.ssh/authorized_keys': No such file or directory",
+        };
+        assert!(installer
+            .keys_still_present(&mut missing, &removed)
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -9,21 +9,80 @@ use crate::serial_channel::SerialChannel;
 #[cfg(target_os = "windows")]
 use crate::serial_channel_windows::WindowsSerialChannel;
 use crate::{
+    cli::{MachineType, TestMode},
     communication::{ChannelConfig, CommunicationChannel},
-    config::CommunicationConfig,
+    config::{
+        CommunicationConfig, TestConfig, DEFAULT_SSH_KEEPALIVE_SECS, DEFAULT_SSH_RECONNECT_ATTEMPTS,
+    },
     error::{Error, Result},
+    local_channel::LocalChannel,
+    runner::shell_quote,
+    secret::{mask_sudo_password, pipes_password, Redactor},
+    snapshot::{RecordingChannel, ReplayChannel},
     ssh_channel::SshChannel,
+    telnet_channel::TelnetChannel,
 };
-use std::time::Duration;
-use tracing::{debug, info};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Per-run settings made available to tests alongside the connection
+#[derive(Debug, Clone, Default)]
+pub struct RunContext {
+    pub mode: TestMode,
+    pub tests: TestConfig,
+    /// Configured or detected machine, when it is one we know
+    pub machine: Option<MachineType>,
+    /// Masks sensitive data in results; unset with `--no-redact`
+    pub redactor: Option<Redactor>,
+}
 
 pub struct Target {
     channel: Box<dyn CommunicationChannel>,
     config: CommunicationConfig,
+    context: RunContext,
+    /// Shared with every pooled session opened from this target
+    cache: Arc<Mutex<CommandCache>>,
+    /// Most attempts any command needed since the last `take_command_attempts`
+    command_attempts: u32,
+    /// When the running test's time is up; commands are cut short to meet it
+    deadline: Option<Instant>,
+    /// Target clock when the run started, so evidence can leave out the run's own activity
+    run_started: Option<String>,
+}
+
+/// What a run has learned about the device, reused by every session on it
+#[derive(Default)]
+struct CommandCache {
+    /// Whether each tool probed by `has_command` is on the PATH
+    commands: HashMap<String, bool>,
+    /// Output of read-only commands, reused when `cache_commands` is enabled
+    outputs: HashMap<String, CommandResult>,
+    hits: u32,
 }
 
+/// Reported instead of running a password pipeline when `allow_sudo_password` is off
+pub const SUDO_PASSWORD_DISABLED: &str =
+    "sudo password over stdin disabled (allow_sudo_password = false)";
+
+/// Printed after each command when retries are enabled, so a cut-off reply can
+/// be told apart from a complete one. The command spells it with an empty
+/// quoted string in the middle so the echoed command line never matches it.
+const COMPLETION_MARKER: &str = "__SCC_DONE__";
+const COMPLETION_MARKER_COMMAND: &str = "__SCC_\"\"DONE__";
+
+/// Delay before the first retry when `command_retry_delay_ms` is not set
+const DEFAULT_COMMAND_RETRY_DELAY_MS: u64 = 500;
+
 impl Target {
     pub fn new(config: CommunicationConfig) -> Result<Self> {
+        if let Some(path) = &config.replay_snapshot {
+            let channel = ReplayChannel::from_file(Path::new(path))?;
+            return Ok(Self::from_channel(Box::new(channel), config));
+        }
+
         let channel_config = config.to_channel_config()?;
         let channel: Box<dyn CommunicationChannel> = match channel_config {
             ChannelConfig::Ssh { .. } => {
@@ -34,7 +93,14 @@ impl Target {
                     password: config.password.clone().unwrap_or_default(),
                     ssh_key_path: config.ssh_key_path.clone(),
                     timeout: config.timeout as u32,
-                    ssh_multiplex: config.ssh_multiplex.unwrap_or(false),
+                    ssh_session_pool: config.ssh_session_pool.unwrap_or(false),
+                    bind_address: config.bind_address.clone(),
+                    keepalive_secs: config
+                        .ssh_keepalive_secs
+                        .unwrap_or(DEFAULT_SSH_KEEPALIVE_SECS),
+                    reconnect_attempts: config
+                        .ssh_reconnect_attempts
+                        .unwrap_or(DEFAULT_SSH_RECONNECT_ATTEMPTS),
                 })?)
             }
             #[cfg(not(target_os = "windows"))]
@@ -63,9 +129,87 @@ impl Target {
                     password: config.serial_password.clone(),
                 },
             )?),
+            ChannelConfig::Local { timeout } => {
+                Box::new(LocalChannel::from_channel_config(ChannelConfig::Local {
+                    timeout,
+                })?)
+            }
+            telnet @ ChannelConfig::Telnet { .. } => {
+                Box::new(TelnetChannel::from_channel_config(telnet)?)
+            }
         };
+        let channel: Box<dyn CommunicationChannel> = match &config.record_snapshot {
+            Some(path) => Box::new(RecordingChannel::new(channel, PathBuf::from(path))),
+            None => channel,
+        };
+
+        Ok(Self::from_channel(channel, config))
+    }
+
+    /// Wrap an already constructed communication channel
+    pub fn from_channel(
+        channel: Box<dyn CommunicationChannel>,
+        config: CommunicationConfig,
+    ) -> Self {
+        Self {
+            channel,
+            config,
+            context: RunContext::default(),
+            cache: Arc::default(),
+            command_attempts: 0,
+            deadline: None,
+            run_started: None,
+        }
+    }
+
+    /// Another target on the same device for running work concurrently, if the
+    /// channel can open a pooled session
+    pub fn pooled_session(&self) -> Option<Target> {
+        let channel = self.channel.pooled_session()?;
+        let mut target = Self::from_channel(channel, self.config.clone());
+        target.context = self.context.clone();
+        target.cache = Arc::clone(&self.cache);
+        target.run_started = self.run_started.clone();
+        Some(target)
+    }
+
+    /// Whether `pooled_session` can open another target, checked without opening one
+    pub fn supports_pooling(&self) -> bool {
+        self.channel.supports_pooling()
+    }
+
+    /// Number of times the connection has been re-established after dropping
+    pub fn reconnect_count(&self) -> u32 {
+        self.channel.reconnect_count()
+    }
+
+    pub fn communication_config(&self) -> &CommunicationConfig {
+        &self.config
+    }
+
+    pub fn context(&self) -> &RunContext {
+        &self.context
+    }
+
+    pub fn set_context(&mut self, context: RunContext) {
+        self.context = context;
+    }
 
-        Ok(Self { channel, config })
+    /// Limit every command until the deadline to the time remaining before it
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    /// Resolve a probe command by key, preferring an operator override from
+    /// the `[tests.commands]` config table over the built-in default
+    pub fn probe_command(&self, key: &str, default: &str) -> String {
+        match self.context.tests.commands.get(key) {
+            Some(command) => {
+                debug!("Using configured command for {}: {}", key, command);
+                command.clone()
+            }
+            None => default.to_string(),
+        }
     }
 
     pub async fn connect(&mut self) -> Result<()> {
@@ -78,22 +222,99 @@ impl Target {
         self.channel.disconnect().await
     }
 
+    /// Password piped to `sudo -S` for privileged probes. A local channel has no
+    /// login, so it uses the optional `password` from the config (the sudo password
+    /// of the user running the CLI) and is empty unless one is set. Telnet logs in
+    /// with the same `password`.
     pub fn get_password(&self) -> &str {
         match self.config.channel_type.as_str() {
-            "ssh" => self.config.password.as_deref().unwrap_or(""),
+            "ssh" | "local" | "telnet" => self.config.password.as_deref().unwrap_or(""),
             "serial" => self.config.serial_password.as_deref().unwrap_or(""),
             _ => "",
         }
     }
 
+    /// Prefix running a command as root with the configured
+    /// `privilege_escalation_cmd`, reading the password from stdin: the
+    /// `sudo -S` of `echo '<password>' | sudo -S <command>`. Tools that can't
+    /// read a password, such as doas, run non-interactively instead.
+    pub fn elevation_prefix(&self) -> String {
+        let (program, flags) = self.escalation_tool();
+        let stdin_flag = if self.elevation_reads_password() {
+            "-S"
+        } else {
+            "-n"
+        };
+        format!("{} {}{}", program, stdin_flag, flags)
+    }
+
+    /// Whether the escalation tool reads a password from stdin. Only sudo does,
+    /// so a password is never piped to anything else.
+    pub fn elevation_reads_password(&self) -> bool {
+        self.escalation_tool().0.ends_with("sudo")
+    }
+
+    /// Prefix running a command as root with the target password:
+    /// `echo '<password>' | sudo -S` with the password shell-quoted, or just the non-interactive prefix for
+    /// tools that can't read a password
+    pub fn elevation_pipe(&self) -> String {
+        self.elevation_pipe_with(self.get_password())
+    }
+
+    /// `elevation_pipe` trying `password` instead of the target password
+    pub fn elevation_pipe_with(&self, password: &str) -> String {
+        if self.elevation_reads_password() {
+            format!(
+                "echo {} | {}",
+                shell_quote(password),
+                self.elevation_prefix()
+            )
+        } else {
+            self.elevation_prefix()
+        }
+    }
+
+    /// Prefix running a command as root without ever prompting for a password
+    pub fn elevation_prefix_non_interactive(&self) -> String {
+        let (program, flags) = self.escalation_tool();
+        format!("{} -n{}", program, flags)
+    }
+
+    /// How many lines of command output to quote in test details: `concise`,
+    /// or all of them with `--verbose-evidence`
+    pub fn evidence_lines(&self, concise: usize) -> usize {
+        if self.context.tests.verbose_evidence {
+            usize::MAX
+        } else {
+            concise
+        }
+    }
+
+    /// `| head -N` limiting evidence a command collects, dropped with
+    /// `--verbose-evidence`
+    pub fn evidence_head(&self, concise: usize) -> String {
+        if self.context.tests.verbose_evidence {
+            String::new()
+        } else {
+            format!(" | head -{}", concise)
+        }
+    }
+
+    /// The escalation program and any extra flags configured after it
+    fn escalation_tool(&self) -> (&str, String) {
+        let mut words = self
+            .context
+            .tests
+            .privilege_escalation_cmd
+            .split_whitespace();
+        let program = words.next().unwrap_or("sudo");
+        let flags: String = words.map(|flag| format!(" {}", flag)).collect();
+        (program, flags)
+    }
+
     pub async fn execute_command(&mut self, command: &str) -> Result<CommandResult> {
-        debug!("Executing command: {}", command);
-        let output = self.channel.execute_command(command).await?;
-        Ok(CommandResult {
-            stdout: output.stdout,
-            stderr: output.stderr,
-            exit_code: output.exit_code,
-        })
+        debug!("Executing command: {}", mask_sudo_password(command));
+        self.execute_with_retries(command, None).await
     }
 
     pub async fn execute_command_with_timeout(
@@ -101,16 +322,194 @@ impl Target {
         command: &str,
         timeout: Duration,
     ) -> Result<CommandResult> {
-        debug!("Executing command with timeout {:?}: {}", timeout, command);
-        let output = self
-            .channel
-            .execute_command_with_timeout(command, timeout)
-            .await?;
-        Ok(CommandResult {
-            stdout: output.stdout,
-            stderr: output.stderr,
-            exit_code: output.exit_code,
-        })
+        debug!(
+            "Executing command with timeout {:?}: {}",
+            timeout,
+            mask_sudo_password(command)
+        );
+        self.execute_with_retries(command, Some(timeout)).await
+    }
+
+    fn cache(&self) -> MutexGuard<'_, CommandCache> {
+        self.cache.lock().expect("command cache lock poisoned")
+    }
+
+    /// Output previously cached for a command, counting the hit
+    fn cached_output(&self, command: &str) -> Option<CommandResult> {
+        let mut cache = self.cache();
+        let cached = cache.outputs.get(command).cloned()?;
+        cache.hits += 1;
+        Some(cached)
+    }
+
+    fn cache_output(&self, command: &str, result: &CommandResult) {
+        self.cache()
+            .outputs
+            .insert(command.to_string(), result.clone());
+    }
+
+    /// Hits and entries of the command output cache, for measuring its effect.
+    /// Pooled sessions share the cache, so this covers the whole run.
+    pub fn command_cache_stats(&self) -> (u32, usize) {
+        let cache = self.cache();
+        (cache.hits, cache.outputs.len())
+    }
+
+    /// Forget cached command output, e.g. after changing the target's configuration
+    pub fn clear_command_cache(&mut self) {
+        self.cache().outputs.clear();
+    }
+
+    /// Run a command once per run and reuse its output afterwards, whether or
+    /// not `cache_commands` is enabled. For evidence several tests share.
+    pub async fn execute_command_shared(&mut self, command: &str) -> Result<CommandResult> {
+        if let Some(cached) = self.cached_output(command) {
+            return Ok(cached);
+        }
+        let result = self.execute_command(command).await?;
+        self.cache_output(command, &result);
+        Ok(result)
+    }
+
+    /// Record the target's clock as the start of the run
+    pub async fn mark_run_started(&mut self) -> Result<()> {
+        let date = self.execute_command("date '+%Y-%m-%d %H:%M:%S'").await?;
+        let started = date.stdout.trim();
+        self.run_started =
+            (date.exit_code == 0 && !started.is_empty()).then(|| started.to_string());
+        Ok(())
+    }
+
+    /// Target clock when the run started, if it could be read
+    pub fn run_started(&self) -> Option<&str> {
+        self.run_started.as_deref()
+    }
+
+    /// Most attempts a single command needed since the last call, then reset
+    pub fn take_command_attempts(&mut self) -> u32 {
+        std::mem::take(&mut self.command_attempts)
+    }
+
+    /// Run a command, retrying with exponential backoff when `command_retries`
+    /// is configured and the command errors, times out or its output is cut off
+    async fn execute_with_retries(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+    ) -> Result<CommandResult> {
+        if !self.context.tests.allow_sudo_password && pipes_password(command) {
+            debug!("Not sending sudo password: allow_sudo_password is disabled");
+            return Ok(CommandResult {
+                stdout: String::new(),
+                stderr: SUDO_PASSWORD_DISABLED.to_string(),
+                exit_code: 1,
+            });
+        }
+
+        let cacheable = self.context.tests.cache_commands && is_cacheable_command(command);
+        if cacheable {
+            if let Some(cached) = self.cached_output(command) {
+                debug!("Command cache hit: {}", mask_sudo_password(command));
+                return Ok(cached);
+            }
+        }
+
+        let result = self.execute_uncached(command, timeout).await?;
+        if cacheable {
+            self.cache_output(command, &result);
+        }
+        Ok(result)
+    }
+
+    async fn execute_uncached(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+    ) -> Result<CommandResult> {
+        let retries = self.config.command_retries.unwrap_or(0);
+        if retries == 0 {
+            let output = self.dispatch(command, timeout).await?;
+            self.command_attempts = self.command_attempts.max(1);
+            return Ok(CommandResult {
+                stdout: output.stdout,
+                stderr: output.stderr,
+                exit_code: output.exit_code,
+            });
+        }
+
+        let checked = format!("{}; echo \"{}:$?\"", command, COMPLETION_MARKER_COMMAND);
+        let mut delay = Duration::from_millis(
+            self.config
+                .command_retry_delay_ms
+                .unwrap_or(DEFAULT_COMMAND_RETRY_DELAY_MS),
+        );
+        let mut attempt = 1;
+        loop {
+            self.command_attempts = self.command_attempts.max(attempt);
+            let last_attempt = attempt > retries;
+            match self.dispatch(&checked, timeout).await {
+                Ok(output) => match strip_completion_marker(&output.stdout) {
+                    Some((stdout, exit_code)) => {
+                        return Ok(CommandResult {
+                            stdout,
+                            stderr: output.stderr,
+                            exit_code,
+                        })
+                    }
+                    None if last_attempt => {
+                        warn!(
+                            "⚠️  Output of '{}' still looks incomplete after {} attempts",
+                            mask_sudo_password(command),
+                            attempt
+                        );
+                        return Ok(CommandResult {
+                            stdout: output.stdout,
+                            stderr: output.stderr,
+                            exit_code: output.exit_code,
+                        });
+                    }
+                    None => debug!(
+                        "Incomplete output from '{}' (attempt {})",
+                        mask_sudo_password(command),
+                        attempt
+                    ),
+                },
+                Err(e) if last_attempt => return Err(e),
+                Err(e) => debug!(
+                    "'{}' failed on attempt {}: {}",
+                    mask_sudo_password(command),
+                    attempt,
+                    e
+                ),
+            }
+
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+
+    async fn dispatch(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+    ) -> Result<crate::communication::CommandOutput> {
+        let remaining = self
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        let timeout = match (timeout, remaining) {
+            (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+            (timeout, remaining) => timeout.or(remaining),
+        };
+
+        match timeout {
+            Some(timeout) => {
+                self.channel
+                    .execute_command_with_timeout(command, timeout)
+                    .await
+            }
+            None => self.channel.execute_command(command).await,
+        }
     }
 
     pub async fn upload_file(&mut self, local_path: &str, remote_path: &str) -> Result<()> {
@@ -162,6 +561,22 @@ impl Target {
         }
     }
 
+    /// Seconds since the target booted, read from /proc/uptime
+    pub async fn get_uptime_secs(&mut self) -> Result<f64> {
+        let result = self.execute_command("cat /proc/uptime").await?;
+        result
+            .stdout
+            .split_whitespace()
+            .next()
+            .and_then(|secs| secs.parse::<f64>().ok())
+            .ok_or_else(|| {
+                Error::CommandExecution(format!(
+                    "Failed to read /proc/uptime: {}",
+                    result.stderr.trim()
+                ))
+            })
+    }
+
     pub async fn get_cpu_info(&mut self) -> Result<String> {
         let result = self
             .execute_command("cat /proc/cpuinfo | grep 'model name' | head -1 | cut -d':' -f2")
@@ -214,6 +629,31 @@ impl Target {
         Ok(result.stdout.trim().to_string())
     }
 
+    /// Commit of the booted OSTree deployment (LmP images), empty when the
+    /// image is not OSTree-based
+    pub async fn get_ostree_commit(&mut self) -> Result<String> {
+        let result = self
+            .execute_command("ostree admin status 2>/dev/null")
+            .await?;
+        Ok(parse_ostree_commit(&result.stdout).unwrap_or_default())
+    }
+
+    /// Board serial number from the device tree, DMI or (on Raspberry Pi) cpuinfo
+    pub async fn get_serial_number(&mut self) -> Result<String> {
+        let result = self
+            .execute_command(
+                "cat /sys/firmware/devicetree/base/serial-number 2>/dev/null \
+                 || cat /sys/class/dmi/id/product_serial 2>/dev/null \
+                 || awk -F': ' '/^Serial/ {print $2}' /proc/cpuinfo",
+            )
+            .await?;
+        // Device tree strings are NUL terminated
+        Ok(result
+            .stdout
+            .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+            .to_string())
+    }
+
     pub async fn get_wireguard_status(&mut self) -> Result<String> {
         let result = self
             .execute_command("systemctl is-active wireguard-client 2>/dev/null || echo 'Inactive'")
@@ -221,6 +661,65 @@ impl Target {
         Ok(result.stdout.trim().to_string())
     }
 
+    pub async fn get_hostname(&mut self) -> Result<String> {
+        let result = self
+            .execute_command("hostname 2>/dev/null || cat /etc/hostname")
+            .await?;
+        Ok(result.stdout.trim().to_string())
+    }
+
+    /// Operating system name as reported by `uname -s` (e.g. Linux, FreeBSD)
+    pub async fn get_os_type(&mut self) -> Result<String> {
+        let result = self.execute_command("uname -s").await?;
+        if result.exit_code == 0 && !result.stdout.trim().is_empty() {
+            Ok(result.stdout.trim().to_string())
+        } else {
+            Ok("Unknown".to_string())
+        }
+    }
+
+    pub async fn get_architecture(&mut self) -> Result<String> {
+        let result = self.execute_command("uname -m").await?;
+        if result.exit_code == 0 {
+            Ok(result.stdout.trim().to_string())
+        } else {
+            Ok("Unknown".to_string())
+        }
+    }
+
+    /// Whether a tool is on the target's PATH. Results are remembered for the
+    /// rest of the run so tests can check freely without extra round-trips.
+    pub async fn has_command(&mut self, name: &str) -> bool {
+        if let Some(present) = self.cache().commands.get(name).copied() {
+            return present;
+        }
+
+        match self
+            .execute_command(&format!("command -v {} >/dev/null 2>&1", name))
+            .await
+        {
+            Ok(result) => {
+                let present = result.success();
+                self.cache().commands.insert(name.to_string(), present);
+                present
+            }
+            Err(e) => {
+                debug!("Could not check for command {}: {}", name, e);
+                false
+            }
+        }
+    }
+
+    /// The first of `names` on the target's PATH, checked like `has_command`
+    pub async fn first_command<'a>(&mut self, names: &[&'a str]) -> Option<&'a str> {
+        for name in names {
+            if self.has_command(name).await {
+                return Some(name);
+            }
+        }
+        None
+    }
+
     pub async fn file_exists(&mut self, path: &str) -> Result<bool> {
         let result = self.execute_command(&format!("test -f {}", path)).await?;
         Ok(result.exit_code == 0)
@@ -352,7 +851,7 @@ impl Target {
     }
 
     pub async fn get_system_info(&mut self) -> Result<SystemInfo> {
-        Ok(SystemInfo {
+        let mut info = SystemInfo {
             kernel_version: self
                 .get_kernel_version()
                 .await
@@ -389,10 +888,96 @@ impl Target {
                 .get_wireguard_status()
                 .await
                 .unwrap_or_else(|_| "Unknown".to_string()),
-        })
+            hostname: self
+                .get_hostname()
+                .await
+                .unwrap_or_else(|_| "Unknown".to_string()),
+            architecture: self
+                .get_architecture()
+                .await
+                .unwrap_or_else(|_| "Unknown".to_string()),
+            os_type: self
+                .get_os_type()
+                .await
+                .unwrap_or_else(|_| "Unknown".to_string()),
+            machine_type: "unknown".to_string(),
+            os_version_id: String::new(),
+            ostree_commit: self.get_ostree_commit().await.unwrap_or_default(),
+            serial_number: self.get_serial_number().await.unwrap_or_default(),
+        };
+        info.os_version_id = info.os_release_value("VERSION_ID").unwrap_or_default();
+        Ok(info)
     }
 }
 
+/// Commit of the booted deployment (marked `*`) in `ostree admin status` output,
+/// e.g. `* lmp 3f2e...9a.0` gives `3f2e...9a`
+fn parse_ostree_commit(status: &str) -> Option<String> {
+    status.lines().find_map(|line| {
+        let mut fields = line.trim().strip_prefix('*')?.split_whitespace();
+        let deployment = fields.nth(1)?;
+        let commit = deployment.split('.').next()?;
+        (!commit.is_empty()).then(|| commit.to_string())
+    })
+}
+
+/// Programs that only read state, so running them twice in one run gives the same answer
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "cat", "dmesg", "echo", "grep", "head", "ls", "lsmod", "modinfo", "readlink", "sort", "stat",
+    "tail", "test", "tr", "uname", "uniq", "wc",
+];
+
+/// Files that change from one moment to the next even on an idle device
+const VOLATILE_PATHS: &[&str] = &[
+    "/proc/uptime",
+    "/proc/loadavg",
+    "/proc/stat",
+    "/proc/meminfo",
+    "/proc/interrupts",
+    "/proc/net/",
+    "/proc/self/",
+    "/proc/sys/kernel/random/",
+    "/sys/class/thermal/",
+];
+
+/// Whether a command's output can be reused within a run: every stage of the
+/// pipeline is a read-only program, nothing is written through a redirect and
+/// no volatile file is read
+fn is_cacheable_command(command: &str) -> bool {
+    let stripped = command.replace("2>/dev/null", "").replace("2>&1", "");
+    if stripped.contains('>') || stripped.contains('`') || stripped.contains("$(") {
+        return false;
+    }
+    if VOLATILE_PATHS.iter().any(|path| command.contains(path)) {
+        return false;
+    }
+
+    stripped
+        .split(['|', ';', '&'])
+        .map(str::trim)
+        .filter(|stage| !stage.is_empty())
+        .all(|stage| {
+            let mut words = stage
+                .split_whitespace()
+                .skip_while(|word| *word == "sudo" || word.starts_with('-'));
+            words
+                .next()
+                .is_some_and(|program| READ_ONLY_COMMANDS.contains(&program))
+        })
+}
+
+/// Split the completion marker off command output, returning the output before
+/// it and the exit status it carries; `None` if the marker never arrived
+fn strip_completion_marker(stdout: &str) -> Option<(String, i32)> {
+    let trimmed = stdout.trim_end();
+    let index = trimmed.rfind(COMPLETION_MARKER)?;
+    let exit_code = trimmed[index + COMPLETION_MARKER.len()..]
+        .strip_prefix(':')?
+        .parse()
+        .ok()?;
+    Some((trimmed[..index].to_string(), exit_code))
+}
+
 #[derive(Debug, Clone)]
 pub struct CommandResult {
     pub stdout: String,
@@ -406,7 +991,7 @@ impl CommandResult {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct SystemInfo {
     pub kernel_version: String,
     pub uptime: String,
@@ -417,4 +1002,280 @@ pub struct SystemInfo {
     pub os_release: String,
     pub foundries_registration: String,
     pub wireguard_status: String,
+    #[serde(default)]
+    pub hostname: String,
+    #[serde(default)]
+    pub architecture: String,
+    #[serde(default)]
+    pub os_type: String,
+    #[serde(default)]
+    pub machine_type: String,
+    /// VERSION_ID from /etc/os-release
+    #[serde(default)]
+    pub os_version_id: String,
+    /// Deployed OSTree commit on LmP and other OSTree-based images
+    #[serde(default)]
+    pub ostree_commit: String,
+    #[serde(default)]
+    pub serial_number: String,
+}
+
+impl SystemInfo {
+    /// False only when the target positively reported a non-Linux OS
+    pub fn is_linux(&self) -> bool {
+        self.os_type.is_empty()
+            || self.os_type == "Unknown"
+            || self.os_type.eq_ignore_ascii_case("linux")
+    }
+
+    /// Value of `key` in os-release, without quotes
+    pub fn os_release_value(&self, key: &str) -> Option<String> {
+        self.os_release.lines().find_map(|line| {
+            let (k, value) = line.split_once('=')?;
+            (k == key).then(|| value.trim_matches('"').to_string())
+        })
+    }
+
+    /// Human readable OS name taken from os-release
+    pub fn os_name(&self) -> String {
+        let mut name = None;
+        let mut id_version = (None, None);
+
+        for line in self.os_release.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim_matches('"');
+                match key {
+                    "PRETTY_NAME" => name = Some(value.to_string()),
+                    "ID" => id_version.0 = Some(value),
+                    "VERSION" => id_version.1 = Some(value),
+                    _ => {}
+                }
+            }
+        }
+
+        match (name, id_version) {
+            (Some(name), _) => name,
+            (None, (Some(id), Some(version))) => format!("{} {}", id, version),
+            (None, (Some(id), None)) => id.to_string(),
+            _ => "Unknown".to_string(),
+        }
+    }
+
+    /// Identifying fields for the "System Under Test" block of every report
+    pub fn system_under_test(&self) -> Vec<(&'static str, String)> {
+        let or_unknown = |value: &str| {
+            if value.is_empty() {
+                "Unknown".to_string()
+            } else {
+                value.to_string()
+            }
+        };
+
+        vec![
+            ("Hostname", or_unknown(&self.hostname)),
+            ("OS", self.os_name()),
+            ("OS Version", or_unknown(&self.os_version_id)),
+            ("OS Type", or_unknown(&self.os_type)),
+            ("Kernel", or_unknown(&self.kernel_version)),
+            ("OSTree Commit", or_unknown(&self.ostree_commit)),
+            ("Architecture", or_unknown(&self.architecture)),
+            ("Machine Type", or_unknown(&self.machine_type)),
+            ("Serial Number", or_unknown(&self.serial_number)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::communication::CommandOutput;
+    use crate::config::Config;
+    use async_trait::async_trait;
+
+    /// Cuts off the first reply before the command finishes, like a slow serial console
+    struct FlakyChannel {
+        calls: u32,
+    }
+
+    #[async_trait]
+    impl CommunicationChannel for FlakyChannel {
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn execute_command(&mut self, command: &str) -> Result<CommandOutput> {
+            self.calls += 1;
+            assert!(!command.contains(COMPLETION_MARKER));
+            let stdout = if self.calls == 1 {
+                "line 1\n".to_string()
+            } else {
+                format!("line 1\nline 2\n{}:3\n", COMPLETION_MARKER)
+            };
+            Ok(CommandOutput {
+                stdout,
+                stderr: String::new(),
+                exit_code: 0,
+            })
+        }
+
+        async fn execute_command_with_timeout(
+            &mut self,
+            command: &str,
+            _timeout: Duration,
+        ) -> Result<CommandOutput> {
+            self.execute_command(command).await
+        }
+
+        async fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn description(&self) -> String {
+            "flaky channel".to_string()
+        }
+
+        fn pooled_session(&self) -> Option<Box<dyn CommunicationChannel>> {
+            Some(Box::new(FlakyChannel { calls: 0 }))
+        }
+
+        fn supports_pooling(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_truncated_output_is_retried() {
+        let mut config = Config::default().communication;
+        config.command_retries = Some(2);
+        config.command_retry_delay_ms = Some(1);
+        let mut target = Target::from_channel(Box::new(FlakyChannel { calls: 0 }), config);
+
+        let result = target.execute_command("cat /proc/cmdline").await.unwrap();
+
+        assert_eq!(result.stdout, "line 1\nline 2\n");
+        assert_eq!(result.exit_code, 3);
+        assert_eq!(target.take_command_attempts(), 2);
+        assert_eq!(target.take_command_attempts(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sudo_password_is_not_sent_when_disallowed() {
+        let mut target = Target::from_channel(
+            Box::new(FlakyChannel { calls: 0 }),
+            Config::default().communication,
+        );
+        let mut context = RunContext::default();
+        context.tests.allow_sudo_password = false;
+        target.set_context(context);
+
+        let result = target
+            .execute_command("echo 'secret' | sudo -S cat /etc/shadow")
+            .await
+            .unwrap();
+
+        assert_eq!(result.exit_code, 1);
+        assert_eq!(result.stderr, SUDO_PASSWORD_DISABLED);
+        assert_eq!(target.take_command_attempts(), 0);
+    }
+
+    #[test]
+    fn test_elevation_prefix_follows_escalation_command() {
+        let mut target = Target::from_channel(
+            Box::new(FlakyChannel { calls: 0 }),
+            Config::default().communication,
+        );
+        assert_eq!(target.elevation_prefix(), "sudo -S");
+        assert_eq!(target.elevation_prefix_non_interactive(), "sudo -n");
+
+        let mut context = RunContext::default();
+        context.tests.privilege_escalation_cmd = "/usr/bin/sudo -E".to_string();
+        target.set_context(context.clone());
+        assert_eq!(target.elevation_prefix(), "/usr/bin/sudo -S -E");
+        assert_eq!(
+            target.elevation_pipe_with("pw"),
+            "echo 'pw' | /usr/bin/sudo -S -E"
+        );
+        assert_eq!(
+            target.elevation_pipe_with("it's"),
+            "echo 'it'\\''s' | /usr/bin/sudo -S -E"
+        );
+
+        // doas can't read a password, so none is ever piped to it
+        context.tests.privilege_escalation_cmd = "doas".to_string();
+        target.set_context(context);
+        assert_eq!(target.elevation_prefix(), "doas -n");
+        assert_eq!(target.elevation_prefix_non_interactive(), "doas -n");
+        assert_eq!(target.elevation_pipe_with("pw"), "doas -n");
+    }
+
+    #[tokio::test]
+    async fn test_read_only_command_output_is_cached() {
+        let mut target = Target::from_channel(
+            Box::new(FlakyChannel { calls: 0 }),
+            Config::default().communication,
+        );
+        let mut context = RunContext::default();
+        context.tests.cache_commands = true;
+        target.set_context(context);
+
+        let first = target.execute_command("cat /etc/os-release").await.unwrap();
+        let second = target.execute_command("cat /etc/os-release").await.unwrap();
+        assert_eq!(first.stdout, "line 1\n");
+        assert_eq!(second.stdout, first.stdout);
+        assert_eq!(target.command_cache_stats(), (1, 1));
+
+        // Volatile reads go to the device every time
+        let uptime = target.execute_command("cat /proc/uptime").await.unwrap();
+        assert_ne!(uptime.stdout, first.stdout);
+
+        assert!(is_cacheable_command(
+            "dmesg 2>/dev/null | grep -i tpm | head -5"
+        ));
+        assert!(is_cacheable_command("echo 'pw' | sudo -S cat /etc/shadow"));
+        assert!(!is_cacheable_command(
+            "echo 1 > /proc/sys/kernel/kptr_restrict"
+        ));
+        assert!(!is_cacheable_command("systemctl restart sshd"));
+        assert!(!is_cacheable_command("ps aux | grep sshd"));
+    }
+
+    #[tokio::test]
+    async fn test_pooled_sessions_share_the_command_cache() {
+        let mut target = Target::from_channel(
+            Box::new(FlakyChannel { calls: 0 }),
+            Config::default().communication,
+        );
+        let mut context = RunContext::default();
+        context.tests.cache_commands = true;
+        target.set_context(context);
+
+        let mut worker = target.pooled_session().unwrap();
+        let first = worker.execute_command("cat /etc/os-release").await.unwrap();
+        let second = target.execute_command("cat /etc/os-release").await.unwrap();
+        assert_eq!(second.stdout, first.stdout);
+        assert_eq!(target.command_cache_stats(), (1, 1));
+    }
+
+    #[test]
+    fn test_build_identity_parsing() {
+        let status =
+            "  lmp 1111aaaa.1 (rollback)\n* lmp 3f2e9a0c4b.0\n    origin refspec: 3f2e9a0c4b\n";
+        assert_eq!(parse_ostree_commit(status).as_deref(), Some("3f2e9a0c4b"));
+        assert_eq!(parse_ostree_commit(""), None);
+
+        let info = SystemInfo {
+            os_release: "PRETTY_NAME=\"Linux-microPlatform 4.0.20\"\nVERSION_ID=\"4.0.20\"\n"
+                .to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.os_release_value("VERSION_ID").as_deref(),
+            Some("4.0.20")
+        );
+        assert_eq!(info.os_name(), "Linux-microPlatform 4.0.20");
+    }
 }
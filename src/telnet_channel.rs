@@ -0,0 +1,486 @@
+/*
+ * Security Compliance CLI - Telnet Communication Channel
+ * Copyright (C) 2025 Dynamic Devices Ltd
+ * Licensed under GPLv3 - see LICENSE file for details
+ */
+
+use crate::communication::{ChannelConfig, CommandOutput, CommunicationChannel};
+use crate::error::{Error, Result};
+use crate::secret::{mask_sudo_password, redact_output};
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Instant};
+use tracing::{debug, info};
+
+// Telnet command bytes (RFC 854) and the options we are willing to accept
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const OPT_ECHO: u8 = 1;
+const OPT_SUPPRESS_GO_AHEAD: u8 = 3;
+
+/// Printed before and after each command so its output and exit code can be cut
+/// out of the console stream. The commands spell them with an empty quoted string
+/// in the middle so the echoed command line never matches.
+const BEGIN_MARKER: &str = "__SCC_BEGIN__";
+const BEGIN_MARKER_COMMAND: &str = "__SCC_\"\"BEGIN__";
+const EXIT_MARKER: &str = "__SCC_EXIT__";
+const EXIT_MARKER_COMMAND: &str = "__SCC_\"\"EXIT__";
+
+/// Strip telnet negotiation from the front of `pending`, appending plain data to
+/// `data` and returning the replies to send back. An incomplete sequence at the
+/// end is left in `pending` for the next read.
+///
+/// The server may echo and suppress go-ahead; every other option is refused so
+/// the session stays a plain NVT stream.
+fn decode_telnet(pending: &mut Vec<u8>, data: &mut Vec<u8>) -> Vec<u8> {
+    let mut replies = Vec::new();
+    let mut i = 0;
+
+    while i < pending.len() {
+        if pending[i] != IAC {
+            data.push(pending[i]);
+            i += 1;
+            continue;
+        }
+        let Some(&command) = pending.get(i + 1) else {
+            break;
+        };
+        match command {
+            IAC => {
+                data.push(IAC);
+                i += 2;
+            }
+            DO | DONT | WILL | WONT => {
+                let Some(&option) = pending.get(i + 2) else {
+                    break;
+                };
+                match command {
+                    DO => replies.extend_from_slice(&[IAC, WONT, option]),
+                    WILL if option == OPT_ECHO || option == OPT_SUPPRESS_GO_AHEAD => {
+                        replies.extend_from_slice(&[IAC, DO, option])
+                    }
+                    WILL => replies.extend_from_slice(&[IAC, DONT, option]),
+                    _ => {}
+                }
+                i += 3;
+            }
+            SB => {
+                // Skip the whole subnegotiation, up to and including IAC SE
+                match pending[i + 2..]
+                    .windows(2)
+                    .position(|pair| pair == [IAC, SE])
+                {
+                    Some(end) => i += 2 + end + 2,
+                    None => break,
+                }
+            }
+            _ => i += 2,
+        }
+    }
+
+    pending.drain(..i);
+    replies
+}
+
+/// Last non-empty line of console output, ignoring trailing whitespace
+fn last_line(text: &str) -> &str {
+    text.lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or("")
+}
+
+fn is_login_prompt(text: &str) -> bool {
+    last_line(text).ends_with("login:")
+}
+
+fn is_password_prompt(text: &str) -> bool {
+    last_line(text).ends_with("assword:")
+}
+
+fn is_shell_prompt(text: &str) -> bool {
+    let line = last_line(text);
+    line.ends_with('$') || line.ends_with('#') || line.ends_with('>')
+}
+
+/// Wrap a command so its output is bracketed by the begin and exit markers
+fn marked_command(command: &str) -> String {
+    format!(
+        "echo \"{}\"; {}; echo \"{}$?\"",
+        BEGIN_MARKER_COMMAND, command, EXIT_MARKER_COMMAND
+    )
+}
+
+/// Cut a marked command's output and exit code out of the console text, or
+/// `None` until the exit marker line has been received in full
+fn parse_marked_output(text: &str) -> Option<(String, i32)> {
+    let text = text.replace('\r', "");
+    let begin = format!("{}\n", BEGIN_MARKER);
+    let start = text.find(&begin)? + begin.len();
+    let rest = &text[start..];
+
+    let end = rest.find(EXIT_MARKER)?;
+    let after = &rest[end + EXIT_MARKER.len()..];
+    let (code, _) = after.split_once('\n')?;
+    let exit_code = code.trim().parse().ok()?;
+
+    Some((rest[..end].to_string(), exit_code))
+}
+
+pub struct TelnetChannel {
+    config: TelnetChannelConfig,
+    stream: Option<TcpStream>,
+    pending: Vec<u8>,
+    logged_in: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TelnetChannelConfig {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub timeout: u32,
+}
+
+impl TelnetChannel {
+    pub fn new(config: TelnetChannelConfig) -> Self {
+        Self {
+            config,
+            stream: None,
+            pending: Vec::new(),
+            logged_in: false,
+        }
+    }
+
+    pub fn from_channel_config(config: ChannelConfig) -> Result<Self> {
+        match config {
+            ChannelConfig::Telnet {
+                host,
+                port,
+                user,
+                password,
+                timeout,
+            } => Ok(Self::new(TelnetChannelConfig {
+                host,
+                port,
+                user,
+                password,
+                timeout,
+            })),
+            _ => Err(Error::Config(
+                "Invalid channel config for Telnet".to_string(),
+            )),
+        }
+    }
+
+    async fn send_line(&mut self, line: &str) -> Result<()> {
+        let stream = self
+            .stream
+            .as_mut()
+            .ok_or_else(|| Error::Communication("Telnet session not connected".to_string()))?;
+
+        debug!("Telnet TX: {:?}", mask_sudo_password(line));
+
+        stream
+            .write_all(format!("{}\r\n", line).as_bytes())
+            .await
+            .map_err(|e| Error::Communication(format!("Failed to send to telnet server: {}", e)))
+    }
+
+    /// Read console text until `done` accepts everything received so far,
+    /// answering option negotiation along the way
+    async fn read_until(
+        &mut self,
+        timeout_duration: Duration,
+        done: impl Fn(&str) -> bool,
+    ) -> Result<String> {
+        let stream = self
+            .stream
+            .as_mut()
+            .ok_or_else(|| Error::Communication("Telnet session not connected".to_string()))?;
+
+        let deadline = Instant::now() + timeout_duration;
+        let mut data = Vec::new();
+
+        loop {
+            let text = String::from_utf8_lossy(&data);
+            if done(&text) {
+                return Ok(text.into_owned());
+            }
+
+            let mut temp_buf = [0u8; 1024];
+            let n = match timeout(
+                deadline.saturating_duration_since(Instant::now()),
+                stream.read(&mut temp_buf),
+            )
+            .await
+            {
+                Ok(Ok(0)) => {
+                    return Err(Error::Communication(
+                        "Telnet server closed the connection".to_string(),
+                    ))
+                }
+                Ok(Ok(n)) => n,
+                Ok(Err(e)) => {
+                    return Err(Error::Communication(format!("Telnet read error: {}", e)));
+                }
+                Err(_) => {
+                    return Err(Error::CommandExecution(format!(
+                        "Timeout after {:?} waiting for telnet output; received: {:?}",
+                        timeout_duration,
+                        redact_output(last_line(&text))
+                    )));
+                }
+            };
+
+            self.pending.extend_from_slice(&temp_buf[..n]);
+            let replies = decode_telnet(&mut self.pending, &mut data);
+            if !replies.is_empty() {
+                stream.write_all(&replies).await.map_err(|e| {
+                    Error::Communication(format!("Failed to answer telnet negotiation: {}", e))
+                })?;
+            }
+        }
+    }
+
+    async fn login(&mut self) -> Result<()> {
+        let step = Duration::from_secs(self.config.timeout as u64);
+
+        // Some servers print a banner and wait for a keypress before prompting
+        let mut text = match self
+            .read_until(Duration::from_secs(2), |t| {
+                is_login_prompt(t) || is_password_prompt(t) || is_shell_prompt(t)
+            })
+            .await
+        {
+            Ok(text) => text,
+            Err(_) => {
+                self.send_line("").await?;
+                self.read_until(step, |t| {
+                    is_login_prompt(t) || is_password_prompt(t) || is_shell_prompt(t)
+                })
+                .await?
+            }
+        };
+
+        if is_login_prompt(&text) {
+            info!(
+                "Telnet login prompt found, logging in as {}",
+                self.config.user
+            );
+            let user = self.config.user.clone();
+            self.send_line(&user).await?;
+            text = self
+                .read_until(step, |t| is_password_prompt(t) || is_shell_prompt(t))
+                .await?;
+        }
+
+        if is_password_prompt(&text) {
+            let password = self.config.password.clone();
+            self.send_line(&password).await?;
+            text = self
+                .read_until(step, |t| {
+                    is_shell_prompt(t) || is_login_prompt(t) || t.contains("incorrect")
+                })
+                .await?;
+        }
+
+        if !is_shell_prompt(&text) {
+            return Err(Error::Communication(format!(
+                "Telnet login to {}:{} as {} failed: {}",
+                self.config.host,
+                self.config.port,
+                self.config.user,
+                last_line(&text)
+            )));
+        }
+
+        self.logged_in = true;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CommunicationChannel for TelnetChannel {
+    async fn connect(&mut self) -> Result<()> {
+        info!(
+            "Connecting to telnet server: {}:{}",
+            self.config.host, self.config.port
+        );
+
+        let address = format!("{}:{}", self.config.host, self.config.port);
+        let stream = timeout(
+            Duration::from_secs(self.config.timeout as u64),
+            TcpStream::connect(&address),
+        )
+        .await
+        .map_err(|_| Error::Communication(format!("Timeout connecting to {}", address)))?
+        .map_err(|e| Error::Communication(format!("Failed to connect to {}: {}", address, e)))?;
+
+        self.stream = Some(stream);
+        self.pending.clear();
+        self.login().await?;
+
+        info!("Telnet session established successfully");
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.shutdown().await;
+        }
+        self.logged_in = false;
+        info!("Telnet session closed");
+        Ok(())
+    }
+
+    async fn execute_command(&mut self, command: &str) -> Result<CommandOutput> {
+        self.execute_command_with_timeout(command, Duration::from_secs(self.config.timeout as u64))
+            .await
+    }
+
+    async fn execute_command_with_timeout(
+        &mut self,
+        command: &str,
+        timeout_duration: Duration,
+    ) -> Result<CommandOutput> {
+        if !self.logged_in {
+            return Err(Error::Communication(
+                "Telnet session not connected".to_string(),
+            ));
+        }
+
+        debug!(
+            "Executing telnet command: {} (timeout: {}s)",
+            mask_sudo_password(command),
+            timeout_duration.as_secs()
+        );
+
+        self.send_line(&marked_command(command)).await?;
+        let text = self
+            .read_until(timeout_duration, |t| parse_marked_output(t).is_some())
+            .await?;
+        let (stdout, exit_code) = parse_marked_output(&text).unwrap_or_default();
+        debug!("Command completed with exit code: {}", exit_code);
+
+        Ok(CommandOutput {
+            stdout,
+            stderr: String::new(), // Telnet merges stderr into the console stream
+            exit_code,
+        })
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.logged_in
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Telnet connection to {}@{}:{}",
+            self.config.user, self.config.host, self.config.port
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_decode_telnet_answers_negotiation() {
+        let mut pending = vec![b'a', IAC, DO, 24, IAC, WILL, OPT_ECHO, b'b', IAC, IAC, IAC];
+        let mut data = Vec::new();
+        let replies = decode_telnet(&mut pending, &mut data);
+
+        assert_eq!(data, vec![b'a', b'b', IAC]);
+        assert_eq!(replies, vec![IAC, WONT, 24, IAC, DO, OPT_ECHO]);
+        // A lone IAC may be the start of a split sequence, so it waits for more data
+        assert_eq!(pending, vec![IAC]);
+    }
+
+    #[test]
+    fn test_parse_marked_output_ignores_echo_and_prompt() {
+        let command = marked_command("ls /missing");
+        let text = format!(
+            "{}\r\n{}\r\nls: /missing: No such file\r\n{}2\r\n# ",
+            command, BEGIN_MARKER, EXIT_MARKER
+        );
+        assert_eq!(
+            parse_marked_output(&text),
+            Some(("ls: /missing: No such file\n".to_string(), 2))
+        );
+        // The echoed command alone must not look like a finished command
+        assert_eq!(parse_marked_output(&command), None);
+        assert_eq!(
+            parse_marked_output(&format!("{}\n{}1", BEGIN_MARKER, EXIT_MARKER)),
+            None
+        );
+    }
+
+    /// The client's negotiation replies are not UTF-8, so read raw lines
+    async fn next_line(reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>) -> String {
+        let mut line = Vec::new();
+        reader.read_until(b'\n', &mut line).await.unwrap();
+        String::from_utf8_lossy(&line).into_owned()
+    }
+
+    #[tokio::test]
+    async fn test_telnet_channel_logs_in_and_runs_commands() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (read, mut write) = socket.into_split();
+            let mut reader = BufReader::new(read);
+
+            write.write_all(&[IAC, DO, 24]).await.unwrap();
+            write.write_all(b"Welcome\r\ndevice login: ").await.unwrap();
+            let user = next_line(&mut reader).await;
+            write.write_all(b"Password: ").await.unwrap();
+            let password = next_line(&mut reader).await;
+            write.write_all(b"\r\nroot@device:~# ").await.unwrap();
+
+            let command = next_line(&mut reader).await;
+            let reply = format!(
+                "{}\r\n{}\r\nhello\r\n{}3\r\nroot@device:~# ",
+                command.trim_end(),
+                BEGIN_MARKER,
+                EXIT_MARKER
+            );
+            write.write_all(reply.as_bytes()).await.unwrap();
+            (user, password, command)
+        });
+
+        let mut channel = TelnetChannel::new(TelnetChannelConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            user: "root".to_string(),
+            password: "secret".to_string(),
+            timeout: 5,
+        });
+        channel.connect().await.unwrap();
+        let output = channel
+            .execute_command("sh -c 'echo hello; exit 3'")
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "hello\n");
+        assert_eq!(output.exit_code, 3);
+
+        let (user, password, command) = server.await.unwrap();
+        // The refusal of the terminal-type option arrives ahead of the username
+        assert!(user.trim_end().ends_with("root"));
+        assert_eq!(password.trim_end(), "secret");
+        assert!(command.contains("echo hello; exit 3"));
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
-    error::Result,
+    cli::{MachineType, Severity, IMX_MACHINES},
+    error::{Error, Result},
     target::Target,
-    tests::{create_test_result, SecurityTest, TestResult, TestStatus},
+    tests::{create_test_result, SecurityTest, SkipReason, TestResult, TestStatus},
 };
 use async_trait::async_trait;
 use std::time::Instant;
@@ -16,6 +17,7 @@ pub enum BootSecurityTests {
     OpteeSigned,
     TfaSigned,
     BootChainVerification,
+    ImaEvmEnabled,
 }
 
 #[async_trait]
@@ -31,6 +33,7 @@ impl SecurityTest for BootSecurityTests {
             Self::OpteeSigned => self.test_optee_signed(target).await,
             Self::TfaSigned => self.test_tfa_signed(target).await,
             Self::BootChainVerification => self.test_boot_chain_verification(target).await,
+            Self::ImaEvmEnabled => self.test_ima_evm_enabled(target).await,
         };
 
         let duration = start_time.elapsed();
@@ -45,6 +48,20 @@ impl SecurityTest for BootSecurityTests {
                 details,
                 duration,
             )),
+            Err(Error::Skipped {
+                reason,
+                message,
+                details,
+            }) => Ok(create_test_result(
+                self.test_id(),
+                self.test_name(),
+                self.category(),
+                TestStatus::Skipped,
+                &message,
+                details,
+                duration,
+            )
+            .with_skip_reason(reason)),
             Err(e) => Ok(create_test_result(
                 self.test_id(),
                 self.test_name(),
@@ -66,6 +83,7 @@ impl SecurityTest for BootSecurityTests {
             Self::OpteeSigned => "boot_005",
             Self::TfaSigned => "boot_006",
             Self::BootChainVerification => "boot_007",
+            Self::ImaEvmEnabled => "boot_008",
         }
     }
 
@@ -78,6 +96,7 @@ impl SecurityTest for BootSecurityTests {
             Self::OpteeSigned => "OP-TEE Signature Verification",
             Self::TfaSigned => "TF-A Signature Verification",
             Self::BootChainVerification => "Complete Boot Chain Verification",
+            Self::ImaEvmEnabled => "IMA/EVM Measured Boot",
         }
     }
 
@@ -94,6 +113,40 @@ impl SecurityTest for BootSecurityTests {
             Self::OpteeSigned => "Validates that the OP-TEE Trusted Execution Environment is properly signed and verified. OP-TEE provides secure world isolation for sensitive operations like cryptographic key storage and secure boot validation.",
             Self::TfaSigned => "Verifies ARM Trusted Firmware-A (TF-A) signature validation for secure world boot components. TF-A is the first software to run and establishes the root of trust for the entire system.",
             Self::BootChainVerification => "Performs end-to-end verification of the complete secure boot chain from hardware root of trust through all firmware stages. Ensures no gaps in the chain of trust that could be exploited by attackers.",
+            Self::ImaEvmEnabled => "Checks the Linux Integrity Measurement Architecture and Extended Verification Module that continue the chain of trust past boot. Confirms IMA has a policy and records runtime measurements, reads the ima_policy, ima_appraise and evm kernel parameters, and reports whether appraisal is enforcing or only measuring. Enforcing appraisal with EVM stops modified executables and configuration from running.",
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            Self::SecureBootEnabled => &["cra", "imx93", "imx8mm"],
+            Self::UBootSigned => &["cra"],
+            Self::KernelSigned => &["cra"],
+            Self::ModuleSigning => &["cra"],
+            Self::OpteeSigned => &["cra"],
+            Self::TfaSigned => &["cra"],
+            Self::BootChainVerification => &["cra", "imx93", "imx8mm"],
+            Self::ImaEvmEnabled => &["cra"],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::SecureBootEnabled => Severity::Critical,
+            Self::UBootSigned => Severity::Critical,
+            Self::KernelSigned => Severity::Critical,
+            Self::ModuleSigning => Severity::High,
+            Self::OpteeSigned => Severity::High,
+            Self::TfaSigned => Severity::High,
+            Self::BootChainVerification => Severity::Critical,
+            Self::ImaEvmEnabled => Severity::High,
+        }
+    }
+
+    fn applicable_machines(&self) -> &[MachineType] {
+        match self {
+            Self::OpteeSigned | Self::TfaSigned => IMX_MACHINES,
+            _ => &[],
         }
     }
 }
@@ -104,7 +157,12 @@ impl BootSecurityTests {
         debug!("Checking sudo access for privileged boot tests");
 
         // Try a simple sudo command that doesn't require password input
-        let result = target.execute_command("sudo -n true 2>/dev/null").await;
+        let result = target
+            .execute_command(&format!(
+                "{} true 2>/dev/null",
+                target.elevation_prefix_non_interactive()
+            ))
+            .await;
 
         match result {
             Ok(cmd_result) => {
@@ -152,29 +210,63 @@ impl BootSecurityTests {
         {
             debug!("Command failed with permission error, checking sudo access");
 
+            if !target.context().tests.allow_sudo_password {
+                let sudo_result = target
+                    .execute_command(&format!(
+                        "{} {} 2>/dev/null",
+                        target.elevation_prefix_non_interactive(),
+                        command
+                    ))
+                    .await?;
+                if sudo_result.exit_code == 0 {
+                    return Ok(sudo_result);
+                }
+                return Err(Error::Skipped {
+                    reason: SkipReason::InsufficientPrivilege,
+                    message:
+                        "kernel access needs sudo with a password and allow_sudo_password is disabled"
+                            .to_string(),
+                    details: None,
+                });
+            }
+
             if self.check_sudo_access(target).await? {
                 warn!("⚠️  Elevated privileges required for kernel access. Using sudo for boot security tests.");
                 warn!(
                     "💡 For better security testing, run as root or configure passwordless sudo."
                 );
 
-                // Try with sudo using password input - we'll try common embedded passwords
-                let common_passwords = ["fio"]; // Focus on the most likely password first
-
-                for password in &common_passwords {
-                    debug!(
-                        "Trying sudo with password authentication: {}",
-                        if password.is_empty() {
-                            "passwordless"
-                        } else {
-                            "with password"
-                        }
+                // Only the configured target password is used, plus any candidates the
+                // operator explicitly opted into. Blindly guessing vendor default
+                // passwords was removed: a security tool should not attempt logins
+                // with credentials nobody gave it.
+                let mut passwords: Vec<String> = Vec::new();
+                let candidates = std::iter::once(target.get_password().to_string()).chain(
+                    target
+                        .context()
+                        .tests
+                        .sudo_candidate_passwords
+                        .iter()
+                        .cloned(),
+                );
+                for password in candidates {
+                    if !password.is_empty() && !passwords.contains(&password) {
+                        passwords.push(password);
+                    }
+                }
+
+                // Only sudo reads a password; any other tool gets one attempt
+                if !target.elevation_reads_password() {
+                    passwords = vec![String::new()];
+                }
+
+                for password in &passwords {
+                    debug!("Trying sudo with password authentication");
+                    let sudo_command = format!(
+                        "{} {} 2>/dev/null",
+                        target.elevation_pipe_with(password),
+                        command
                     );
-                    let sudo_command = if password.is_empty() {
-                        format!("sudo -n {}", command)
-                    } else {
-                        format!("echo '{}' | sudo -S {} 2>/dev/null", password, command)
-                    };
 
                     let sudo_result = target.execute_command(&sudo_command).await?;
 
@@ -209,11 +301,8 @@ impl BootSecurityTests {
         let mut secure_indicators = Vec::new();
 
         // Check for ELE management tools availability (informational only, not a security concern)
-        let ele_tools_check = target
-            .execute_command("which ele_mu_ctl ele_status 2>/dev/null || echo 'tools_not_found'")
-            .await?;
-        let ele_tools_available = !ele_tools_check.stdout.contains("tools_not_found")
-            && !ele_tools_check.stdout.trim().is_empty();
+        let ele_tools_available =
+            target.has_command("ele_mu_ctl").await && target.has_command("ele_status").await;
 
         if ele_tools_available {
             // Try to get ELE status using tools
@@ -340,7 +429,12 @@ impl BootSecurityTests {
             details.push(format!("FIT images: {}", fit_images.stdout.trim()));
 
             // Check if FIT images contain signatures
-            let fit_sigs = target.execute_command("strings /var/rootdirs/mnt/boot/*.itb 2>/dev/null | grep -i 'signature\\|rsa\\|hash.*sign' | head -3").await?;
+            let fit_sigs = target
+                .execute_command(&format!(
+                    "strings /var/rootdirs/mnt/boot/*.itb 2>/dev/null | grep -i 'signature\\|rsa\\|hash.*sign'{}",
+                    target.evidence_head(3)
+                ))
+                .await?;
             if !fit_sigs.stdout.is_empty() {
                 uboot_indicators.push("FIT signatures detected");
                 details.push(format!("FIT signatures: {}", fit_sigs.stdout.trim()));
@@ -454,7 +548,7 @@ impl BootSecurityTests {
                 ele_secure_boot
                     .stdout
                     .lines()
-                    .take(2)
+                    .take(target.evidence_lines(2))
                     .collect::<Vec<_>>()
                     .join("; ")
             ));
@@ -543,7 +637,7 @@ impl BootSecurityTests {
 
         // Check loaded modules for signature info
         let signed_modules = target
-            .execute_command("cat /proc/modules | head -5")
+            .execute_command(&format!("cat /proc/modules{}", target.evidence_head(5)))
             .await?;
 
         if module_sig
@@ -906,4 +1000,170 @@ impl BootSecurityTests {
             )),
         }
     }
+
+    async fn test_ima_evm_enabled(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let cmdline = target.execute_command("cat /proc/cmdline").await?;
+        let cmdline = cmdline.stdout.trim().to_string();
+
+        // securityfs is root-only on most images, so go through the sudo fallback
+        let ima_dir = self
+            .execute_kernel_command(target, "ls /sys/kernel/security/ima")
+            .await?;
+        if ima_dir.exit_code != 0 || ima_dir.stdout.trim().is_empty() {
+            return Ok((
+                TestStatus::Warning,
+                "IMA not available - kernel built without CONFIG_IMA or securityfs unreadable"
+                    .to_string(),
+                Some(format!("Kernel command line: {}", cmdline)),
+            ));
+        }
+
+        let mut details = Vec::new();
+        let kernel_params: Vec<&str> = cmdline
+            .split_whitespace()
+            .filter(|param| {
+                param.starts_with("ima_policy=")
+                    || param.starts_with("ima_appraise=")
+                    || param.starts_with("evm=")
+                    || *param == "ima_tcb"
+            })
+            .collect();
+        details.push(format!(
+            "IMA/EVM kernel parameters: {}",
+            if kernel_params.is_empty() {
+                "none".to_string()
+            } else {
+                kernel_params.join(" ")
+            }
+        ));
+
+        let policy = if ima_dir.stdout.contains("policy") {
+            self.execute_kernel_command(target, "cat /sys/kernel/security/ima/policy")
+                .await?
+                .stdout
+        } else {
+            String::new()
+        };
+        let policy_rules = policy
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count();
+        details.push(format!(
+            "IMA policy rules: {}",
+            if ima_dir.stdout.contains("policy") {
+                policy_rules.to_string()
+            } else {
+                "policy not readable (CONFIG_IMA_READ_POLICY not set)".to_string()
+            }
+        ));
+
+        let measurements = self
+            .execute_kernel_command(
+                target,
+                "wc -l /sys/kernel/security/ima/ascii_runtime_measurements",
+            )
+            .await?;
+        let measurements: usize = measurements
+            .stdout
+            .split_whitespace()
+            .next()
+            .and_then(|count| count.parse().ok())
+            .unwrap_or(0);
+        details.push(format!("Runtime measurements: {}", measurements));
+
+        let evm = self
+            .execute_kernel_command(target, "cat /sys/kernel/security/evm")
+            .await?;
+        let evm_mode: u32 = evm.stdout.trim().parse().unwrap_or(0);
+        details.push(format!(
+            "EVM: {}",
+            if evm_mode == 0 {
+                "not initialized".to_string()
+            } else {
+                format!("initialized (mode {})", evm_mode)
+            }
+        ));
+
+        let appraisal = ima_appraisal_mode(&cmdline, &policy);
+        details.push(format!("IMA appraisal: {}", appraisal));
+
+        // The boot_aggregate entry is always present, so one measurement means no policy
+        if measurements <= 1 && policy_rules == 0 {
+            return Ok((
+                TestStatus::Warning,
+                "IMA present but no measurement policy loaded".to_string(),
+                Some(details.join("\n")),
+            ));
+        }
+
+        if appraisal == "enforce" && evm_mode != 0 {
+            Ok((
+                TestStatus::Passed,
+                format!(
+                    "IMA appraisal enforcing with EVM ({} measurements)",
+                    measurements
+                ),
+                Some(details.join("\n")),
+            ))
+        } else if appraisal == "enforce" {
+            Ok((
+                TestStatus::Warning,
+                "IMA appraisal enforcing but EVM not initialized - file metadata is unprotected"
+                    .to_string(),
+                Some(details.join("\n")),
+            ))
+        } else {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "IMA measuring only ({} measurements, appraisal {})",
+                    measurements, appraisal
+                ),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+}
+
+/// IMA appraisal mode from the kernel command line and the loaded policy:
+/// `ima_appraise=` wins, otherwise appraise rules in the policy mean enforcement
+fn ima_appraisal_mode(cmdline: &str, policy: &str) -> &'static str {
+    let param = cmdline
+        .split_whitespace()
+        .find_map(|param| param.strip_prefix("ima_appraise="));
+    match param {
+        Some("enforce") => "enforce",
+        Some("log") => "log",
+        Some("fix") => "fix",
+        Some("off") => "off",
+        _ if policy
+            .lines()
+            .any(|line| line.trim_start().starts_with("appraise"))
+            || cmdline.contains("ima_policy=appraise_tcb")
+            || cmdline.contains("ima_appraise_tcb") =>
+        {
+            "enforce"
+        }
+        _ => "not configured",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ima_appraisal_mode() {
+        let policy = "measure func=BPRM_CHECK\nappraise func=BPRM_CHECK appraise_type=imasig\n";
+        assert_eq!(ima_appraisal_mode("root=/dev/mmcblk0p2", policy), "enforce");
+        assert_eq!(ima_appraisal_mode("ima_appraise=fix", policy), "fix");
+        assert_eq!(
+            ima_appraisal_mode("ima_policy=tcb", "measure func=BPRM_CHECK\n"),
+            "not configured"
+        );
+        assert_eq!(ima_appraisal_mode("ima_policy=appraise_tcb", ""), "enforce");
+    }
 }
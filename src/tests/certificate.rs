@@ -1,9 +1,12 @@
 use crate::{
-    error::Result,
+    cli::Severity,
+    error::{Error, Result},
+    runner::shell_quote,
     target::Target,
-    tests::{create_test_result, SecurityTest, TestResult, TestStatus},
+    tests::{create_test_result, SecurityTest, SkipReason, TestResult, TestStatus},
 };
 use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
 use std::time::Instant;
 
 #[derive(Debug, Clone)]
@@ -18,6 +21,8 @@ pub enum CertificateTests {
     TlsCertValidation,
     CertificateRotation,
     ComplianceStandards,
+    OcspStapling,
+    CertificatePinning,
 }
 
 #[async_trait]
@@ -36,6 +41,8 @@ impl SecurityTest for CertificateTests {
             Self::TlsCertValidation => self.test_tls_cert_validation(target).await,
             Self::CertificateRotation => self.test_certificate_rotation(target).await,
             Self::ComplianceStandards => self.test_compliance_standards(target).await,
+            Self::OcspStapling => self.test_ocsp_stapling(target).await,
+            Self::CertificatePinning => self.test_certificate_pinning(target).await,
         };
 
         let duration = start_time.elapsed();
@@ -50,6 +57,20 @@ impl SecurityTest for CertificateTests {
                 details,
                 duration,
             )),
+            Err(Error::Skipped {
+                reason,
+                message,
+                details,
+            }) => Ok(create_test_result(
+                self.test_id(),
+                self.test_name(),
+                self.category(),
+                TestStatus::Skipped,
+                &message,
+                details,
+                duration,
+            )
+            .with_skip_reason(reason)),
             Err(e) => Ok(create_test_result(
                 self.test_id(),
                 self.test_name(),
@@ -74,6 +95,8 @@ impl SecurityTest for CertificateTests {
             Self::TlsCertValidation => "certificate_008",
             Self::CertificateRotation => "certificate_009",
             Self::ComplianceStandards => "certificate_010",
+            Self::OcspStapling => "certificate_011",
+            Self::CertificatePinning => "certificate_012",
         }
     }
 
@@ -89,6 +112,8 @@ impl SecurityTest for CertificateTests {
             Self::TlsCertValidation => "TLS Certificate Validation",
             Self::CertificateRotation => "Certificate Rotation Mechanisms",
             Self::ComplianceStandards => "Certificate Compliance Standards",
+            Self::OcspStapling => "OCSP Stapling & Revocation Liveness",
+            Self::CertificatePinning => "Backend Certificate Pinning",
         }
     }
 
@@ -108,6 +133,42 @@ impl SecurityTest for CertificateTests {
             Self::TlsCertValidation => "Validates TLS/SSL certificate configuration and validation processes for secure communications. Ensures proper certificate verification in network protocols. Essential for preventing encrypted communication interception and maintaining data confidentiality in transit.",
             Self::CertificateRotation => "Verifies automated certificate rotation and renewal mechanisms to maintain security without service interruption. Ensures certificates are regularly updated and replaced before expiration. Important for maintaining operational security and preventing certificate-related outages.",
             Self::ComplianceStandards => "Validates certificate management compliance with industry standards such as PKCS, RFC specifications, and regulatory requirements. Ensures certificate practices meet legal and industry requirements. Critical for regulatory compliance and interoperability with external systems and partners.",
+            Self::OcspStapling => "Actively verifies that certificate revocation status can be established, rather than only checking that revocation tooling exists. Probes the configured TLS endpoints (or a local TLS service when none are configured) for a stapled OCSP response, optionally queries the issuer's OCSP responder, and fails when a local CRL has passed its next update time. Important for ensuring revoked credentials are actually detected in the field.",
+            Self::CertificatePinning => "Checks whether clients talking to the management backend, such as aktualizr-lite and MQTT bridges, pin a dedicated CA or the server public key instead of trusting the whole system CA store. Pinning stops a rogue or compromised public CA from impersonating the backend and intercepting updates or telemetry. Supports the RED and ETSI EN 303 645 secure communication requirements.",
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            Self::X509Validation => &["cra", "red"],
+            Self::PkiInfrastructure => &["cra", "red"],
+            Self::CertificateExpiration => &["cra", "red"],
+            Self::CertificateChain => &["cra", "red"],
+            Self::CertificateRevocation => &["cra", "red"],
+            Self::SecureCertStorage => &["cra", "red"],
+            Self::CaCertManagement => &["cra", "red"],
+            Self::TlsCertValidation => &["cra", "red"],
+            Self::CertificateRotation => &["cra", "red"],
+            Self::ComplianceStandards => &["cra", "red"],
+            Self::OcspStapling => &["cra", "red"],
+            Self::CertificatePinning => &["cra", "red"],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::X509Validation => Severity::High,
+            Self::PkiInfrastructure => Severity::Medium,
+            Self::CertificateExpiration => Severity::High,
+            Self::CertificateChain => Severity::High,
+            Self::CertificateRevocation => Severity::Medium,
+            Self::SecureCertStorage => Severity::High,
+            Self::CaCertManagement => Severity::Medium,
+            Self::TlsCertValidation => Severity::High,
+            Self::CertificateRotation => Severity::Medium,
+            Self::ComplianceStandards => Severity::Low,
+            Self::OcspStapling => Severity::Low,
+            Self::CertificatePinning => Severity::Medium,
         }
     }
 }
@@ -118,17 +179,14 @@ impl CertificateTests {
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
         // Check OpenSSL availability
-        let openssl_check = target
-            .execute_command("openssl version 2>/dev/null || echo 'no_openssl'")
-            .await?;
-
-        if openssl_check.stdout.contains("no_openssl") {
-            return Ok((
-                TestStatus::Skipped,
-                "OpenSSL not available for certificate validation".to_string(),
-                None,
-            ));
+        if !target.has_command("openssl").await {
+            return Err(Error::Skipped {
+                reason: SkipReason::MissingTool,
+                message: "OpenSSL not available for certificate validation".to_string(),
+                details: None,
+            });
         }
+        let openssl_version = target.execute_command("openssl version").await?;
 
         // Find system certificates
         let cert_locations = target.execute_command("find /etc/ssl /usr/share/ca-certificates /etc/pki -name '*.crt' -o -name '*.pem' 2>/dev/null | wc -l").await?;
@@ -149,7 +207,7 @@ impl CertificateTests {
 
         let details = format!(
             "OpenSSL: {}\nCertificate count: {}\nValidation test: {}",
-            openssl_check.stdout.trim(),
+            openssl_version.stdout.trim(),
             cert_count,
             if cert_validation.stdout.contains("validation_failed") {
                 "Failed"
@@ -247,59 +305,91 @@ impl CertificateTests {
         &self,
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
-        // Check system certificates for expiration
-        let cert_expiry = target.execute_command("find /etc/ssl/certs -name '*.pem' -exec openssl x509 -in {} -enddate -noout \\; 2>/dev/null | head -5 || echo 'no_cert_dates'").await?;
+        let (fail_days, warn_days) = {
+            let tests = &target.context().tests;
+            (tests.cert_expiry_fail_days, tests.cert_expiry_warn_days)
+        };
+
+        // Read notAfter of every device and service certificate on disk
+        let cert_dates = target.execute_command(&cert_expiry_scan()).await?;
 
         // Check for certificate monitoring tools
-        let monitoring_tools = target.execute_command("which certbot 2>/dev/null || which cert-manager 2>/dev/null || echo 'no_monitoring'").await?;
+        let monitoring_tool = target.first_command(&["certbot", "cert-manager"]).await;
 
         // Check for automated renewal
         let renewal_check = target.execute_command("systemctl list-timers | grep -i cert || crontab -l 2>/dev/null | grep -i cert || echo 'no_renewal'").await?;
 
-        let mut expiry_features = Vec::new();
-
-        if !cert_expiry.stdout.contains("no_cert_dates") {
-            expiry_features.push("Certificate expiry readable");
-        }
-        if !monitoring_tools.stdout.contains("no_monitoring") {
-            expiry_features.push("Monitoring tools available");
-        }
-        if !renewal_check.stdout.contains("no_renewal") {
-            expiry_features.push("Automated renewal configured");
+        let expiries = parse_cert_expiries(&cert_dates.stdout, Utc::now().naive_utc());
+        let expiring = expiries
+            .iter()
+            .filter(|cert| cert.days_remaining <= fail_days)
+            .count();
+        let expiring_soon = expiries
+            .iter()
+            .filter(|cert| cert.days_remaining > fail_days && cert.days_remaining <= warn_days)
+            .count();
+
+        let mut details = vec![
+            format!(
+                "Certificates checked: {} (fail within {} days, warn within {})",
+                expiries.len(),
+                fail_days,
+                warn_days
+            ),
+            format!("Monitoring: {}", monitoring_tool.unwrap_or("no_monitoring")),
+            format!(
+                "Renewal: {}",
+                if renewal_check.stdout.contains("no_renewal") {
+                    "Not configured"
+                } else {
+                    "Configured"
+                }
+            ),
+        ];
+        if !expiries.is_empty() {
+            details.push("Soonest expiring:".to_string());
+            details.extend(
+                expiries
+                    .iter()
+                    .take(target.evidence_lines(10))
+                    .map(|cert| format!("  {}", cert)),
+            );
         }
+        let details = Some(details.join("\n"));
 
-        let details = format!(
-            "Certificate dates: {}\nMonitoring: {}\nRenewal: {}",
-            if cert_expiry.stdout.contains("no_cert_dates") {
-                "Not available"
-            } else {
-                "Available"
-            },
-            monitoring_tools.stdout.trim(),
-            if renewal_check.stdout.contains("no_renewal") {
-                "Not configured"
-            } else {
-                "Configured"
-            }
-        );
-
-        if expiry_features.len() >= 3 {
+        if expiries.is_empty() {
+            Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No device certificates outside the system CA trust stores".to_string(),
+                details,
+            })
+        } else if expiring > 0 {
             Ok((
-                TestStatus::Passed,
-                "Certificate expiration monitoring complete".to_string(),
-                Some(details),
+                TestStatus::Failed,
+                format!(
+                    "{} certificate(s) expired or expiring within {} days (soonest: {})",
+                    expiring, fail_days, expiries[0]
+                ),
+                details,
             ))
-        } else if expiry_features.len() >= 2 {
+        } else if expiring_soon > 0 {
             Ok((
                 TestStatus::Warning,
-                "Basic expiration monitoring".to_string(),
-                Some(details),
+                format!(
+                    "{} certificate(s) expiring within {} days (soonest: {})",
+                    expiring_soon, warn_days, expiries[0]
+                ),
+                details,
             ))
         } else {
             Ok((
-                TestStatus::Failed,
-                "No certificate expiration monitoring".to_string(),
-                Some(details),
+                TestStatus::Passed,
+                format!(
+                    "All {} certificates valid for more than {} days",
+                    expiries.len(),
+                    warn_days
+                ),
+                details,
             ))
         }
     }
@@ -503,7 +593,10 @@ impl CertificateTests {
         let ca_count = target.execute_command("grep -c 'BEGIN CERTIFICATE' /etc/ssl/certs/ca-certificates.crt 2>/dev/null || echo '0'").await?;
 
         // Check CA update mechanism
-        let ca_update = target.execute_command("which update-ca-certificates 2>/dev/null || which ca-certificates-update 2>/dev/null || echo 'no_update'").await?;
+        let ca_update = target
+            .first_command(&["update-ca-certificates", "ca-certificates-update"])
+            .await
+            .is_some();
 
         // Check CA certificate validity
         let ca_validity = target.execute_command("openssl x509 -in /etc/ssl/certs/ca-certificates.crt -enddate -noout 2>/dev/null | head -1 || echo 'no_validity'").await?;
@@ -515,7 +608,7 @@ impl CertificateTests {
         if ca_cert_count > 100 {
             ca_features.push("Comprehensive CA bundle");
         }
-        if !ca_update.stdout.contains("no_update") {
+        if ca_update {
             ca_features.push("CA update tools available");
         }
         if !ca_validity.stdout.contains("no_validity") {
@@ -525,10 +618,10 @@ impl CertificateTests {
         let details = format!(
             "CA certificates: {}\nUpdate tools: {}\nValidity check: {}",
             ca_cert_count,
-            if ca_update.stdout.contains("no_update") {
-                "Not available"
-            } else {
+            if ca_update {
                 "Available"
+            } else {
+                "Not available"
             },
             if ca_validity.stdout.contains("no_validity") {
                 "Not available"
@@ -562,6 +655,11 @@ impl CertificateTests {
         &self,
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
+        let endpoints = target.context().tests.tls_endpoints.clone();
+        if !endpoints.is_empty() {
+            return self.test_tls_endpoints(target, &endpoints).await;
+        }
+
         // Check for TLS services
         let tls_services = target
             .execute_command("netstat -tuln | grep ':443\\|:993\\|:995\\|:636' | wc -l")
@@ -620,6 +718,67 @@ impl CertificateTests {
         }
     }
 
+    /// Connect to each configured endpoint and validate what it presents
+    async fn test_tls_endpoints(
+        &self,
+        target: &mut Target,
+        endpoints: &[String],
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let now = Utc::now().naive_utc();
+        let mut details = Vec::new();
+        let mut failed = Vec::new();
+        let mut unreachable = Vec::new();
+
+        for endpoint in endpoints {
+            let Some((host, port)) = split_tls_endpoint(endpoint) else {
+                failed.push(endpoint.clone());
+                details.push(format!("{}: not a host:port endpoint", endpoint));
+                continue;
+            };
+            let server_name = host.trim_start_matches('[').trim_end_matches(']');
+            let probe = target
+                .execute_command(&format!(
+                    "out=$(echo | timeout 15 openssl s_client -connect '{}:{}' -servername '{}' 2>&1); echo \"$out\" | grep -E '^CONNECTED|Verify return code|^New, '; echo \"$out\" | openssl x509 -noout -enddate -subject -issuer 2>/dev/null",
+                    host, port, server_name
+                ))
+                .await?;
+
+            let (status, summary) = TlsEndpointProbe::parse(&probe.stdout).assess(now);
+            match status {
+                TestStatus::Failed => failed.push(endpoint.clone()),
+                TestStatus::Warning => unreachable.push(endpoint.clone()),
+                _ => {}
+            }
+            details.push(format!("{}: {}", endpoint, summary));
+        }
+
+        let details = Some(details.join("\n"));
+        if !failed.is_empty() {
+            Ok((
+                TestStatus::Failed,
+                format!(
+                    "{} of {} TLS endpoint(s) failed validation: {}",
+                    failed.len(),
+                    endpoints.len(),
+                    failed.join(", ")
+                ),
+                details,
+            ))
+        } else if !unreachable.is_empty() {
+            Ok((
+                TestStatus::Warning,
+                format!("No TLS handshake with: {}", unreachable.join(", ")),
+                details,
+            ))
+        } else {
+            Ok((
+                TestStatus::Passed,
+                format!("All {} TLS endpoint(s) validated", endpoints.len()),
+                details,
+            ))
+        }
+    }
+
     async fn test_certificate_rotation(
         &self,
         target: &mut Target,
@@ -685,13 +844,17 @@ impl CertificateTests {
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
         // Check certificate key lengths
-        let key_lengths = target.execute_command("find /etc/ssl/certs -name '*.pem' -exec openssl x509 -in {} -text -noout \\; 2>/dev/null | grep -E 'Public-Key:|RSA Public-Key:' | head -5 || echo 'no_key_info'").await?;
+        let key_lengths = target.execute_command(&format!("find /etc/ssl/certs -name '*.pem' -exec openssl x509 -in {{}} -text -noout \\; 2>/dev/null | grep -E 'Public-Key:|RSA Public-Key:'{} || echo 'no_key_info'", target.evidence_head(5))).await?;
 
         // Check signature algorithms
         let sig_algorithms = target.execute_command("find /etc/ssl/certs -name '*.pem' -exec openssl x509 -in {} -text -noout \\; 2>/dev/null | grep 'Signature Algorithm:' | sort | uniq -c || echo 'no_sig_info'").await?;
 
         // Check certificate validity periods
-        let validity_periods = target.execute_command("find /etc/ssl/certs -name '*.pem' -exec openssl x509 -in {} -dates -noout \\; 2>/dev/null | head -10 || echo 'no_validity_info'").await?;
+        let validity_periods = target.execute_command(&format!("find /etc/ssl/certs -name '*.pem' -exec openssl x509 -in {{}} -dates -noout \\; 2>/dev/null{} || echo 'no_validity_info'", target.evidence_head(10))).await?;
+        let key_info =
+            !key_lengths.stdout.trim().is_empty() && !key_lengths.stdout.contains("no_key_info");
+        let validity_info = !validity_periods.stdout.trim().is_empty()
+            && !validity_periods.stdout.contains("no_validity_info");
 
         let mut compliance_features = Vec::new();
 
@@ -701,26 +864,26 @@ impl CertificateTests {
         if sig_algorithms.stdout.contains("sha256") && !sig_algorithms.stdout.contains("sha1") {
             compliance_features.push("Modern signature algorithms");
         }
-        if !validity_periods.stdout.contains("no_validity_info") {
+        if validity_info {
             compliance_features.push("Certificate validity tracking");
         }
 
         let details = format!(
             "Key lengths: {}\nSignature algorithms: {}\nValidity periods: {}",
-            if key_lengths.stdout.contains("no_key_info") {
-                "Not available"
-            } else {
+            if key_info {
                 "Available"
+            } else {
+                "Not available"
             },
             if sig_algorithms.stdout.contains("no_sig_info") {
                 "Not available"
             } else {
                 "Available"
             },
-            if validity_periods.stdout.contains("no_validity_info") {
-                "Not available"
-            } else {
+            if validity_info {
                 "Available"
+            } else {
+                "Not available"
             }
         );
 
@@ -744,4 +907,658 @@ impl CertificateTests {
             ))
         }
     }
+
+    async fn test_ocsp_stapling(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        if !target.has_command("openssl").await {
+            return Err(Error::Skipped {
+                reason: SkipReason::MissingTool,
+                message: "OpenSSL not available for revocation checks".to_string(),
+                details: None,
+            });
+        }
+
+        let mut details = Vec::new();
+
+        // Probe the configured TLS endpoints, or else a local TLS service
+        let mut endpoints = Vec::new();
+        for endpoint in &target.context().tests.tls_endpoints {
+            if let Some((host, port)) = split_tls_endpoint(endpoint) {
+                let server_name = host.trim_start_matches('[').trim_end_matches(']');
+                endpoints.push((format!("{}:{}", host, port), Some(server_name.to_string())));
+            }
+        }
+        if endpoints.is_empty() {
+            let listeners = target
+                .execute_command("ss -tln 2>/dev/null || netstat -tln 2>/dev/null")
+                .await?;
+            let tls_ports = ["443", "8443", "8883", "993", "995", "636"];
+            let tls_port = tls_ports.iter().find(|port| {
+                listeners
+                    .stdout
+                    .lines()
+                    .any(|line| line.contains("LISTEN") && line.contains(&format!(":{} ", port)))
+            });
+            match tls_port {
+                Some(port) => endpoints.push((format!("127.0.0.1:{}", port), None)),
+                None => {
+                    details.push("No local TLS service found to probe for stapling".to_string())
+                }
+            }
+        }
+
+        let mut stapled = 0;
+        let mut revoked = Vec::new();
+        let mut responder_good = 0;
+
+        for (endpoint, server_name) in &endpoints {
+            let connect = match server_name {
+                Some(name) => format!(
+                    "-connect {} -servername {}",
+                    shell_quote(endpoint),
+                    shell_quote(name)
+                ),
+                None => format!("-connect {}", shell_quote(endpoint)),
+            };
+
+            let status = target
+                .execute_command(&format!(
+                    "echo | timeout 10 openssl s_client {} -status 2>/dev/null | grep -E 'OCSP Response Status|Cert Status|OCSP response:'",
+                    connect
+                ))
+                .await?;
+            details.push(format!(
+                "{} stapled OCSP: {}",
+                endpoint,
+                status.stdout.trim()
+            ));
+
+            let endpoint_stapled = status.stdout.contains("OCSP Response Status: successful");
+            if endpoint_stapled {
+                stapled += 1;
+            }
+            if status.stdout.contains("Cert Status: revoked") {
+                revoked.push(endpoint.clone());
+                continue;
+            }
+
+            // Without a stapled response, ask the issuer's responder directly if allowed
+            if !endpoint_stapled && target.context().tests.allow_external_network {
+                let responder = target
+                    .execute_command(&format!(
+                        "d=$(mktemp -d) && cd $d && echo | timeout 10 openssl s_client {} -showcerts 2>/dev/null | awk '/BEGIN CERT/{{n++}} n{{print > \"cert\" n \".pem\"}}' && uri=$(openssl x509 -in cert1.pem -noout -ocsp_uri 2>/dev/null) && [ -n \"$uri\" ] && [ -f cert2.pem ] && timeout 15 openssl ocsp -issuer cert2.pem -cert cert1.pem -url \"$uri\" -noverify 2>&1; cd / && rm -rf $d",
+                        connect
+                    ))
+                    .await?;
+                details.push(format!(
+                    "{} OCSP responder: {}",
+                    endpoint,
+                    responder.stdout.trim()
+                ));
+
+                if responder.stdout.contains(": revoked") {
+                    revoked.push(endpoint.clone());
+                } else if responder.stdout.contains(": good") {
+                    responder_good += 1;
+                }
+            }
+        }
+
+        // Establish CRL freshness from nextUpdate of local CRLs
+        let crl_dates = target
+            .execute_command("find /etc/ssl /etc/pki -name '*.crl' 2>/dev/null | head -10 | while read f; do echo \"$f $(openssl crl -in \"$f\" -noout -nextupdate 2>/dev/null)\"; done")
+            .await?;
+        let crls = CrlFreshness::parse(&crl_dates.stdout, Utc::now().naive_utc());
+        details.push(crls.to_string());
+
+        let details = Some(details.join("\n"));
+
+        if !revoked.is_empty() {
+            Ok((
+                TestStatus::Failed,
+                format!("Presented certificate is revoked: {}", revoked.join(", ")),
+                details,
+            ))
+        } else if !crls.expired.is_empty() {
+            Ok((
+                TestStatus::Failed,
+                format!(
+                    "{} CRL(s) past their next update: {}",
+                    crls.expired.len(),
+                    crls.expired.join(", ")
+                ),
+                details,
+            ))
+        } else if !crls.stale.is_empty() {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "{} CRL(s) without a readable next update: {}",
+                    crls.stale.len(),
+                    crls.stale.join(", ")
+                ),
+                details,
+            ))
+        } else if !endpoints.is_empty() && stapled == endpoints.len() {
+            Ok((
+                TestStatus::Passed,
+                "Valid OCSP response stapled".to_string(),
+                details,
+            ))
+        } else if stapled + responder_good > 0 {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "Certificate not revoked but OCSP stapling not enabled on {} of {} endpoint(s)",
+                    endpoints.len() - stapled,
+                    endpoints.len()
+                ),
+                details,
+            ))
+        } else if crls.fresh > 0 {
+            Ok((
+                TestStatus::Passed,
+                format!("{} CRL(s) current", crls.fresh),
+                details,
+            ))
+        } else if !endpoints.is_empty() {
+            Ok((
+                TestStatus::Warning,
+                "No OCSP stapling and no current CRL - revocation liveness unverified".to_string(),
+                details,
+            ))
+        } else {
+            Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "Inconclusive - no TLS service or CRLs to check".to_string(),
+                details,
+            })
+        }
+    }
+
+    async fn test_certificate_pinning(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let mut details = Vec::new();
+        let mut pins = Vec::new();
+        let mut ca_store_only = Vec::new();
+
+        // aktualizr-lite trusts whatever tls_cacert_path points at for the device gateway
+        let sota_command = target.probe_command(
+            "certificate_012.sota_config",
+            "cat /usr/lib/sota/conf.d/*.toml /etc/sota/conf.d/*.toml /var/sota/sota.toml 2>/dev/null",
+        );
+        let sota = target.execute_command(&sota_command).await?;
+        if !sota.stdout.trim().is_empty() {
+            match sota_trust_anchor(&sota.stdout) {
+                Some(path) if is_system_ca_store(&path) => {
+                    details.push(format!("aktualizr-lite CA: {} (system store)", path));
+                    ca_store_only.push("aktualizr-lite");
+                }
+                Some(path) => {
+                    details.push(format!("aktualizr-lite CA: {} (dedicated)", path));
+                    pins.push(format!("aktualizr-lite pinned to {}", path));
+                }
+                None => {
+                    details.push(
+                        "aktualizr-lite: no tls_cacert_path, using system CA store".to_string(),
+                    );
+                    ca_store_only.push("aktualizr-lite");
+                }
+            }
+        }
+
+        // Explicit public key/certificate pins in application and client configuration
+        let pin_command = target.probe_command(
+            "certificate_012.app_pins",
+            "grep -rIlE 'pinnedpubkey|pin-sha256|pinned_?(cert|key|pubkey)|public_key_pin|cert_?pin' /etc /usr/lib/sota 2>/dev/null | head -10",
+        );
+        let app_pins = target.execute_command(&pin_command).await?;
+        for file in app_pins
+            .stdout
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+        {
+            details.push(format!("Pin configuration: {}", file.trim()));
+            pins.push(format!("pin in {}", file.trim()));
+        }
+
+        // MQTT bridges to a cloud backend should name their own CA file
+        let mqtt = target
+            .execute_command(
+                "grep -rhE '^[[:space:]]*(address|bridge_cafile|bridge_capath)' /etc/mosquitto 2>/dev/null",
+            )
+            .await?;
+        if mqtt.stdout.contains("address") {
+            match mqtt
+                .stdout
+                .lines()
+                .find(|line| line.trim_start().starts_with("bridge_ca"))
+                .and_then(|line| line.split_whitespace().nth(1))
+            {
+                Some(path) if !is_system_ca_store(path) => {
+                    details.push(format!("MQTT bridge CA: {} (dedicated)", path));
+                    pins.push(format!("MQTT bridge pinned to {}", path));
+                }
+                _ => {
+                    details.push("MQTT bridge: trusts the system CA store".to_string());
+                    ca_store_only.push("MQTT bridge");
+                }
+            }
+        }
+
+        if pins.is_empty() && ca_store_only.is_empty() {
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No management backend client configuration found".to_string(),
+                details: None,
+            });
+        }
+
+        if !ca_store_only.is_empty() {
+            details.push(String::new());
+            details.push(
+                "Trusting every CA in the system store lets a rogue or compromised CA impersonate the backend.\n\
+                 Point the client at a dedicated CA (e.g. tls_cacert_path) or pin the server public key."
+                    .to_string(),
+            );
+            return Ok((
+                TestStatus::Warning,
+                format!(
+                    "Backend connections rely solely on the public CA store: {}",
+                    ca_store_only.join(", ")
+                ),
+                Some(details.join("\n")),
+            ));
+        }
+
+        Ok((
+            TestStatus::Passed,
+            format!("Backend trust is pinned: {}", pins.join("; ")),
+            Some(details.join("\n")),
+        ))
+    }
+}
+
+/// The CA file aktualizr-lite trusts for the device gateway, from its TOML configuration.
+/// Later files override earlier ones, so the last `tls_cacert_path` wins.
+fn sota_trust_anchor(config: &str) -> Option<String> {
+    config
+        .lines()
+        .filter_map(|line| line.trim().split_once('='))
+        .filter(|(key, _)| key.trim() == "tls_cacert_path")
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .rfind(|path| !path.is_empty())
+}
+
+/// Directories holding the distribution-wide CA trust stores
+const SYSTEM_CA_STORE_DIRS: [&str; 3] = ["/etc/ssl/certs", "/etc/pki/tls", "/etc/pki/ca-trust"];
+
+/// Path fragments naming a distribution-wide CA bundle wherever it lives
+const SYSTEM_CA_BUNDLE_NAMES: [&str; 2] = ["ca-certificates", "ca-bundle"];
+
+/// True for the distribution-wide CA bundles that trust every public CA
+fn is_system_ca_store(path: &str) -> bool {
+    SYSTEM_CA_STORE_DIRS.iter().any(|dir| path.starts_with(dir))
+        || SYSTEM_CA_BUNDLE_NAMES
+            .iter()
+            .any(|name| path.contains(name))
+}
+
+/// Shell script printing `<path>|notAfter=<date>` for every certificate on disk,
+/// pruning the system CA trust stores so their roots are never parsed
+fn cert_expiry_scan() -> String {
+    let prune = SYSTEM_CA_STORE_DIRS
+        .iter()
+        .map(|dir| format!("-path {}", dir))
+        .chain(
+            SYSTEM_CA_BUNDLE_NAMES
+                .iter()
+                .map(|name| format!("-path '*{}*'", name)),
+        )
+        .collect::<Vec<_>>()
+        .join(" -o ");
+    format!(
+        "find /etc/ssl /etc/pki /var/sota \\( {} \\) -prune -o -type f \\( -name '*.pem' -o -name '*.crt' -o -name '*.cert' \\) -print 2>/dev/null | while read f; do d=$(openssl x509 -in \"$f\" -enddate -noout 2>/dev/null) && echo \"$f|$d\"; done",
+        prune
+    )
+}
+
+/// Parse a date as printed by `openssl x509 -enddate` / `openssl crl -nextupdate`,
+/// e.g. `Jan  1 00:00:00 2030 GMT`
+fn parse_openssl_date(date: &str) -> Option<NaiveDateTime> {
+    let normalized = date.split_whitespace().collect::<Vec<_>>().join(" ");
+    NaiveDateTime::parse_from_str(&normalized, "%b %d %H:%M:%S %Y GMT").ok()
+}
+
+/// Local CRLs sorted by whether their `nextUpdate` has passed
+#[derive(Debug, Default, PartialEq)]
+struct CrlFreshness {
+    fresh: usize,
+    /// Past `nextUpdate`, so revocations since then are missing
+    expired: Vec<String>,
+    /// No `nextUpdate` could be read, so freshness cannot be told
+    stale: Vec<String>,
+}
+
+impl CrlFreshness {
+    /// Parse `<path> nextUpdate=<date>` lines; the date part is missing or
+    /// `NONE` when the CRL has no next update or could not be read
+    fn parse(output: &str, now: NaiveDateTime) -> Self {
+        let mut crls = Self::default();
+        for line in output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let (path, next_update) = match line.rsplit_once(" nextUpdate=") {
+                Some((path, date)) => (path, parse_openssl_date(date)),
+                None => (line, None),
+            };
+            match next_update {
+                Some(next_update) if next_update > now => crls.fresh += 1,
+                Some(_) => crls.expired.push(path.to_string()),
+                None => crls.stale.push(path.to_string()),
+            }
+        }
+        crls
+    }
+}
+
+impl std::fmt::Display for CrlFreshness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CRLs: {} fresh, {} expired, {} without next update",
+            self.fresh,
+            self.expired.len(),
+            self.stale.len()
+        )
+    }
+}
+
+/// A certificate on the target and how long it has left
+#[derive(Debug, Clone, PartialEq)]
+struct CertExpiry {
+    path: String,
+    /// Negative once the certificate has expired
+    days_remaining: i64,
+}
+
+impl std::fmt::Display for CertExpiry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.days_remaining < 0 {
+            write!(
+                f,
+                "{} (expired {} days ago)",
+                self.path, -self.days_remaining
+            )
+        } else {
+            write!(f, "{} ({} days remaining)", self.path, self.days_remaining)
+        }
+    }
+}
+
+/// Parse `<path>|notAfter=<date>` lines into certificates, soonest expiring first
+fn parse_cert_expiries(output: &str, now: NaiveDateTime) -> Vec<CertExpiry> {
+    let mut expiries: Vec<CertExpiry> = output
+        .lines()
+        .filter_map(|line| {
+            let (path, date) = line.trim().split_once("|notAfter=")?;
+            let not_after = parse_openssl_date(date)?;
+            Some(CertExpiry {
+                path: path.to_string(),
+                days_remaining: (not_after - now).num_days(),
+            })
+        })
+        .collect();
+    expiries.sort_by(|a, b| {
+        a.days_remaining
+            .cmp(&b.days_remaining)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    expiries
+}
+
+/// Split a configured `host:port` TLS endpoint. IPv6 hosts are written in
+/// brackets, e.g. `[fd00::1]:8443`.
+pub fn split_tls_endpoint(endpoint: &str) -> Option<(&str, u16)> {
+    let (host, port) = endpoint.rsplit_once(':')?;
+    let valid_host = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'));
+    if !valid_host || (host.contains(':') && !host.starts_with('[')) {
+        return None;
+    }
+    Some((host, port.parse().ok()?))
+}
+
+/// What `openssl s_client` reported for one endpoint, plus the presented certificate
+#[derive(Debug, Default)]
+struct TlsEndpointProbe {
+    /// The TCP connection was made, whether or not a handshake followed
+    connected: bool,
+    protocol: Option<String>,
+    verify_code: Option<u32>,
+    verify_reason: String,
+    not_after: Option<NaiveDateTime>,
+    subject: Option<String>,
+    issuer: Option<String>,
+}
+
+impl TlsEndpointProbe {
+    fn parse(output: &str) -> Self {
+        let mut probe = Self::default();
+        for line in output.lines().map(str::trim) {
+            if line.starts_with("CONNECTED(") {
+                probe.connected = true;
+            } else if let Some(rest) = line.strip_prefix("Verify return code:") {
+                let rest = rest.trim();
+                let (code, reason) = rest.split_once(' ').unwrap_or((rest, ""));
+                probe.verify_code = code.parse().ok();
+                probe.verify_reason = reason.trim_matches(|c| c == '(' || c == ')').to_string();
+            } else if let Some(rest) = line.strip_prefix("New, ") {
+                // "New, TLSv1.3, Cipher is ..."; "(NONE)" when no handshake completed
+                if let Some(protocol) = rest.split(',').next().filter(|p| p.starts_with("TLS")) {
+                    probe.protocol.get_or_insert_with(|| protocol.to_string());
+                }
+            } else if let Some(date) = line.strip_prefix("notAfter=") {
+                probe.not_after = parse_openssl_date(date);
+            } else if let Some(subject) = line.strip_prefix("subject=") {
+                probe.subject = Some(subject.trim().to_string());
+            } else if let Some(issuer) = line.strip_prefix("issuer=") {
+                probe.issuer = Some(issuer.trim().to_string());
+            }
+        }
+        probe
+    }
+
+    /// Outcome for the endpoint: failed for an expired, untrusted self-signed or
+    /// otherwise unverifiable certificate, a pre-TLS 1.2 protocol, a handshake
+    /// refused on a reachable port or no certificate at all; warning when the
+    /// port could not be reached. A self-signed certificate the target
+    /// deliberately trusts verifies with code 0 and passes.
+    ///
+    /// The protocol is only taken from the `New, TLSv1.x` line: after a failed
+    /// handshake openssl still prints an SSL-Session `Protocol` and a verify
+    /// code of 0, next to `New, (NONE)`.
+    fn assess(&self, now: NaiveDateTime) -> (TestStatus, String) {
+        let Some(protocol) = &self.protocol else {
+            return if self.connected {
+                (
+                    TestStatus::Failed,
+                    "TLS handshake failed on a reachable port (no TLS 1.2+ offered?)".to_string(),
+                )
+            } else {
+                (TestStatus::Warning, "no TLS handshake".to_string())
+            };
+        };
+        if self.subject.is_none() && self.not_after.is_none() {
+            return (
+                TestStatus::Failed,
+                format!("no certificate presented ({})", protocol),
+            );
+        }
+
+        let mut problems = Vec::new();
+        if matches!(protocol.as_str(), "SSLv3" | "TLSv1" | "TLSv1.1") {
+            problems.push(format!("negotiated {} (TLS 1.2+ required)", protocol));
+        }
+        let days_remaining = self.not_after.map(|not_after| (not_after - now).num_days());
+        if days_remaining.is_some_and(|days| days < 0) || self.verify_code == Some(10) {
+            problems.push("certificate expired".to_string());
+        }
+        let self_signed = matches!(self.verify_code, Some(18) | Some(19))
+            || (self.subject.is_some() && self.subject == self.issuer);
+        match self.verify_code {
+            Some(0) => {}
+            _ if self_signed => problems.push("untrusted self-signed certificate".to_string()),
+            Some(10) => {}
+            Some(code) => problems.push(format!(
+                "chain does not verify ({}: {})",
+                code, self.verify_reason
+            )),
+            None => problems.push("chain verification result missing".to_string()),
+        }
+
+        let expiry = days_remaining
+            .map(|days| format!("{} days remaining", days))
+            .unwrap_or_else(|| "expiry unknown".to_string());
+        if problems.is_empty() {
+            (
+                TestStatus::Passed,
+                format!("{}, chain verified, {}", protocol, expiry),
+            )
+        } else {
+            (
+                TestStatus::Failed,
+                format!("{} ({}, {})", problems.join("; "), protocol, expiry),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crl_freshness() {
+        let now = parse_openssl_date("Oct 16 12:00:00 2026 GMT").unwrap();
+        let output = "/etc/ssl/crl/issuing.crl nextUpdate=Nov  1 00:00:00 2026 GMT\n\
+                      /etc/pki/root.crl nextUpdate=Sep 30 00:00:00 2026 GMT\n\
+                      /etc/ssl/crl/corrupt.crl \n\
+                      /etc/ssl/crl/open.crl nextUpdate=NONE\n";
+        let crls = CrlFreshness::parse(output, now);
+        assert_eq!(crls.fresh, 1);
+        assert_eq!(crls.expired, vec!["/etc/pki/root.crl"]);
+        assert_eq!(
+            crls.stale,
+            vec!["/etc/ssl/crl/corrupt.crl", "/etc/ssl/crl/open.crl"]
+        );
+    }
+
+    #[test]
+    fn test_sota_trust_anchor_and_system_store() {
+        let config = "[tls]\nserver = \"https://ota-lite.foundries.io:8443\"\n\n\
+                      [import]\ntls_cacert_path = \"/etc/ssl/certs/ca-certificates.crt\"\n\
+                      [import]\ntls_cacert_path = \"/var/sota/root.crt\"\n";
+
+        let anchor = sota_trust_anchor(config).unwrap();
+        assert_eq!(anchor, "/var/sota/root.crt");
+        assert!(!is_system_ca_store(&anchor));
+        assert!(is_system_ca_store("/etc/ssl/certs/ca-certificates.crt"));
+        assert_eq!(sota_trust_anchor("[tls]\nserver = \"x\"\n"), None);
+    }
+
+    #[test]
+    fn test_cert_expiry_scan_prunes_trust_stores() {
+        let scan = cert_expiry_scan();
+        let (find, _) = scan.split_once(" -prune ").unwrap();
+        for dir in SYSTEM_CA_STORE_DIRS {
+            assert!(find.contains(&format!("-path {}", dir)), "{}", find);
+        }
+        assert!(find.contains("-path '*ca-bundle*'"), "{}", find);
+        assert!(scan.contains("-type f"));
+    }
+
+    #[test]
+    fn test_parse_cert_expiries_sorts_by_days_remaining() {
+        let now = parse_openssl_date("Oct 16 12:00:00 2026 GMT").unwrap();
+        let output = "/etc/ssl/device.pem|notAfter=Dec  1 12:00:00 2026 GMT\n\
+                      /var/sota/client.pem|notAfter=Oct 26 12:00:00 2026 GMT\n\
+                      /etc/ssl/old.pem|notAfter=Oct  1 12:00:00 2026 GMT\n\
+                      /etc/ssl/broken.pem|notAfter=garbage\n";
+
+        let expiries = parse_cert_expiries(output, now);
+        assert_eq!(expiries.len(), 3);
+        assert_eq!(expiries[0].path, "/etc/ssl/old.pem");
+        assert_eq!(expiries[0].days_remaining, -15);
+        assert_eq!(
+            expiries[0].to_string(),
+            "/etc/ssl/old.pem (expired 15 days ago)"
+        );
+        assert_eq!(expiries[1].days_remaining, 10);
+        assert_eq!(expiries[2].days_remaining, 46);
+        assert_eq!(
+            expiries[2].to_string(),
+            "/etc/ssl/device.pem (46 days remaining)"
+        );
+    }
+
+    #[test]
+    fn test_tls_endpoint_probe_assessment() {
+        let now = parse_openssl_date("Oct 16 12:00:00 2026 GMT").unwrap();
+
+        let good = TlsEndpointProbe::parse(
+            "New, TLSv1.3, Cipher is TLS_AES_256_GCM_SHA384\n    Protocol  : TLSv1.3\n    \
+             Verify return code: 0 (ok)\nnotAfter=Jan 14 12:00:00 2027 GMT\n\
+             subject=CN = api.example.com\nissuer=C = US, O = Let's Encrypt, CN = R3\n",
+        );
+        let (status, summary) = good.assess(now);
+        assert_eq!(status, TestStatus::Passed);
+        assert_eq!(summary, "TLSv1.3, chain verified, 90 days remaining");
+
+        let legacy_self_signed = TlsEndpointProbe::parse(
+            "New, TLSv1, Cipher is AES256-SHA\n    Protocol  : TLSv1\n    \
+             Verify return code: 18 (self-signed certificate)\nnotAfter=Oct  1 12:00:00 2026 GMT\n\
+             subject=CN = gateway\nissuer=CN = gateway\n",
+        );
+        let (status, summary) = legacy_self_signed.assess(now);
+        assert_eq!(status, TestStatus::Failed);
+        assert!(summary.contains("negotiated TLSv1 (TLS 1.2+ required)"));
+        assert!(summary.contains("certificate expired"));
+        assert!(summary.contains("untrusted self-signed certificate"));
+
+        let refused = TlsEndpointProbe::parse("connect:errno=111\nNew, (NONE), Cipher is (NONE)\n");
+        assert_eq!(refused.assess(now).0, TestStatus::Warning);
+
+        // A TLS 1.0-only server: the handshake fails yet openssl still prints a
+        // session protocol and a clean verify code
+        let legacy_only = TlsEndpointProbe::parse(
+            "CONNECTED(00000003)
+New, (NONE), Cipher is (NONE)
+                 Protocol  : TLSv1.3
+    Verify return code: 0 (ok)
+",
+        );
+        let (status, summary) = legacy_only.assess(now);
+        assert_eq!(status, TestStatus::Failed);
+        assert!(summary.contains("handshake failed"));
+
+        assert_eq!(
+            split_tls_endpoint("10.0.0.5:8443"),
+            Some(("10.0.0.5", 8443))
+        );
+        assert_eq!(
+            split_tls_endpoint("[fd00::1]:443"),
+            Some(("[fd00::1]", 443))
+        );
+        assert_eq!(split_tls_endpoint("fd00::1"), None);
+        assert_eq!(split_tls_endpoint("host';reboot;':443"), None);
+    }
 }
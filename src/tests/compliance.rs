@@ -1,5 +1,7 @@
 use crate::{
-    error::Result,
+    cli::Severity,
+    error::{Error, Result},
+    runner::shell_quote,
     target::Target,
     tests::{create_test_result, SecurityTest, TestResult, TestStatus},
 };
@@ -13,12 +15,16 @@ pub enum ComplianceTests {
     RedSecurityRequirements,
     IncidentResponse,
     AuditLogging,
+    EgressFiltering,
+    CryptoInventory,
+    AuditRuleCoverage,
 }
 
 #[async_trait]
 impl SecurityTest for ComplianceTests {
     async fn run(&self, target: &mut Target) -> Result<TestResult> {
         let start_time = Instant::now();
+        let mut metadata = Vec::new();
 
         let result = match self {
             Self::CraDataProtection => self.test_cra_data_protection(target).await,
@@ -28,6 +34,9 @@ impl SecurityTest for ComplianceTests {
             Self::RedSecurityRequirements => self.test_red_security_requirements(target).await,
             Self::IncidentResponse => self.test_incident_response(target).await,
             Self::AuditLogging => self.test_audit_logging(target).await,
+            Self::EgressFiltering => self.test_egress_filtering(target).await,
+            Self::CryptoInventory => self.test_crypto_inventory(target, &mut metadata).await,
+            Self::AuditRuleCoverage => self.test_audit_rule_coverage(target).await,
         };
 
         let duration = start_time.elapsed();
@@ -41,7 +50,22 @@ impl SecurityTest for ComplianceTests {
                 &message,
                 details,
                 duration,
-            )),
+            )
+            .with_metadata(metadata)),
+            Err(Error::Skipped {
+                reason,
+                message,
+                details,
+            }) => Ok(create_test_result(
+                self.test_id(),
+                self.test_name(),
+                self.category(),
+                TestStatus::Skipped,
+                &message,
+                details,
+                duration,
+            )
+            .with_skip_reason(reason)),
             Err(e) => Ok(create_test_result(
                 self.test_id(),
                 self.test_name(),
@@ -61,6 +85,9 @@ impl SecurityTest for ComplianceTests {
             Self::RedSecurityRequirements => "compliance_003",
             Self::IncidentResponse => "compliance_004",
             Self::AuditLogging => "compliance_005",
+            Self::EgressFiltering => "compliance_006",
+            Self::CryptoInventory => "compliance_007",
+            Self::AuditRuleCoverage => "compliance_008",
         }
     }
 
@@ -71,6 +98,9 @@ impl SecurityTest for ComplianceTests {
             Self::RedSecurityRequirements => "RED Security Requirements (3.3)",
             Self::IncidentResponse => "Incident Response Capability",
             Self::AuditLogging => "Security Audit Logging",
+            Self::EgressFiltering => "Outbound Traffic Minimization",
+            Self::CryptoInventory => "Cryptographic Algorithm Inventory",
+            Self::AuditRuleCoverage => "Audit Rule Coverage",
         }
     }
 
@@ -85,6 +115,35 @@ impl SecurityTest for ComplianceTests {
             Self::RedSecurityRequirements => "Confirms compliance with UK CE RED (Radio Equipment Directive) Essential Requirements 3.3 for cybersecurity. Validates that radio equipment incorporates appropriate security features to prevent unauthorized access and protect against cyber threats. Required for CE marking of radio equipment in the UK market.",
             Self::IncidentResponse => "Assesses incident response and security event handling capabilities required for regulatory compliance. Checks for proper logging, monitoring, and response mechanisms that enable detection and mitigation of security incidents. Essential for meeting regulatory reporting obligations and maintaining security posture.",
             Self::AuditLogging => "Validates comprehensive security audit logging capabilities required for compliance frameworks. Ensures security events are properly logged, stored, and available for audit purposes. Critical for forensic analysis, compliance reporting, and demonstrating due diligence in security monitoring.",
+            Self::EgressFiltering => "Checks that outbound network traffic is default-deny with an explicit allowlist in the firewall OUTPUT chain or nftables output hook. A device that can connect anywhere outbound is a data-exfiltration and command-and-control risk. Supports the CRA and ETSI EN 303 645 expectation of a minimized attack surface.",
+            Self::CryptoInventory => "Inventories the cryptography enabled on the device across the kernel crypto API, OpenSSL providers and policy, TLS default ciphers, SSH server algorithms and host key sizes. Flags deprecated primitives such as DES, RC4, MD5, SHA-1 (including SHA-1 MAC TLS suites), pre-TLS 1.2 minimums and RSA keys below 2048 bits where OpenSSL, TLS or SSH configuration still allows them; algorithms the kernel merely registers are inventoried but not flagged. The consolidated inventory is recorded in the result metadata as reusable evidence for FIPS, CRA and ETSI EN 303 645 reviews.",
+            Self::AuditRuleCoverage => "Checks what the kernel audit subsystem actually records, not just that auditd runs. Looks for rules watching account and privilege files (/etc/passwd, /etc/shadow, /etc/sudoers), privileged command execution and system time changes, and counts the loaded rules. An empty ruleset leaves no forensic trail for the incident handling CRA expects.",
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            Self::CraDataProtection => &["cra"],
+            Self::CraVulnerabilityManagement => &["cra"],
+            Self::RedSecurityRequirements => &["red", "wireless"],
+            Self::IncidentResponse => &["cra"],
+            Self::AuditLogging => &["cra"],
+            Self::EgressFiltering => &["cra", "red"],
+            Self::CryptoInventory => &["cra", "red"],
+            Self::AuditRuleCoverage => &["cra"],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::CraDataProtection => Severity::High,
+            Self::CraVulnerabilityManagement => Severity::High,
+            Self::RedSecurityRequirements => Severity::High,
+            Self::IncidentResponse => Severity::Medium,
+            Self::AuditLogging => Severity::Medium,
+            Self::EgressFiltering => Severity::Medium,
+            Self::CryptoInventory => Severity::Low,
+            Self::AuditRuleCoverage => Severity::Medium,
         }
     }
 }
@@ -109,13 +168,13 @@ impl ComplianceTests {
         ));
 
         // Check secure communications (TLS)
-        let tls_check = target
-            .execute_command("openssl version 2>/dev/null || echo 'not_available'")
-            .await?;
-        if !tls_check.stdout.contains("not_available") {
+        if target.has_command("openssl").await {
+            let tls_check = target.execute_command("openssl version").await?;
             compliance_items.push("Secure communications (TLS)");
+            details.push(format!("TLS support: {}", tls_check.stdout.trim()));
+        } else {
+            details.push("TLS support: not_available".to_string());
         }
-        details.push(format!("TLS support: {}", tls_check.stdout.trim()));
 
         // Check access controls
         let access_control = target
@@ -185,9 +244,7 @@ impl ComplianceTests {
             .await?;
 
         // Check for vulnerability scanning tools
-        let vuln_tools = target
-            .execute_command("which nmap 2>/dev/null || which lynis 2>/dev/null || echo 'none'")
-            .await?;
+        let vuln_tool = target.first_command(&["nmap", "lynis"]).await;
 
         // Check system update status
         let last_update = target.execute_command("stat -c %Y /var/lib/rpm/rpmdb.sqlite 2>/dev/null || stat -c %Y /var/lib/dpkg/status 2>/dev/null || echo '0'").await?;
@@ -202,7 +259,7 @@ impl ComplianceTests {
             compliance_features.push("OTA service");
         }
 
-        if !vuln_tools.stdout.contains("none") {
+        if vuln_tool.is_some() {
             compliance_features.push("Vulnerability tools");
         }
 
@@ -210,7 +267,7 @@ impl ComplianceTests {
             "Update mechanism: {:?}\nOTA status: {}\nVuln tools: {}\nLast update: {}",
             compliance_features,
             aktualizr_check.stdout.trim(),
-            vuln_tools.stdout.trim(),
+            vuln_tool.unwrap_or("none"),
             last_update.stdout.trim()
         );
 
@@ -252,7 +309,10 @@ impl ComplianceTests {
 
         // Check authentication
         let auth_check = target
-            .execute_command("cat /etc/pam.d/common-auth 2>/dev/null | grep -v '^#' | head -3")
+            .execute_command(&format!(
+                "cat /etc/pam.d/common-auth 2>/dev/null | grep -v '^#'{}",
+                target.evidence_head(3)
+            ))
             .await?;
         if !auth_check.stdout.is_empty() {
             security_measures.push("Authentication");
@@ -319,18 +379,13 @@ impl ComplianceTests {
         let syslog_check = target.execute_command("systemctl is-active rsyslog 2>/dev/null || systemctl is-active syslog-ng 2>/dev/null || echo 'not_active'").await?;
 
         // Check for monitoring tools
-        let monitoring = target
-            .execute_command(
-                "which journalctl 2>/dev/null && echo 'systemd-journal' || echo 'no_journal'",
-            )
-            .await?;
+        let journal_available = target.has_command("journalctl").await;
 
         // Check for network monitoring
         let netmon = target
-            .execute_command(
-                "which tcpdump 2>/dev/null || which netstat 2>/dev/null || echo 'no_netmon'",
-            )
-            .await?;
+            .first_command(&["tcpdump", "netstat"])
+            .await
+            .is_some();
 
         // Check for incident response scripts/tools
         let incident_tools = target.execute_command("find /usr/local/bin /opt -name '*incident*' -o -name '*response*' 2>/dev/null | wc -l").await?;
@@ -341,11 +396,11 @@ impl ComplianceTests {
             capabilities.push("System logging");
         }
 
-        if monitoring.stdout.contains("systemd-journal") {
+        if journal_available {
             capabilities.push("Event monitoring");
         }
 
-        if !netmon.stdout.contains("no_netmon") {
+        if netmon {
             capabilities.push("Network monitoring");
         }
 
@@ -354,30 +409,40 @@ impl ComplianceTests {
             capabilities.push("Incident tools");
         }
 
+        let journal = recent_security_events(target).await?;
+        if journal.persistent {
+            capabilities.push("Persistent logs");
+        }
+
         let details = format!(
-            "Logging: {}\nMonitoring: {}\nNetwork tools: {}\nIncident tools: {}",
+            "Logging: {}\nMonitoring: {}\nNetwork tools: {}\nIncident tools: {}\n{}",
             syslog_check.stdout.trim(),
-            monitoring.stdout.contains("systemd-journal"),
-            !netmon.stdout.contains("no_netmon"),
-            tool_count
+            journal_available,
+            netmon,
+            tool_count,
+            journal.details()
         );
+        let events = match journal.events.total() {
+            0 => String::new(),
+            _ => format!(" ({})", journal.events),
+        };
 
         if capabilities.len() >= 3 {
             Ok((
                 TestStatus::Passed,
-                "Incident response capability good".to_string(),
+                format!("Incident response capability good{}", events),
                 Some(details),
             ))
         } else if capabilities.len() >= 2 {
             Ok((
                 TestStatus::Warning,
-                "Basic incident response capability".to_string(),
+                format!("Basic incident response capability{}", events),
                 Some(details),
             ))
         } else {
             Ok((
                 TestStatus::Failed,
-                "Limited incident response capability".to_string(),
+                format!("Limited incident response capability{}", events),
                 Some(details),
             ))
         }
@@ -405,11 +470,7 @@ impl ComplianceTests {
             .await?;
 
         // Check security-related logs
-        let security_logs = target
-            .execute_command(
-                "journalctl --since='1 hour ago' | grep -i 'security\\|auth\\|fail' | wc -l",
-            )
-            .await?;
+        let journal = recent_security_events(target).await?;
 
         let mut audit_features = Vec::new();
 
@@ -426,17 +487,20 @@ impl ComplianceTests {
             audit_features.push("log rotation");
         }
 
-        let security_events: usize = security_logs.stdout.trim().parse().unwrap_or(0);
-        if security_events > 0 {
+        if journal.events.total() > 0 {
             audit_features.push("security events");
         }
 
+        if journal.persistent {
+            audit_features.push("persistent journal");
+        }
+
         let details = format!(
-            "Audit daemon: {}\nJournal size: {}\nLogrotate configs: {}\nSecurity events (1h): {}",
+            "Audit daemon: {}\nJournal size: {}\nLogrotate configs: {}\n{}",
             auditd.stdout.trim(),
             journal_size.stdout.trim(),
             logrotate_configs,
-            security_events
+            journal.details()
         );
 
         if audit_features.len() >= 3 {
@@ -459,4 +523,732 @@ impl ComplianceTests {
             ))
         }
     }
+
+    async fn test_egress_filtering(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        // CRA/ETSI expect outbound connections to be minimized, not just inbound ones
+        let iptables_command = target.probe_command(
+            "compliance_006.iptables_output",
+            "iptables -S OUTPUT 2>/dev/null",
+        );
+        let mut iptables = target.execute_command(&iptables_command).await?;
+        if iptables.exit_code != 0 {
+            let sudo_command = format!("{} {}", target.elevation_pipe(), iptables_command);
+            iptables = target.execute_command(&sudo_command).await?;
+        }
+
+        let nft_command =
+            target.probe_command("compliance_006.nft_ruleset", "nft list ruleset 2>/dev/null");
+        let mut nft = target.execute_command(&nft_command).await?;
+        if nft.exit_code != 0 {
+            let sudo_command = format!("{} {}", target.elevation_pipe(), nft_command);
+            nft = target.execute_command(&sudo_command).await?;
+        }
+
+        let mut policies = Vec::new();
+        if iptables.exit_code == 0 && !iptables.stdout.trim().is_empty() {
+            policies.push(("iptables", parse_iptables_egress(&iptables.stdout)));
+        }
+        if nft.exit_code == 0 && nft.stdout.contains("hook output") {
+            policies.push(("nftables", parse_nft_egress(&nft.stdout)));
+        }
+
+        if policies.is_empty() {
+            return Ok((
+                TestStatus::Warning,
+                "Unable to read outbound firewall rules".to_string(),
+                Some(format!(
+                    "iptables exit code: {}\nnft exit code: {}",
+                    iptables.exit_code, nft.exit_code
+                )),
+            ));
+        }
+
+        let mut details = Vec::new();
+        for (source, policy) in &policies {
+            details.push(format!(
+                "{} egress: {}",
+                source,
+                if policy.default_deny {
+                    "default-deny"
+                } else {
+                    "allow-all"
+                }
+            ));
+            if policy.default_deny {
+                if policy.allowed.is_empty() {
+                    details.push("  No outbound destinations allowed".to_string());
+                }
+                for destination in &policy.allowed {
+                    details.push(format!("  Allowed: {}", destination));
+                }
+            }
+        }
+
+        if let Some((source, policy)) = policies.iter().find(|(_, policy)| policy.default_deny) {
+            Ok((
+                TestStatus::Passed,
+                format!(
+                    "Egress is default-deny ({}, {} allowed destinations)",
+                    source,
+                    policy.allowed.len()
+                ),
+                Some(details.join("\n")),
+            ))
+        } else {
+            Ok((
+                TestStatus::Warning,
+                "Egress is allow-all - outbound connections are unrestricted".to_string(),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+
+    async fn test_crypto_inventory(
+        &self,
+        target: &mut Target,
+        inventory: &mut Vec<(String, String)>,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let mut details = Vec::new();
+        let mut findings = Vec::new();
+
+        // Kernel crypto API algorithms: inventoried only, since every kernel
+        // registers md5/sha1 for internal users whatever the policy
+        let kernel_crypto = target
+            .execute_command("grep '^name' /proc/crypto 2>/dev/null | awk '{print $3}' | sort -u")
+            .await?;
+        let kernel_algorithms: Vec<&str> = kernel_crypto.stdout.split_whitespace().collect();
+        details.push(format!(
+            "Kernel crypto ({}): {}",
+            kernel_algorithms.len(),
+            kernel_algorithms.join(", ")
+        ));
+        inventory.push(("crypto.kernel".to_string(), kernel_algorithms.join(",")));
+
+        // OpenSSL version, providers and system-wide policy
+        if !target.has_command("openssl").await {
+            details.push("OpenSSL: not_available".to_string());
+        } else {
+            let openssl_version = target.execute_command("openssl version").await?;
+            details.push(format!("OpenSSL: {}", openssl_version.stdout.trim()));
+            inventory.push((
+                "crypto.openssl_version".to_string(),
+                openssl_version.stdout.trim().to_string(),
+            ));
+            let providers = target
+                .execute_command("openssl list -providers 2>/dev/null | grep -i 'name:'")
+                .await?;
+            if !providers.stdout.trim().is_empty() {
+                let providers: Vec<&str> = providers
+                    .stdout
+                    .lines()
+                    .map(|line| line.trim().trim_start_matches("name:").trim())
+                    .collect();
+                details.push(format!("OpenSSL providers: {}", providers.join(", ")));
+                inventory.push(("crypto.openssl_providers".to_string(), providers.join(",")));
+            }
+
+            let policy = target
+                .execute_command("grep -hE '^\\s*(MinProtocol|CipherString|Ciphersuites)' /etc/ssl/openssl.cnf /usr/lib/ssl/openssl.cnf 2>/dev/null")
+                .await?;
+            if !policy.stdout.trim().is_empty() {
+                details.push(format!("OpenSSL policy:\n{}", policy.stdout.trim()));
+                inventory.push((
+                    "crypto.openssl_policy".to_string(),
+                    policy
+                        .stdout
+                        .lines()
+                        .map(str::trim)
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                ));
+                let weak = weak_openssl_policy(&policy.stdout);
+                if !weak.is_empty() {
+                    findings.push(format!("OpenSSL policy: {}", weak.join(", ")));
+                }
+            }
+
+            let tls_ciphers = target
+                .execute_command("openssl ciphers 'DEFAULT' 2>/dev/null")
+                .await?;
+            let ciphers: Vec<&str> = tls_ciphers
+                .stdout
+                .trim()
+                .split(':')
+                .filter(|suite| !suite.is_empty())
+                .collect();
+            details.push(format!("TLS default cipher list: {} suites", ciphers.len()));
+            inventory.push(("crypto.tls_default_ciphers".to_string(), ciphers.join(",")));
+            let weak = weak_tls_suites(&ciphers);
+            if !weak.is_empty() {
+                findings.push(format!("TLS default ciphers: {}", weak.join(", ")));
+            }
+        }
+
+        // Algorithms the SSH server will negotiate
+        let sshd_command =
+            "sshd -T 2>/dev/null | grep -E '^(ciphers|macs|kexalgorithms|hostkeyalgorithms) '";
+        let mut sshd = target.execute_command(sshd_command).await?;
+        if sshd.stdout.trim().is_empty() {
+            let sudo_command = format!("{} sh -c \"{}\"", target.elevation_pipe(), sshd_command);
+            sshd = target.execute_command(&sudo_command).await?;
+        }
+        if sshd.stdout.trim().is_empty() {
+            details.push(
+                "SSH server algorithms: not available (no OpenSSH sshd or no access)".to_string(),
+            );
+        } else {
+            details.push(format!("SSH server algorithms:\n{}", sshd.stdout.trim()));
+            for line in sshd.stdout.lines() {
+                if let Some((setting, algorithms)) = line.trim().split_once(' ') {
+                    inventory.push((format!("crypto.ssh_{}", setting), algorithms.to_string()));
+                }
+            }
+            let weak = deprecated_primitives(&sshd.stdout);
+            if !weak.is_empty() {
+                findings.push(format!("SSH server: {}", weak.join(", ")));
+            }
+        }
+
+        // Host key sizes
+        let host_keys = target
+            .execute_command("for key in /etc/ssh/ssh_host_*_key.pub; do [ -f \"$key\" ] && ssh-keygen -lf \"$key\"; done 2>/dev/null")
+            .await?;
+        let mut host_key_sizes = Vec::new();
+        for line in host_keys.stdout.lines() {
+            let bits: u32 = line
+                .split_whitespace()
+                .next()
+                .and_then(|bits| bits.parse().ok())
+                .unwrap_or(0);
+            details.push(format!("SSH host key: {}", line.trim()));
+            if let Some(key_type) = line.split_whitespace().last() {
+                host_key_sizes.push(format!("{}-{}", key_type.trim_matches(['(', ')']), bits));
+            }
+            if line.contains("(RSA)") && bits < 2048 {
+                findings.push(format!("SSH host key: RSA-{} (below 2048 bits)", bits));
+            }
+        }
+        if !host_key_sizes.is_empty() {
+            inventory.push(("crypto.ssh_host_keys".to_string(), host_key_sizes.join(",")));
+        }
+
+        if !findings.is_empty() {
+            details.push("Deprecated primitives still allowed by configuration:".to_string());
+            details.extend(findings.iter().map(|finding| format!("  • {}", finding)));
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "Deprecated cryptography allowed in {} place(s)",
+                    findings.len()
+                ),
+                Some(details.join("\n")),
+            ))
+        } else {
+            Ok((
+                TestStatus::Passed,
+                "No deprecated cryptographic primitives allowed by TLS, SSH or OpenSSL policy"
+                    .to_string(),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+
+    async fn test_audit_rule_coverage(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        if !target.has_command("auditctl").await {
+            return Ok((
+                TestStatus::Failed,
+                "No audit rules: auditctl is not installed".to_string(),
+                Some("Install auditd and load a ruleset from /etc/audit/rules.d".to_string()),
+            ));
+        }
+
+        let list_command = target.probe_command("compliance_008.rules", "auditctl -l 2>&1");
+        let mut rules = target.execute_command(&list_command).await?;
+        if rules.exit_code != 0 {
+            let sudo_command = format!("{} {}", target.elevation_pipe(), list_command);
+            rules = target.execute_command(&sudo_command).await?;
+        }
+        if rules.exit_code != 0 {
+            return Ok((
+                TestStatus::Warning,
+                "Unable to list audit rules (auditctl needs root)".to_string(),
+                Some(rules.stdout),
+            ));
+        }
+
+        let active: Vec<&str> = rules
+            .stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('-'))
+            .collect();
+        if active.is_empty() {
+            return Ok((
+                TestStatus::Failed,
+                "auditd has no rules loaded".to_string(),
+                Some(rules.stdout),
+            ));
+        }
+
+        let missing = missing_audit_watches(&active);
+        let min_rules = target.context().tests.min_audit_rules;
+        let mut details = vec![format!(
+            "Active audit rules: {} (minimum {})",
+            active.len(),
+            min_rules
+        )];
+        for (watch, _) in RECOMMENDED_AUDIT_WATCHES {
+            let mark = if missing.contains(watch) {
+                "⚠️"
+            } else {
+                "✅"
+            };
+            details.push(format!("{} {}", mark, watch));
+        }
+
+        if active.len() < min_rules {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "Only {} audit rules loaded (expected at least {})",
+                    active.len(),
+                    min_rules
+                ),
+                Some(details.join("\n")),
+            ))
+        } else if !missing.is_empty() {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "{} audit rules, missing watches: {}",
+                    active.len(),
+                    missing.join(", ")
+                ),
+                Some(details.join("\n")),
+            ))
+        } else {
+            Ok((
+                TestStatus::Passed,
+                format!("{} audit rules cover all recommended watches", active.len()),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+}
+
+/// Security events found in the journal within the `tests.log_since` window
+#[derive(Debug, Default, PartialEq)]
+struct SecurityEvents {
+    failed_sudo: usize,
+    ssh_auth_failures: usize,
+    selinux_denials: usize,
+}
+
+impl SecurityEvents {
+    fn total(&self) -> usize {
+        self.failed_sudo + self.ssh_auth_failures + self.selinux_denials
+    }
+}
+
+impl std::fmt::Display for SecurityEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} failed sudo, {} SSH auth failures, {} SELinux denials",
+            self.failed_sudo, self.ssh_auth_failures, self.selinux_denials
+        )
+    }
+}
+
+/// Recent security events and how the journal keeping them is stored
+struct JournalEvidence {
+    events: SecurityEvents,
+    since: String,
+    persistent: bool,
+    privileged: bool,
+}
+
+impl JournalEvidence {
+    fn details(&self) -> String {
+        format!(
+            "Security events since '{}': {}\nJournal: {}{}",
+            self.since,
+            self.events,
+            if self.persistent {
+                "persistent (/var/log/journal)"
+            } else {
+                "volatile, lost on reboot"
+            },
+            if self.privileged {
+                ""
+            } else {
+                " (read without root, other users' events not counted)"
+            }
+        )
+    }
+}
+
+/// Count failed sudo attempts, SSH authentication failures and SELinux denials
+/// in journal output
+fn parse_security_events(journal: &str) -> SecurityEvents {
+    let mut events = SecurityEvents::default();
+    // sudo logs both a PAM failure per attempt and a summary of incorrect
+    // attempts; either may be missing depending on the PAM setup
+    let mut sudo_pam = 0;
+    let mut sudo_summary = 0;
+    for line in journal.lines() {
+        if line.contains("sudo:auth): authentication failure") {
+            sudo_pam += 1;
+        } else if line.contains("sudo") && line.contains("incorrect password attempt") {
+            sudo_summary += incorrect_attempts(line);
+        } else if line.contains("sshd")
+            && (line.contains("Invalid user ")
+                || (line.contains("Failed ") && !line.contains("for invalid user")))
+        {
+            events.ssh_auth_failures += 1;
+        } else if line.contains("avc:") && line.contains("denied") {
+            events.selinux_denials += 1;
+        }
+    }
+    events.failed_sudo = sudo_pam.max(sudo_summary);
+    events
+}
+
+/// Attempts counted by a sudo `N incorrect password attempts` summary line
+fn incorrect_attempts(line: &str) -> usize {
+    line.split(" incorrect password attempt")
+        .next()
+        .and_then(|head| head.split_whitespace().last())
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(1)
+}
+
+/// Read recent security events from the journal, as root where possible since
+/// an unprivileged user only sees their own journal. Events are counted up to
+/// the start of the run so the run's own sudo password probing is left out,
+/// and the journal is read once for every test that reports on it.
+async fn recent_security_events(target: &mut Target) -> Result<JournalEvidence> {
+    let since = target.context().tests.log_since.clone();
+    let until = match target.run_started() {
+        Some(started) => format!(" --until {}", shell_quote(started)),
+        None => String::new(),
+    };
+    let script = format!(
+        "journalctl --since {}{} --no-pager -q 2>/dev/null | grep -E 'sudo|sshd|avc:' || true",
+        shell_quote(&since),
+        until
+    );
+    let quoted = shell_quote(&script);
+
+    let privileged = target
+        .execute_command_shared(&format!("{} sh -c {}", target.elevation_pipe(), quoted))
+        .await?;
+    let as_root = privileged.exit_code == 0;
+    let journal = if as_root {
+        privileged
+    } else {
+        target
+            .execute_command_shared(&format!("sh -c {}", quoted))
+            .await?
+    };
+
+    let storage = target
+        .execute_command_shared("test -d /var/log/journal && echo persistent || echo volatile")
+        .await?;
+
+    Ok(JournalEvidence {
+        events: parse_security_events(&journal.stdout),
+        since,
+        persistent: storage.stdout.trim() == "persistent",
+        privileged: as_root,
+    })
+}
+
+/// Outbound firewall posture parsed from a ruleset
+#[derive(Debug, Default, PartialEq)]
+struct EgressPolicy {
+    default_deny: bool,
+    allowed: Vec<String>,
+}
+
+/// Parse `iptables -S OUTPUT` output into an egress policy
+fn parse_iptables_egress(rules: &str) -> EgressPolicy {
+    let mut policy = EgressPolicy::default();
+
+    for line in rules.lines().map(str::trim) {
+        if let Some(target) = line.strip_prefix("-P OUTPUT ") {
+            policy.default_deny |= matches!(target.trim(), "DROP" | "REJECT");
+        } else if let Some(rule) = line.strip_prefix("-A OUTPUT ") {
+            let rule = rule.trim();
+            if rule == "-j DROP" || rule == "-j REJECT" {
+                // Catch-all drop at the end of the chain
+                policy.default_deny = true;
+            } else if let Some(matches) = rule.strip_suffix("-j ACCEPT") {
+                let matches = matches.trim();
+                policy.allowed.push(if matches.is_empty() {
+                    "any".to_string()
+                } else {
+                    matches.to_string()
+                });
+            }
+        }
+    }
+
+    if policy.allowed.iter().any(|rule| rule == "any") {
+        policy.default_deny = false;
+    }
+    policy
+}
+
+/// Parse `nft list ruleset` output into an egress policy, looking only at output-hook chains
+fn parse_nft_egress(ruleset: &str) -> EgressPolicy {
+    let mut policy = EgressPolicy::default();
+    let mut in_output_chain = false;
+    let mut depth = 0usize;
+    let mut chain_depth = 0usize;
+
+    for line in ruleset.lines().map(str::trim) {
+        if line.starts_with("chain ") && line.ends_with('{') {
+            chain_depth = depth + 1;
+        }
+        depth += line.matches('{').count();
+        depth = depth.saturating_sub(line.matches('}').count());
+
+        if depth < chain_depth {
+            in_output_chain = false;
+            chain_depth = 0;
+            continue;
+        }
+
+        if line.contains("hook output") {
+            in_output_chain = true;
+            policy.default_deny |= line.contains("policy drop");
+            continue;
+        }
+
+        if in_output_chain {
+            if line == "drop" || line == "reject" {
+                policy.default_deny = true;
+            } else if let Some(matches) = line.strip_suffix("accept") {
+                let matches = matches.trim();
+                policy.allowed.push(if matches.is_empty() {
+                    "any".to_string()
+                } else {
+                    matches.to_string()
+                });
+            }
+        }
+    }
+
+    if policy.allowed.iter().any(|rule| rule == "any") {
+        policy.default_deny = false;
+    }
+    policy
+}
+
+/// Deprecated primitives of a TLS cipher list: those named in a suite plus
+/// SHA-1 for suites authenticated with an HMAC-SHA1 (`...-SHA`)
+fn weak_tls_suites(suites: &[&str]) -> Vec<&'static str> {
+    let mut found = deprecated_primitives(&suites.join(":"));
+    if suites.iter().any(|suite| suite.ends_with("-SHA")) && !found.contains(&"SHA-1") {
+        found.push("SHA-1");
+    }
+    found
+}
+
+/// Weaknesses in OpenSSL's configured `MinProtocol`/`CipherString`/`Ciphersuites`
+fn weak_openssl_policy(policy: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    for line in policy.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "MinProtocol" if matches!(value, "SSLv3" | "TLSv1" | "TLSv1.1") => {
+                found.push(format!("MinProtocol {}", value));
+            }
+            "CipherString" | "Ciphersuites" => {
+                // `!MD5` and the like exclude a primitive rather than enable it
+                let enabled: Vec<&str> = value
+                    .split([':', ',', ' '])
+                    .filter(|part| !part.starts_with(['!', '-']))
+                    .collect();
+                for primitive in weak_tls_suites(&enabled) {
+                    if !found.iter().any(|known| known == primitive) {
+                        found.push(primitive.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Deprecated primitives named anywhere in an algorithm listing, e.g. `des3_ede`,
+/// `ecb(arc4)` or `hmac-sha1`
+fn deprecated_primitives(listing: &str) -> Vec<&'static str> {
+    let mut found = Vec::new();
+    for part in listing
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .map(str::to_ascii_lowercase)
+    {
+        let primitive = match part.as_str() {
+            "des" | "3des" | "des3" | "tdes" | "desx" => "DES",
+            "rc4" | "arc4" => "RC4",
+            "md4" | "md5" => "MD5",
+            "sha1" => "SHA-1",
+            _ => continue,
+        };
+        if !found.contains(&primitive) {
+            found.push(primitive);
+        }
+    }
+    found
+}
+
+/// Audit coverage expected on a production device, with the rule fragments that
+/// provide it in `auditctl -l` output
+const RECOMMENDED_AUDIT_WATCHES: &[(&str, &[&str])] = &[
+    ("/etc/passwd", &["/etc/passwd"]),
+    ("/etc/shadow", &["/etc/shadow"]),
+    ("/etc/sudoers", &["/etc/sudoers"]),
+    ("privileged commands", &["perm=x", "euid=0"]),
+    (
+        "time changes",
+        &[
+            "adjtimex",
+            "settimeofday",
+            "clock_settime",
+            "/etc/localtime",
+        ],
+    ),
+];
+
+/// Recommended watches that no loaded audit rule provides
+fn missing_audit_watches(rules: &[&str]) -> Vec<&'static str> {
+    RECOMMENDED_AUDIT_WATCHES
+        .iter()
+        .filter(|(_, fragments)| {
+            !rules
+                .iter()
+                .any(|rule| fragments.iter().any(|fragment| rule.contains(fragment)))
+        })
+        .map(|(watch, _)| *watch)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_egress_policy_parsing() {
+        let iptables = "-P OUTPUT DROP\n-A OUTPUT -o lo -j ACCEPT\n-A OUTPUT -d 10.0.0.0/8 -p tcp -m tcp --dport 443 -j ACCEPT\n";
+        let policy = parse_iptables_egress(iptables);
+        assert!(policy.default_deny);
+        assert_eq!(
+            policy.allowed,
+            vec!["-o lo", "-d 10.0.0.0/8 -p tcp -m tcp --dport 443"]
+        );
+
+        assert!(!parse_iptables_egress("-P OUTPUT ACCEPT\n").default_deny);
+
+        let nft = "table inet filter {\n\tchain input {\n\t\ttype filter hook input priority filter; policy drop;\n\t}\n\tchain output {\n\t\ttype filter hook output priority filter; policy drop;\n\t\toifname \"lo\" accept\n\t\tip daddr 192.168.1.10 tcp dport 8883 accept\n\t}\n}\n";
+        let policy = parse_nft_egress(nft);
+        assert!(policy.default_deny);
+        assert_eq!(
+            policy.allowed,
+            vec!["oifname \"lo\"", "ip daddr 192.168.1.10 tcp dport 8883"]
+        );
+    }
+
+    #[test]
+    fn test_security_event_counting() {
+        let journal = "\
+Oct 16 09:12:01 imx93 sudo[812]: pam_unix(sudo:auth): authentication failure; logname= uid=1000 euid=0 tty=/dev/pts/0 ruser=fio rhost=  user=fio
+Oct 16 09:12:09 imx93 sudo[812]: pam_unix(sudo:auth): authentication failure; logname= uid=1000 euid=0 tty=/dev/pts/0 ruser=fio rhost=  user=fio
+Oct 16 09:12:12 imx93 sudo[812]:      fio : 2 incorrect password attempts ; TTY=pts/0 ; PWD=/home/fio ; USER=root ; COMMAND=/bin/sh
+Oct 16 09:12:30 imx93 sudo[820]:      fio : TTY=pts/0 ; PWD=/home/fio ; USER=root ; COMMAND=/bin/ls
+Oct 16 10:01:44 imx93 sshd[901]: Invalid user admin from 192.168.1.50 port 51234
+Oct 16 10:01:46 imx93 sshd[901]: Failed password for invalid user admin from 192.168.1.50 port 51234 ssh2
+Oct 16 10:02:03 imx93 sshd[905]: Failed password for fio from 192.168.1.50 port 51240 ssh2
+Oct 16 10:02:09 imx93 sshd[905]: Accepted password for fio from 192.168.1.50 port 51240 ssh2
+Oct 16 11:30:00 imx93 kernel: audit: type=1400 audit(1760614200.123:42): avc:  denied  { read } for  pid=1002 comm=\"aktualizr\" name=\"sota\"
+";
+        let events = parse_security_events(journal);
+        assert_eq!(
+            events,
+            SecurityEvents {
+                failed_sudo: 2,
+                ssh_auth_failures: 2,
+                selinux_denials: 1,
+            }
+        );
+        assert_eq!(events.total(), 5);
+
+        // Without pam_unix only the summary line records the failure
+        let summary =
+            "Oct 16 09:12:12 imx93 sudo[812]:      fio : 1 incorrect password attempt ; TTY=pts/0";
+        assert_eq!(parse_security_events(summary).failed_sudo, 1);
+
+        // The summary line counts every attempt of one sudo invocation
+        let summary = "Oct 16 09:12:12 imx93 sudo[812]:      fio : 3 incorrect password attempts ; TTY=pts/0\n\
+Oct 16 09:20:40 imx93 sudo[830]:      fio : 1 incorrect password attempt ; TTY=pts/0";
+        assert_eq!(parse_security_events(summary).failed_sudo, 4);
+    }
+
+    #[test]
+    fn test_deprecated_primitives() {
+        assert_eq!(
+            deprecated_primitives("des3_ede\necb(arc4)\nhmac(sha256)\nmd5"),
+            vec!["DES", "RC4", "MD5"]
+        );
+        assert_eq!(
+            deprecated_primitives("macs hmac-sha1-etm@openssh.com,umac-128@openssh.com"),
+            vec!["SHA-1"]
+        );
+        assert!(deprecated_primitives(
+            "ciphers chacha20-poly1305@openssh.com,aes256-gcm@openssh.com"
+        )
+        .is_empty());
+
+        assert_eq!(
+            weak_tls_suites(&["TLS_AES_256_GCM_SHA384", "ECDHE-RSA-AES128-SHA"]),
+            vec!["SHA-1"]
+        );
+        assert!(weak_tls_suites(&["ECDHE-RSA-AES256-GCM-SHA384"]).is_empty());
+        assert_eq!(
+            weak_openssl_policy("MinProtocol = TLSv1\nCipherString = DEFAULT:!MD5:!RC4\n"),
+            vec!["MinProtocol TLSv1"]
+        );
+        assert_eq!(
+            weak_openssl_policy("CipherString = DEFAULT:RC4-SHA\n"),
+            vec!["RC4", "SHA-1"]
+        );
+    }
+
+    #[test]
+    fn test_missing_audit_watches() {
+        let rules = [
+            "-w /etc/passwd -p wa -k identity",
+            "-w /etc/sudoers -p wa -k scope",
+            "-a always,exit -F arch=b64 -S adjtimex,settimeofday -F key=time-change",
+        ];
+        assert_eq!(
+            missing_audit_watches(&rules),
+            vec!["/etc/shadow", "privileged commands"]
+        );
+        assert_eq!(
+            missing_audit_watches(&[]).len(),
+            RECOMMENDED_AUDIT_WATCHES.len()
+        );
+    }
 }
@@ -1,7 +1,8 @@
 use crate::{
-    error::Result,
+    cli::{Severity, TestMode},
+    error::{Error, Result},
     target::Target,
-    tests::{create_test_result, SecurityTest, TestResult, TestStatus},
+    tests::{create_test_result, SecurityTest, SkipReason, TestResult, TestStatus},
 };
 use async_trait::async_trait;
 use std::time::Instant;
@@ -15,6 +16,7 @@ pub enum ContainerSecurityTests {
     UserNamespaces,
     SelinuxContexts,
     SeccompProfiles,
+    PrivilegedContainers,
 }
 
 #[async_trait]
@@ -30,6 +32,7 @@ impl SecurityTest for ContainerSecurityTests {
             Self::UserNamespaces => self.test_user_namespaces(target).await,
             Self::SelinuxContexts => self.test_selinux_contexts(target).await,
             Self::SeccompProfiles => self.test_seccomp_profiles(target).await,
+            Self::PrivilegedContainers => self.test_privileged_containers(target).await,
         };
 
         let duration = start_time.elapsed();
@@ -44,6 +47,20 @@ impl SecurityTest for ContainerSecurityTests {
                 details,
                 duration,
             )),
+            Err(Error::Skipped {
+                reason,
+                message,
+                details,
+            }) => Ok(create_test_result(
+                self.test_id(),
+                self.test_name(),
+                self.category(),
+                TestStatus::Skipped,
+                &message,
+                details,
+                duration,
+            )
+            .with_skip_reason(reason)),
             Err(e) => Ok(create_test_result(
                 self.test_id(),
                 self.test_name(),
@@ -65,6 +82,7 @@ impl SecurityTest for ContainerSecurityTests {
             Self::UserNamespaces => "container_005",
             Self::SelinuxContexts => "container_006",
             Self::SeccompProfiles => "container_007",
+            Self::PrivilegedContainers => "container_008",
         }
     }
 
@@ -77,6 +95,7 @@ impl SecurityTest for ContainerSecurityTests {
             Self::UserNamespaces => "User Namespaces and Capabilities",
             Self::SelinuxContexts => "SELinux Container Contexts",
             Self::SeccompProfiles => "Seccomp Security Profiles",
+            Self::PrivilegedContainers => "Privileged Containers",
         }
     }
 
@@ -93,6 +112,33 @@ impl SecurityTest for ContainerSecurityTests {
             Self::UserNamespaces => "Verifies user namespace isolation is properly configured to map container users to unprivileged host users. Prevents containers from running as root on the host system. Essential security feature for reducing the impact of container breakout vulnerabilities.",
             Self::SelinuxContexts => "Checks SELinux mandatory access control contexts for containers to enforce fine-grained security policies. Validates that containers run with appropriate SELinux labels and restrictions. Important for defense-in-depth security and containing potential breaches.",
             Self::SeccompProfiles => "Validates seccomp (secure computing) profiles that restrict system calls available to containers. Reduces attack surface by blocking potentially dangerous system calls. Critical for preventing privilege escalation and system compromise through container exploits.",
+            Self::PrivilegedContainers => "Detects running containers started with --privileged, dangerous added capabilities such as CAP_SYS_ADMIN or CAP_NET_ADMIN, or the host PID or network namespace. Any of these lets a compromised container take over the host, so they must not be present on production devices.",
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            Self::DockerSecurityConfig => &["cra"],
+            Self::ContainerImageSecurity => &["cra"],
+            Self::RuntimeSecurity => &["cra"],
+            Self::NetworkIsolation => &["cra"],
+            Self::UserNamespaces => &["cra"],
+            Self::SelinuxContexts => &["cra"],
+            Self::SeccompProfiles => &["cra"],
+            Self::PrivilegedContainers => &["cra"],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::DockerSecurityConfig => Severity::High,
+            Self::ContainerImageSecurity => Severity::Medium,
+            Self::RuntimeSecurity => Severity::High,
+            Self::NetworkIsolation => Severity::Medium,
+            Self::UserNamespaces => Severity::Medium,
+            Self::SelinuxContexts => Severity::Low,
+            Self::SeccompProfiles => Severity::Medium,
+            Self::PrivilegedContainers => Severity::Critical,
         }
     }
 }
@@ -103,13 +149,7 @@ impl ContainerSecurityTests {
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
         // Check if Docker/Podman is installed
-        let docker_check = target
-            .execute_command(
-                "which docker 2>/dev/null || which podman 2>/dev/null || echo 'not_found'",
-            )
-            .await?;
-
-        if docker_check.stdout.contains("not_found") {
+        if target.first_command(&["docker", "podman"]).await.is_none() {
             return Ok((
                 TestStatus::Failed,
                 "No container runtime detected - install Docker or Podman".to_string(),
@@ -173,7 +213,7 @@ impl ContainerSecurityTests {
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
         // Check for image scanning tools
-        let scanning_tools = target.execute_command("which trivy 2>/dev/null || which clair 2>/dev/null || which grype 2>/dev/null || echo 'no_scanners'").await?;
+        let scanner = target.first_command(&["trivy", "clair", "grype"]).await;
 
         // Check running containers
         let running_containers = target.execute_command("docker ps --format 'table {{.Names}}\t{{.Image}}' 2>/dev/null || podman ps --format 'table {{.Names}}\t{{.Image}}' 2>/dev/null || echo 'no_containers'").await?;
@@ -183,7 +223,7 @@ impl ContainerSecurityTests {
 
         let mut security_indicators = Vec::new();
 
-        if !scanning_tools.stdout.contains("no_scanners") {
+        if scanner.is_some() {
             security_indicators.push("Image scanning tools available");
         }
         if !base_images.stdout.contains("no_secure_bases") {
@@ -195,7 +235,7 @@ impl ContainerSecurityTests {
 
         let details = format!(
             "Scanners: {}\nContainers: {}\nBase images: {}",
-            scanning_tools.stdout.trim(),
+            scanner.unwrap_or("no_scanners"),
             running_containers.stdout,
             base_images.stdout
         );
@@ -391,11 +431,11 @@ impl ContainerSecurityTests {
         if selinux_status.stdout.contains("no_selinux")
             || selinux_status.stdout.trim() == "Disabled"
         {
-            return Ok((
-                TestStatus::Skipped,
-                "SELinux not available or disabled".to_string(),
-                None,
-            ));
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "SELinux not available or disabled".to_string(),
+                details: None,
+            });
         }
 
         // Check container SELinux contexts
@@ -506,4 +546,160 @@ impl ContainerSecurityTests {
             ))
         }
     }
+
+    async fn test_privileged_containers(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let runtime = if target.has_command("docker").await {
+            "docker"
+        } else if target.has_command("podman").await {
+            "podman"
+        } else {
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No container runtime installed".to_string(),
+                details: None,
+            });
+        };
+
+        // One line per running container: name|privileged|added caps|pid mode|network mode
+        let inspect_command = target.probe_command(
+            "container_008.inspect",
+            &format!(
+                "{0} ps --quiet | xargs -r {0} inspect --format '{{{{.Name}}}}|{{{{.HostConfig.Privileged}}}}|{{{{join .HostConfig.CapAdd \",\"}}}}|{{{{.HostConfig.PidMode}}}}|{{{{.HostConfig.NetworkMode}}}}'",
+                runtime
+            ),
+        );
+        let mut inspect = target.execute_command(&inspect_command).await?;
+        if inspect.exit_code != 0 {
+            let sudo_command = format!(
+                "{} sh -c \"{}\"",
+                target.elevation_pipe(),
+                inspect_command.replace('"', "\\\"")
+            );
+            inspect = target.execute_command(&sudo_command).await?;
+        }
+
+        if inspect.exit_code != 0 {
+            return Ok((
+                TestStatus::Warning,
+                format!("Unable to inspect running {} containers", runtime),
+                Some(inspect.stderr),
+            ));
+        }
+
+        let containers: Vec<&str> = inspect
+            .stdout
+            .lines()
+            .filter(|line| line.contains('|'))
+            .collect();
+        if containers.is_empty() {
+            return Ok((
+                TestStatus::Passed,
+                "No running containers".to_string(),
+                None,
+            ));
+        }
+
+        let mut details = Vec::new();
+        let mut flagged = 0;
+        for line in &containers {
+            let (name, findings) = privileged_container_findings(line);
+            if findings.is_empty() {
+                details.push(format!("✅ {}: unprivileged", name));
+            } else {
+                flagged += 1;
+                details.push(format!("❌ {}: {}", name, findings.join(", ")));
+            }
+        }
+
+        if flagged == 0 {
+            Ok((
+                TestStatus::Passed,
+                format!("{} running containers, none privileged", containers.len()),
+                Some(details.join("\n")),
+            ))
+        } else {
+            let status = if matches!(target.context().mode, TestMode::Production) {
+                TestStatus::Failed
+            } else {
+                TestStatus::Warning
+            };
+            Ok((
+                status,
+                format!(
+                    "{} of {} running containers are privileged or share host namespaces",
+                    flagged,
+                    containers.len()
+                ),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+}
+
+/// Capabilities that give a container control over the host or its network
+const DANGEROUS_CAPABILITIES: &[&str] =
+    &["ALL", "SYS_ADMIN", "NET_ADMIN", "SYS_MODULE", "SYS_PTRACE"];
+
+/// Container name and the privilege problems in one line of the container_008
+/// inspect output (`name|privileged|cap,cap|pid mode|network mode`)
+fn privileged_container_findings(line: &str) -> (String, Vec<String>) {
+    let fields: Vec<&str> = line.trim().split('|').collect();
+    let field = |i: usize| fields.get(i).map(|f| f.trim()).unwrap_or("");
+    let name = field(0).trim_start_matches('/').to_string();
+
+    let mut findings = Vec::new();
+    if field(1) == "true" {
+        findings.push("privileged".to_string());
+    }
+    for cap in field(2)
+        .trim_matches(|c| c == '[' || c == ']')
+        .split([',', ' '])
+        .map(|cap| cap.trim().to_uppercase())
+        .filter(|cap| !cap.is_empty())
+    {
+        let bare = cap.trim_start_matches("CAP_");
+        if DANGEROUS_CAPABILITIES.contains(&bare) {
+            findings.push(format!("CAP_{}", bare));
+        }
+    }
+    if field(3) == "host" {
+        findings.push("host PID namespace".to_string());
+    }
+    if field(4) == "host" {
+        findings.push("host network namespace".to_string());
+    }
+
+    (name, findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privileged_container_findings() {
+        let (name, findings) = privileged_container_findings(
+            "/agent|true|[CAP_NET_ADMIN SYS_ADMIN CHOWN]|host|bridge",
+        );
+        assert_eq!(name, "agent");
+        assert_eq!(
+            findings,
+            vec![
+                "privileged",
+                "CAP_NET_ADMIN",
+                "CAP_SYS_ADMIN",
+                "host PID namespace"
+            ]
+        );
+
+        let (name, findings) = privileged_container_findings("/web|false|||default");
+        assert_eq!(name, "web");
+        assert!(findings.is_empty());
+
+        let (_, findings) = privileged_container_findings("/net|false|NET_RAW|private|host");
+        assert_eq!(findings, vec!["host network namespace"]);
+    }
 }
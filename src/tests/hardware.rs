@@ -1,7 +1,8 @@
 use crate::{
-    error::Result,
+    cli::{MachineType, Severity, IMX_MACHINES},
+    error::{Error, Result},
     target::Target,
-    tests::{create_test_result, SecurityTest, TestResult, TestStatus},
+    tests::{create_test_result, SecurityTest, SkipReason, TestResult, TestStatus},
 };
 use async_trait::async_trait;
 use std::time::Instant;
@@ -15,6 +16,9 @@ pub enum HardwareSecurityTests {
     RandomNumberGenerator,
     Pcf2131Rtc,
     UsbSecurity,
+    FaultInjectionProtection,
+    IommuDmaProtection,
+    Tpm2Status,
 }
 
 #[async_trait]
@@ -30,6 +34,9 @@ impl SecurityTest for HardwareSecurityTests {
             Self::RandomNumberGenerator => self.test_random_number_generator(target).await,
             Self::Pcf2131Rtc => self.test_pcf2131_rtc(target).await,
             Self::UsbSecurity => self.test_usb_security(target).await,
+            Self::FaultInjectionProtection => self.test_fault_injection_protection(target).await,
+            Self::IommuDmaProtection => self.test_iommu_dma_protection(target).await,
+            Self::Tpm2Status => self.test_tpm2_status(target).await,
         };
 
         let duration = start_time.elapsed();
@@ -44,6 +51,20 @@ impl SecurityTest for HardwareSecurityTests {
                 details,
                 duration,
             )),
+            Err(Error::Skipped {
+                reason,
+                message,
+                details,
+            }) => Ok(create_test_result(
+                self.test_id(),
+                self.test_name(),
+                self.category(),
+                TestStatus::Skipped,
+                &message,
+                details,
+                duration,
+            )
+            .with_skip_reason(reason)),
             Err(e) => Ok(create_test_result(
                 self.test_id(),
                 self.test_name(),
@@ -65,6 +86,9 @@ impl SecurityTest for HardwareSecurityTests {
             Self::RandomNumberGenerator => "hardware_005",
             Self::Pcf2131Rtc => "hardware_006",
             Self::UsbSecurity => "hardware_007",
+            Self::FaultInjectionProtection => "hardware_008",
+            Self::IommuDmaProtection => "hardware_009",
+            Self::Tpm2Status => "hardware_010",
         }
     }
 
@@ -77,6 +101,9 @@ impl SecurityTest for HardwareSecurityTests {
             Self::RandomNumberGenerator => "Hardware RNG",
             Self::Pcf2131Rtc => "PCF2131 Real-Time Clock",
             Self::UsbSecurity => "USB Security Configuration",
+            Self::FaultInjectionProtection => "Fault Injection Protection",
+            Self::IommuDmaProtection => "DMA Protection (IOMMU)",
+            Self::Tpm2Status => "TPM 2.0 Status",
         }
     }
 
@@ -93,6 +120,47 @@ impl SecurityTest for HardwareSecurityTests {
             Self::RandomNumberGenerator => "Ensures the hardware random number generator (TRNG - True Random Number Generator) is functional and providing sufficient entropy. Critical for cryptographic key generation, secure communications, and preventing predictable security vulnerabilities.",
             Self::Pcf2131Rtc => "Validates the PCF2131 Real-Time Clock functionality on i.MX93 E-Ink platforms. The RTC provides accurate timekeeping for security events, certificate validation, and time-based security policies. Critical for maintaining security audit trails and time-sensitive cryptographic operations.",
             Self::UsbSecurity => "Evaluates USB security configuration including host/device mode validation, USB port restrictions, and device enumeration controls. Checks for proper USB security policies to prevent unauthorized device connections and data exfiltration. Essential for preventing BadUSB attacks and maintaining USB interface security.",
+            Self::FaultInjectionProtection => "Reports whether the SoC or secure element exposes glitch, fault-injection or brown-out protection through regulator monitors, tamper blocks, firmware flags or kernel messages. This is advisory and platform-specific, so the test is skipped when nothing is exposed. Gives auditors in regulated markets a recorded answer on fault-injection resistance.",
+            Self::IommuDmaProtection => "Checks whether an IOMMU/SMMU is enabled to confine DMA from peripherals, using the registered IOMMUs, IOMMU groups, kernel command line and boot messages. Without it, a malicious device on an exposed USB, PCIe or Thunderbolt port can read or overwrite system memory directly. Reports a warning when the hardware has an IOMMU that is disabled or bypassed, and is skipped on SoCs without one.",
+            Self::Tpm2Status => "Detects a discrete or firmware TPM on platforms without an i.MX secure enclave. Checks the TPM device nodes, reads the TPM family and manufacturer with tpm2-tools when installed, and confirms the SHA-256 PCR bank holds boot measurements. A working TPM 2.0 provides protected key storage and measured boot for attestation; TPM 1.2 uses deprecated algorithms.",
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            Self::EdgeLockEnclave => &["cra", "imx93"],
+            Self::SecureEnclaveStatus => &["cra"],
+            Self::HardwareRootOfTrust => &["cra", "imx93", "imx8mm"],
+            Self::CryptoAcceleration => &["imx8mm"],
+            Self::RandomNumberGenerator => &["cra"],
+            Self::Pcf2131Rtc => &["imx93"],
+            Self::UsbSecurity => &["cra"],
+            Self::FaultInjectionProtection => &["cra"],
+            Self::IommuDmaProtection => &["cra"],
+            Self::Tpm2Status => &["cra"],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::EdgeLockEnclave => Severity::High,
+            Self::SecureEnclaveStatus => Severity::Medium,
+            Self::HardwareRootOfTrust => Severity::High,
+            Self::CryptoAcceleration => Severity::Low,
+            Self::RandomNumberGenerator => Severity::Medium,
+            Self::Pcf2131Rtc => Severity::Low,
+            Self::UsbSecurity => Severity::Medium,
+            Self::FaultInjectionProtection => Severity::Medium,
+            Self::IommuDmaProtection => Severity::Medium,
+            Self::Tpm2Status => Severity::Medium,
+        }
+    }
+
+    fn applicable_machines(&self) -> &[MachineType] {
+        match self {
+            Self::EdgeLockEnclave | Self::Pcf2131Rtc => &[MachineType::Imx93JaguarEink],
+            Self::CryptoAcceleration => IMX_MACHINES,
+            _ => &[],
         }
     }
 }
@@ -161,23 +229,29 @@ impl HardwareSecurityTests {
         }
 
         // Check ELE status via device tree if available
-        let dt_check = target.execute_command("find /proc/device-tree -name '*ele*' -o -name '*s4*' -o -name '*mu*' 2>/dev/null | head -5").await?;
+        let dt_check = target
+            .execute_command(&format!(
+                "find /proc/device-tree -name '*ele*' -o -name '*s4*' -o -name '*mu*' 2>/dev/null{}",
+                target.evidence_head(5)
+            ))
+            .await?;
         if !dt_check.stdout.is_empty() {
             indicators.push("ELE device tree entries found");
             details.push(format!("Device tree ELE entries:\n{}", dt_check.stdout));
         }
 
         // Look for ELE management tools warnings
-        let ele_tools = target
-            .execute_command(
-                "which ele_mu_ctl simple-ele-test 2>/dev/null || echo 'tools_not_found'",
-            )
-            .await?;
-        if ele_tools.stdout.contains("tools_not_found") {
+        let mut ele_tools = Vec::new();
+        for tool in ["ele_mu_ctl", "simple-ele-test"] {
+            if target.has_command(tool).await {
+                ele_tools.push(tool);
+            }
+        }
+        if ele_tools.is_empty() {
             warnings.push("ELE management tools not installed (optional for operation)");
         } else {
             indicators.push("ELE management tools available");
-            details.push(format!("ELE tools: {}", ele_tools.stdout));
+            details.push(format!("ELE tools: {}", ele_tools.join(", ")));
         }
 
         // Detailed summary
@@ -265,7 +339,10 @@ impl HardwareSecurityTests {
 
         // Check for secure boot fuses
         let fuse_check = target
-            .execute_command("find /sys -name '*fuse*' -o -name '*otp*' 2>/dev/null | head -5")
+            .execute_command(&format!(
+                "find /sys -name '*fuse*' -o -name '*otp*' 2>/dev/null{}",
+                target.evidence_head(5)
+            ))
             .await?;
 
         let details = format!(
@@ -360,29 +437,90 @@ impl HardwareSecurityTests {
             )
             .await?;
 
-        let entropy: u32 = hwrng.stdout.trim().parse().unwrap_or(0);
+        // Check for rngd feeding the pool from another source
+        let rngd = target
+            .execute_command("pidof rngd >/dev/null 2>&1 && echo 'running' || echo 'not_running'")
+            .await?;
 
-        let details = format!(
-            "Entropy available: {}\nRNG devices: {}\nRNG current: {}",
-            entropy, rng_devices.stdout, rng_quality.stdout
+        let entropy: u32 = hwrng.stdout.trim().parse().unwrap_or(0);
+        let hw_source = match rng_quality.stdout.trim() {
+            "" | "none" | "not_available" => None,
+            current => Some(current.to_string()),
+        };
+        let rngd_running = rngd.stdout.trim() == "running";
+
+        let mut details = format!(
+            "Entropy available: {}\nRNG devices: {}\nRNG current: {}\nrngd: {}",
+            entropy,
+            rng_devices.stdout,
+            rng_quality.stdout,
+            rngd.stdout.trim()
         );
 
-        if entropy > 1000 && rng_devices.stdout.contains("hwrng") {
+        if target.context().tests.rng_sample_test {
+            // A quick sanity check of the output, not a certification of the RNG
+            let sample = target
+                .execute_command(&format!(
+                    "timeout 5 head -c {} /dev/random 2>/dev/null | od -An -v -tx1",
+                    RNG_SAMPLE_BYTES
+                ))
+                .await?;
+            let bytes = parse_od_bytes(&sample.stdout);
+            if bytes.len() < RNG_SAMPLE_BYTES {
+                details.push_str(&format!(
+                    "\nSample sanity check: skipped, read {} of {} bytes",
+                    bytes.len(),
+                    RNG_SAMPLE_BYTES
+                ));
+            } else {
+                let failures = fips_sanity_failures(&bytes[..RNG_SAMPLE_BYTES]);
+                if !failures.is_empty() {
+                    details.push_str(&format!(
+                        "\nSample sanity check (FIPS 140-2 style, not a certification): {}",
+                        failures.join("; ")
+                    ));
+                    return Ok((
+                        TestStatus::Failed,
+                        format!(
+                            "/dev/random output failed {} sanity check(s)",
+                            failures.len()
+                        ),
+                        Some(details),
+                    ));
+                }
+                details.push_str(
+                    "\nSample sanity check (FIPS 140-2 style, not a certification): monobit, runs and long run passed",
+                );
+            }
+        }
+
+        if entropy <= 500 {
             Ok((
-                TestStatus::Passed,
-                format!("Hardware RNG active (entropy: {})", entropy),
+                TestStatus::Failed,
+                format!("Insufficient entropy ({})", entropy),
                 Some(details),
             ))
-        } else if entropy > 500 {
+        } else if hw_source.is_none() && !rngd_running {
             Ok((
                 TestStatus::Warning,
-                format!("RNG available but low entropy ({})", entropy),
+                "Only the software CRNG feeds the entropy pool, no hardware RNG or rngd"
+                    .to_string(),
+                Some(details),
+            ))
+        } else if entropy > 1000 {
+            let source = match hw_source {
+                Some(current) => format!("Hardware RNG {} active", current),
+                None => "rngd feeding the entropy pool".to_string(),
+            };
+            Ok((
+                TestStatus::Passed,
+                format!("{} (entropy: {})", source, entropy),
                 Some(details),
             ))
         } else {
             Ok((
-                TestStatus::Failed,
-                format!("Insufficient entropy ({})", entropy),
+                TestStatus::Warning,
+                format!("RNG available but low entropy ({})", entropy),
                 Some(details),
             ))
         }
@@ -522,7 +660,7 @@ impl HardwareSecurityTests {
                 usb_controllers
                     .stdout
                     .lines()
-                    .take(3)
+                    .take(target.evidence_lines(3))
                     .collect::<Vec<_>>()
                     .join("; ")
             ));
@@ -546,12 +684,15 @@ impl HardwareSecurityTests {
 
         // Check USB configuration and permissions
         let usb_permissions = target
-            .execute_command(
-                "ls -la /dev/bus/usb/*/* 2>/dev/null | head -5 || echo 'no_usb_devices'",
-            )
+            .execute_command(&format!(
+                "ls -la /dev/bus/usb/*/* 2>/dev/null{} || echo 'no_usb_devices'",
+                target.evidence_head(5)
+            ))
             .await?;
 
-        if !usb_permissions.stdout.contains("no_usb_devices") {
+        if !usb_permissions.stdout.trim().is_empty()
+            && !usb_permissions.stdout.contains("no_usb_devices")
+        {
             // Check if USB devices have restrictive permissions
             if usb_permissions.stdout.contains("crw-rw----") {
                 security_features.push("Restrictive USB device permissions");
@@ -563,7 +704,7 @@ impl HardwareSecurityTests {
                 usb_permissions
                     .stdout
                     .lines()
-                    .take(2)
+                    .take(target.evidence_lines(2))
                     .collect::<Vec<_>>()
                     .join("; ")
             ));
@@ -571,7 +712,10 @@ impl HardwareSecurityTests {
 
         // Check for USB host/device mode configuration
         let usb_mode_check = target
-            .execute_command("find /sys/class/udc -name '*' 2>/dev/null | head -3")
+            .execute_command(&format!(
+                "find /sys/class/udc -name '*' 2>/dev/null{}",
+                target.evidence_head(3)
+            ))
             .await?;
 
         if !usb_mode_check.stdout.is_empty() {
@@ -584,16 +728,22 @@ impl HardwareSecurityTests {
 
         // Check for USB OTG configuration
         let usb_otg = target
-            .execute_command(
-                "dmesg | grep -i 'otg\\|usb.*host.*device' | head -2 || echo 'no_otg_messages'",
-            )
+            .execute_command(&format!(
+                "dmesg | grep -i 'otg\\|usb.*host.*device'{} || echo 'no_otg_messages'",
+                target.evidence_head(2)
+            ))
             .await?;
 
-        if !usb_otg.stdout.contains("no_otg_messages") {
+        if !usb_otg.stdout.trim().is_empty() && !usb_otg.stdout.contains("no_otg_messages") {
             security_features.push("USB OTG configuration detected");
             details.push(format!(
                 "USB OTG: {}",
-                usb_otg.stdout.lines().take(1).collect::<Vec<_>>().join("")
+                usb_otg
+                    .stdout
+                    .lines()
+                    .take(target.evidence_lines(1))
+                    .collect::<Vec<_>>()
+                    .join("; ")
             ));
         }
 
@@ -636,7 +786,10 @@ impl HardwareSecurityTests {
 
         // Check USB autosuspend settings
         let usb_autosuspend = target
-            .execute_command("find /sys/bus/usb/devices -name 'autosuspend' -exec cat {} \\; 2>/dev/null | head -3")
+            .execute_command(&format!(
+                "find /sys/bus/usb/devices -name 'autosuspend' -exec cat {{}} \\; 2>/dev/null{}",
+                target.evidence_head(3)
+            ))
             .await?;
 
         if !usb_autosuspend.stdout.is_empty() {
@@ -683,4 +836,492 @@ impl HardwareSecurityTests {
             ))
         }
     }
+
+    async fn test_fault_injection_protection(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        // There is no portable interface for this: indicators are advisory, and
+        // only a detector state read by the platform-specific
+        // `hardware_008.tamper_state` probe (e.g. a tamper-enable register) can pass
+        let mut details = Vec::new();
+        let mut indicators = Vec::new();
+
+        // Regulators that report under-voltage state can detect brown-out conditions
+        let regulator_command = target.probe_command(
+            "hardware_008.regulator_monitors",
+            "ls -d /sys/class/regulator/*/under_voltage* 2>/dev/null",
+        );
+        let regulators = target.execute_command(&regulator_command).await?;
+        if !regulators.stdout.trim().is_empty() {
+            indicators.push("Regulator under-voltage monitoring");
+            details.push(format!(
+                "Regulator under-voltage monitors:\n{}",
+                regulators.stdout.trim()
+            ));
+        }
+
+        // i.MX SNVS and similar blocks provide tamper, voltage and clock monitors
+        let tamper_command = target.probe_command(
+            "hardware_008.tamper_devices",
+            "ls /sys/bus/platform/devices/ 2>/dev/null | grep -iE 'tamper|snvs|bbsm'",
+        );
+        let tamper = target.execute_command(&tamper_command).await?;
+        if !tamper.stdout.trim().is_empty() {
+            indicators.push("Tamper/secure monitor block");
+            details.push(format!("Tamper monitor devices:\n{}", tamper.stdout.trim()));
+        }
+
+        // Raspberry Pi firmware reports under-voltage events via get_throttled
+        let throttled = target
+            .execute_command("vcgencmd get_throttled 2>/dev/null")
+            .await?;
+        if let Some(value) = throttled.stdout.trim().strip_prefix("throttled=0x") {
+            if let Ok(flags) = u32::from_str_radix(value, 16) {
+                indicators.push("Firmware under-voltage detection");
+                details.push(format!(
+                    "Firmware throttling flags: 0x{:x} (under-voltage now: {}, since boot: {})",
+                    flags,
+                    flags & 0x1 != 0,
+                    flags & 0x10000 != 0
+                ));
+            }
+        }
+
+        // Kernel messages from glitch, brown-out or tamper detectors
+        let dmesg_command = target.probe_command(
+            "hardware_008.dmesg",
+            &format!(
+                "dmesg 2>/dev/null | grep -iE 'brown-?out|glitch|tamper|voltage monitor|clock monitor|fault injection'{}",
+                target.evidence_head(20)
+            ),
+        );
+        let dmesg = target.execute_command(&dmesg_command).await?;
+        if !dmesg.stdout.trim().is_empty() {
+            indicators.push("Kernel fault/tamper messages");
+            details.push(format!("Kernel messages:\n{}", dmesg.stdout.trim()));
+        }
+
+        let state_command = target.probe_command("hardware_008.tamper_state", "");
+        let detector_enabled = if state_command.trim().is_empty() {
+            false
+        } else {
+            let state = target.execute_command(&state_command).await?;
+            details.push(format!("Detector state: {}", state.stdout.trim()));
+            detector_state_enabled(&state.stdout)
+        };
+        if detector_enabled {
+            details.insert(0, format!("Indicators: {}", indicators.join(", ")));
+            return Ok((
+                TestStatus::Passed,
+                "Tamper/glitch detector reported enabled".to_string(),
+                Some(details.join("\n")),
+            ));
+        }
+
+        if indicators.is_empty() {
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "Platform does not expose glitch or brown-out protection status"
+                    .to_string(),
+                details: Some(
+                    "No regulator monitors, tamper blocks, firmware flags or kernel messages found"
+                        .to_string(),
+                ),
+            });
+        }
+
+        details.insert(0, format!("Indicators: {}", indicators.join(", ")));
+        details.push(
+            "Advisory: these show monitoring hardware exists, not that glitch protection is enabled; set a hardware_008.tamper_state probe that reads the detector's enable state"
+                .to_string(),
+        );
+        Ok((
+            TestStatus::Warning,
+            format!(
+                "Fault-injection/brown-out indicators found ({}), protection not confirmed",
+                indicators.len()
+            ),
+            Some(details.join("\n")),
+        ))
+    }
+
+    async fn test_iommu_dma_protection(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let mut details = Vec::new();
+
+        let iommus = target
+            .execute_command("ls /sys/class/iommu/ 2>/dev/null")
+            .await?;
+        let groups = target
+            .execute_command("ls /sys/kernel/iommu_groups/ 2>/dev/null | wc -l")
+            .await?;
+        let group_count: usize = groups.stdout.trim().parse().unwrap_or(0);
+        details.push(format!(
+            "Registered IOMMUs: {}",
+            if iommus.stdout.trim().is_empty() {
+                "none".to_string()
+            } else {
+                iommus
+                    .stdout
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        ));
+        details.push(format!("IOMMU groups: {}", group_count));
+
+        // Kernel parameters that switch translation off or let devices bypass it
+        let cmdline = target.execute_command("cat /proc/cmdline").await?;
+        let bypass_flags: Vec<&str> = cmdline
+            .stdout
+            .split_whitespace()
+            .filter(|param| {
+                matches!(
+                    *param,
+                    "iommu=off"
+                        | "iommu=pt"
+                        | "iommu.passthrough=1"
+                        | "intel_iommu=off"
+                        | "amd_iommu=off"
+                        | "arm-smmu.disable_bypass=0"
+                )
+            })
+            .collect();
+        if !bypass_flags.is_empty() {
+            details.push(format!("Bypass parameters: {}", bypass_flags.join(" ")));
+        }
+
+        // Does the SoC have an IOMMU at all, whether or not the kernel uses it?
+        let hardware_command = target.probe_command(
+            "hardware_009.iommu_hardware",
+            "ls /sys/bus/platform/devices/ 2>/dev/null | grep -iE 'iommu|smmu'; \
+             find /proc/device-tree -maxdepth 3 \\( -iname '*iommu*' -o -iname '*smmu*' \\) 2>/dev/null | head -5",
+        );
+        let hardware = target.execute_command(&hardware_command).await?;
+        let dmesg = target
+            .execute_command(&format!(
+                "dmesg 2>/dev/null | grep -iE 'iommu|smmu|DMAR|AMD-Vi'{}",
+                target.evidence_head(10)
+            ))
+            .await?;
+        if !hardware.stdout.trim().is_empty() {
+            details.push(format!("IOMMU hardware:\n{}", hardware.stdout.trim()));
+        }
+        if !dmesg.stdout.trim().is_empty() {
+            details.push(format!("Kernel messages:\n{}", dmesg.stdout.trim()));
+        }
+        let hardware_present = !hardware.stdout.trim().is_empty()
+            || !dmesg.stdout.trim().is_empty()
+            || !iommus.stdout.trim().is_empty();
+
+        let active = group_count > 0 && !iommus.stdout.trim().is_empty();
+
+        if active && bypass_flags.is_empty() {
+            Ok((
+                TestStatus::Passed,
+                format!("IOMMU enabled ({} DMA isolation groups)", group_count),
+                Some(details.join("\n")),
+            ))
+        } else if active {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "IOMMU present but bypassed by kernel parameters: {}",
+                    bypass_flags.join(" ")
+                ),
+                Some(details.join("\n")),
+            ))
+        } else if hardware_present {
+            Ok((
+                TestStatus::Warning,
+                "IOMMU hardware present but not enabled - peripherals have unrestricted DMA"
+                    .to_string(),
+                Some(details.join("\n")),
+            ))
+        } else {
+            Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No IOMMU/SMMU found on this SoC".to_string(),
+                details: Some(details.join("\n")),
+            })
+        }
+    }
+
+    async fn test_tpm2_status(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let devices = target
+            .execute_command("ls -d /dev/tpm0 /dev/tpmrm0 /sys/class/tpm/tpm0 2>/dev/null")
+            .await?;
+        if devices.stdout.trim().is_empty() {
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No TPM hardware present".to_string(),
+                details: None,
+            });
+        }
+
+        let mut details = vec![format!(
+            "TPM nodes: {}",
+            devices
+                .stdout
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(", ")
+        )];
+
+        // tpm_version_major appeared in Linux 5.6; the resource manager only exists for 2.0
+        let version = target
+            .execute_command("cat /sys/class/tpm/tpm0/tpm_version_major 2>/dev/null")
+            .await?;
+        let mut version = match version.stdout.trim() {
+            "" if devices.stdout.contains("/dev/tpmrm0") => "2".to_string(),
+            "" => "unknown".to_string(),
+            major => major.to_string(),
+        };
+
+        if target.has_command("tpm2_getcap").await {
+            let getcap_command = target.probe_command(
+                "hardware_010.getcap",
+                "tpm2_getcap properties-fixed 2>/dev/null",
+            );
+            let mut getcap = target.execute_command(&getcap_command).await?;
+            if getcap.exit_code != 0 {
+                let sudo_command = format!("{} {}", target.elevation_pipe(), getcap_command);
+                getcap = target.execute_command(&sudo_command).await?;
+            }
+            if let Some(family) = tpm_fixed_property(&getcap.stdout, "TPM2_PT_FAMILY_INDICATOR") {
+                if family.starts_with('2') {
+                    version = "2".to_string();
+                }
+                details.push(format!("Family: {}", family));
+            }
+            if let Some(manufacturer) = tpm_fixed_property(&getcap.stdout, "TPM2_PT_MANUFACTURER") {
+                details.push(format!("Manufacturer: {}", manufacturer));
+            }
+        } else {
+            details.push("tpm2-tools not installed; fixed properties not read".to_string());
+        }
+        details.push(format!("TPM major version: {}", version));
+
+        if version == "1" {
+            return Ok((
+                TestStatus::Warning,
+                "TPM 1.2 present - TPM 2.0 is required for current attestation schemes".to_string(),
+                Some(details.join("\n")),
+            ));
+        }
+        if version != "2" {
+            return Ok((
+                TestStatus::Warning,
+                "TPM device present but its version could not be determined".to_string(),
+                Some(details.join("\n")),
+            ));
+        }
+
+        let banks = target
+            .execute_command("ls -d /sys/class/tpm/tpm0/pcr-* 2>/dev/null")
+            .await?;
+        let banks: Vec<&str> = banks
+            .stdout
+            .split_whitespace()
+            .filter_map(|path| path.rsplit("pcr-").next())
+            .collect();
+        details.push(format!(
+            "PCR banks: {}",
+            if banks.is_empty() {
+                "not exposed in sysfs".to_string()
+            } else {
+                banks.join(", ")
+            }
+        ));
+
+        if !banks.contains(&"sha256") {
+            return Ok((
+                TestStatus::Passed,
+                "TPM 2.0 present".to_string(),
+                Some(details.join("\n")),
+            ));
+        }
+
+        // PCR 0 holds the firmware measurement; all zeros means nothing was measured
+        let pcr0 = target
+            .execute_command("cat /sys/class/tpm/tpm0/pcr-sha256/0 2>/dev/null")
+            .await?;
+        let pcr0 = pcr0.stdout.trim();
+        details.push(format!("PCR 0 (sha256): {}", pcr0));
+        if pcr0.is_empty() || pcr0.chars().all(|c| c == '0') {
+            Ok((
+                TestStatus::Warning,
+                "TPM 2.0 present but inactive - boot measurements are not being extended"
+                    .to_string(),
+                Some(details.join("\n")),
+            ))
+        } else {
+            Ok((
+                TestStatus::Passed,
+                "TPM 2.0 present and active with populated PCR banks".to_string(),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+}
+
+/// Bytes read from `/dev/random` for the sample check: the 20000 bits the
+/// FIPS 140-2 power-up tests were defined on
+const RNG_SAMPLE_BYTES: usize = 2500;
+
+/// Bytes from `od -An -v -tx1` output
+fn parse_od_bytes(output: &str) -> Vec<u8> {
+    output
+        .split_whitespace()
+        .filter_map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect()
+}
+
+/// Run the FIPS 140-2 monobit, runs and long run tests on a 20000 bit
+/// sample, returning a description of each test it fails
+fn fips_sanity_failures(sample: &[u8]) -> Vec<String> {
+    // Allowed number of runs of each length 1..=5 and 6+, for each bit value,
+    // as tightened by FIPS 140-2 change notice 1
+    const RUN_BOUNDS: [(usize, usize); 6] = [
+        (2343, 2657),
+        (1135, 1365),
+        (542, 708),
+        (251, 373),
+        (111, 201),
+        (111, 201),
+    ];
+
+    let mut failures = Vec::new();
+    let ones: u32 = sample.iter().map(|byte| byte.count_ones()).sum();
+    if !(9726..=10274).contains(&ones) {
+        failures.push(format!("monobit: {} ones in 20000 bits", ones));
+    }
+
+    let bits = sample
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1));
+    let mut runs = [[0usize; 6]; 2];
+    let mut longest = 0;
+    let mut current: Option<(u8, usize)> = None;
+    for bit in bits.map(Some).chain(std::iter::once(None)) {
+        match (current, bit) {
+            (Some((value, length)), Some(next)) if value == next => {
+                current = Some((value, length + 1));
+            }
+            (previous, next) => {
+                if let Some((value, length)) = previous {
+                    runs[value as usize][length.min(6) - 1] += 1;
+                    longest = longest.max(length);
+                }
+                current = next.map(|value| (value, 1));
+            }
+        }
+    }
+    for (value, counts) in runs.iter().enumerate() {
+        for (index, (count, (low, high))) in counts.iter().zip(RUN_BOUNDS).enumerate() {
+            if !(low..=high).contains(count) {
+                let length = if index == 5 {
+                    "6+".to_string()
+                } else {
+                    (index + 1).to_string()
+                };
+                failures.push(format!(
+                    "runs: {} runs of {} {}s, expected {}-{}",
+                    count, length, value, low, high
+                ));
+            }
+        }
+    }
+    if longest >= 26 {
+        failures.push(format!("long run: {} identical bits in a row", longest));
+    }
+    failures
+}
+
+/// Value of a fixed property in `tpm2_getcap properties-fixed` output, e.g. the
+/// `"2.0"` of `TPM2_PT_FAMILY_INDICATOR:` followed by `raw:` and `value: "2.0"` lines
+fn tpm_fixed_property(output: &str, name: &str) -> Option<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with(name))
+        .skip(1)
+        .take_while(|line| line.starts_with(char::is_whitespace))
+        .find_map(|line| line.trim().strip_prefix("value:"))
+        .map(|value| value.trim().trim_matches('"').to_string())
+}
+
+/// Whether a `hardware_008.tamper_state` probe reports its detector enabled:
+/// any line reading `1`, `enabled`, `on` or `active`
+fn detector_state_enabled(output: &str) -> bool {
+    output.lines().any(|line| {
+        let value = line.rsplit([':', '=']).next().unwrap_or(line).trim();
+        matches!(
+            value.to_ascii_lowercase().as_str(),
+            "1" | "enabled" | "on" | "active"
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tpm_fixed_property() {
+        let getcap = "TPM2_PT_FAMILY_INDICATOR:\n  raw: 0x322E3000\n  value: \"2.0\"\nTPM2_PT_LEVEL:\n  raw: 0\nTPM2_PT_MANUFACTURER:\n  raw: 0x49465800\n  value: \"IFX\"\n";
+        assert_eq!(
+            tpm_fixed_property(getcap, "TPM2_PT_FAMILY_INDICATOR").as_deref(),
+            Some("2.0")
+        );
+        assert_eq!(
+            tpm_fixed_property(getcap, "TPM2_PT_MANUFACTURER").as_deref(),
+            Some("IFX")
+        );
+        assert_eq!(tpm_fixed_property(getcap, "TPM2_PT_LEVEL"), None);
+    }
+
+    #[test]
+    fn test_detector_state_enabled() {
+        assert!(detector_state_enabled("tamper0: enabled\n"));
+        assert!(detector_state_enabled("1\n"));
+        assert!(!detector_state_enabled("tamper0: disabled\nglitch=0\n"));
+        assert!(!detector_state_enabled(""));
+    }
+
+    #[test]
+    fn test_rng_sample_sanity() {
+        assert_eq!(
+            parse_od_bytes(" 8f 00 ff\n 1a\n"),
+            vec![0x8f, 0x00, 0xff, 0x1a]
+        );
+
+        // xorshift output stands in for a healthy RNG
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let random: Vec<u8> = (0..RNG_SAMPLE_BYTES)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 32) as u8
+            })
+            .collect();
+        assert!(fips_sanity_failures(&random).is_empty());
+
+        let stuck = vec![0u8; RNG_SAMPLE_BYTES];
+        let failures = fips_sanity_failures(&stuck);
+        assert!(failures[0].starts_with("monobit: 0 ones"));
+        assert!(failures.iter().any(|f| f.starts_with("long run: 20000")));
+
+        // Balanced but perfectly alternating bits fail the runs test
+        let alternating = vec![0xaau8; RNG_SAMPLE_BYTES];
+        let failures = fips_sanity_failures(&alternating);
+        assert!(failures.iter().all(|f| f.starts_with("runs:")));
+        assert!(!failures.is_empty());
+    }
 }
@@ -1,12 +1,13 @@
 use crate::{
-    cli::{TestMode, TestSuite},
+    cli::{FailOnStatus, MachineType, Severity, TestMode, TestSuite},
+    config::ProductConfig,
     error::Result,
     target::{SystemInfo, Target},
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 pub mod boot;
@@ -34,6 +35,28 @@ pub trait SecurityTest {
     fn test_name(&self) -> &str;
     fn category(&self) -> &str;
     fn description(&self) -> &str;
+
+    /// Labels for selecting tests with `--tag`: the frameworks (`cra`, `red`)
+    /// and platforms (`imx93`, `imx8mm`, `foundries`, `wireless`) a test covers
+    fn tags(&self) -> &[&str] {
+        &[]
+    }
+
+    /// How much a failure or warning of this test matters, for `--fail-on`
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    /// Machines whose hardware this test checks; empty means every machine.
+    /// On any other known machine the test is skipped instead of run.
+    fn applicable_machines(&self) -> &[MachineType] {
+        &[]
+    }
+
+    /// Shell commands that would fix a failed or warning result, if the test can suggest any
+    fn remediation(&self, _result: &TestResult) -> Option<Vec<String>> {
+        None
+    }
 }
 
 // Unified enum for all security tests
@@ -57,9 +80,18 @@ pub struct TestResult {
     pub status: TestStatus,
     pub message: String,
     pub details: Option<String>,
+    #[serde(with = "duration_ms")]
     pub duration: Duration,
     pub timestamp: DateTime<Utc>,
     pub metadata: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<Vec<String>>,
+    /// Severity of the test, set by the runner from the test definition
+    #[serde(default)]
+    pub severity: Severity,
+    /// Why a Skipped test did not run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<SkipReason>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -71,6 +103,29 @@ pub enum TestStatus {
     Error,
 }
 
+/// Why a test was skipped, separating tests that could not run on this
+/// target from tests that do not apply to it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SkipReason {
+    /// The test only applies to other machine types or platforms
+    NotApplicableToMachine,
+    /// The hardware, service or configuration the test examines is absent
+    NotPresent,
+    /// A tool the test needs is not installed on the target
+    MissingTool,
+    /// The test needs root or sudo access it didn't get
+    InsufficientPrivilege,
+    /// The check was turned off in the configuration
+    Disabled,
+}
+
+impl SkipReason {
+    /// The test could have told us something with the right tools or access
+    pub fn is_inconclusive(&self) -> bool {
+        matches!(self, Self::MissingTool | Self::InsufficientPrivilege)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestSuiteResults {
     pub suite_name: String,
@@ -81,15 +136,230 @@ pub struct TestSuiteResults {
     pub warnings: usize,
     pub skipped: usize,
     pub errors: usize,
+    #[serde(with = "duration_ms")]
     pub duration: Duration,
     pub timestamp: DateTime<Utc>,
     pub system_info: SystemInfo,
     pub results: Vec<TestResult>,
+    #[serde(default)]
+    pub gate: GatePolicy,
+    /// Times the connection dropped and was re-established during the run
+    #[serde(default)]
+    pub reconnects: u32,
+    /// Product identity given for the report headers
+    #[serde(default)]
+    pub product: ProductConfig,
+}
+
+/// Outcome counts for the tests of one category
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategorySummary {
+    pub category: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub warnings: usize,
+    pub skipped: usize,
+    pub errors: usize,
+}
+
+impl std::fmt::Display for CategorySummary {
+    /// e.g. "4/5 passed, 1 failed", leaving out outcomes that did not occur
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} passed", self.passed, self.total)?;
+        for (count, label) in [
+            (self.failed, "failed"),
+            (self.warnings, "warnings"),
+            (self.skipped, "skipped"),
+            (self.errors, "errors"),
+        ] {
+            if count > 0 {
+                write!(f, ", {} {}", count, label)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TestResult {
+    /// Failed or warning: something an operator should look at
+    pub fn is_finding(&self) -> bool {
+        matches!(self.status, TestStatus::Failed | TestStatus::Warning)
+    }
+
+    /// Record why the test was skipped
+    pub fn with_skip_reason(mut self, reason: SkipReason) -> Self {
+        self.skip_reason = Some(reason);
+        self
+    }
+
+    /// Attach machine-readable facts the test collected
+    pub fn with_metadata(mut self, metadata: Vec<(String, String)>) -> Self {
+        self.metadata.extend(metadata);
+        self
+    }
+}
+
+/// Durations as whole milliseconds in JSON, so tools like `jq` can use them directly.
+///
+/// Results saved before the switch stored serde's `{secs, nanos}` form; both are accepted.
+pub(crate) mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Millis(u64),
+        Struct { secs: u64, nanos: u32 },
+    }
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Millis(ms) => Duration::from_millis(ms),
+            Stored::Struct { secs, nanos } => Duration::new(secs, nanos),
+        })
+    }
+}
+
+/// Which non-pass outcomes fail a run, beyond outright failures
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GatePolicy {
+    /// Tests that errored out do not fail the run on their own
+    pub errors_non_gating: bool,
+    /// Categories in which a warning fails the run
+    pub fail_on_warning_categories: Vec<String>,
+    /// When set, only failures and warnings at or above this severity fail the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on: Option<Severity>,
+    /// When set, any test at or above this status fails the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on_status: Option<FailOnStatus>,
+    /// Warnings never fail the run
+    #[serde(default)]
+    pub exit_zero_on_warning: bool,
+}
+
+impl GatePolicy {
+    /// Whether this failed or warning result fails the run on its own
+    pub fn finding_fails(&self, result: &TestResult) -> bool {
+        if self.exit_zero_on_warning && result.status == TestStatus::Warning {
+            return false;
+        }
+        match self.fail_on {
+            Some(threshold) => result.is_finding() && result.severity >= threshold,
+            None => result.status == TestStatus::Failed || self.warning_fails(result),
+        }
+    }
+
+    pub fn warning_fails(&self, result: &TestResult) -> bool {
+        result.status == TestStatus::Warning
+            && !self.exit_zero_on_warning
+            && self
+                .fail_on_warning_categories
+                .iter()
+                .any(|category| category.eq_ignore_ascii_case(&result.category))
+    }
 }
 
 impl TestSuiteResults {
+    /// Builds an "All" suite around `results`, with the counts derived from their statuses
+    #[cfg(test)]
+    pub(crate) fn sample(results: Vec<TestResult>) -> Self {
+        let count = |status: TestStatus| results.iter().filter(|r| r.status == status).count();
+        Self {
+            suite_name: "All".to_string(),
+            test_mode: "PreProduction".to_string(),
+            total_tests: results.len(),
+            passed: count(TestStatus::Passed),
+            failed: count(TestStatus::Failed),
+            warnings: count(TestStatus::Warning),
+            skipped: count(TestStatus::Skipped),
+            errors: count(TestStatus::Error),
+            duration: Duration::ZERO,
+            timestamp: Utc::now(),
+            system_info: SystemInfo::default(),
+            results,
+            gate: GatePolicy::default(),
+            reconnects: 0,
+            product: Default::default(),
+        }
+    }
+
     pub fn overall_passed(&self) -> bool {
-        self.failed == 0 && self.errors == 0
+        let errors_pass = self.errors == 0 || self.gate.errors_non_gating;
+        if let Some(status) = self.gate.fail_on_status {
+            return errors_pass
+                && match status {
+                    FailOnStatus::Warning => {
+                        self.failed == 0 && (self.warnings == 0 || self.gate.exit_zero_on_warning)
+                    }
+                    FailOnStatus::Failed => self.failed == 0,
+                    FailOnStatus::Error => true,
+                };
+        }
+
+        let findings_pass = match self.gate.fail_on {
+            Some(_) => !self
+                .results
+                .iter()
+                .any(|result| self.gate.finding_fails(result)),
+            None => {
+                self.failed == 0
+                    && !self
+                        .results
+                        .iter()
+                        .any(|result| self.gate.warning_fails(result))
+            }
+        };
+        findings_pass && errors_pass
+    }
+
+    /// Outcome counts per category, in the order categories first appear
+    pub fn category_summary(&self) -> Vec<CategorySummary> {
+        let mut summary: Vec<CategorySummary> = Vec::new();
+        for result in &self.results {
+            let index = match summary
+                .iter()
+                .position(|entry| entry.category == result.category)
+            {
+                Some(index) => index,
+                None => {
+                    summary.push(CategorySummary {
+                        category: result.category.clone(),
+                        total: 0,
+                        passed: 0,
+                        failed: 0,
+                        warnings: 0,
+                        skipped: 0,
+                        errors: 0,
+                    });
+                    summary.len() - 1
+                }
+            };
+            let entry = &mut summary[index];
+            entry.total += 1;
+            match result.status {
+                TestStatus::Passed => entry.passed += 1,
+                TestStatus::Failed => entry.failed += 1,
+                TestStatus::Warning => entry.warnings += 1,
+                TestStatus::Skipped => entry.skipped += 1,
+                TestStatus::Error => entry.errors += 1,
+            }
+        }
+        summary
+    }
+
+    /// Failed and warning tests of at least `severity`
+    pub fn findings_at_or_above(&self, severity: Severity) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.is_finding() && result.severity >= severity)
+            .count()
     }
 
     pub fn success_rate(&self) -> f64 {
@@ -166,10 +436,64 @@ impl SecurityTest for SecurityTestEnum {
             SecurityTestEnum::Production(test) => test.description(),
         }
     }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            SecurityTestEnum::Boot(test) => test.tags(),
+            SecurityTestEnum::Hardware(test) => test.tags(),
+            SecurityTestEnum::Network(test) => test.tags(),
+            SecurityTestEnum::Runtime(test) => test.tags(),
+            SecurityTestEnum::Compliance(test) => test.tags(),
+            SecurityTestEnum::Container(test) => test.tags(),
+            SecurityTestEnum::Certificate(test) => test.tags(),
+            SecurityTestEnum::Production(test) => test.tags(),
+        }
+    }
+
+    fn applicable_machines(&self) -> &[MachineType] {
+        match self {
+            SecurityTestEnum::Boot(test) => test.applicable_machines(),
+            SecurityTestEnum::Hardware(test) => test.applicable_machines(),
+            SecurityTestEnum::Network(test) => test.applicable_machines(),
+            SecurityTestEnum::Runtime(test) => test.applicable_machines(),
+            SecurityTestEnum::Compliance(test) => test.applicable_machines(),
+            SecurityTestEnum::Container(test) => test.applicable_machines(),
+            SecurityTestEnum::Certificate(test) => test.applicable_machines(),
+            SecurityTestEnum::Production(test) => test.applicable_machines(),
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            SecurityTestEnum::Boot(test) => test.severity(),
+            SecurityTestEnum::Hardware(test) => test.severity(),
+            SecurityTestEnum::Network(test) => test.severity(),
+            SecurityTestEnum::Runtime(test) => test.severity(),
+            SecurityTestEnum::Compliance(test) => test.severity(),
+            SecurityTestEnum::Container(test) => test.severity(),
+            SecurityTestEnum::Certificate(test) => test.severity(),
+            SecurityTestEnum::Production(test) => test.severity(),
+        }
+    }
+
+    fn remediation(&self, result: &TestResult) -> Option<Vec<String>> {
+        match self {
+            SecurityTestEnum::Boot(test) => test.remediation(result),
+            SecurityTestEnum::Hardware(test) => test.remediation(result),
+            SecurityTestEnum::Network(test) => test.remediation(result),
+            SecurityTestEnum::Runtime(test) => test.remediation(result),
+            SecurityTestEnum::Compliance(test) => test.remediation(result),
+            SecurityTestEnum::Container(test) => test.remediation(result),
+            SecurityTestEnum::Certificate(test) => test.remediation(result),
+            SecurityTestEnum::Production(test) => test.remediation(result),
+        }
+    }
 }
 
 pub struct TestRegistry {
-    tests: HashMap<String, SecurityTestEnum>,
+    /// Keyed by test ID; ordered so listings and run order are reproducible
+    tests: BTreeMap<String, SecurityTestEnum>,
+    custom_tests: Vec<String>,
 }
 
 impl Default for TestRegistry {
@@ -181,7 +505,8 @@ impl Default for TestRegistry {
 impl TestRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
-            tests: HashMap::new(),
+            tests: BTreeMap::new(),
+            custom_tests: Vec::new(),
         };
 
         // Register all test categories
@@ -208,6 +533,7 @@ impl TestRegistry {
         self.register(SecurityTestEnum::Boot(
             BootSecurityTests::BootChainVerification,
         ));
+        self.register(SecurityTestEnum::Boot(BootSecurityTests::ImaEvmEnabled));
     }
 
     fn register_hardware_tests(&mut self) {
@@ -233,6 +559,15 @@ impl TestRegistry {
         self.register(SecurityTestEnum::Hardware(
             HardwareSecurityTests::UsbSecurity,
         ));
+        self.register(SecurityTestEnum::Hardware(
+            HardwareSecurityTests::FaultInjectionProtection,
+        ));
+        self.register(SecurityTestEnum::Hardware(
+            HardwareSecurityTests::IommuDmaProtection,
+        ));
+        self.register(SecurityTestEnum::Hardware(
+            HardwareSecurityTests::Tpm2Status,
+        ));
     }
 
     fn register_runtime_tests(&mut self) {
@@ -264,6 +599,27 @@ impl TestRegistry {
         self.register(SecurityTestEnum::Runtime(
             RuntimeSecurityTests::FoundriesLmpSecurity,
         ));
+        self.register(SecurityTestEnum::Runtime(
+            RuntimeSecurityTests::ApplicationAllowlisting,
+        ));
+        self.register(SecurityTestEnum::Runtime(
+            RuntimeSecurityTests::TmpHardening,
+        ));
+        self.register(SecurityTestEnum::Runtime(
+            RuntimeSecurityTests::ModuleLoadingRestriction,
+        ));
+        self.register(SecurityTestEnum::Runtime(
+            RuntimeSecurityTests::RuntimeAttestation,
+        ));
+        self.register(SecurityTestEnum::Runtime(
+            RuntimeSecurityTests::ModuleBlacklist,
+        ));
+        self.register(SecurityTestEnum::Runtime(
+            RuntimeSecurityTests::ServiceSandboxing,
+        ));
+        self.register(SecurityTestEnum::Runtime(
+            RuntimeSecurityTests::SensitiveFilePermissions,
+        ));
     }
 
     fn register_network_tests(&mut self) {
@@ -281,6 +637,12 @@ impl TestRegistry {
         self.register(SecurityTestEnum::Network(
             NetworkSecurityTests::NetworkEncryption,
         ));
+        self.register(SecurityTestEnum::Network(
+            NetworkSecurityTests::DiscoveryProtocols,
+        ));
+        self.register(SecurityTestEnum::Network(
+            NetworkSecurityTests::Ipv6Security,
+        ));
     }
 
     fn register_compliance_tests(&mut self) {
@@ -298,6 +660,15 @@ impl TestRegistry {
             ComplianceTests::IncidentResponse,
         ));
         self.register(SecurityTestEnum::Compliance(ComplianceTests::AuditLogging));
+        self.register(SecurityTestEnum::Compliance(
+            ComplianceTests::EgressFiltering,
+        ));
+        self.register(SecurityTestEnum::Compliance(
+            ComplianceTests::CryptoInventory,
+        ));
+        self.register(SecurityTestEnum::Compliance(
+            ComplianceTests::AuditRuleCoverage,
+        ));
     }
 
     fn register_container_tests(&mut self) {
@@ -323,6 +694,9 @@ impl TestRegistry {
         self.register(SecurityTestEnum::Container(
             ContainerSecurityTests::SeccompProfiles,
         ));
+        self.register(SecurityTestEnum::Container(
+            ContainerSecurityTests::PrivilegedContainers,
+        ));
     }
 
     fn register_certificate_tests(&mut self) {
@@ -357,6 +731,12 @@ impl TestRegistry {
         self.register(SecurityTestEnum::Certificate(
             CertificateTests::ComplianceStandards,
         ));
+        self.register(SecurityTestEnum::Certificate(
+            CertificateTests::OcspStapling,
+        ));
+        self.register(SecurityTestEnum::Certificate(
+            CertificateTests::CertificatePinning,
+        ));
     }
 
     fn register_production_tests(&mut self) {
@@ -397,9 +777,38 @@ impl TestRegistry {
         self.tests.insert(test.test_id().to_string(), test);
     }
 
-    pub fn get_tests_for_suite_and_mode(&self, suite: &TestSuite, mode: &TestMode) -> Vec<&str> {
+    pub fn get_tests_for_suite_and_mode(
+        &self,
+        suite: &TestSuite,
+        exclude_categories: &[String],
+        exclude_tests: &[String],
+        tags: &[String],
+        mode: &TestMode,
+    ) -> Vec<&str> {
         let mut test_ids = self.get_tests_for_suite(suite);
 
+        // Keep tests carrying any of the requested tags
+        if !tags.is_empty() {
+            test_ids.retain(|test_id| {
+                self.get_test(test_id).is_some_and(|test| {
+                    test.tags()
+                        .iter()
+                        .any(|tag| tags.iter().any(|wanted| wanted.eq_ignore_ascii_case(tag)))
+                })
+            });
+        }
+
+        // Drop excluded categories after suite expansion, before mode filtering
+        if !exclude_categories.is_empty() {
+            test_ids.retain(|test_id| {
+                self.get_test(test_id).is_some_and(|test| {
+                    !exclude_categories
+                        .iter()
+                        .any(|category| category.eq_ignore_ascii_case(test.category()))
+                })
+            });
+        }
+
         // Filter tests based on mode
         match mode {
             TestMode::PreProduction => {
@@ -412,6 +821,9 @@ impl TestRegistry {
             }
         }
 
+        // Drop individually excluded tests once the suite and mode are resolved
+        test_ids.retain(|test_id| !exclude_tests.iter().any(|excluded| excluded == test_id));
+
         test_ids
     }
 
@@ -426,10 +838,12 @@ impl TestRegistry {
             TestSuite::Container => self.get_tests_by_category("container"),
             TestSuite::Certificate => self.get_tests_by_category("certificate"),
             TestSuite::Production => self.get_tests_by_category("production"),
-            TestSuite::Custom => {
-                // TODO: Load from config file
-                vec![]
-            }
+            TestSuite::Custom => self
+                .custom_tests
+                .iter()
+                .filter_map(|test_id| self.tests.get_key_value(test_id))
+                .map(|(id, _)| id.as_str())
+                .collect(),
         }
     }
 
@@ -441,6 +855,44 @@ impl TestRegistry {
             .collect()
     }
 
+    /// Tests making up the custom suite, from `custom_tests` in the config file
+    pub fn set_custom_tests(&mut self, test_ids: Vec<String>) {
+        self.custom_tests = test_ids;
+    }
+
+    /// All known test categories, sorted
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = self.tests.values().map(|test| test.category()).collect();
+        categories.sort_unstable();
+        categories.dedup();
+        categories
+    }
+
+    /// Every tag carried by at least one test, sorted
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = self
+            .tests
+            .values()
+            .flat_map(|test| test.tags().iter().copied())
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    /// IDs of every registered test, sorted
+    pub fn test_ids(&self) -> Vec<&str> {
+        self.tests.keys().map(|id| id.as_str()).collect()
+    }
+
+    /// The first of `test_ids` that is not a registered test
+    pub fn unknown_test_id<'a>(&self, test_ids: &'a [String]) -> Option<&'a str> {
+        test_ids
+            .iter()
+            .map(String::as_str)
+            .find(|test_id| self.get_test(test_id).is_none())
+    }
+
     pub fn get_test(&self, test_id: &str) -> Option<&SecurityTestEnum> {
         self.tests.get(test_id)
     }
@@ -449,7 +901,7 @@ impl TestRegistry {
         println!("Available Security Compliance Tests:");
         println!("==================================");
 
-        let mut categories: HashMap<String, Vec<&str>> = HashMap::new();
+        let mut categories: BTreeMap<String, Vec<&str>> = BTreeMap::new();
 
         for (test_id, test) in &self.tests {
             categories
@@ -464,6 +916,17 @@ impl TestRegistry {
                 if let Some(test) = self.get_test(test_id) {
                     println!("  🔍 {} - {}", test.test_id(), test.test_name());
                     println!("      {}", test.description());
+                    if !test.tags().is_empty() {
+                        println!("      🏷️  Tags: {}", test.tags().join(", "));
+                    }
+                    if !test.applicable_machines().is_empty() {
+                        let machines: Vec<&str> = test
+                            .applicable_machines()
+                            .iter()
+                            .map(MachineType::as_str)
+                            .collect();
+                        println!("      🖥️  Machines: {}", machines.join(", "));
+                    }
                 }
             }
         }
@@ -495,9 +958,45 @@ pub fn create_test_result(
         duration,
         timestamp: Utc::now(),
         metadata: HashMap::new(),
+        remediation: None,
+        severity: Severity::default(),
+        skip_reason: None,
     }
 }
 
+/// Where sysctl remediation commands persist their settings
+pub const SYSCTL_REMEDIATION_FILE: &str = "/etc/sysctl.d/99-security-compliance.conf";
+
+/// Turn the "add to /etc/sysctl.conf" block that tests put in their details into
+/// commands that apply each setting now and persist it across reboots. The
+/// persisted line replaces any earlier one for the key, so running the
+/// commands again leaves a single entry.
+pub fn sysctl_remediation(details: &str) -> Vec<String> {
+    details
+        .lines()
+        .skip_while(|line| !line.contains("add to /etc/sysctl.conf"))
+        .skip(1)
+        .map(str::trim)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim(), value.trim()))
+        })
+        .flat_map(|(key, value)| {
+            [
+                format!("sysctl -w {}={}", key, value),
+                format!(
+                    "touch {file} && sed -i '/^{pattern} *=/d' {file} && echo '{} = {}' >> {file}",
+                    key,
+                    value,
+                    file = SYSCTL_REMEDIATION_FILE,
+                    pattern = key.replace('.', "\\."),
+                ),
+            ]
+        })
+        .collect()
+}
+
 pub async fn check_command_success(
     target: &mut Target,
     command: &str,
@@ -516,3 +1015,220 @@ pub async fn check_command_success(
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod helper_tests {
+    use super::*;
+
+    #[test]
+    fn test_fail_on_threshold_gates_by_severity() {
+        let finding = |status: TestStatus, severity: Severity| {
+            let mut result = create_test_result("t", "T", "boot", status, "", None, Duration::ZERO);
+            result.severity = severity;
+            result
+        };
+        let mut results = TestSuiteResults::sample(vec![
+            finding(TestStatus::Passed, Severity::Critical),
+            finding(TestStatus::Failed, Severity::Low),
+            finding(TestStatus::Warning, Severity::High),
+        ]);
+        assert!(!results.overall_passed());
+        assert_eq!(results.findings_at_or_above(Severity::High), 1);
+        assert_eq!(results.findings_at_or_above(Severity::Low), 2);
+
+        results.gate.fail_on = Some(Severity::Critical);
+        assert!(results.overall_passed());
+
+        results.gate.fail_on = Some(Severity::High);
+        assert!(!results.overall_passed());
+    }
+
+    #[test]
+    fn test_excluded_tests_are_removed_after_suite_resolution() {
+        let registry = TestRegistry::new();
+        let excluded = vec!["certificate_005".to_string(), "network_004".to_string()];
+        let all = registry.get_tests_for_suite_and_mode(
+            &TestSuite::All,
+            &[],
+            &[],
+            &[],
+            &TestMode::Production,
+        );
+        let remaining = registry.get_tests_for_suite_and_mode(
+            &TestSuite::All,
+            &[],
+            &excluded,
+            &[],
+            &TestMode::Production,
+        );
+
+        assert_eq!(remaining.len(), all.len() - 2);
+        assert!(!remaining.contains(&"certificate_005"));
+        assert!(!remaining.contains(&"network_004"));
+        assert_eq!(registry.unknown_test_id(&excluded), None);
+        assert_eq!(
+            registry.unknown_test_id(&["network_999".to_string()]),
+            Some("network_999")
+        );
+    }
+
+    #[test]
+    fn test_tags_select_union_of_tests() {
+        let registry = TestRegistry::new();
+        let by_tag = |tags: &[&str]| {
+            let tags: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+            registry.get_tests_for_suite_and_mode(
+                &TestSuite::All,
+                &[],
+                &[],
+                &tags,
+                &TestMode::Production,
+            )
+        };
+
+        let imx93 = by_tag(&["imx93"]);
+        assert!(imx93.contains(&"hardware_001"));
+        assert!(!imx93.contains(&"network_003"));
+
+        let union = by_tag(&["imx93", "WIRELESS"]);
+        assert!(union.contains(&"hardware_001") && union.contains(&"network_003"));
+        assert_eq!(union.len(), imx93.len() + by_tag(&["wireless"]).len());
+        assert!(registry.tags().contains(&"cra"));
+    }
+
+    #[test]
+    fn test_suites_list_tests_in_id_order() {
+        let registry = TestRegistry::new();
+        for suite in [TestSuite::All, TestSuite::Runtime] {
+            let ids = registry.get_tests_for_suite(&suite);
+            let mut sorted = ids.clone();
+            sorted.sort_unstable();
+            assert_eq!(ids, sorted);
+        }
+    }
+
+    #[test]
+    fn test_custom_suite_uses_configured_order() {
+        let mut registry = TestRegistry::new();
+        assert!(registry.get_tests_for_suite(&TestSuite::Custom).is_empty());
+
+        registry.set_custom_tests(vec![
+            "runtime_001".to_string(),
+            "boot_001".to_string(),
+            "unknown_001".to_string(),
+        ]);
+        assert_eq!(
+            registry.get_tests_for_suite(&TestSuite::Custom),
+            ["runtime_001", "boot_001"]
+        );
+    }
+
+    #[test]
+    fn test_duration_serializes_as_milliseconds() {
+        let mut result = create_test_result(
+            "boot_001",
+            "Secure Boot Enabled",
+            "boot",
+            TestStatus::Passed,
+            "ok",
+            None,
+            Duration::from_millis(1250),
+        );
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["duration"], 1250);
+
+        result = serde_json::from_value(json).unwrap();
+        assert_eq!(result.duration, Duration::from_millis(1250));
+
+        let mut legacy = serde_json::to_value(&result).unwrap();
+        legacy["duration"] = serde_json::json!({ "secs": 2, "nanos": 500_000_000 });
+        let result: TestResult = serde_json::from_value(legacy).unwrap();
+        assert_eq!(result.duration, Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn test_sysctl_remediation_from_details() {
+        let details = "KPTR_RESTRICT: 0\n\nTo make changes persistent, add to /etc/sysctl.conf:\n  kernel.kptr_restrict = 1\n  kernel.dmesg_restrict = 1\n\nTrailing notes = ignored";
+        assert_eq!(
+            sysctl_remediation(details),
+            vec![
+                "sysctl -w kernel.kptr_restrict=1".to_string(),
+                format!(
+                    "touch {0} && sed -i '/^kernel\\.kptr_restrict *=/d' {0} && echo 'kernel.kptr_restrict = 1' >> {0}",
+                    SYSCTL_REMEDIATION_FILE
+                ),
+                "sysctl -w kernel.dmesg_restrict=1".to_string(),
+                format!(
+                    "touch {0} && sed -i '/^kernel\\.dmesg_restrict *=/d' {0} && echo 'kernel.dmesg_restrict = 1' >> {0}",
+                    SYSCTL_REMEDIATION_FILE
+                ),
+            ]
+        );
+        assert!(sysctl_remediation("No recommendations").is_empty());
+    }
+
+    #[test]
+    fn test_gate_policy_controls_warnings_and_errors() {
+        let mut results = TestSuiteResults::sample(vec![
+            create_test_result(
+                "boot_001",
+                "Secure Boot",
+                "boot",
+                TestStatus::Warning,
+                "",
+                None,
+                Duration::ZERO,
+            ),
+            create_test_result(
+                "runtime_001",
+                "Filesystem Encryption",
+                "runtime",
+                TestStatus::Error,
+                "",
+                None,
+                Duration::ZERO,
+            ),
+        ]);
+        assert!(!results.overall_passed());
+
+        results.gate.errors_non_gating = true;
+        assert!(results.overall_passed());
+
+        results.gate.fail_on_warning_categories = vec!["Boot".to_string()];
+        assert!(!results.overall_passed());
+
+        results.gate.exit_zero_on_warning = true;
+        assert!(results.overall_passed());
+
+        results.gate.exit_zero_on_warning = false;
+        results.gate.fail_on_status = Some(FailOnStatus::Warning);
+        assert!(!results.overall_passed());
+        results.gate.exit_zero_on_warning = true;
+        assert!(results.overall_passed());
+        results.gate.exit_zero_on_warning = false;
+        results.gate.fail_on_status = Some(FailOnStatus::Failed);
+        assert!(results.overall_passed());
+        results.gate.errors_non_gating = false;
+        results.gate.fail_on_status = Some(FailOnStatus::Error);
+        assert!(!results.overall_passed());
+    }
+
+    #[test]
+    fn test_category_summary_groups_results() {
+        let result = |test_id: &str, category: &str, status: TestStatus| {
+            create_test_result(test_id, test_id, category, status, "", None, Duration::ZERO)
+        };
+        let results = TestSuiteResults::sample(vec![
+            result("network_001", "network", TestStatus::Passed),
+            result("boot_001", "boot", TestStatus::Warning),
+            result("network_002", "network", TestStatus::Failed),
+            result("network_003", "network", TestStatus::Passed),
+        ]);
+
+        let summary = results.category_summary();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].category, "network");
+        assert_eq!(summary[0].to_string(), "2/3 passed, 1 failed");
+        assert_eq!(summary[1].to_string(), "0/1 passed, 1 warnings");
+    }
+}
@@ -1,9 +1,16 @@
 use crate::{
-    error::Result,
+    cli::{Severity, TestMode},
+    error::{Error, Result},
+    runner::shell_quote,
     target::Target,
-    tests::{create_test_result, SecurityTest, TestResult, TestStatus},
+    tests::{
+        create_test_result, sysctl_remediation, SecurityTest, SkipReason, TestResult, TestStatus,
+    },
 };
 use async_trait::async_trait;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
 use std::time::Instant;
 
 #[derive(Debug, Clone)]
@@ -13,6 +20,8 @@ pub enum NetworkSecurityTests {
     WifiSecurity,
     BluetoothSecurity,
     NetworkEncryption,
+    DiscoveryProtocols,
+    Ipv6Security,
 }
 
 #[async_trait]
@@ -26,6 +35,8 @@ impl SecurityTest for NetworkSecurityTests {
             Self::WifiSecurity => self.test_wifi_security(target).await,
             Self::BluetoothSecurity => self.test_bluetooth_security(target).await,
             Self::NetworkEncryption => self.test_network_encryption(target).await,
+            Self::DiscoveryProtocols => self.test_discovery_protocols(target).await,
+            Self::Ipv6Security => self.test_ipv6_security(target).await,
         };
 
         let duration = start_time.elapsed();
@@ -40,6 +51,20 @@ impl SecurityTest for NetworkSecurityTests {
                 details,
                 duration,
             )),
+            Err(Error::Skipped {
+                reason,
+                message,
+                details,
+            }) => Ok(create_test_result(
+                self.test_id(),
+                self.test_name(),
+                self.category(),
+                TestStatus::Skipped,
+                &message,
+                details,
+                duration,
+            )
+            .with_skip_reason(reason)),
             Err(e) => Ok(create_test_result(
                 self.test_id(),
                 self.test_name(),
@@ -59,6 +84,8 @@ impl SecurityTest for NetworkSecurityTests {
             Self::WifiSecurity => "network_003",
             Self::BluetoothSecurity => "network_004",
             Self::NetworkEncryption => "network_005",
+            Self::DiscoveryProtocols => "network_006",
+            Self::Ipv6Security => "network_007",
         }
     }
 
@@ -69,6 +96,8 @@ impl SecurityTest for NetworkSecurityTests {
             Self::WifiSecurity => "WiFi Security Configuration",
             Self::BluetoothSecurity => "Bluetooth Security",
             Self::NetworkEncryption => "Network Encryption",
+            Self::DiscoveryProtocols => "Discovery Protocol Exposure",
+            Self::Ipv6Security => "IPv6 Security Configuration",
         }
     }
 
@@ -83,6 +112,43 @@ impl SecurityTest for NetworkSecurityTests {
             Self::WifiSecurity => "Validates WiFi security protocols and configuration to prevent wireless network attacks. Checks for WPA3/WPA2 encryption, secure authentication methods, and proper wireless security policies. Essential for protecting wireless communications from eavesdropping and unauthorized access.",
             Self::BluetoothSecurity => "Assesses Bluetooth security configuration and identifies potential vulnerabilities in wireless personal area network communications. Checks for secure pairing, encryption settings, and Bluetooth service security. Important for preventing Bluetooth-based attacks and unauthorized device connections.",
             Self::NetworkEncryption => "Verifies that network communications are properly encrypted using strong cryptographic protocols. Checks for TLS/SSL implementation, secure cipher suites, and encrypted communication channels. Fundamental for protecting data in transit from interception and manipulation.",
+            Self::DiscoveryProtocols => "Detects chatty service discovery and name resolution protocols (mDNS/Avahi, SSDP/UPnP, LLMNR, NetBIOS) listening on the device. These broadcast device details to the local network and expand the attack surface without being needed by most fixed-function field devices. Supports the ETSI EN 303 645 requirement to minimize exposed attack surfaces.",
+            Self::Ipv6Security => "Reviews IPv6, which is often enabled with permissive defaults next to a hardened IPv4 setup. Checks that router advertisements and ICMPv6 redirects are ignored so the device cannot be rerouted by a rogue host, and lists globally routable addresses and IPv6 listeners that IPv4-only firewall rules may not cover.",
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            Self::OpenPorts => &["cra", "red"],
+            Self::NetworkServices => &["cra", "red"],
+            Self::WifiSecurity => &["red", "wireless"],
+            Self::BluetoothSecurity => &["red", "wireless"],
+            Self::NetworkEncryption => &["cra", "red"],
+            Self::DiscoveryProtocols => &["cra", "red"],
+            Self::Ipv6Security => &["cra", "red"],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::OpenPorts => Severity::High,
+            Self::NetworkServices => Severity::High,
+            Self::WifiSecurity => Severity::High,
+            Self::BluetoothSecurity => Severity::Medium,
+            Self::NetworkEncryption => Severity::High,
+            Self::DiscoveryProtocols => Severity::Medium,
+            Self::Ipv6Security => Severity::Medium,
+        }
+    }
+
+    fn remediation(&self, result: &TestResult) -> Option<Vec<String>> {
+        match self {
+            Self::Ipv6Security => result
+                .details
+                .as_deref()
+                .map(sysctl_remediation)
+                .filter(|commands| !commands.is_empty()),
+            _ => None,
         }
     }
 }
@@ -92,6 +158,11 @@ impl NetworkSecurityTests {
         &self,
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
+        let allowed_ports = target.context().tests.allowed_ports.clone();
+        if !allowed_ports.is_empty() {
+            return self.test_open_ports_allowlist(target, &allowed_ports).await;
+        }
+
         // Check listening ports
         let netstat = target
             .execute_command("netstat -tuln 2>/dev/null || ss -tuln")
@@ -105,10 +176,9 @@ impl NetworkSecurityTests {
             .count();
 
         // Check for risky ports
-        let risky_ports = ["21", "23", "25", "53", "80", "135", "139", "445"];
         let mut open_risky = Vec::new();
 
-        for port in &risky_ports {
+        for port in RISKY_PORTS {
             // Use word boundaries to avoid false positives (e.g., :53 matching :5355)
             let port_patterns = [
                 format!(":{} ", port),  // Port followed by space
@@ -155,6 +225,97 @@ impl NetworkSecurityTests {
         }
     }
 
+    /// Classify every listening socket against the product's expected ports
+    async fn test_open_ports_allowlist(
+        &self,
+        target: &mut Target,
+        allowed_ports: &[u16],
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        // Owning processes of other users' sockets are only visible to root
+        let privileged = target
+            .execute_command(&format!(
+                "{} ss -tulnp 2>/dev/null",
+                target.elevation_pipe()
+            ))
+            .await?;
+        let listing = if privileged.exit_code == 0 && !privileged.stdout.trim().is_empty() {
+            privileged
+        } else {
+            target
+                .execute_command("ss -tulnp 2>/dev/null || netstat -tuln")
+                .await?
+        };
+
+        let sockets = parse_listening_sockets(&listing.stdout);
+        let (exposed, loopback): (Vec<_>, Vec<_>) =
+            sockets.iter().partition(|socket| !socket.is_loopback());
+        let (allowed, unexpected): (Vec<_>, Vec<_>) = exposed
+            .into_iter()
+            .partition(|socket| allowed_ports.contains(&socket.port));
+
+        let mut details = vec![format!(
+            "Allowed ports: {}",
+            allowed_ports
+                .iter()
+                .map(|port| port.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )];
+        details.push(format!("Unexpected ({}):", unexpected.len()));
+        details.extend(unexpected.iter().map(|socket| format!("  {}", socket)));
+        details.push(format!("Allowed ({}):", allowed.len()));
+        details.extend(allowed.iter().map(|socket| format!("  {}", socket)));
+        details.push(format!("Loopback only: {}", loopback.len()));
+        let details = Some(details.join("\n"));
+
+        if sockets.is_empty() {
+            return Ok((
+                TestStatus::Warning,
+                "Could not list listening sockets".to_string(),
+                details,
+            ));
+        }
+
+        let mut ports: Vec<(u16, &str)> = unexpected
+            .iter()
+            .map(|socket| (socket.port, socket.protocol.as_str()))
+            .collect();
+        ports.sort_unstable();
+        ports.dedup();
+        let ports: Vec<String> = ports
+            .into_iter()
+            .map(|(port, protocol)| format!("{}/{}", port, protocol))
+            .collect();
+
+        if unexpected.is_empty() {
+            Ok((
+                TestStatus::Passed,
+                format!(
+                    "Only allowed ports exposed ({} listening, {} loopback-only)",
+                    allowed.len(),
+                    loopback.len()
+                ),
+                details,
+            ))
+        } else if matches!(target.context().mode, TestMode::Production)
+            || unexpected
+                .iter()
+                .any(|socket| RISKY_PORTS.contains(&socket.port))
+        {
+            Ok((
+                TestStatus::Failed,
+                format!("Unexpected ports exposed: {}", ports.join(", ")),
+                details,
+            ))
+        } else {
+            Ok((
+                TestStatus::Warning,
+                format!("Unexpected ports exposed: {}", ports.join(", ")),
+                details,
+            ))
+        }
+    }
+
     async fn test_network_services(
         &self,
         target: &mut Target,
@@ -205,45 +366,82 @@ impl NetworkSecurityTests {
         &self,
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
-        // Check WiFi interface
-        let wifi_interfaces = target
-            .execute_command("iwconfig 2>/dev/null | grep -E 'IEEE 802.11|ESSID' || echo 'no_wifi'")
+        let iw_dev = target
+            .execute_command("iw dev 2>/dev/null || echo 'iw_not_available'")
             .await?;
+        let interfaces: Vec<&str> = iw_dev
+            .stdout
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Interface "))
+            .collect();
 
-        // Check WiFi security
-        let wifi_security = target
-            .execute_command("iw dev 2>/dev/null | grep -A10 Interface || echo 'iw_not_available'")
+        // The network the supplicant is associated with now, and every network it may join
+        let wpa_cli = target.execute_command("wpa_cli status 2>/dev/null").await?;
+        let wpa_conf = target
+            .execute_command("cat /etc/wpa_supplicant/*.conf /etc/wpa_supplicant.conf 2>/dev/null")
             .await?;
 
-        // Check for WPA supplicant
-        let wpa_status = target
-            .execute_command("systemctl is-active wpa_supplicant 2>/dev/null || echo 'not_active'")
-            .await?;
+        let mut networks = parse_wpa_supplicant_networks(&wpa_conf.stdout);
+        if let Some(current) = parse_wpa_cli_status(&wpa_cli.stdout) {
+            if !networks.iter().any(|n| n.ssid == current.ssid) {
+                networks.push(current);
+            }
+        }
 
-        let details = format!(
-            "WiFi interfaces: {}\nWiFi security: {}\nWPA status: {}",
-            wifi_interfaces.stdout,
-            wifi_security.stdout,
-            wpa_status.stdout.trim()
-        );
+        if interfaces.is_empty() && networks.is_empty() {
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No WiFi interface detected".to_string(),
+                details: None,
+            });
+        }
 
-        if wifi_interfaces.stdout.contains("no_wifi") {
+        let mut details = vec![format!(
+            "WiFi interfaces: {}",
+            if interfaces.is_empty() {
+                "none reported by iw".to_string()
+            } else {
+                interfaces.join(", ")
+            }
+        )];
+        if networks.is_empty() {
+            details.push("Configured networks: none found".to_string());
+            return Ok((
+                TestStatus::Warning,
+                "WiFi present but no network configuration could be read".to_string(),
+                Some(details.join("\n")),
+            ));
+        }
+        details.push("Configured networks:".to_string());
+        for network in &networks {
+            details.push(format!("  {}", network));
+        }
+
+        let count =
+            |level: WifiSecurityLevel| networks.iter().filter(|n| n.security == level).count();
+        let insecure = count(WifiSecurityLevel::Open) + count(WifiSecurityLevel::Wep);
+        let wpa2_psk = count(WifiSecurityLevel::Wpa2Psk);
+        let production = matches!(target.context().mode, TestMode::Production);
+
+        if insecure > 0 {
             Ok((
-                TestStatus::Skipped,
-                "No WiFi interface detected".to_string(),
-                None,
+                TestStatus::Failed,
+                format!("{} open or WEP WiFi networks configured", insecure),
+                Some(details.join("\n")),
             ))
-        } else if wpa_status.stdout.trim() == "active" {
+        } else if wpa2_psk > 0 && production {
+            details.push(String::new());
+            details.push("Use WPA3-SAE (key_mgmt=SAE, ieee80211w=2) or WPA2/3-Enterprise for production devices".to_string());
             Ok((
-                TestStatus::Passed,
-                "WiFi security (WPA) active".to_string(),
-                Some(details),
+                TestStatus::Warning,
+                format!("{} WiFi networks use WPA2-PSK rather than WPA3", wpa2_psk),
+                Some(details.join("\n")),
             ))
         } else {
             Ok((
-                TestStatus::Warning,
-                "WiFi present but security status unclear".to_string(),
-                Some(details),
+                TestStatus::Passed,
+                format!("All {} WiFi networks use WPA2 or stronger", networks.len()),
+                Some(details.join("\n")),
             ))
         }
     }
@@ -252,49 +450,110 @@ impl NetworkSecurityTests {
         &self,
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
-        // Check Bluetooth status
-        let bt_status = target
-            .execute_command("systemctl is-active bluetooth 2>/dev/null || echo 'not_active'")
+        let adapters = target
+            .execute_command("ls /sys/class/bluetooth 2>/dev/null")
             .await?;
+        let adapters: Vec<&str> = adapters
+            .stdout
+            .split_whitespace()
+            .filter(|name| name.starts_with("hci"))
+            .collect();
+        if adapters.is_empty() {
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No Bluetooth adapter detected".to_string(),
+                details: None,
+            });
+        }
 
-        // Check Bluetooth configuration
-        let hci_status = target
-            .execute_command("hciconfig 2>/dev/null || echo 'hci_not_available'")
+        let show = target
+            .execute_command("bluetoothctl show 2>/dev/null")
             .await?;
-
-        // Check if Bluetooth is discoverable
-        let bt_discoverable = target
+        let adapter = key_values(&show.stdout, ':');
+        let main_conf = target
+            .execute_command("cat /etc/bluetooth/main.conf 2>/dev/null")
+            .await?;
+        let conf = key_values(&main_conf.stdout, '=');
+        // BlueZ keeps the keys of each bonded device in /var/lib/bluetooth/<adapter>/<device>/info
+        let bonds = target
             .execute_command(
-                "hciconfig | grep -i 'ISCAN\\|PSCAN' 2>/dev/null || echo 'not_discoverable'",
+                "for f in /var/lib/bluetooth/*/*/info; do [ -r \"$f\" ] && echo \"[Device $f]\" && cat \"$f\"; done 2>/dev/null",
             )
             .await?;
+        let just_works = unauthenticated_bonds(&bonds.stdout);
 
-        let details = format!(
-            "BT status: {}\nHCI status: {}\nDiscoverable: {}",
-            bt_status.stdout.trim(),
-            hci_status.stdout,
-            bt_discoverable.stdout
-        );
+        let setting = |values: &HashMap<String, String>, key: &str| {
+            values
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string())
+        };
+        let powered = setting(&adapter, "Powered") == "yes";
+        let discoverable = setting(&adapter, "Discoverable") == "yes";
+        let pairable = setting(&adapter, "Pairable") == "yes";
+        // BlueZ defaults: discoverable for 180s, pairable with no time limit
+        let discoverable_timeout = conf
+            .get("DiscoverableTimeout")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(180);
+        let pairable_timeout = conf
+            .get("PairableTimeout")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(0);
 
-        if bt_status.stdout.trim() == "active" {
-            if bt_discoverable.stdout.contains("not_discoverable") {
-                Ok((
-                    TestStatus::Passed,
-                    "Bluetooth active but not discoverable".to_string(),
-                    Some(details),
-                ))
-            } else {
-                Ok((
-                    TestStatus::Warning,
-                    "Bluetooth active and may be discoverable".to_string(),
-                    Some(details),
-                ))
-            }
+        let mut details = vec![
+            format!("Adapters: {}", adapters.join(", ")),
+            format!("Powered: {}", setting(&adapter, "Powered")),
+            format!("Discoverable: {}", setting(&adapter, "Discoverable")),
+            format!("Pairable: {}", setting(&adapter, "Pairable")),
+            format!("DiscoverableTimeout (main.conf): {}s", discoverable_timeout),
+            format!("PairableTimeout (main.conf): {}s", pairable_timeout),
+        ];
+        if !just_works.is_empty() {
+            details.push("Paired without authentication (Just Works):".to_string());
+            details.extend(just_works.iter().map(|device| format!("  {}", device)));
+        }
+
+        let production = matches!(target.context().mode, TestMode::Production);
+        let mut failures = Vec::new();
+        let mut warnings = Vec::new();
+        if discoverable && discoverable_timeout == 0 {
+            failures.push("adapter is permanently discoverable".to_string());
+        } else if discoverable {
+            warnings.push("adapter is discoverable".to_string());
+        }
+        if pairable && pairable_timeout == 0 {
+            warnings.push("adapter is pairable with no PairableTimeout".to_string());
+        }
+        if !just_works.is_empty() {
+            warnings.push(format!(
+                "{} devices paired without authentication",
+                just_works.len()
+            ));
+        }
+        if production && powered {
+            warnings.push("adapter is powered in production".to_string());
+        }
+
+        let details = Some(details.join("\n"));
+        if !failures.is_empty() {
+            failures.extend(warnings);
+            Ok((
+                TestStatus::Failed,
+                format!("Bluetooth: {}", failures.join(", ")),
+                details,
+            ))
+        } else if !warnings.is_empty() {
+            Ok((
+                TestStatus::Warning,
+                format!("Bluetooth: {}", warnings.join(", ")),
+                details,
+            ))
         } else {
             Ok((
                 TestStatus::Passed,
-                "Bluetooth not active (secure)".to_string(),
-                Some(details),
+                "Bluetooth not discoverable and pairings authenticated".to_string(),
+                details,
             ))
         }
     }
@@ -304,16 +563,14 @@ impl NetworkSecurityTests {
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
         // Check for TLS/SSL support
-        let tls_support = target
-            .execute_command("openssl version 2>/dev/null || echo 'openssl_not_available'")
-            .await?;
+        let tls_version = if target.has_command("openssl").await {
+            Some(target.execute_command("openssl version").await?.stdout)
+        } else {
+            None
+        };
 
         // Check for VPN capabilities
-        let vpn_support = target
-            .execute_command(
-                "which openvpn 2>/dev/null || which strongswan 2>/dev/null || echo 'no_vpn'",
-            )
-            .await?;
+        let vpn_tool = target.first_command(&["openvpn", "strongswan"]).await;
 
         // Check for IPsec
         let ipsec_support = target
@@ -322,11 +579,11 @@ impl NetworkSecurityTests {
 
         let mut encryption_features = Vec::new();
 
-        if !tls_support.stdout.contains("openssl_not_available") {
+        if tls_version.is_some() {
             encryption_features.push("OpenSSL/TLS");
         }
 
-        if !vpn_support.stdout.contains("no_vpn") {
+        if vpn_tool.is_some() {
             encryption_features.push("VPN");
         }
 
@@ -337,8 +594,10 @@ impl NetworkSecurityTests {
 
         let details = format!(
             "TLS: {}\nVPN: {}\nIPsec policies: {}\nFeatures: {:?}",
-            tls_support.stdout.trim(),
-            vpn_support.stdout.trim(),
+            tls_version
+                .as_deref()
+                .map_or("openssl_not_available", str::trim),
+            vpn_tool.unwrap_or("no_vpn"),
             ipsec_policies,
             encryption_features
         );
@@ -363,4 +622,673 @@ impl NetworkSecurityTests {
             ))
         }
     }
+
+    async fn test_discovery_protocols(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        // Process names need privileges on most systems, so fall back to sudo
+        let command = target.probe_command(
+            "network_006.listeners",
+            "ss -tulnp 2>/dev/null || netstat -tulnp 2>/dev/null",
+        );
+        let mut listeners = target.execute_command(&command).await?;
+        if !listeners.stdout.contains("users:(") && !listeners.stdout.contains('/') {
+            let sudo_listeners = target
+                .execute_command(&format!(
+                    "{} sh -c {} 2>/dev/null",
+                    target.elevation_pipe(),
+                    shell_quote(&command)
+                ))
+                .await?;
+            if sudo_listeners.exit_code == 0 && !sudo_listeners.stdout.trim().is_empty() {
+                listeners = sudo_listeners;
+            }
+        }
+
+        if listeners.stdout.trim().is_empty() {
+            return Err(Error::Skipped {
+                reason: SkipReason::MissingTool,
+                message: "Could not list listening sockets (ss/netstat unavailable)".to_string(),
+                details: None,
+            });
+        }
+
+        let services = discovery_listeners(&listeners.stdout);
+        let mut details = String::from("Discovery services listening:\n");
+        if services.is_empty() {
+            details.push_str("  none\n");
+        }
+        for service in &services {
+            details.push_str(&format!("  • {}\n", service));
+        }
+        details.push_str(&format!("\nListening sockets:\n{}", listeners.stdout));
+
+        if services.is_empty() {
+            Ok((
+                TestStatus::Passed,
+                "No mDNS, SSDP, LLMNR or NetBIOS discovery services listening".to_string(),
+                Some(details),
+            ))
+        } else {
+            details.push_str(
+                "\nDiscovery protocols advertise the device and its services on the local network.\n\
+                 Disable them on fixed-function devices unless a feature depends on them, e.g.:\n\
+                 systemctl disable --now avahi-daemon minissdpd nmbd\n\
+                 resolvectl llmnr <interface> no  (or LLMNR=no in /etc/systemd/resolved.conf)\n",
+            );
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "{} discovery service(s) active: {}",
+                    services.len(),
+                    services
+                        .iter()
+                        .map(|service| service.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                Some(details),
+            ))
+        }
+    }
+
+    async fn test_ipv6_security(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let mut settings = Vec::new();
+        for key in ["disable_ipv6", "accept_ra", "accept_redirects"] {
+            let value = target
+                .execute_command(&format!(
+                    "cat /proc/sys/net/ipv6/conf/all/{} 2>/dev/null",
+                    key
+                ))
+                .await?;
+            settings.push((key, value.stdout.trim().to_string()));
+        }
+        let setting = |key: &str| {
+            settings
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.as_str())
+                .unwrap_or("")
+        };
+
+        if setting("disable_ipv6").is_empty() {
+            return Ok((
+                TestStatus::Passed,
+                "IPv6 not built into the kernel".to_string(),
+                None,
+            ));
+        }
+        if setting("disable_ipv6") == "1" {
+            return Ok((
+                TestStatus::Passed,
+                "IPv6 disabled (net.ipv6.conf.all.disable_ipv6=1)".to_string(),
+                None,
+            ));
+        }
+
+        let mut details: Vec<String> = settings
+            .iter()
+            .map(|(key, value)| format!("net.ipv6.conf.all.{}: {}", key, value))
+            .collect();
+
+        let addresses = target
+            .execute_command("ip -6 addr show scope global 2>/dev/null")
+            .await?;
+        let global_addresses: Vec<&str> = addresses
+            .stdout
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("inet6 "))
+            .filter_map(|rest| rest.split_whitespace().next())
+            .collect();
+        details.push(String::new());
+        if global_addresses.is_empty() {
+            details.push("Globally routable IPv6 addresses: none".to_string());
+        } else {
+            details.push("Globally routable IPv6 addresses:".to_string());
+            for address in &global_addresses {
+                details.push(format!("  {}", address));
+            }
+        }
+
+        let sockets = target
+            .execute_command("ss -6 -tuln 2>/dev/null || netstat -6 -tuln 2>/dev/null")
+            .await?;
+        let listeners = ipv6_listeners(&sockets.stdout);
+        if listeners.is_empty() {
+            details.push("IPv6 listeners: none beyond loopback".to_string());
+        } else {
+            details.push("IPv6 listeners:".to_string());
+            for listener in &listeners {
+                details.push(format!("  {}", listener));
+            }
+        }
+
+        let mut issues = Vec::new();
+        let mut persistent = Vec::new();
+        if setting("accept_ra") != "0" {
+            issues.push("router advertisements accepted");
+            persistent.push("  net.ipv6.conf.all.accept_ra = 0");
+            persistent.push("  net.ipv6.conf.default.accept_ra = 0");
+        }
+        if setting("accept_redirects") != "0" {
+            issues.push("ICMPv6 redirects accepted");
+            persistent.push("  net.ipv6.conf.all.accept_redirects = 0");
+            persistent.push("  net.ipv6.conf.default.accept_redirects = 0");
+        }
+
+        let exposure = format!(
+            "{} global addresses, {} listeners",
+            global_addresses.len(),
+            listeners.len()
+        );
+        if issues.is_empty() {
+            Ok((
+                TestStatus::Passed,
+                format!("IPv6 hardened ({})", exposure),
+                Some(details.join("\n")),
+            ))
+        } else {
+            details.push(String::new());
+            details.push("To make changes persistent, add to /etc/sysctl.conf:".to_string());
+            details.extend(persistent.iter().map(|line| line.to_string()));
+            Ok((
+                TestStatus::Warning,
+                format!("IPv6 {} ({})", issues.join(" and "), exposure),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+}
+
+/// How a WiFi network authenticates, weakest first
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WifiSecurityLevel {
+    Open,
+    Wep,
+    Wpa2Psk,
+    Wpa3Sae,
+    Enterprise,
+}
+
+impl WifiSecurityLevel {
+    /// Classify wpa_supplicant `key_mgmt` values, from the config file (`WPA-PSK SAE`)
+    /// or `wpa_cli status` (`WPA2-PSK`, `WPA2/IEEE 802.1X/EAP`). Transition mode
+    /// that still accepts PSK counts as WPA2-PSK, since clients can be downgraded.
+    fn classify(key_mgmt: &str, wep: bool) -> Self {
+        let key_mgmt = key_mgmt.to_ascii_uppercase();
+        if key_mgmt.contains("PSK") {
+            Self::Wpa2Psk
+        } else if key_mgmt.contains("EAP") || key_mgmt.contains("802.1X") {
+            Self::Enterprise
+        } else if key_mgmt.contains("SAE") {
+            Self::Wpa3Sae
+        } else if wep {
+            Self::Wep
+        } else {
+            Self::Open
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Wep => "WEP",
+            Self::Wpa2Psk => "WPA2-PSK",
+            Self::Wpa3Sae => "WPA3-SAE",
+            Self::Enterprise => "WPA2/3-Enterprise",
+        }
+    }
+}
+
+/// A network the device is configured to join
+#[derive(Debug, Clone, PartialEq)]
+struct WifiNetwork {
+    ssid: String,
+    key_mgmt: String,
+    security: WifiSecurityLevel,
+}
+
+impl std::fmt::Display for WifiNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} (key_mgmt={})",
+            mask_ssid(&self.ssid),
+            self.security.label(),
+            self.key_mgmt
+        )
+    }
+}
+
+/// Keep only the first and last characters of an SSID so reports identify
+/// the network without disclosing it
+fn mask_ssid(ssid: &str) -> String {
+    let chars: Vec<char> = ssid.chars().collect();
+    match chars.len() {
+        0 => "<hidden>".to_string(),
+        1..=2 => "*".repeat(chars.len()),
+        n => format!("{}{}{}", chars[0], "*".repeat(n - 2), chars[n - 1]),
+    }
+}
+
+/// Networks from the `network={...}` blocks of wpa_supplicant.conf files
+fn parse_wpa_supplicant_networks(conf: &str) -> Vec<WifiNetwork> {
+    let mut networks = Vec::new();
+    let mut block: Option<Vec<(String, String)>> = None;
+
+    for line in conf.lines() {
+        let line = line.trim();
+        if line.starts_with("network={") {
+            block = Some(Vec::new());
+        } else if line == "}" {
+            let Some(fields) = block.take() else {
+                continue;
+            };
+            let field = |key: &str| {
+                fields
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| v.trim_matches('"').to_string())
+            };
+            // wpa_supplicant's default when key_mgmt is not given
+            let key_mgmt = field("key_mgmt").unwrap_or_else(|| "WPA-PSK WPA-EAP".to_string());
+            let wep = fields.iter().any(|(k, _)| k.starts_with("wep_key"));
+            networks.push(WifiNetwork {
+                ssid: field("ssid").unwrap_or_default(),
+                security: WifiSecurityLevel::classify(&key_mgmt, wep),
+                key_mgmt,
+            });
+        } else if let (Some(fields), Some((key, value))) = (block.as_mut(), line.split_once('=')) {
+            fields.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    networks
+}
+
+/// The network `wpa_cli status` reports as associated, if any
+fn parse_wpa_cli_status(status: &str) -> Option<WifiNetwork> {
+    let field = |key: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+    };
+    let ssid = field("ssid")?;
+    let key_mgmt = field("key_mgmt").unwrap_or("NONE");
+    let wep = field("pairwise_cipher").is_some_and(|cipher| cipher.starts_with("WEP"));
+    Some(WifiNetwork {
+        ssid: ssid.to_string(),
+        key_mgmt: key_mgmt.to_string(),
+        security: WifiSecurityLevel::classify(key_mgmt, wep),
+    })
+}
+
+/// `key<sep>value` lines such as `bluetoothctl show` or an ini file, with
+/// keys and values trimmed; section headers and comments are skipped
+fn key_values(output: &str, separator: char) -> HashMap<String, String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#') && !line.starts_with('['))
+        .filter_map(|line| line.split_once(separator))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Devices in a dump of BlueZ bond info files whose keys were created without
+/// MITM protection: an unauthenticated link key (types 4 and 7) or an LE
+/// long term key with `Authenticated=0`
+fn unauthenticated_bonds(dump: &str) -> Vec<String> {
+    let mut devices = Vec::new();
+    let mut device: Option<&str> = None;
+    let mut section = "";
+
+    for line in dump.lines().map(str::trim) {
+        if let Some(path) = line
+            .strip_prefix("[Device ")
+            .and_then(|l| l.strip_suffix(']'))
+        {
+            // .../<adapter>/<device>/info
+            device = path.rsplit('/').nth(1);
+            continue;
+        }
+        if line.starts_with('[') {
+            section = line;
+            continue;
+        }
+        let Some(name) = device else {
+            continue;
+        };
+        let unauthenticated = match (section, line.split_once('=')) {
+            ("[LinkKey]", Some(("Type", value))) => matches!(value.trim(), "4" | "7"),
+            ("[LongTermKey]", Some(("Authenticated", value))) => value.trim() == "0",
+            _ => false,
+        };
+        if unauthenticated && !devices.iter().any(|d| d == name) {
+            devices.push(name.to_string());
+        }
+    }
+
+    devices
+}
+
+/// Well-known discovery and name-resolution ports: (port, protocol label)
+const DISCOVERY_PORTS: &[(u16, &str)] = &[
+    (5353, "mDNS"),
+    (1900, "SSDP/UPnP"),
+    (5355, "LLMNR"),
+    (137, "NetBIOS name"),
+    (138, "NetBIOS datagram"),
+    (139, "NetBIOS session"),
+];
+
+/// A discovery protocol socket found in the listening socket table
+#[derive(Debug, Clone, PartialEq)]
+struct DiscoveryService {
+    protocol: &'static str,
+    transport: String,
+    port: u16,
+    process: Option<String>,
+}
+
+impl std::fmt::Display for DiscoveryService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}/{})", self.protocol, self.transport, self.port)?;
+        if let Some(process) = &self.process {
+            write!(f, " by {}", process)?;
+        }
+        Ok(())
+    }
+}
+
+/// The owning process name in an `ss -p` `users:(("name",pid=..))` column
+fn ss_process() -> &'static Regex {
+    static SS_PROCESS: OnceLock<Regex> = OnceLock::new();
+    SS_PROCESS.get_or_init(|| Regex::new(r#"users:\(\("([^"]+)""#).expect("valid ss process regex"))
+}
+
+/// Pick discovery services out of `ss -tulnp` or `netstat -tulnp` output,
+/// reporting each protocol/transport/port/process combination once. Services
+/// bound only to loopback can't be discovered from the network and are left out.
+fn discovery_listeners(output: &str) -> Vec<DiscoveryService> {
+    let ss_process = ss_process();
+    let mut services: Vec<DiscoveryService> = Vec::new();
+
+    for line in output.lines() {
+        let mut fields = line.split_whitespace();
+        let transport = match fields.next() {
+            Some(netid) if netid.starts_with("tcp") => "tcp",
+            Some(netid) if netid.starts_with("udp") => "udp",
+            _ => continue,
+        };
+        let fields: Vec<&str> = fields.collect();
+
+        // The local address is the first "<addr>:<port>" column
+        let Some((address, port)) = fields.iter().find_map(|field| {
+            let (address, port) = field.rsplit_once(':')?;
+            Some((address, port.parse::<u16>().ok()?))
+        }) else {
+            continue;
+        };
+        let Some((_, protocol)) = DISCOVERY_PORTS.iter().find(|(known, _)| *known == port) else {
+            continue;
+        };
+
+        let process = ss_process
+            .captures(line)
+            .map(|captures| captures[1].to_string())
+            .or_else(|| {
+                fields
+                    .last()
+                    .and_then(|field| field.split_once('/'))
+                    .filter(|(pid, _)| pid.chars().all(|c| c.is_ascii_digit()))
+                    .map(|(_, name)| name.to_string())
+            });
+
+        let socket = ListeningSocket {
+            protocol: transport.to_string(),
+            address: address.to_string(),
+            port,
+            process,
+        };
+        if socket.is_loopback() {
+            continue;
+        }
+
+        let service = DiscoveryService {
+            protocol,
+            transport: socket.protocol,
+            port,
+            process: socket.process,
+        };
+        if !services.contains(&service) {
+            services.push(service);
+        }
+    }
+
+    services
+}
+
+/// Plaintext or commonly attacked services that fail network_001 when exposed
+const RISKY_PORTS: &[u16] = &[21, 23, 25, 53, 80, 135, 139, 445];
+
+/// A listening socket from `ss -tulnp` or `netstat -tuln`
+#[derive(Debug, Clone, PartialEq)]
+struct ListeningSocket {
+    protocol: String,
+    address: String,
+    port: u16,
+    process: Option<String>,
+}
+
+impl ListeningSocket {
+    fn is_loopback(&self) -> bool {
+        let address = self.address.trim_start_matches('[').trim_end_matches(']');
+        address.starts_with("127.") || address == "::1" || address.ends_with("%lo")
+    }
+}
+
+impl std::fmt::Display for ListeningSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} on {}", self.port, self.protocol, self.address)?;
+        if let Some(process) = &self.process {
+            write!(f, " by {}", process)?;
+        }
+        Ok(())
+    }
+}
+
+/// Listening TCP and bound UDP sockets from `ss -tulnp` or `netstat -tuln`
+fn parse_listening_sockets(output: &str) -> Vec<ListeningSocket> {
+    let ss_process = ss_process();
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let protocol = match fields.next() {
+                Some(netid) if netid.starts_with("tcp") => "tcp",
+                Some(netid) if netid.starts_with("udp") => "udp",
+                _ => return None,
+            };
+            if protocol == "tcp" && !line.contains("LISTEN") {
+                return None;
+            }
+
+            // The local address is the first "<addr>:<port>" column
+            let (address, port) = fields.find_map(|field| {
+                let (address, port) = field.rsplit_once(':')?;
+                Some((address, port.parse::<u16>().ok()?))
+            })?;
+
+            Some(ListeningSocket {
+                protocol: protocol.to_string(),
+                address: address.to_string(),
+                port,
+                process: ss_process
+                    .captures(line)
+                    .map(|captures| captures[1].to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Local addresses of IPv6 sockets from `ss -6 -tuln` or `netstat -6 -tuln`,
+/// as `proto address`, leaving out loopback-only ones
+fn ipv6_listeners(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let proto = fields.first()?;
+            if !proto.starts_with("tcp") && !proto.starts_with("udp") {
+                return None;
+            }
+            // ss puts the local address fifth, netstat fourth
+            let local = fields
+                .iter()
+                .skip(3)
+                .take(2)
+                .find(|field| field.contains(':'))?;
+            if local.starts_with("[::1]") || local.starts_with("::1:") {
+                return None;
+            }
+            Some(format!("{} {}", proto.trim_end_matches('6'), local))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_listeners_parses_ss_and_netstat() {
+        let ss = "Netid State  Recv-Q Send-Q Local Address:Port Peer Address:Port Process\n\
+                  udp   UNCONN 0      0            0.0.0.0:5353      0.0.0.0:*     users:((\"avahi-daemon\",pid=412,fd=12))\n\
+                  udp   UNCONN 0      0               [::]:5353         [::]:*     users:((\"avahi-daemon\",pid=412,fd=13))\n\
+                  tcp   LISTEN 0      128          0.0.0.0:22        0.0.0.0:*     users:((\"sshd\",pid=300,fd=3))\n\
+                  tcp   LISTEN 0      4096      127.0.0.53%lo:5355   0.0.0.0:*\n\
+                  udp   UNCONN 0      0               [::1]:5353        [::]:*\n\
+                  tcp   LISTEN 0      4096          0.0.0.0:5355      0.0.0.0:*     users:((\"systemd-resolve\",pid=310,fd=12))\n";
+        let netstat = "udp        0      0 0.0.0.0:1900            0.0.0.0:*                           611/minissdpd\n";
+
+        let services = discovery_listeners(ss);
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[0].to_string(), "mDNS (udp/5353) by avahi-daemon");
+        assert_eq!(
+            services[1].to_string(),
+            "LLMNR (tcp/5355) by systemd-resolve"
+        );
+
+        let services = discovery_listeners(netstat);
+        assert_eq!(services[0].to_string(), "SSDP/UPnP (udp/1900) by minissdpd");
+    }
+
+    #[test]
+    fn test_ipv6_listeners_skips_loopback() {
+        let ss = "Netid State  Recv-Q Send-Q Local Address:Port Peer Address:Port\n\
+                  tcp   LISTEN 0      128            [::]:22           [::]:*\n\
+                  tcp   LISTEN 0      128           [::1]:631          [::]:*\n\
+                  udp   UNCONN 0      0        [fe80::1%eth0]:546      [::]:*\n";
+        assert_eq!(
+            ipv6_listeners(ss),
+            vec!["tcp [::]:22", "udp [fe80::1%eth0]:546"]
+        );
+
+        let netstat = "tcp6       0      0 :::80                   :::*                    LISTEN\n\
+                       tcp6       0      0 ::1:25                  :::*                    LISTEN\n";
+        assert_eq!(ipv6_listeners(netstat), vec!["tcp :::80"]);
+    }
+
+    #[test]
+    fn test_wifi_networks_classified_by_key_management() {
+        let conf = "ctrl_interface=/var/run/wpa_supplicant\n\
+                    network={\n  ssid=\"HomeNet\"\n  psk=\"secret\"\n}\n\
+                    network={\n  ssid=\"Factory\"\n  key_mgmt=SAE\n  ieee80211w=2\n}\n\
+                    network={\n  ssid=\"Corp\"\n  key_mgmt=WPA-EAP\n  eap=TLS\n}\n\
+                    network={\n  ssid=\"Legacy\"\n  key_mgmt=NONE\n  wep_key0=\"abcde\"\n}\n\
+                    network={\n  ssid=\"Cafe\"\n  key_mgmt=NONE\n}\n\
+                    network={\n  ssid=\"Mixed\"\n  key_mgmt=WPA-PSK SAE\n}\n";
+        let levels: Vec<WifiSecurityLevel> = parse_wpa_supplicant_networks(conf)
+            .iter()
+            .map(|n| n.security)
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                WifiSecurityLevel::Wpa2Psk,
+                WifiSecurityLevel::Wpa3Sae,
+                WifiSecurityLevel::Enterprise,
+                WifiSecurityLevel::Wep,
+                WifiSecurityLevel::Open,
+                WifiSecurityLevel::Wpa2Psk,
+            ]
+        );
+
+        let status = "bssid=aa:bb:cc:dd:ee:ff\nssid=Factory\npairwise_cipher=CCMP\nkey_mgmt=SAE\nwpa_state=COMPLETED\n";
+        let current = parse_wpa_cli_status(status).unwrap();
+        assert_eq!(current.security, WifiSecurityLevel::Wpa3Sae);
+        assert_eq!(current.to_string(), "F*****y: WPA3-SAE (key_mgmt=SAE)");
+        assert!(parse_wpa_cli_status("wpa_state=DISCONNECTED\n").is_none());
+    }
+
+    #[test]
+    fn test_bluetooth_state_and_unauthenticated_bonds() {
+        let show = "Controller 00:1A:7D:DA:71:13 (public)\n\
+                    \tPowered: yes\n\tDiscoverable: no\n\tPairable: yes\n";
+        let adapter = key_values(show, ':');
+        assert_eq!(adapter["Powered"], "yes");
+        assert_eq!(adapter["Discoverable"], "no");
+
+        let conf = key_values(
+            "[General]\n#DiscoverableTimeout = 0\nPairableTimeout = 30\n",
+            '=',
+        );
+        assert_eq!(conf.get("DiscoverableTimeout"), None);
+        assert_eq!(conf["PairableTimeout"], "30");
+
+        let dump = "[Device /var/lib/bluetooth/00:1A:7D:DA:71:13/AA:BB:CC:DD:EE:01/info]\n\
+                    [General]\nName=Headset\n[LinkKey]\nKey=0011\nType=4\n\
+                    [Device /var/lib/bluetooth/00:1A:7D:DA:71:13/AA:BB:CC:DD:EE:02/info]\n\
+                    [LinkKey]\nType=5\n[LongTermKey]\nAuthenticated=1\n\
+                    [Device /var/lib/bluetooth/00:1A:7D:DA:71:13/AA:BB:CC:DD:EE:03/info]\n\
+                    [LongTermKey]\nAuthenticated=0\n";
+        assert_eq!(
+            unauthenticated_bonds(dump),
+            vec!["AA:BB:CC:DD:EE:01", "AA:BB:CC:DD:EE:03"]
+        );
+    }
+
+    #[test]
+    fn test_parse_listening_sockets() {
+        let ss = "Netid State  Recv-Q Send-Q Local Address:Port Peer Address:Port Process\n\
+                  udp   UNCONN 0      0      127.0.0.53%lo:53     0.0.0.0:*     users:((\"systemd-resolve\",pid=310,fd=13))\n\
+                  tcp   LISTEN 0      128    0.0.0.0:22           0.0.0.0:*     users:((\"sshd\",pid=401,fd=3))\n\
+                  tcp   LISTEN 0      128    [::]:8080            [::]:*        users:((\"node\",pid=777,fd=20))\n\
+                  tcp   LISTEN 0      128    [::1]:631            [::]:*\n\
+                  tcp   ESTAB  0      0      10.0.0.2:22          10.0.0.9:51234\n";
+
+        let sockets = parse_listening_sockets(ss);
+        assert_eq!(sockets.len(), 4);
+        assert!(sockets[0].is_loopback());
+        assert!(sockets[3].is_loopback());
+        assert!(!sockets[1].is_loopback());
+        assert_eq!(sockets[1].to_string(), "22/tcp on 0.0.0.0 by sshd");
+        assert_eq!(sockets[2].address, "[::]");
+        assert_eq!(sockets[2].port, 8080);
+
+        let netstat = "Proto Recv-Q Send-Q Local Address  Foreign Address State\n\
+                       tcp        0      0 0.0.0.0:23     0.0.0.0:*       LISTEN\n\
+                       udp        0      0 0.0.0.0:5353   0.0.0.0:*\n";
+        let sockets = parse_listening_sockets(netstat);
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0].port, 23);
+        assert_eq!(sockets[1].protocol, "udp");
+        assert_eq!(sockets[1].process, None);
+    }
 }
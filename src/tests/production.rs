@@ -1,7 +1,8 @@
 use crate::{
-    error::Result,
+    cli::Severity,
+    error::{Error, Result},
     target::Target,
-    tests::{create_test_result, SecurityTest, TestResult, TestStatus},
+    tests::{create_test_result, sysctl_remediation, SecurityTest, TestResult, TestStatus},
 };
 use async_trait::async_trait;
 use std::time::Instant;
@@ -52,6 +53,20 @@ impl SecurityTest for ProductionTests {
                 details,
                 duration,
             )),
+            Err(Error::Skipped {
+                reason,
+                message,
+                details,
+            }) => Ok(create_test_result(
+                self.test_id(),
+                self.test_name(),
+                self.category(),
+                TestStatus::Skipped,
+                &message,
+                details,
+                duration,
+            )
+            .with_skip_reason(reason)),
             Err(e) => Ok(create_test_result(
                 self.test_id(),
                 self.test_name(),
@@ -112,6 +127,47 @@ impl SecurityTest for ProductionTests {
             Self::FileSystemHardening => "Validates filesystem security hardening including proper permissions, access controls, and security attributes. Checks for secure mount options, file permissions, and directory restrictions. Important for preventing unauthorized file access and privilege escalation through filesystem vulnerabilities.",
         }
     }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            Self::DebugInterfacesDisabled => &["cra"],
+            Self::DevelopmentToolsRemoved => &["cra"],
+            Self::DefaultCredentialsChanged => &["cra", "red"],
+            Self::UnnecessaryServicesDisabled => &["cra"],
+            Self::LoggingConfigured => &["cra"],
+            Self::MonitoringEnabled => &["cra"],
+            Self::BackupSystemsActive => &["cra"],
+            Self::SecurityUpdatesEnabled => &["cra", "red"],
+            Self::NetworkHardening => &["cra", "red"],
+            Self::FileSystemHardening => &["cra"],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::DebugInterfacesDisabled => Severity::Critical,
+            Self::DevelopmentToolsRemoved => Severity::High,
+            Self::DefaultCredentialsChanged => Severity::Critical,
+            Self::UnnecessaryServicesDisabled => Severity::Medium,
+            Self::LoggingConfigured => Severity::Medium,
+            Self::MonitoringEnabled => Severity::Low,
+            Self::BackupSystemsActive => Severity::Low,
+            Self::SecurityUpdatesEnabled => Severity::High,
+            Self::NetworkHardening => Severity::High,
+            Self::FileSystemHardening => Severity::Medium,
+        }
+    }
+
+    fn remediation(&self, result: &TestResult) -> Option<Vec<String>> {
+        match self {
+            Self::NetworkHardening => result
+                .details
+                .as_deref()
+                .map(sysctl_remediation)
+                .filter(|commands| !commands.is_empty()),
+            _ => None,
+        }
+    }
 }
 
 impl ProductionTests {
@@ -213,10 +269,7 @@ impl ProductionTests {
         let mut found_tools = Vec::new();
 
         for tool in &dev_tools {
-            let tool_check = target
-                .execute_command(&format!("which {} 2>/dev/null || echo 'not_found'", tool))
-                .await?;
-            if !tool_check.stdout.contains("not_found") {
+            if target.has_command(tool).await {
                 found_tools.push(*tool);
             }
         }
@@ -490,35 +543,37 @@ impl ProductionTests {
         }
 
         // Check system monitoring
-        let system_stats = target.execute_command("which top 2>/dev/null && which ps 2>/dev/null && which iostat 2>/dev/null || echo 'basic_tools_missing'").await?;
+        let mut system_tools_available = true;
+        for tool in ["top", "ps", "iostat"] {
+            if !target.has_command(tool).await {
+                system_tools_available = false;
+            }
+        }
 
         // Check log monitoring
         let log_monitoring = target.execute_command("ps aux | grep -E 'logwatch|fail2ban|swatch' | grep -v grep || echo 'no_log_monitoring'").await?;
 
         // Check network monitoring
-        let network_monitoring = target
-            .execute_command(
-                "which netstat 2>/dev/null && which ss 2>/dev/null || echo 'network_tools_missing'",
-            )
-            .await?;
+        let network_tools_available =
+            target.has_command("netstat").await && target.has_command("ss").await;
 
         let details = format!(
             "Active monitoring: {:?}\nSystem tools: {}\nLog monitoring: {}\nNetwork tools: {}",
             active_monitoring,
-            if system_stats.stdout.contains("basic_tools_missing") {
-                "Missing"
-            } else {
+            if system_tools_available {
                 "Available"
+            } else {
+                "Missing"
             },
             if log_monitoring.stdout.contains("no_log_monitoring") {
                 "None"
             } else {
                 "Active"
             },
-            if network_monitoring.stdout.contains("network_tools_missing") {
-                "Missing"
-            } else {
+            if network_tools_available {
                 "Available"
+            } else {
+                "Missing"
             }
         );
 
@@ -528,9 +583,7 @@ impl ProductionTests {
                 "Comprehensive monitoring enabled".to_string(),
                 Some(details),
             ))
-        } else if !active_monitoring.is_empty()
-            || !system_stats.stdout.contains("basic_tools_missing")
-        {
+        } else if !active_monitoring.is_empty() || system_tools_available {
             Ok((
                 TestStatus::Warning,
                 "Basic monitoring available".to_string(),
@@ -554,10 +607,7 @@ impl ProductionTests {
         let mut available_tools = Vec::new();
 
         for tool in &backup_tools {
-            let tool_check = target
-                .execute_command(&format!("which {} 2>/dev/null", tool))
-                .await?;
-            if !tool_check.stdout.trim().is_empty() {
+            if target.has_command(tool).await {
                 available_tools.push(*tool);
             }
         }
@@ -723,7 +773,7 @@ impl ProductionTests {
             hardening_features.push("TCP wrappers configured");
         }
 
-        let details = format!(
+        let mut details = format!(
             "Firewall: {}\nNetwork params: {}\nRisky services: {}\nTCP wrappers: {}",
             firewall_status.stdout.trim(),
             if net_params.stdout.contains("no_sysctl") {
@@ -735,6 +785,27 @@ impl ProductionTests {
             wrapper_files
         );
 
+        // Recommend the hardened value for any network parameter that is not already set
+        let unhardened: Vec<&str> = [
+            "net.ipv4.ip_forward",
+            "net.ipv4.conf.all.send_redirects",
+            "net.ipv4.conf.all.accept_redirects",
+        ]
+        .into_iter()
+        .filter(|param| {
+            net_params.stdout.lines().any(|line| {
+                line.split_once('=')
+                    .is_some_and(|(key, value)| key.trim() == *param && value.trim() != "0")
+            })
+        })
+        .collect();
+        if !unhardened.is_empty() {
+            details.push_str("\n\nTo make changes persistent, add to /etc/sysctl.conf:");
+            for param in unhardened {
+                details.push_str(&format!("\n  {} = 0", param));
+            }
+        }
+
         if hardening_features.len() >= 3 {
             Ok((
                 TestStatus::Passed,
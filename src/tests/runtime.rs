@@ -1,9 +1,13 @@
 use crate::{
-    error::Result,
+    cli::{Severity, TestMode},
+    error::{Error, Result},
     target::Target,
-    tests::{create_test_result, SecurityTest, TestResult, TestStatus},
+    tests::{
+        create_test_result, sysctl_remediation, SecurityTest, SkipReason, TestResult, TestStatus,
+    },
 };
 use async_trait::async_trait;
+use std::collections::BTreeSet;
 use std::time::Instant;
 
 #[derive(Debug, Clone)]
@@ -17,6 +21,13 @@ pub enum RuntimeSecurityTests {
     KernelProtections,
     ReadOnlyFilesystem,
     FoundriesLmpSecurity,
+    ApplicationAllowlisting,
+    TmpHardening,
+    ModuleLoadingRestriction,
+    RuntimeAttestation,
+    ModuleBlacklist,
+    ServiceSandboxing,
+    SensitiveFilePermissions,
 }
 
 #[async_trait]
@@ -34,6 +45,13 @@ impl SecurityTest for RuntimeSecurityTests {
             Self::KernelProtections => self.test_kernel_protections(target).await,
             Self::ReadOnlyFilesystem => self.test_readonly_filesystem(target).await,
             Self::FoundriesLmpSecurity => self.test_foundries_lmp_security(target).await,
+            Self::ApplicationAllowlisting => self.test_application_allowlisting(target).await,
+            Self::TmpHardening => self.test_tmp_hardening(target).await,
+            Self::ModuleLoadingRestriction => self.test_module_loading_restriction(target).await,
+            Self::RuntimeAttestation => self.test_runtime_attestation(target).await,
+            Self::ModuleBlacklist => self.test_module_blacklist(target).await,
+            Self::ServiceSandboxing => self.test_service_sandboxing(target).await,
+            Self::SensitiveFilePermissions => self.test_sensitive_file_permissions(target).await,
         };
 
         let duration = start_time.elapsed();
@@ -48,6 +66,20 @@ impl SecurityTest for RuntimeSecurityTests {
                 details,
                 duration,
             )),
+            Err(Error::Skipped {
+                reason,
+                message,
+                details,
+            }) => Ok(create_test_result(
+                self.test_id(),
+                self.test_name(),
+                self.category(),
+                TestStatus::Skipped,
+                &message,
+                details,
+                duration,
+            )
+            .with_skip_reason(reason)),
             Err(e) => Ok(create_test_result(
                 self.test_id(),
                 self.test_name(),
@@ -71,6 +103,13 @@ impl SecurityTest for RuntimeSecurityTests {
             Self::KernelProtections => "runtime_007",
             Self::ReadOnlyFilesystem => "runtime_008",
             Self::FoundriesLmpSecurity => "runtime_009",
+            Self::ApplicationAllowlisting => "runtime_010",
+            Self::TmpHardening => "runtime_011",
+            Self::ModuleLoadingRestriction => "runtime_012",
+            Self::RuntimeAttestation => "runtime_013",
+            Self::ModuleBlacklist => "runtime_014",
+            Self::ServiceSandboxing => "runtime_015",
+            Self::SensitiveFilePermissions => "runtime_016",
         }
     }
 
@@ -85,6 +124,13 @@ impl SecurityTest for RuntimeSecurityTests {
             Self::KernelProtections => "Kernel Security Protections",
             Self::ReadOnlyFilesystem => "Read-Only Filesystem Protection",
             Self::FoundriesLmpSecurity => "Foundries.io LMP Security Features",
+            Self::ApplicationAllowlisting => "Application Allowlisting",
+            Self::TmpHardening => "Temporary Filesystem Hardening",
+            Self::ModuleLoadingRestriction => "Kernel Module Loading Restriction",
+            Self::RuntimeAttestation => "Runtime Attestation Readiness",
+            Self::ModuleBlacklist => "Kernel Module Blacklist",
+            Self::ServiceSandboxing => "Service Sandboxing",
+            Self::SensitiveFilePermissions => "Sensitive File Permissions",
         }
     }
 
@@ -95,7 +141,7 @@ impl SecurityTest for RuntimeSecurityTests {
     fn description(&self) -> &str {
         match self {
             Self::FilesystemEncryption => "Validates that sensitive data is protected at rest through full disk encryption using LUKS (Linux Unified Key Setup). Checks for encrypted root filesystem and proper key management. Essential for protecting data confidentiality if the device is physically compromised or stolen.",
-            Self::FirewallActive => "Ensures network traffic filtering is active through iptables/netfilter firewall rules. Validates that only authorized network connections are permitted and malicious traffic is blocked. Critical for preventing network-based attacks and unauthorized access attempts.",
+            Self::FirewallActive => "Ensures network traffic filtering is active through nftables or iptables, checking the INPUT, FORWARD and OUTPUT default policies. Validates that only authorized network connections are permitted and malicious traffic is blocked. Critical for preventing network-based attacks and unauthorized access attempts.",
             Self::SelinuxStatus => "Verifies SELinux (Security-Enhanced Linux) mandatory access control framework is active and properly configured. SELinux provides fine-grained security policies that limit process capabilities and prevent privilege escalation attacks, even if applications are compromised.",
             Self::SshConfiguration => "Evaluates SSH daemon security configuration including authentication methods, encryption protocols, and access controls. Checks for secure key exchange, disabled password authentication, and proper user restrictions. Fundamental for secure remote administration and preventing SSH-based attacks.",
             Self::UserPermissions => "Analyzes user account security including privilege separation, sudo configuration, and account policies. Ensures principle of least privilege is enforced and prevents unauthorized privilege escalation. Critical for maintaining system integrity and preventing insider threats.",
@@ -103,6 +149,66 @@ impl SecurityTest for RuntimeSecurityTests {
             Self::KernelProtections => "Validates kernel-level security features including ASLR (Address Space Layout Randomization), stack protection, and other exploit mitigation techniques. These protections make it significantly harder for attackers to exploit memory corruption vulnerabilities and achieve code execution.",
             Self::ReadOnlyFilesystem => "Validates that critical system directories are mounted read-only to prevent unauthorized modifications and enhance system integrity. Checks Foundries.io LMP read-only root filesystem configuration with proper writable areas for logs, data, and temporary files. Essential for preventing persistent attacks and maintaining system consistency.",
             Self::FoundriesLmpSecurity => "Comprehensive evaluation of Foundries.io Linux Micro Platform (LMP) specific security features including OSTree immutable filesystem, aktualizr-lite OTA updates, Docker security, and platform-specific hardening. Validates that LMP security architecture is properly configured for embedded IoT deployment security.",
+            Self::ApplicationAllowlisting => "Detects application allowlisting controls that restrict which binaries may execute, such as fapolicyd, IMA appraisal in enforce mode, or the IPE exec-control LSM. Verifies that the control is actively enforcing rather than merely installed or running in permissive mode. Execution control is a strong runtime-integrity measure for fixed-function devices, blocking unauthorized or tampered executables.",
+            Self::TmpHardening => "Verifies that /tmp, /var/tmp and /dev/shm are separate mounts with noexec, nosuid and nodev, or size-limited tmpfs, and that /tmp keeps its sticky bit. World-writable, executable temporary directories are a common staging area for post-exploitation payloads. Reports specific per-mount findings that can be fixed directly in fstab or systemd mount units.",
+            Self::ModuleLoadingRestriction => "Checks that loading kernel modules at runtime is restricted through kernel.modules_disabled, module signature enforcement or kernel lockdown, and whether on-demand autoloading is locked down. Unrestricted module loading lets an attacker with root insert a rootkit into the kernel even on a device with a signed boot chain. Production mode fails when unsigned modules can be loaded.",
+            Self::RuntimeAttestation => "Checks that measured-boot values can be exported for remote attestation, through populated TPM PCRs and event log, the IMA measurement list or DICE (an EdgeLock Enclave alone counts as hardware, not a measurement), and that an attestation agent is configured to report them. This ties the boot chain checks to a posture a remote verifier can confirm while the device runs. Warns when measurements exist but nothing reports them, and is skipped where there is no measurement hardware.",
+            Self::ModuleBlacklist => "Checks that rarely needed filesystems and risky drivers (cramfs, freevxfs, usb-storage, firewire-core, bluetooth, ...) are disabled in /etc/modprobe.d with blacklist or install /bin/true entries, and that no blacklisted module is loaded anyway. Removing unused kernel attack surface complements the kernel protection and module loading checks.",
+            Self::ServiceSandboxing => "Inspects every enabled systemd service for the sandboxing directives modern distributions ship with: NoNewPrivileges, ProtectSystem, ProtectHome, PrivateTmp, RestrictSUIDSGID and a reduced CapabilityBoundingSet. Reports the share of services setting at least the configured number of directives and lists the least hardened ones, which limits what a compromised daemon can reach.",
+            Self::SensitiveFilePermissions => "Scans for sensitive files other users can read or write: private keys in /etc/ssl/private and users' ~/.ssh/id_* files, /etc/shadow and /etc/gshadow with their backups, editor and package backups (*.bak, *~, *.old, *.orig) under /etc, and any world-readable file under the configured sensitive directories. Each offending path is reported with its octal mode. A group or world readable private key fails the test in production mode.",
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        match self {
+            Self::FilesystemEncryption => &["cra", "red"],
+            Self::FirewallActive => &["cra", "red"],
+            Self::SelinuxStatus => &["cra"],
+            Self::SshConfiguration => &["cra", "red"],
+            Self::UserPermissions => &["cra"],
+            Self::ServiceHardening => &["cra"],
+            Self::KernelProtections => &["cra"],
+            Self::ReadOnlyFilesystem => &["cra"],
+            Self::FoundriesLmpSecurity => &["cra", "foundries"],
+            Self::ApplicationAllowlisting => &["cra"],
+            Self::TmpHardening => &["cra"],
+            Self::ModuleLoadingRestriction => &["cra"],
+            Self::RuntimeAttestation => &["cra"],
+            Self::ModuleBlacklist => &["cra"],
+            Self::ServiceSandboxing => &["cra"],
+            Self::SensitiveFilePermissions => &["cra", "red"],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::FilesystemEncryption => Severity::High,
+            Self::FirewallActive => Severity::High,
+            Self::SelinuxStatus => Severity::Medium,
+            Self::SshConfiguration => Severity::Critical,
+            Self::UserPermissions => Severity::High,
+            Self::ServiceHardening => Severity::Medium,
+            Self::KernelProtections => Severity::High,
+            Self::ReadOnlyFilesystem => Severity::Medium,
+            Self::FoundriesLmpSecurity => Severity::Medium,
+            Self::ApplicationAllowlisting => Severity::Medium,
+            Self::TmpHardening => Severity::Low,
+            Self::ModuleLoadingRestriction => Severity::Medium,
+            Self::RuntimeAttestation => Severity::Low,
+            Self::ModuleBlacklist => Severity::Low,
+            Self::ServiceSandboxing => Severity::Medium,
+            Self::SensitiveFilePermissions => Severity::High,
+        }
+    }
+
+    fn remediation(&self, result: &TestResult) -> Option<Vec<String>> {
+        match self {
+            Self::KernelProtections => result
+                .details
+                .as_deref()
+                .map(sysctl_remediation)
+                .filter(|commands| !commands.is_empty()),
+            _ => None,
         }
     }
 }
@@ -121,7 +227,7 @@ impl RuntimeSecurityTests {
             .await?;
 
         // Check if cryptsetup is available
-        let cryptsetup = target.execute_command("which cryptsetup").await?;
+        let cryptsetup_available = target.has_command("cryptsetup").await;
 
         // Check mount points for encrypted filesystems
         let mount_check = target
@@ -146,7 +252,7 @@ impl RuntimeSecurityTests {
                 "Device mapper present but LUKS not confirmed".to_string(),
                 Some(details.join("\n")),
             ))
-        } else if cryptsetup.exit_code == 0 {
+        } else if cryptsetup_available {
             Ok((
                 TestStatus::Warning,
                 "Cryptsetup available but no encrypted filesystems detected".to_string(),
@@ -166,84 +272,127 @@ impl RuntimeSecurityTests {
         target: &mut Target,
     ) -> Result<(TestStatus, String, Option<String>)> {
         // Check iptables rules - try direct access first, then sudo if needed
-        let iptables = target.execute_command("iptables -L -n").await?;
+        let rules_command = target.probe_command("runtime_002.iptables_rules", "iptables -L -n");
+        let iptables = target.execute_command(&rules_command).await?;
         let mut iptables_result = iptables.clone();
         let mut used_sudo = false;
 
         if iptables.exit_code != 0 {
-            // Try with sudo if direct access failed
-            // Use echo to pass the password to sudo via stdin (-S option)
-            let sudo_command = format!("echo '{}' | sudo -S iptables -L -n", target.get_password());
+            // Try with sudo if direct access failed, piping the password to
+            // `sudo -S` where the escalation tool reads one. An override
+            // replaces the whole elevated command, e.g. for a wrapped sudo.
+            let sudo_command = target.probe_command(
+                "runtime_002.iptables_rules_sudo",
+                &format!("{} {}", target.elevation_pipe(), rules_command),
+            );
             iptables_result = target.execute_command(&sudo_command).await?;
             used_sudo = true;
         }
 
-        // Check if iptables service is running
-        let _iptables_service = target
-            .execute_command("systemctl is-active iptables 2>/dev/null || echo 'not_running'")
-            .await?;
+        // Modern distros filter with nftables; iptables -L shows nothing for native nft tables
+        let nft_command = target.probe_command("runtime_002.nft_ruleset", "nft list ruleset");
+        let mut nft = target.execute_command(&nft_command).await?;
+        if nft.exit_code != 0 {
+            nft = target
+                .execute_command(&format!("{} {}", target.elevation_pipe(), nft_command))
+                .await?;
+        }
 
         // Check for netfilter modules
-        let netfilter_modules = target
-            .execute_command("lsmod | grep -E 'iptable|netfilter|nf_'")
-            .await?;
+        let modules_command = target.probe_command(
+            "runtime_002.netfilter_modules",
+            "lsmod | grep -E 'iptable|netfilter|nf_'",
+        );
+        let netfilter_modules = target.execute_command(&modules_command).await?;
 
         // Check if iptables binary is available
-        let iptables_available = target.execute_command("which iptables").await?;
+        let iptables_available = target.has_command("iptables").await;
+
+        // A routing device legitimately forwards packets
+        let ip_forward = target
+            .execute_command("cat /proc/sys/net/ipv4/ip_forward 2>/dev/null")
+            .await?;
+        let routing = ip_forward.stdout.trim() == "1";
+
+        let nft_policies = if nft.exit_code == 0 {
+            parse_nft_policies(&nft.stdout)
+        } else {
+            FirewallPolicies::default()
+        };
+        let iptables_policies = if iptables_result.exit_code == 0 {
+            parse_iptables_policies(&iptables_result.stdout)
+        } else {
+            FirewallPolicies::default()
+        };
+        let (backend, policies) = if !nft_policies.is_empty() {
+            ("nftables", nft_policies)
+        } else {
+            ("iptables", iptables_policies)
+        };
 
         let mut details = Vec::new();
+        if !policies.is_empty() {
+            details.push(format!("Active backend: {}", backend));
+            details.push(format!("Default policies: {}", policies));
+            details.push(format!(
+                "Egress filtering: {}",
+                if policies.filters_egress() {
+                    "present"
+                } else {
+                    "none (OUTPUT accepts everything)"
+                }
+            ));
+            details.push(format!("IP forwarding: {}", routing));
+        }
         details.push(format!("iptables rules:\n{}", iptables_result.stdout));
+        if nft.exit_code == 0 {
+            details.push(format!("nftables ruleset:\n{}", nft.stdout));
+        }
         details.push(format!("Netfilter modules: {}", netfilter_modules.stdout));
-        details.push(format!(
-            "iptables binary available: {}",
-            iptables_available.exit_code == 0
-        ));
+        details.push(format!("iptables binary available: {}", iptables_available));
         if used_sudo {
             details.push("Used sudo to access iptables rules".to_string());
         }
 
-        // Check if we have any firewall rules configured
-        if iptables_result.exit_code == 0
-            && !iptables_result
-                .stdout
-                .contains("Chain INPUT (policy ACCEPT)")
-        {
-            Ok((
-                TestStatus::Passed,
-                "Firewall rules configured".to_string(),
-                Some(details.join("\n")),
-            ))
-        } else if iptables_result.exit_code == 0
-            && iptables_result
-                .stdout
-                .contains("Chain INPUT (policy ACCEPT)")
-        {
-            // iptables is available but using default ACCEPT policy
-            let rule_count = iptables_result
-                .stdout
-                .lines()
-                .filter(|line| {
-                    line.contains("ACCEPT") || line.contains("DROP") || line.contains("REJECT")
-                })
-                .count();
-
-            if rule_count > 3 {
-                // More than just the default chains
+        if !policies.is_empty() {
+            if policies.input.as_ref().is_some_and(FirewallChain::drops) {
+                if !routing && !policies.forward.as_ref().is_some_and(FirewallChain::drops) {
+                    Ok((
+                        TestStatus::Warning,
+                        format!(
+                            "{} filters INPUT but FORWARD accepts by default on a non-routing device",
+                            backend
+                        ),
+                        Some(details.join("\n")),
+                    ))
+                } else {
+                    Ok((
+                        TestStatus::Passed,
+                        format!("Firewall rules configured ({})", backend),
+                        Some(details.join("\n")),
+                    ))
+                }
+            } else if policies.input.as_ref().map_or(0, |chain| chain.rules) > 0 {
                 Ok((
                     TestStatus::Warning,
-                    "iptables available with some rules but default ACCEPT policy".to_string(),
+                    format!(
+                        "{} available with some rules but default ACCEPT policy",
+                        backend
+                    ),
                     Some(details.join("\n")),
                 ))
             } else {
-                // Default configuration - treat as warning in pre-production, but note that iptables is present
+                // Default configuration - treat as warning in pre-production
                 Ok((
                     TestStatus::Warning,
-                    "iptables present but firewall not configured (default ACCEPT policy)"
-                        .to_string(),
+                    format!(
+                        "{} present but firewall not configured (default ACCEPT policy)",
+                        backend
+                    ),
                     Some(details.join("\n")),
                 ))
             }
-        } else if iptables_available.exit_code == 0 {
+        } else if iptables_available {
             // iptables binary exists but both direct and sudo commands failed
             if iptables_result.stderr.contains("Permission denied")
                 || iptables_result.stderr.contains("Operation not permitted")
@@ -261,10 +410,11 @@ impl RuntimeSecurityTests {
                 ))
             }
         } else {
-            // No iptables at all - warning for pre-production environments
+            // No firewall tooling at all - warning for pre-production environments
             Ok((
                 TestStatus::Warning,
-                "No firewall configuration detected (iptables not available)".to_string(),
+                "No firewall configuration detected (neither nftables nor iptables rules readable)"
+                    .to_string(),
                 Some(details.join("\n")),
             ))
         }
@@ -570,7 +720,10 @@ impl RuntimeSecurityTests {
 
         // Check sudo configuration
         let sudo_config = target
-            .execute_command("cat /etc/sudoers.d/* 2>/dev/null | grep -v '^#' | head -10")
+            .execute_command(&format!(
+                "cat /etc/sudoers.d/* 2>/dev/null | grep -v '^#'{}",
+                target.evidence_head(10)
+            ))
             .await?;
 
         // Check for users with shell access
@@ -1087,22 +1240,45 @@ impl RuntimeSecurityTests {
         let is_lmp = !lmp_check.stdout.is_empty();
 
         // Check mount points and their read-only status
-        let mounts = target
-            .execute_command("mount | grep -E '^/dev|^overlay|^tmpfs'")
-            .await?;
+        let mounts_command = target.probe_command(
+            "runtime_008.mounts",
+            "mount | grep -E '^/dev|^overlay|^tmpfs'",
+        );
+        let mounts = target.execute_command(&mounts_command).await?;
         details.push(format!("Mount points:\n{}", mounts.stdout));
 
+        // Mount option probe; `{path}` is replaced with the directory being checked
+        let mount_options_command = target.probe_command(
+            "runtime_008.mount_options",
+            "findmnt -n -o OPTIONS {path} 2>/dev/null | grep -o 'ro\\|rw' | head -1",
+        );
+
+        // BusyBox images ship without findmnt, so fall back to reading the mount table directly
+        let mount_table = if target.has_command("findmnt").await {
+            None
+        } else {
+            let table_command = target.probe_command(
+                "runtime_008.mount_table",
+                "cat /proc/mounts 2>/dev/null || mount",
+            );
+            let table = target.execute_command(&table_command).await?;
+            details.push("findmnt not available, using /proc/mounts".to_string());
+            Some(table.stdout)
+        };
+
         // Check critical system directories that should be read-only
         let critical_dirs = ["/", "/usr", "/bin", "/sbin", "/lib", "/lib64", "/boot"];
 
         for dir in &critical_dirs {
-            let mount_check = target
-                .execute_command(&format!(
-                    "findmnt -n -o OPTIONS {} 2>/dev/null | grep -o 'ro\\|rw' | head -1",
-                    dir
-                ))
-                .await?;
-            let mount_status = mount_check.stdout.trim();
+            let mount_status = match &mount_table {
+                Some(table) => mount_access(table, dir).unwrap_or_default().to_string(),
+                None => target
+                    .execute_command(&mount_options_command.replace("{path}", dir))
+                    .await?
+                    .stdout
+                    .trim()
+                    .to_string(),
+            };
 
             if mount_status == "ro" {
                 readonly_mounts.push(*dir);
@@ -1152,13 +1328,15 @@ impl RuntimeSecurityTests {
         let writable_dirs = ["/var", "/tmp", "/home", "/opt", "/etc"];
 
         for dir in &writable_dirs {
-            let mount_check = target
-                .execute_command(&format!(
-                    "findmnt -n -o OPTIONS {} 2>/dev/null | grep -o 'ro\\|rw' | head -1",
-                    dir
-                ))
-                .await?;
-            let mount_status = mount_check.stdout.trim();
+            let mount_status = match &mount_table {
+                Some(table) => mount_access(table, dir).unwrap_or_default().to_string(),
+                None => target
+                    .execute_command(&mount_options_command.replace("{path}", dir))
+                    .await?
+                    .stdout
+                    .trim()
+                    .to_string(),
+            };
 
             if mount_status == "rw" {
                 writable_areas.push(*dir);
@@ -1185,7 +1363,10 @@ impl RuntimeSecurityTests {
 
             // Check for persistent data areas
             let persistent_check = target
-                .execute_command("ls -la /var/sota /var/lib 2>/dev/null | head -5")
+                .execute_command(&format!(
+                    "ls -la /var/sota /var/lib 2>/dev/null{}",
+                    target.evidence_head(5)
+                ))
                 .await?;
             if !persistent_check.stdout.is_empty() {
                 details.push("Persistent data areas found in /var".to_string());
@@ -1193,9 +1374,11 @@ impl RuntimeSecurityTests {
         }
 
         // Test write protection by attempting to create a file in read-only areas
-        let write_test = target
-            .execute_command("touch /usr/test_readonly_check 2>&1 || echo 'write_blocked'")
-            .await?;
+        let write_test_command = target.probe_command(
+            "runtime_008.write_test",
+            "touch /usr/test_readonly_check 2>&1 || echo 'write_blocked'",
+        );
+        let write_test = target.execute_command(&write_test_command).await?;
         if write_test.stdout.contains("write_blocked") || write_test.stdout.contains("Read-only") {
             readonly_mounts.push("/usr (write-protected)");
             details.push("Write protection verified on /usr".to_string());
@@ -1263,11 +1446,11 @@ impl RuntimeSecurityTests {
             .await?;
 
         if lmp_check.stdout.is_empty() {
-            return Ok((
-                TestStatus::Skipped,
-                "Not a Foundries.io LMP system".to_string(),
-                None,
-            ));
+            return Err(Error::Skipped {
+                reason: SkipReason::NotApplicableToMachine,
+                message: "Not a Foundries.io LMP system".to_string(),
+                details: None,
+            });
         }
 
         lmp_features.push("Foundries.io LMP detected");
@@ -1285,7 +1468,7 @@ impl RuntimeSecurityTests {
                 ostree_status
                     .stdout
                     .lines()
-                    .take(2)
+                    .take(target.evidence_lines(2))
                     .collect::<Vec<_>>()
                     .join("; ")
             ));
@@ -1429,4 +1612,1196 @@ impl RuntimeSecurityTests {
             ))
         }
     }
+
+    async fn test_application_allowlisting(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let mut details = Vec::new();
+        let mut enforcing = Vec::new();
+        let mut permissive = Vec::new();
+
+        // fapolicyd - userspace application allowlisting daemon
+        let fapolicyd = target
+            .execute_command("systemctl is-active fapolicyd 2>/dev/null || echo 'not_active'")
+            .await?;
+        let fapolicyd_installed = target.has_command("fapolicyd").await
+            || target
+                .execute_command("test -d /etc/fapolicyd")
+                .await?
+                .success();
+        if fapolicyd.stdout.trim() == "active" {
+            let permissive_setting = target
+                .execute_command(
+                    "grep -E '^\\s*permissive\\s*=' /etc/fapolicyd/fapolicyd.conf 2>/dev/null",
+                )
+                .await?;
+            details.push(format!(
+                "fapolicyd: active ({})",
+                permissive_setting.stdout.trim()
+            ));
+            if permissive_setting.stdout.contains('1') {
+                permissive.push("fapolicyd (permissive)");
+            } else {
+                enforcing.push("fapolicyd");
+            }
+        } else if fapolicyd_installed {
+            details.push("fapolicyd: installed but not running".to_string());
+            permissive.push("fapolicyd (inactive)");
+        }
+
+        // IMA appraisal - kernel enforced file integrity on exec
+        let cmdline = target.execute_command("cat /proc/cmdline").await?;
+        let ima_policy = target
+            .execute_command("cat /sys/kernel/security/ima/policy 2>/dev/null | grep -c 'appraise.*BPRM_CHECK' || echo 0")
+            .await?;
+        let exec_appraise_rules: usize = ima_policy
+            .stdout
+            .lines()
+            .next()
+            .and_then(|l| l.trim().parse().ok())
+            .unwrap_or(0);
+        if cmdline.stdout.contains("ima_appraise=enforce") || exec_appraise_rules > 0 {
+            details.push(format!(
+                "IMA appraisal: enforce (exec rules: {})",
+                exec_appraise_rules
+            ));
+            enforcing.push("IMA appraisal");
+        } else if cmdline.stdout.contains("ima_appraise=") {
+            details.push("IMA appraisal: configured but not enforcing".to_string());
+            permissive.push("IMA appraisal (log/fix)");
+        }
+
+        // Integrity Policy Enforcement (IPE) - exec-control LSM
+        let lsm_modules = target
+            .execute_command("cat /sys/kernel/security/lsm 2>/dev/null")
+            .await?;
+        if lsm_modules.stdout.split(',').any(|m| m.trim() == "ipe") {
+            let ipe_enforce = target
+                .execute_command("cat /sys/kernel/security/ipe/enforce 2>/dev/null")
+                .await?;
+            details.push(format!("IPE LSM: enforce={}", ipe_enforce.stdout.trim()));
+            if ipe_enforce.stdout.trim() == "1" {
+                enforcing.push("IPE");
+            } else {
+                permissive.push("IPE (permissive)");
+            }
+        }
+        details.push(format!("Active LSM modules: {}", lsm_modules.stdout.trim()));
+
+        let details = Some(details.join("\n"));
+
+        if !enforcing.is_empty() {
+            Ok((
+                TestStatus::Passed,
+                format!(
+                    "Application allowlisting enforced: {}",
+                    enforcing.join(", ")
+                ),
+                details,
+            ))
+        } else if !permissive.is_empty() {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "Application allowlisting present but not enforcing: {}",
+                    permissive.join(", ")
+                ),
+                details,
+            ))
+        } else if matches!(target.context().mode, TestMode::Production) {
+            Ok((
+                TestStatus::Failed,
+                "No application allowlisting (fapolicyd, IMA appraisal, IPE)".to_string(),
+                details,
+            ))
+        } else {
+            Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No application allowlisting configured".to_string(),
+                details,
+            })
+        }
+    }
+
+    async fn test_tmp_hardening(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let mut details = Vec::new();
+        let mut issues = Vec::new();
+
+        let table_command = target.probe_command(
+            "runtime_011.mount_table",
+            "cat /proc/mounts 2>/dev/null || mount",
+        );
+        let table = target.execute_command(&table_command).await?;
+
+        for path in ["/tmp", "/var/tmp", "/dev/shm"] {
+            let Some((fstype, options)) = mount_entry(&table.stdout, path) else {
+                issues.push(format!(
+                    "{} is not a separate mount, so it cannot have noexec,nosuid,nodev",
+                    path
+                ));
+                continue;
+            };
+
+            let options: Vec<&str> = options.split(',').collect();
+            let missing: Vec<&str> = ["noexec", "nosuid", "nodev"]
+                .into_iter()
+                .filter(|flag| !options.contains(flag))
+                .collect();
+            let size_limited = fstype == "tmpfs" && options.iter().any(|o| o.starts_with("size="));
+
+            details.push(format!("{}: {} ({})", path, fstype, options.join(",")));
+            if missing.is_empty() {
+                continue;
+            }
+            if size_limited {
+                details.push(format!(
+                    "  {} is a size-limited tmpfs but lacks {}",
+                    path,
+                    missing.join(",")
+                ));
+            } else {
+                issues.push(format!("{} is mounted without {}", path, missing.join(",")));
+            }
+        }
+
+        // A world-writable /tmp without the sticky bit lets users delete each other's files
+        let tmp_mode = target
+            .execute_command("stat -c '%a' /tmp 2>/dev/null")
+            .await?;
+        let mode = u32::from_str_radix(tmp_mode.stdout.trim(), 8).ok();
+        let sticky_missing = mode.is_some_and(|mode| mode & 0o002 != 0 && mode & 0o1000 == 0);
+        if let Some(mode) = mode {
+            details.push(format!("/tmp permissions: {:o}", mode));
+        }
+        if sticky_missing {
+            issues.push("/tmp is world-writable without the sticky bit".to_string());
+        }
+
+        if !issues.is_empty() {
+            details.push("Findings:".to_string());
+            details.extend(issues.iter().map(|issue| format!("  • {}", issue)));
+        }
+
+        if sticky_missing {
+            Ok((
+                TestStatus::Failed,
+                "/tmp is world-writable without the sticky bit".to_string(),
+                Some(details.join("\n")),
+            ))
+        } else if !issues.is_empty() {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "Temporary filesystems not fully hardened ({} findings)",
+                    issues.len()
+                ),
+                Some(details.join("\n")),
+            ))
+        } else {
+            Ok((
+                TestStatus::Passed,
+                "Temporary filesystems mounted noexec,nosuid,nodev".to_string(),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+
+    async fn test_module_loading_restriction(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let mut details = Vec::new();
+
+        let modules_disabled = target
+            .execute_command("cat /proc/sys/kernel/modules_disabled 2>/dev/null")
+            .await?;
+        let modules_disabled = modules_disabled.stdout.trim() == "1";
+        details.push(format!("kernel.modules_disabled: {}", modules_disabled));
+
+        // sig_enforce is only exposed when CONFIG_MODULE_SIG is built in
+        let sig_enforce = target
+            .execute_command("cat /sys/module/module/parameters/sig_enforce 2>/dev/null")
+            .await?;
+        let cmdline = target.execute_command("cat /proc/cmdline").await?;
+        let lockdown = target
+            .execute_command("cat /sys/kernel/security/lockdown 2>/dev/null")
+            .await?;
+        let lockdown_active = lockdown.stdout.contains("[integrity]")
+            || lockdown.stdout.contains("[confidentiality]");
+        let signatures_enforced = sig_enforce.stdout.trim() == "Y"
+            || cmdline.stdout.contains("module.sig_enforce=1")
+            || lockdown_active;
+        details.push(format!(
+            "module.sig_enforce: {}",
+            if sig_enforce.stdout.trim().is_empty() {
+                "not available (CONFIG_MODULE_SIG not set?)"
+            } else {
+                sig_enforce.stdout.trim()
+            }
+        ));
+        if !lockdown.stdout.trim().is_empty() {
+            details.push(format!("Kernel lockdown: {}", lockdown.stdout.trim()));
+        }
+
+        // An empty or no-op usermode helper stops the kernel autoloading modules on demand
+        let modprobe = target
+            .execute_command("cat /proc/sys/kernel/modprobe 2>/dev/null")
+            .await?;
+        let modprobe_path = modprobe.stdout.trim();
+        let autoload_locked = modprobe_path.is_empty()
+            || modprobe_path.ends_with("/false")
+            || modprobe_path.ends_with("/true");
+        details.push(format!(
+            "kernel.modprobe: {}",
+            if modprobe_path.is_empty() {
+                "(empty)"
+            } else {
+                modprobe_path
+            }
+        ));
+
+        let loaded = target
+            .execute_command("cat /proc/modules 2>/dev/null | wc -l")
+            .await?;
+        details.push(format!("Loaded modules: {}", loaded.stdout.trim()));
+
+        if modules_disabled {
+            return Ok((
+                TestStatus::Passed,
+                "Runtime module loading disabled (kernel.modules_disabled=1)".to_string(),
+                Some(details.join("\n")),
+            ));
+        }
+
+        if signatures_enforced {
+            let message = if autoload_locked {
+                "Only signed modules can load and on-demand autoloading is locked down"
+            } else {
+                "Only signed kernel modules can load"
+            };
+            return Ok((
+                TestStatus::Passed,
+                message.to_string(),
+                Some(details.join("\n")),
+            ));
+        }
+
+        details.push(String::new());
+        details.push(
+            "Any root process can insert an unsigned module (e.g. a rootkit), even with a signed boot chain.\n\
+             Boot with module.sig_enforce=1 (or enable kernel lockdown), and once all required modules\n\
+             are loaded run: sysctl -w kernel.modules_disabled=1 (cannot be undone until reboot)"
+                .to_string(),
+        );
+        let message = "Unsigned kernel modules can be loaded at runtime".to_string();
+        if matches!(target.context().mode, TestMode::Production) {
+            Ok((TestStatus::Failed, message, Some(details.join("\n"))))
+        } else {
+            Ok((TestStatus::Warning, message, Some(details.join("\n"))))
+        }
+    }
+
+    async fn test_runtime_attestation(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let mut details = Vec::new();
+        let mut measurements = Vec::new();
+        let mut hardware = Vec::new();
+
+        // TPM: PCRs exported by the kernel (5.12+), falling back to tpm2-tools
+        let tpm = target
+            .execute_command("ls -d /sys/class/tpm/tpm* 2>/dev/null")
+            .await?;
+        if !tpm.stdout.trim().is_empty() {
+            hardware.push("TPM");
+            let pcr_command = target.probe_command(
+                "runtime_013.pcrs",
+                "cat /sys/class/tpm/tpm0/pcr-sha256/0 /sys/class/tpm/tpm0/pcr-sha256/7 2>/dev/null || tpm2_pcrread sha256:0,7 2>/dev/null",
+            );
+            let pcrs = target.execute_command(&pcr_command).await?;
+            let populated = pcrs
+                .stdout
+                .split(|c: char| !c.is_ascii_hexdigit())
+                .any(|value| value.len() >= 40 && value.chars().any(|c| c != '0'));
+            details.push(format!(
+                "TPM PCR 0/7: {}",
+                if pcrs.stdout.trim().is_empty() {
+                    "unreadable"
+                } else if populated {
+                    "populated"
+                } else {
+                    "all zero (boot not measured)"
+                }
+            ));
+            if populated {
+                measurements.push("TPM PCRs");
+            }
+
+            let event_log = target
+                .execute_command(
+                    "test -s /sys/kernel/security/tpm0/binary_bios_measurements && echo present",
+                )
+                .await?;
+            if event_log.stdout.contains("present") {
+                measurements.push("TPM event log");
+                details.push("TPM event log: present".to_string());
+            }
+        }
+
+        let ima = target
+            .execute_command(
+                "wc -l < /sys/kernel/security/ima/ascii_runtime_measurements 2>/dev/null",
+            )
+            .await?;
+        let ima_entries: usize = ima.stdout.trim().parse().unwrap_or(0);
+        if ima_entries > 0 {
+            measurements.push("IMA measurement list");
+            details.push(format!("IMA measurements: {} entries", ima_entries));
+        }
+
+        // EdgeLock Enclave can produce attestation evidence, but its presence
+        // alone says nothing about whether the boot was measured, so it only
+        // counts as hardware. Match its device nodes by exact name.
+        let ele = target
+            .execute_command(
+                "ls /dev/ele_mu* /dev/se_fw* 2>/dev/null; ls /sys/bus/platform/devices/ 2>/dev/null | grep -xiE '([0-9a-f]+\\.)?(ele[-_](if|mu)[0-9]*|se[-_]fw[0-9]*)'",
+            )
+            .await?;
+        if !ele.stdout.trim().is_empty() {
+            hardware.push("EdgeLock Enclave");
+            details.push(format!(
+                "EdgeLock Enclave: {}",
+                ele.stdout.split_whitespace().collect::<Vec<_>>().join(", ")
+            ));
+        }
+        let dice = target
+            .execute_command(
+                "ls /dev/open-dice* 2>/dev/null; ls /proc/device-tree/reserved-memory/ 2>/dev/null | grep -i dice",
+            )
+            .await?;
+        if !dice.stdout.trim().is_empty() {
+            hardware.push("DICE");
+            measurements.push("DICE certificate chain");
+            details.push(format!("DICE: {}", dice.stdout.trim()));
+        }
+
+        if hardware.is_empty() && measurements.is_empty() {
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No measurement hardware (TPM, EdgeLock Enclave, DICE) found".to_string(),
+                details: Some(details.join("\n")),
+            });
+        }
+
+        let agent_command = target.probe_command(
+            "runtime_013.agent",
+            "systemctl list-unit-files 2>/dev/null | grep -iE 'keylime|attest' | awk '{print $1\" \"$2}'",
+        );
+        let agents = target.execute_command(&agent_command).await?;
+        let mut attestation_agents: Vec<String> = agents
+            .stdout
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.contains("disabled"))
+            .map(|line| line.trim().to_string())
+            .collect();
+        for tool in ["keylime_agent", "attestation-agent"] {
+            if target.has_command(tool).await
+                && !attestation_agents.iter().any(|agent| agent.contains(tool))
+            {
+                attestation_agents.push(tool.to_string());
+            }
+        }
+        details.push(format!(
+            "Attestation agents: {}",
+            if attestation_agents.is_empty() {
+                "none".to_string()
+            } else {
+                attestation_agents.join(", ")
+            }
+        ));
+
+        if measurements.is_empty() {
+            return Ok((
+                TestStatus::Warning,
+                format!(
+                    "{} present but the boot chain is not measured",
+                    hardware.join(", ")
+                ),
+                Some(details.join("\n")),
+            ));
+        }
+
+        if attestation_agents.is_empty() {
+            details.push(String::new());
+            details.push(
+                "Boot measurements exist but nothing reports them, so a verifier can't confirm the\n\
+                 device is running the expected software. Configure an attestation agent (e.g. Keylime)."
+                    .to_string(),
+            );
+            return Ok((
+                TestStatus::Warning,
+                format!(
+                    "Measurements available ({}) but no attestation agent configured",
+                    measurements.join(", ")
+                ),
+                Some(details.join("\n")),
+            ));
+        }
+
+        Ok((
+            TestStatus::Passed,
+            format!(
+                "Boot measurements ({}) exported for attestation",
+                measurements.join(", ")
+            ),
+            Some(details.join("\n")),
+        ))
+    }
+
+    async fn test_service_sandboxing(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let min_directives = target.context().tests.min_sandbox_directives;
+        let properties = ServiceSandbox::DIRECTIVES
+            .iter()
+            .map(|directive| format!("-p {}", directive))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let output = target
+            .execute_command(&format!(
+                "units=$(systemctl list-unit-files --type=service --state=enabled --no-legend --no-pager 2>/dev/null | awk '$1 !~ /@/ {{print $1}}'); [ -n \"$units\" ] && systemctl show $units -p Id {} --no-pager 2>/dev/null",
+                properties
+            ))
+            .await?;
+
+        let mut services = parse_service_sandboxes(&output.stdout);
+        if services.is_empty() {
+            return Err(Error::Skipped {
+                reason: SkipReason::NotPresent,
+                message: "No enabled systemd services found".to_string(),
+                details: None,
+            });
+        }
+
+        services.sort_by(|a, b| {
+            a.enabled
+                .len()
+                .cmp(&b.enabled.len())
+                .then_with(|| a.unit.cmp(&b.unit))
+        });
+        let hardened = services
+            .iter()
+            .filter(|service| service.enabled.len() >= min_directives)
+            .count();
+        let percent = hardened * 100 / services.len();
+
+        let mut details = vec![format!(
+            "{}/{} enabled services set at least {} of: {}",
+            hardened,
+            services.len(),
+            min_directives,
+            ServiceSandbox::DIRECTIVES.join(", ")
+        )];
+        details.push("Least hardened:".to_string());
+        details.extend(
+            services
+                .iter()
+                .filter(|service| service.enabled.len() < min_directives)
+                .take(target.evidence_lines(LEAST_HARDENED_LISTED))
+                .map(|service| {
+                    format!(
+                        "  {} ({}/{}; missing {})",
+                        service.unit,
+                        service.enabled.len(),
+                        ServiceSandbox::DIRECTIVES.len(),
+                        service.missing().join(", ")
+                    )
+                }),
+        );
+        let details = Some(details.join("\n"));
+
+        let message = format!(
+            "{}% of enabled services sandboxed ({}/{} set {}+ directives)",
+            percent,
+            hardened,
+            services.len(),
+            min_directives
+        );
+        if percent >= 50 {
+            Ok((TestStatus::Passed, message, details))
+        } else if hardened == 0 && matches!(target.context().mode, TestMode::Production) {
+            Ok((TestStatus::Failed, message, details))
+        } else {
+            Ok((TestStatus::Warning, message, details))
+        }
+    }
+
+    async fn test_sensitive_file_permissions(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let script = sensitive_file_scan(&target.context().tests.sensitive_dirs);
+        let quoted = format!("'{}'", script.replace('\'', "'\\''"));
+
+        // Key directories are usually only listable by root
+        let privileged = target
+            .execute_command(&format!("{} sh -c {}", target.elevation_pipe(), quoted))
+            .await?;
+        let scan = if privileged.exit_code == 0 {
+            privileged
+        } else {
+            target.execute_command(&format!("sh -c {}", quoted)).await?
+        };
+        let as_root = scan.stdout.contains(ROOT_SCAN_MARKER);
+
+        let exposed = parse_exposed_files(&scan.stdout);
+        let mut details: Vec<String> = exposed.iter().map(|file| file.to_string()).collect();
+        if !as_root {
+            details.push(
+                "Scanned without root: directories only root can list were skipped".to_string(),
+            );
+        }
+        let details = Some(details.join("\n")).filter(|details| !details.is_empty());
+
+        let production = matches!(target.context().mode, TestMode::Production);
+        let critical = exposed.iter().any(|file| {
+            file.world_writable()
+                || file.kind == SensitiveFileKind::Shadow
+                || (production && file.kind == SensitiveFileKind::PrivateKey)
+        });
+        let keys = exposed
+            .iter()
+            .filter(|file| file.kind == SensitiveFileKind::PrivateKey)
+            .count();
+
+        if exposed.is_empty() {
+            Ok((
+                TestStatus::Passed,
+                "No sensitive files readable or writable by other users".to_string(),
+                details,
+            ))
+        } else if critical {
+            Ok((
+                TestStatus::Failed,
+                format!(
+                    "{} sensitive file(s) exposed, {} private key(s)",
+                    exposed.len(),
+                    keys
+                ),
+                details,
+            ))
+        } else {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "{} sensitive file(s) readable by other users, {} private key(s)",
+                    exposed.len(),
+                    keys
+                ),
+                details,
+            ))
+        }
+    }
+
+    async fn test_module_blacklist(
+        &self,
+        target: &mut Target,
+    ) -> Result<(TestStatus, String, Option<String>)> {
+        let conf_command = target.probe_command(
+            "runtime_014.modprobe_conf",
+            "cat /etc/modprobe.d/*.conf /lib/modprobe.d/*.conf /usr/lib/modprobe.d/*.conf 2>/dev/null",
+        );
+        let conf = target.execute_command(&conf_command).await?;
+        let blacklisted = blacklisted_modules(&conf.stdout);
+
+        let loaded = target
+            .execute_command("lsmod 2>/dev/null || cat /proc/modules 2>/dev/null")
+            .await?;
+        let loaded: Vec<String> = loaded
+            .stdout
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .filter(|name| *name != "Module")
+            .map(|name| name.replace('-', "_"))
+            .collect();
+
+        let mut details = Vec::new();
+        let mut missing = Vec::new();
+        for module in RECOMMENDED_MODULE_BLACKLIST {
+            let module = module.replace('-', "_");
+            if blacklisted.contains(&module) {
+                details.push(format!("✅ {}: blacklisted", module));
+            } else if loaded.contains(&module) {
+                details.push(format!("ℹ️ {}: in use, not blacklisted", module));
+            } else {
+                details.push(format!("⚠️ {}: not blacklisted", module));
+                missing.push(module);
+            }
+        }
+
+        let bypassed: Vec<&String> = blacklisted
+            .iter()
+            .filter(|module| loaded.contains(module))
+            .collect();
+        for module in &bypassed {
+            details.push(format!("❌ {}: loaded despite blacklist entry", module));
+        }
+
+        if !bypassed.is_empty() {
+            Ok((
+                TestStatus::Failed,
+                format!(
+                    "{} blacklisted modules are loaded: {}",
+                    bypassed.len(),
+                    bypassed
+                        .iter()
+                        .map(|module| module.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                Some(details.join("\n")),
+            ))
+        } else if !missing.is_empty() {
+            Ok((
+                TestStatus::Warning,
+                format!(
+                    "{} unused risky modules are not blacklisted: {}",
+                    missing.len(),
+                    missing.join(", ")
+                ),
+                Some(details.join("\n")),
+            ))
+        } else {
+            Ok((
+                TestStatus::Passed,
+                "Unused risky kernel modules are blacklisted".to_string(),
+                Some(details.join("\n")),
+            ))
+        }
+    }
+}
+
+/// What kind of sensitive file a runtime_016 scan line refers to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SensitiveFileKind {
+    PrivateKey,
+    Shadow,
+    Backup,
+    SensitiveDirectory,
+}
+
+/// A sensitive file other users can read or write
+#[derive(Debug, Clone, PartialEq)]
+struct ExposedFile {
+    kind: SensitiveFileKind,
+    mode: u32,
+    path: String,
+}
+
+impl ExposedFile {
+    fn world_writable(&self) -> bool {
+        self.mode & 0o002 != 0
+    }
+
+    /// Exposure worth reporting for this kind of file, if any. Shadow files are
+    /// commonly readable by the shadow group, so only world access counts.
+    fn problem(&self) -> Option<&'static str> {
+        match self.kind {
+            _ if self.world_writable() => Some("world writable"),
+            SensitiveFileKind::PrivateKey if self.mode & 0o044 != 0 => {
+                Some("private key readable by group/world")
+            }
+            SensitiveFileKind::Shadow if self.mode & 0o004 != 0 => {
+                Some("password hashes readable by everyone")
+            }
+            SensitiveFileKind::Backup if self.mode & 0o004 != 0 => Some("world readable backup"),
+            SensitiveFileKind::SensitiveDirectory if self.mode & 0o004 != 0 => {
+                Some("world readable in sensitive directory")
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ExposedFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04o} {} ({})",
+            self.mode,
+            self.path,
+            self.problem().unwrap_or("ok")
+        )
+    }
+}
+
+/// Default policy and rule count of one netfilter hook
+#[derive(Debug, Clone, PartialEq)]
+struct FirewallChain {
+    policy: String,
+    rules: usize,
+}
+
+impl FirewallChain {
+    fn drops(&self) -> bool {
+        matches!(self.policy.as_str(), "DROP" | "REJECT")
+    }
+}
+
+/// The INPUT, FORWARD and OUTPUT filter chains of a ruleset
+#[derive(Debug, Default, PartialEq)]
+struct FirewallPolicies {
+    input: Option<FirewallChain>,
+    forward: Option<FirewallChain>,
+    output: Option<FirewallChain>,
+}
+
+impl FirewallPolicies {
+    fn is_empty(&self) -> bool {
+        self.input.is_none() && self.forward.is_none() && self.output.is_none()
+    }
+
+    /// OUTPUT drops by default or has rules of its own
+    fn filters_egress(&self) -> bool {
+        self.output
+            .as_ref()
+            .is_some_and(|chain| chain.drops() || chain.rules > 0)
+    }
+
+    fn chain_mut(&mut self, hook: &str) -> Option<&mut Option<FirewallChain>> {
+        match hook.to_ascii_uppercase().as_str() {
+            "INPUT" => Some(&mut self.input),
+            "FORWARD" => Some(&mut self.forward),
+            "OUTPUT" => Some(&mut self.output),
+            _ => None,
+        }
+    }
+
+    /// Fold a base chain into its hook. With several tables on one hook a
+    /// packet must pass all of them, so any dropping chain makes the hook drop.
+    fn merge(&mut self, hook: &str, policy: &str, rules: usize) {
+        let policy = policy.to_ascii_uppercase();
+        if let Some(slot) = self.chain_mut(hook) {
+            match slot {
+                Some(chain) => {
+                    chain.rules += rules;
+                    if !chain.drops() {
+                        chain.policy = policy;
+                    }
+                }
+                None => *slot = Some(FirewallChain { policy, rules }),
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for FirewallPolicies {
+    /// e.g. "INPUT DROP (4 rules), FORWARD DROP (0 rules), OUTPUT ACCEPT (0 rules)"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let chains: Vec<String> = [
+            ("INPUT", &self.input),
+            ("FORWARD", &self.forward),
+            ("OUTPUT", &self.output),
+        ]
+        .iter()
+        .map(|(name, chain)| match chain {
+            Some(chain) => format!("{} {} ({} rules)", name, chain.policy, chain.rules),
+            None => format!("{} none", name),
+        })
+        .collect();
+        write!(f, "{}", chains.join(", "))
+    }
+}
+
+/// Chain policies from `iptables -L -n` (`Chain INPUT (policy DROP)` headers
+/// followed by rule lines) or `iptables -S` (`-P INPUT DROP`, `-A INPUT ...`)
+fn parse_iptables_policies(listing: &str) -> FirewallPolicies {
+    let mut policies = FirewallPolicies::default();
+    let mut current: Option<(String, String, usize)> = None;
+    let flush = |policies: &mut FirewallPolicies, chain: Option<(String, String, usize)>| {
+        if let Some((hook, policy, rules)) = chain {
+            policies.merge(&hook, &policy, rules);
+        }
+    };
+    for line in listing.lines() {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next(), words.next(), words.next()) {
+            (Some("Chain"), Some(chain), Some("(policy"), Some(policy)) => {
+                flush(&mut policies, current.take());
+                current = Some((
+                    chain.to_string(),
+                    policy.trim_end_matches(')').to_string(),
+                    0,
+                ));
+            }
+            (Some("Chain"), ..) => flush(&mut policies, current.take()),
+            (Some("-P"), Some(chain), Some(policy), _) => policies.merge(chain, policy, 0),
+            (Some("-A"), Some(chain), ..) => {
+                if let Some(Some(chain)) = policies.chain_mut(chain) {
+                    chain.rules += 1;
+                }
+            }
+            (Some("target"), ..) | (None, ..) => {}
+            _ => {
+                if let Some((_, _, rules)) = current.as_mut() {
+                    *rules += 1;
+                }
+            }
+        }
+    }
+    flush(&mut policies, current);
+    policies
+}
+
+/// Policies of the filter base chains in `nft list ruleset` output. Chains
+/// without a `policy` accept by default.
+fn parse_nft_policies(ruleset: &str) -> FirewallPolicies {
+    let mut policies = FirewallPolicies::default();
+    // (hook, policy, rules) of the base chain being read
+    let mut chain: Option<(Option<String>, String, usize)> = None;
+    for line in ruleset.lines() {
+        let line = line.trim();
+        if line.starts_with("chain ") && line.ends_with('{') {
+            chain = Some((None, "accept".to_string(), 0));
+        } else if let Some((hook, policy, rules)) = chain.as_mut() {
+            if line == "}" {
+                if let Some(hook) = hook {
+                    policies.merge(hook, policy, *rules);
+                }
+                chain = None;
+            } else if let Some(definition) = line.strip_prefix("type filter hook ") {
+                *hook = definition.split_whitespace().next().map(str::to_string);
+                if let Some((_, rest)) = definition.split_once("policy ") {
+                    *policy = rest.trim_end_matches(';').trim().to_string();
+                }
+            } else if !line.is_empty() && !line.starts_with("type ") {
+                *rules += 1;
+            }
+        }
+    }
+    policies
+}
+
+/// Printed by the runtime_016 scan when it runs as root
+const ROOT_SCAN_MARKER: &str = "scanning-as-root";
+
+/// Shell script listing candidate files as `<kind> <octal mode> <path>` lines
+fn sensitive_file_scan(sensitive_dirs: &[String]) -> String {
+    let stat = |kind: &str| format!("-exec stat -c '{} %a %n' {{}} + 2>/dev/null", kind);
+    let mut script = vec![
+        format!("[ \"$(id -u)\" = 0 ] && echo {}", ROOT_SCAN_MARKER),
+        format!(
+            "find /etc/ssl/private -type f {}",
+            stat("key")
+        ),
+        format!(
+            "find /root/.ssh /home/*/.ssh -maxdepth 1 -type f -name 'id_*' ! -name '*.pub' {}",
+            stat("key")
+        ),
+        "for f in /etc/shadow /etc/gshadow /etc/shadow- /etc/gshadow-; do [ -e \"$f\" ] && stat -c 'shadow %a %n' \"$f\"; done".to_string(),
+        format!(
+            "find /etc -xdev -type f \\( -name '*.bak' -o -name '*~' -o -name '*.old' -o -name '*.orig' \\) -perm -004 {}",
+            stat("backup")
+        ),
+    ];
+    for dir in sensitive_dirs {
+        script.push(format!(
+            "find '{}' -type f \\( -perm -004 -o -perm -002 \\) {}",
+            dir.replace('\'', "'\\''"),
+            stat("dir")
+        ));
+    }
+    script.push("true".to_string());
+    script.join("; ")
+}
+
+/// Parse scan output into exposed files, ignoring files whose mode is fine.
+/// A path found by several scans is reported once, under its first kind.
+fn parse_exposed_files(output: &str) -> Vec<ExposedFile> {
+    let mut files: Vec<ExposedFile> = Vec::new();
+    for line in output.lines() {
+        let mut fields = line.trim().splitn(3, ' ');
+        let (Some(kind), Some(mode), Some(path)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let kind = match kind {
+            "key" => SensitiveFileKind::PrivateKey,
+            "shadow" => SensitiveFileKind::Shadow,
+            "backup" => SensitiveFileKind::Backup,
+            "dir" => SensitiveFileKind::SensitiveDirectory,
+            _ => continue,
+        };
+        let Ok(mode) = u32::from_str_radix(mode, 8) else {
+            continue;
+        };
+        let file = ExposedFile {
+            kind,
+            mode,
+            path: path.to_string(),
+        };
+        if file.problem().is_some() && !files.iter().any(|seen| seen.path == file.path) {
+            files.push(file);
+        }
+    }
+    files
+}
+
+/// Services listed as least hardened in runtime_015 details
+const LEAST_HARDENED_LISTED: usize = 10;
+
+/// Sandboxing state of one systemd service from `systemctl show`
+#[derive(Debug, Clone, PartialEq)]
+struct ServiceSandbox {
+    unit: String,
+    /// Hardening directives the unit sets
+    enabled: Vec<&'static str>,
+}
+
+impl ServiceSandbox {
+    /// Directives runtime_015 looks for, in reporting order
+    const DIRECTIVES: &'static [&'static str] = &[
+        "NoNewPrivileges",
+        "ProtectSystem",
+        "ProtectHome",
+        "PrivateTmp",
+        "RestrictSUIDSGID",
+        "CapabilityBoundingSet",
+    ];
+
+    fn missing(&self) -> Vec<&'static str> {
+        Self::DIRECTIVES
+            .iter()
+            .copied()
+            .filter(|directive| !self.enabled.contains(directive))
+            .collect()
+    }
+}
+
+/// Whether a `systemctl show` property value turns the directive on. Services
+/// keep the full capability set unless they restrict it, so a bounding set
+/// without CAP_SYS_ADMIN counts as reduced.
+fn sandbox_directive_set(directive: &str, value: &str) -> bool {
+    match directive {
+        "ProtectSystem" | "ProtectHome" => !value.is_empty() && value != "no",
+        "CapabilityBoundingSet" => !value.contains("cap_sys_admin"),
+        _ => value == "yes",
+    }
+}
+
+/// Parse `systemctl show -p Id -p <directive>...` output for several units;
+/// each unit's properties form a block separated by a blank line
+fn parse_service_sandboxes(output: &str) -> Vec<ServiceSandbox> {
+    output
+        .split("\n\n")
+        .filter_map(|block| {
+            let properties: Vec<(&str, &str)> = block
+                .lines()
+                .filter_map(|line| line.trim().split_once('='))
+                .collect();
+            let unit = properties
+                .iter()
+                .find(|(key, _)| *key == "Id")
+                .map(|(_, value)| value.to_string())?;
+            let enabled = ServiceSandbox::DIRECTIVES
+                .iter()
+                .copied()
+                .filter(|directive| {
+                    properties.iter().any(|(key, value)| {
+                        key == directive && sandbox_directive_set(directive, value)
+                    })
+                })
+                .collect();
+            Some(ServiceSandbox { unit, enabled })
+        })
+        .collect()
+}
+
+/// Find the filesystem type and options `path` is mounted with in a mount table.
+///
+/// Accepts both `/proc/mounts` lines and `mount` output, and like `findmnt <path>` only
+/// matches exact mountpoints. The last entry wins so over-mounts take precedence.
+fn mount_entry<'a>(table: &'a str, path: &str) -> Option<(&'a str, &'a str)> {
+    table
+        .lines()
+        .rev()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                // mount: "<source> on <target> type <fstype> (<options>)"
+                [_, "on", target, "type", fstype, options, ..] => Some((
+                    *target,
+                    *fstype,
+                    options.trim_matches(|c| c == '(' || c == ')'),
+                )),
+                // /proc/mounts: "<source> <target> <fstype> <options> <dump> <pass>"
+                [_, target, fstype, options, ..] => Some((*target, *fstype, *options)),
+                _ => None,
+            }
+        })
+        .find(|(target, _, _)| *target == path)
+        .map(|(_, fstype, options)| (fstype, options))
+}
+
+/// Modules hardened deployments disable unless the product needs them
+const RECOMMENDED_MODULE_BLACKLIST: &[&str] = &[
+    "cramfs",
+    "freevxfs",
+    "jffs2",
+    "hfs",
+    "hfsplus",
+    "udf",
+    "usb-storage",
+    "firewire-core",
+    "bluetooth",
+];
+
+/// Modules disabled in modprobe configuration, either with `blacklist <mod>` or with an
+/// `install <mod> /bin/true` (or `/bin/false`) override. Names use underscores like lsmod.
+fn blacklisted_modules(conf: &str) -> BTreeSet<String> {
+    conf.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["blacklist", module, ..] => Some(*module),
+                ["install", module, command, ..]
+                    if command.ends_with("/true") || command.ends_with("/false") =>
+                {
+                    Some(*module)
+                }
+                _ => None,
+            }
+        })
+        .map(|module| module.replace('-', "_"))
+        .collect()
+}
+
+/// Look up whether `path` is mounted read-only or read-write in a mount table
+fn mount_access(table: &str, path: &str) -> Option<&'static str> {
+    mount_entry(table, path).and_then(|(_, options)| {
+        options.split(',').find_map(|option| match option {
+            "ro" => Some("ro"),
+            "rw" => Some("rw"),
+            _ => None,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_firewall_chain_policies() {
+        let listing = "Chain INPUT (policy DROP)\ntarget     prot opt source               destination\nACCEPT     all  --  0.0.0.0/0            0.0.0.0/0\nACCEPT     tcp  --  0.0.0.0/0            0.0.0.0/0            tcp dpt:22\n\nChain FORWARD (policy ACCEPT)\ntarget     prot opt source               destination\n\nChain OUTPUT (policy ACCEPT)\ntarget     prot opt source               destination\n";
+        let policies = parse_iptables_policies(listing);
+        assert_eq!(policies.input.as_ref().map(|c| c.rules), Some(2));
+        assert!(policies.input.as_ref().is_some_and(FirewallChain::drops));
+        assert!(!policies.forward.as_ref().is_some_and(FirewallChain::drops));
+        assert!(!policies.filters_egress());
+
+        let rules = "-P INPUT DROP\n-P FORWARD DROP\n-P OUTPUT ACCEPT\n-A OUTPUT -p tcp --dport 25 -j REJECT\n";
+        let policies = parse_iptables_policies(rules);
+        assert!(policies.forward.as_ref().is_some_and(FirewallChain::drops));
+        assert!(policies.filters_egress());
+
+        let ruleset = "table inet filter {\n\tchain input {\n\t\ttype filter hook input priority filter; policy drop;\n\t\tct state established,related accept\n\t\ttcp dport 22 accept\n\t}\n\tchain forward {\n\t\ttype filter hook forward priority filter; policy drop;\n\t}\n\tchain output {\n\t\ttype filter hook output priority filter;\n\t}\n}\ntable ip nat {\n\tchain postrouting {\n\t\ttype nat hook postrouting priority srcnat; policy accept;\n\t\tmasquerade\n\t}\n}\n";
+        let policies = parse_nft_policies(ruleset);
+        assert_eq!(
+            policies.to_string(),
+            "INPUT DROP (2 rules), FORWARD DROP (0 rules), OUTPUT ACCEPT (0 rules)"
+        );
+        assert!(parse_nft_policies("").is_empty());
+    }
+
+    #[test]
+    fn test_mount_access_parses_proc_mounts_and_mount_output() {
+        let proc_mounts = "/dev/root / ext4 ro,relatime 0 0\ntmpfs /tmp tmpfs rw,nosuid,nodev 0 0\n/dev/mmcblk0p2 /usr ext4 rw 0 0\n/dev/mmcblk0p2 /usr ext4 ro,bind 0 0\n";
+        assert_eq!(mount_access(proc_mounts, "/"), Some("ro"));
+        assert_eq!(mount_access(proc_mounts, "/tmp"), Some("rw"));
+        assert_eq!(mount_access(proc_mounts, "/usr"), Some("ro"));
+        assert_eq!(mount_access(proc_mounts, "/var"), None);
+
+        let mount = "/dev/root on / type ext4 (rw,relatime)\noverlay on /etc type overlay (ro,lowerdir=/usr/etc)\n";
+        assert_eq!(mount_access(mount, "/"), Some("rw"));
+        assert_eq!(mount_access(mount, "/etc"), Some("ro"));
+    }
+
+    #[test]
+    fn test_blacklisted_modules_accepts_blacklist_and_install_overrides() {
+        let conf = "# hardening\nblacklist cramfs\ninstall usb-storage /bin/true\ninstall firewire-core /usr/bin/false\ninstall snd /sbin/modprobe --ignore-install snd\noptions bluetooth disable_ertm=1\n";
+        let modules = blacklisted_modules(conf);
+        assert_eq!(
+            modules.into_iter().collect::<Vec<_>>(),
+            vec!["cramfs", "firewire_core", "usb_storage"]
+        );
+    }
+
+    #[test]
+    fn test_parse_service_sandboxes() {
+        let output = "Id=sshd.service\nNoNewPrivileges=no\nProtectSystem=no\nProtectHome=no\n\
+                      PrivateTmp=no\nRestrictSUIDSGID=no\n\
+                      CapabilityBoundingSet=cap_chown cap_sys_admin cap_net_admin\n\n\
+                      Id=systemd-timesyncd.service\nNoNewPrivileges=yes\nProtectSystem=strict\n\
+                      ProtectHome=yes\nPrivateTmp=yes\nRestrictSUIDSGID=yes\n\
+                      CapabilityBoundingSet=cap_sys_time\n\n\
+                      Id=aktualizr-lite.service\nNoNewPrivileges=no\nProtectSystem=full\n\
+                      ProtectHome=read-only\nPrivateTmp=yes\nRestrictSUIDSGID=no\n\
+                      CapabilityBoundingSet=cap_chown cap_sys_admin\n";
+
+        let services = parse_service_sandboxes(output);
+        assert_eq!(services.len(), 3);
+        assert_eq!(services[0].unit, "sshd.service");
+        assert!(services[0].enabled.is_empty());
+        assert_eq!(services[1].enabled.len(), 6);
+        assert_eq!(
+            services[2].enabled,
+            ["ProtectSystem", "ProtectHome", "PrivateTmp"]
+        );
+        assert_eq!(
+            services[2].missing(),
+            [
+                "NoNewPrivileges",
+                "RestrictSUIDSGID",
+                "CapabilityBoundingSet"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_exposed_files() {
+        let output = "key 600 /etc/ssl/private/device.key\n\
+                      key 640 /home/fio/.ssh/id_ed25519\n\
+                      shadow 640 /etc/shadow\n\
+                      shadow 644 /etc/shadow-\n\
+                      backup 644 /etc/ssh/sshd_config.bak\n\
+                      dir 666 /var/sota/client.pem\n\
+                      dir 644 /etc/ssl/private/device.key\n\
+                      garbage line\n";
+
+        let exposed = parse_exposed_files(output);
+        let paths: Vec<&str> = exposed.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/home/fio/.ssh/id_ed25519",
+                "/etc/shadow-",
+                "/etc/ssh/sshd_config.bak",
+                "/var/sota/client.pem",
+                "/etc/ssl/private/device.key",
+            ]
+        );
+        assert_eq!(
+            exposed[0].to_string(),
+            "0640 /home/fio/.ssh/id_ed25519 (private key readable by group/world)"
+        );
+        assert!(exposed[3].world_writable());
+        assert!(sensitive_file_scan(&["/data/it's".to_string()]).contains("'/data/it'\\''s'"));
+    }
 }